@@ -3,6 +3,7 @@ extern crate rocket;
 use std::str::FromStr;
 
 use crate::asset_client::AssetsChainProvider;
+use crate::response::ApiError;
 use crate::AssetsClient;
 use primitives::{Asset, AssetBasic, AssetFull, AssetId, Chain};
 use rocket::serde::json::Json;
@@ -10,35 +11,40 @@ use rocket::tokio::sync::Mutex;
 use rocket::State;
 
 #[get("/assets/<asset_id>")]
-pub async fn get_asset(asset_id: &str, client: &State<Mutex<AssetsClient>>) -> Json<AssetFull> {
-    let asset = client.lock().await.get_asset_full(asset_id).unwrap();
-    Json(asset)
+pub async fn get_asset(asset_id: &str, client: &State<Mutex<AssetsClient>>) -> Result<Json<AssetFull>, ApiError> {
+    let asset = client.lock().await.get_asset_full(asset_id).map_err(|x| ApiError::from(x).asset_not_found())?;
+    Ok(Json(asset))
 }
 
 #[post("/assets", format = "json", data = "<asset_ids>")]
-pub async fn get_assets(asset_ids: Json<Vec<String>>, client: &State<Mutex<AssetsClient>>) -> Json<Vec<AssetBasic>> {
-    let assets = client.lock().await.get_assets(asset_ids.0).unwrap();
-    Json(assets)
+pub async fn get_assets(asset_ids: Json<Vec<String>>, client: &State<Mutex<AssetsClient>>) -> Result<Json<Vec<AssetBasic>>, ApiError> {
+    let assets = client.lock().await.get_assets(asset_ids.0)?;
+    Ok(Json(assets))
 }
 
 #[post("/assets/add", format = "json", data = "<asset_id>")]
-pub async fn add_asset(asset_id: Json<AssetId>, client: &State<Mutex<AssetsClient>>, assets_chain_provider: &State<Mutex<AssetsChainProvider>>) -> Json<Asset> {
+pub async fn add_asset(
+    asset_id: Json<AssetId>,
+    client: &State<Mutex<AssetsClient>>,
+    assets_chain_provider: &State<Mutex<AssetsChainProvider>>,
+) -> Result<Json<Asset>, ApiError> {
     let asset_id = asset_id.0;
+    let token_id = asset_id.token_id.clone().ok_or(ApiError::invalid_request("token id is required"))?;
 
     let asset = assets_chain_provider
         .lock()
         .await
-        .get_token_data(asset_id.chain, asset_id.token_id.clone().unwrap())
+        .get_token_data(asset_id.chain, token_id)
         .await
-        .unwrap();
-    client.lock().await.add_asset(asset.clone()).unwrap();
-    Json(asset)
+        .map_err(ApiError::provider_unavailable)?;
+    client.lock().await.add_asset(asset.clone())?;
+    Ok(Json(asset))
 }
 
 #[get("/assets/list")]
-pub async fn get_assets_list(client: &State<Mutex<AssetsClient>>) -> Json<Vec<AssetBasic>> {
-    let assets = client.lock().await.get_assets_list().unwrap();
-    Json(assets)
+pub async fn get_assets_list(client: &State<Mutex<AssetsClient>>) -> Result<Json<Vec<AssetBasic>>, ApiError> {
+    let assets = client.lock().await.get_assets_list()?;
+    Ok(Json(assets))
 }
 
 #[get("/assets/search?<query>&<chains>&<limit>&<offset>")]
@@ -48,14 +54,13 @@ pub async fn get_assets_search(
     limit: Option<i64>,
     offset: Option<i64>,
     client: &State<Mutex<AssetsClient>>,
-) -> Json<Vec<AssetBasic>> {
+) -> Result<Json<Vec<AssetBasic>>, ApiError> {
     let chains = chains.unwrap_or_default().split(',').flat_map(Chain::from_str).map(|x| x.to_string()).collect();
     let assets = client
         .lock()
         .await
-        .get_assets_search(query.as_str(), chains, limit.unwrap_or(50), offset.unwrap_or(0))
-        .unwrap();
-    Json(assets)
+        .get_assets_search(query.as_str(), chains, limit.unwrap_or(50), offset.unwrap_or(0))?;
+    Ok(Json(assets))
 }
 
 #[get("/assets/by_device_id/<device_id>?<wallet_index>&<from_timestamp>")]
@@ -64,11 +69,11 @@ pub async fn get_assets_ids_by_device_id(
     wallet_index: i32,
     from_timestamp: Option<u32>,
     client: &State<Mutex<AssetsClient>>,
-) -> Json<Vec<String>> {
+) -> Result<Json<Vec<String>>, ApiError> {
     let assets = client
         .lock()
         .await
         .get_assets_ids_by_device_id(device_id, wallet_index, from_timestamp)
-        .unwrap();
-    Json(assets)
+        .map_err(|x| ApiError::from(x).device_not_found())?;
+    Ok(Json(assets))
 }
@@ -1,4 +1,5 @@
 extern crate rocket;
+use crate::response::ApiError;
 use pricer::chart_client::ChartClient;
 use pricer::PriceClient;
 use primitives::asset_price::{ChartPeriod, Charts};
@@ -14,25 +15,28 @@ pub async fn get_charts(
     currency: Option<String>,
     charts_client: &State<Mutex<ChartClient>>,
     price_client: &State<Mutex<PriceClient>>,
-) -> Json<Charts> {
+) -> Result<Json<Charts>, ApiError> {
     let period = ChartPeriod::new(period.unwrap_or_default()).unwrap_or(ChartPeriod::Day);
     let currency_value = currency.clone().unwrap_or(DEFAULT_FIAT_CURRENCY.to_string());
 
-    let coin_id = charts_client.lock().await.get_coin_id(asset_id.as_str()).unwrap();
+    let coin_id = charts_client
+        .lock()
+        .await
+        .get_coin_id(asset_id.as_str())
+        .map_err(|x| ApiError::from(x).asset_not_found())?;
 
     let prices = charts_client
         .lock()
         .await
         .get_charts_prices(coin_id.as_str(), period, currency_value.as_str())
-        .await
-        .unwrap();
+        .await?;
 
     let asset_price = price_client
         .lock()
         .await
         .get_asset_price(asset_id.as_str(), currency_value.as_str())
         .await
-        .unwrap();
+        .map_err(|x| ApiError::from(x).asset_not_found())?;
 
     let response = Charts {
         price: asset_price.price,
@@ -42,5 +46,5 @@ pub async fn get_charts(
         total_volumes: vec![],
     };
 
-    Json(response)
+    Ok(Json(response))
 }
@@ -1,4 +1,5 @@
 extern crate rocket;
+use crate::response::ApiError;
 use fiat::client::Client as FiatProvider;
 use primitives::fiat_quote_request::FiatSellRequest;
 use primitives::{fiat_assets::FiatAssets, fiat_quote::FiatQuotes, fiat_quote_request::FiatBuyRequest};
@@ -16,7 +17,7 @@ pub async fn get_fiat_on_ramp_quotes(
     ip_address: Option<String>,
     ip: std::net::IpAddr,
     fiat_client: &State<Mutex<FiatProvider>>,
-) -> Json<FiatQuotes> {
+) -> Result<Json<FiatQuotes>, ApiError> {
     let request: FiatBuyRequest = FiatBuyRequest {
         asset_id: asset_id.clone(),
         ip_address: ip_address.unwrap_or(ip.to_string()),
@@ -24,11 +25,8 @@ pub async fn get_fiat_on_ramp_quotes(
         fiat_currency: currency,
         wallet_address,
     };
-    let quotes = fiat_client.lock().await.get_buy_quotes(request).await;
-    match quotes {
-        Ok(value) => Json(FiatQuotes { quotes: value }),
-        Err(_) => Json(FiatQuotes { quotes: vec![] }),
-    }
+    let quotes = fiat_client.lock().await.get_buy_quotes(request).await.map_err(ApiError::from_provider_error)?;
+    Ok(Json(FiatQuotes { quotes }))
 }
 
 #[get("/fiat/off_ramp/quotes/<asset_id>?<amount>&<currency>&<wallet_address>&<ip_address>")]
@@ -40,7 +38,7 @@ pub async fn get_fiat_off_ramp_quotes(
     ip_address: Option<String>,
     ip: std::net::IpAddr,
     fiat_client: &State<Mutex<FiatProvider>>,
-) -> Json<FiatQuotes> {
+) -> Result<Json<FiatQuotes>, ApiError> {
     let request = FiatSellRequest {
         asset_id: asset_id.into(),
         ip_address: ip_address.unwrap_or(ip.to_string()),
@@ -48,28 +46,30 @@ pub async fn get_fiat_off_ramp_quotes(
         fiat_currency: currency.into(),
         wallet_address: wallet_address.into(),
     };
-    let quotes = fiat_client.lock().await.get_sell_quotes(request).await;
-    match quotes {
-        Ok(value) => Json(FiatQuotes { quotes: value }),
-        Err(_) => Json(FiatQuotes { quotes: vec![] }),
-    }
+    let quotes = fiat_client.lock().await.get_sell_quotes(request).await.map_err(ApiError::from_provider_error)?;
+    Ok(Json(FiatQuotes { quotes }))
 }
 
 #[get("/fiat/on_ramp/assets")]
-pub async fn get_fiat_on_ramp_assets(fiat_client: &State<Mutex<FiatProvider>>) -> Json<FiatAssets> {
-    let assets = fiat_client.lock().await.get_on_ramp_assets().await.unwrap();
-    Json(assets)
+pub async fn get_fiat_on_ramp_assets(fiat_client: &State<Mutex<FiatProvider>>) -> Result<Json<FiatAssets>, ApiError> {
+    let assets = fiat_client.lock().await.get_on_ramp_assets().await?;
+    Ok(Json(assets))
 }
 
 #[get("/fiat/off_ramp/assets")]
-pub async fn get_fiat_off_ramp_assets(fiat_client: &State<Mutex<FiatProvider>>) -> Json<FiatAssets> {
-    let assets = fiat_client.lock().await.get_off_ramp_assets().await.unwrap();
-    Json(assets)
+pub async fn get_fiat_off_ramp_assets(fiat_client: &State<Mutex<FiatProvider>>) -> Result<Json<FiatAssets>, ApiError> {
+    let assets = fiat_client.lock().await.get_off_ramp_assets().await?;
+    Ok(Json(assets))
 }
 
 #[post("/fiat/webhooks/<provider>", format = "json", data = "<data>")]
-pub async fn create_fiat_webhook(provider: &str, data: Json<serde_json::Value>, fiat_client: &State<Mutex<FiatProvider>>) -> Json<bool> {
+pub async fn create_fiat_webhook(provider: &str, data: Json<serde_json::Value>, fiat_client: &State<Mutex<FiatProvider>>) -> Result<Json<bool>, ApiError> {
     print!("webhook: {}, data: {:?}", provider, serde_json::to_string_pretty(&data.0));
-    let result = fiat_client.lock().await.create_fiat_webhook(provider, data.into_inner()).await.unwrap();
-    Json(result)
+    let result = fiat_client
+        .lock()
+        .await
+        .create_fiat_webhook(provider, data.into_inner())
+        .await
+        .map_err(ApiError::from_provider_error)?;
+    Ok(Json(result))
 }
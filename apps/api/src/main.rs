@@ -15,6 +15,8 @@ mod nft;
 mod nft_client;
 mod parser;
 mod parser_client;
+mod portfolio;
+mod portfolio_client;
 mod price_alerts;
 mod prices;
 mod response;
@@ -40,6 +42,7 @@ use name_resolver::client::Client as NameClient;
 use name_resolver::NameProviderFactory;
 use nft_client::NFTClient;
 use parser_client::ParserClient;
+use portfolio_client::PortfolioClient;
 use pricer::chart_client::ChartClient;
 use pricer::price_client::PriceClient;
 use pricer::PriceAlertClient;
@@ -50,6 +53,7 @@ use security_providers::SecurityProviderFactory;
 use security_scan::SecurityScanClient;
 use settings::Settings;
 use settings_chain::ProviderFactory;
+use std::time::Duration;
 use storage::{ClickhouseClient, DatabaseClient};
 use subscription_client::SubscriptionsClient;
 use swap_client::SwapClient;
@@ -71,13 +75,21 @@ async fn rocket(settings: Settings) -> Rocket<Build> {
     let name_client = NameClient::new(providers);
 
     let providers = ProviderFactory::new_providers(&settings);
+    let chains_health = providers.health();
+    let health_providers = providers.clone();
+    tokio::spawn(async move {
+        loop {
+            health_providers.check_health().await;
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+    });
     let assets_chain_provider = AssetsChainProvider::new(providers);
 
     let pusher_client = PusherClient::new(settings.pusher.url, settings.pusher.ios.topic);
     let devices_client = DevicesClient::new(postgres_url, pusher_client).await;
     let transactions_client = TransactionsClient::new(postgres_url).await;
     let subscriptions_client = SubscriptionsClient::new(postgres_url).await;
-    let metrics_client = MetricsClient::new(postgres_url).await;
+    let metrics_client = MetricsClient::new(postgres_url, chains_health).await;
 
     let security_providers = SecurityProviderFactory::create_providers(&settings_clone);
     let scan_client = SecurityScanClient::new(postgres_url, security_providers).await;
@@ -86,7 +98,22 @@ async fn rocket(settings: Settings) -> Rocket<Build> {
     let swap_client = SwapClient::new(postgres_url).await;
     let providers = FiatProviderFactory::new_providers(settings_clone.clone());
     let fiat_client = FiatProvider::new(postgres_url, providers).await;
-    let nft_client = NFTClient::new(postgres_url).await;
+    let nft_client = NFTClient::new(
+        postgres_url,
+        ProviderFactory::new_nft_providers(&settings_clone),
+        ProviderFactory::new_nft_scan_providers(&settings_clone),
+    )
+    .await;
+    let portfolio_client = PortfolioClient::new(
+        postgres_url,
+        PriceClient::new(redis_url, postgres_url),
+        ChartClient::new(
+            postgres_url,
+            ClickhouseClient::new(&settings_clone.clickhouse.url, &settings_clone.clickhouse.database),
+        ),
+        ProviderFactory::new_balance_providers(&settings_clone),
+    )
+    .await;
 
     rocket::build()
         .attach(AdHoc::on_ignite("Tokio Runtime Configuration", |rocket| async {
@@ -109,9 +136,11 @@ async fn rocket(settings: Settings) -> Rocket<Build> {
         .manage(Mutex::new(scan_client))
         .manage(Mutex::new(parser_client))
         .manage(Mutex::new(swap_client))
-        .manage(Mutex::new(nft_client))
+        .manage(nft_client)
+        .manage(Mutex::new(portfolio_client))
         .manage(Mutex::new(price_alert_client))
         .manage(Mutex::new(assets_chain_provider))
+        .register("/", catchers![response::default_catcher])
         .mount("/", routes![status::get_status,])
         .mount(
             "/v1",
@@ -150,6 +179,7 @@ async fn rocket(settings: Settings) -> Rocket<Build> {
                 nft::get_nft_collectibles,
                 nft::get_nft_collections_by_chain_address,
                 nft::get_nft_collectibles_by_chain_address,
+                portfolio::get_portfolio,
                 price_alerts::get_price_alerts,
                 price_alerts::add_price_alerts,
                 price_alerts::delete_price_alerts,
@@ -1,10 +1,14 @@
+use std::collections::HashMap;
 use std::sync::atomic::AtomicU64;
 
 use prometheus_client::encoding::text::encode;
 use prometheus_client::encoding::EncodeLabelSet;
+use prometheus_client::metrics::counter::Counter;
 use prometheus_client::metrics::family::Family;
 use prometheus_client::metrics::gauge::Gauge;
 use prometheus_client::registry::Registry;
+use settings_chain::ChainHealth;
+use storage::models::PushOutboxStatus;
 use storage::DatabaseClient;
 
 pub struct MetricsClient {
@@ -18,7 +22,17 @@ pub struct MetricsClient {
     pricer_updated_at: Family<PricerStateLabels, Gauge>,
     pricer_price: Family<PricerStateLabels, Gauge<f64, AtomicU64>>,
 
+    push_outbox_count: Family<PushOutboxLabels, Gauge>,
+    push_deliveries: Family<PushOutboxLabels, Counter>,
+
+    node_latency: Family<NodeLabels, Gauge<f64, AtomicU64>>,
+    node_error_rate: Family<NodeLabels, Gauge<f64, AtomicU64>>,
+    node_latest_block: Family<NodeLabels, Gauge>,
+    node_block_lag: Family<NodeLabels, Gauge>,
+    node_is_healthy: Family<NodeLabels, Gauge>,
+
     database: DatabaseClient,
+    chains_health: Vec<ChainHealth>,
 }
 
 #[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
@@ -31,8 +45,19 @@ struct PricerStateLabels {
     asset_id: String,
 }
 
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct PushOutboxLabels {
+    status: String,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct NodeLabels {
+    chain: String,
+    endpoint: String,
+}
+
 impl MetricsClient {
-    pub async fn new(database_url: &str) -> Self {
+    pub async fn new(database_url: &str, chains_health: Vec<ChainHealth>) -> Self {
         let database = DatabaseClient::new(database_url);
 
         let parser_latest_block = Family::<ParserStateLabels, Gauge>::default();
@@ -41,6 +66,13 @@ impl MetricsClient {
         let parser_updated_at = Family::<ParserStateLabels, Gauge>::default();
         let pricer_updated_at = Family::<PricerStateLabels, Gauge>::default();
         let pricer_price = Family::<PricerStateLabels, Gauge<f64, AtomicU64>>::default();
+        let push_outbox_count = Family::<PushOutboxLabels, Gauge>::default();
+        let push_deliveries = Family::<PushOutboxLabels, Counter>::default();
+        let node_latency = Family::<NodeLabels, Gauge<f64, AtomicU64>>::default();
+        let node_error_rate = Family::<NodeLabels, Gauge<f64, AtomicU64>>::default();
+        let node_latest_block = Family::<NodeLabels, Gauge>::default();
+        let node_block_lag = Family::<NodeLabels, Gauge>::default();
+        let node_is_healthy = Family::<NodeLabels, Gauge>::default();
 
         let mut registry = <Registry>::default();
         registry.register("parser_state_latest_block", "Parser latest block", parser_latest_block.clone());
@@ -50,6 +82,15 @@ impl MetricsClient {
         // pricer
         registry.register("pricer_updated_at", "Pricer updated at", pricer_updated_at.clone());
         registry.register("pricer_price", "Pricer price", pricer_price.clone());
+        // pusher
+        registry.register("push_outbox_count", "Push outbox rows by status", push_outbox_count.clone());
+        registry.register("push_deliveries", "Push delivery attempts by outcome", push_deliveries.clone());
+        // nodes
+        registry.register("node_latency_ms", "Node latency moving average in milliseconds", node_latency.clone());
+        registry.register("node_error_rate", "Node error rate moving average", node_error_rate.clone());
+        registry.register("node_latest_block", "Node latest block", node_latest_block.clone());
+        registry.register("node_block_lag", "Node blocks behind the best node of the chain", node_block_lag.clone());
+        registry.register("node_is_healthy", "Node is healthy", node_is_healthy.clone());
 
         Self {
             registry,
@@ -59,12 +100,22 @@ impl MetricsClient {
             parser_updated_at,
             pricer_updated_at,
             pricer_price,
+            push_outbox_count,
+            push_deliveries,
+            node_latency,
+            node_error_rate,
+            node_latest_block,
+            node_block_lag,
+            node_is_healthy,
             database,
+            chains_health,
         }
     }
     pub fn get(&mut self) -> String {
         self.update_parser_states();
         self.update_pricer();
+        self.update_push_outbox();
+        self.update_nodes();
 
         let mut buffer = String::new();
         encode(&mut buffer, &self.registry).unwrap();
@@ -108,4 +159,43 @@ impl MetricsClient {
                 .set(price.price);
         }
     }
+
+    pub fn update_push_outbox(&mut self) {
+        let counts = self
+            .database
+            .get_push_outbox_counts()
+            .unwrap_or_default()
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+        for status in PushOutboxStatus::all() {
+            self.push_outbox_count
+                .get_or_create(&PushOutboxLabels {
+                    status: status.as_ref().to_string(),
+                })
+                .set(counts.get(status.as_ref()).cloned().unwrap_or_default());
+        }
+
+        // totals are stored by the parser, the counters advance by the difference since the last scrape
+        for (status, count) in self.database.get_push_deliveries().unwrap_or_default() {
+            let counter = self.push_deliveries.get_or_create(&PushOutboxLabels { status });
+            counter.inc_by((count as u64).saturating_sub(counter.get()));
+        }
+    }
+
+    pub fn update_nodes(&mut self) {
+        for chain_health in &self.chains_health {
+            for endpoint in chain_health.endpoints() {
+                let labels = NodeLabels {
+                    chain: chain_health.chain.as_ref().to_string(),
+                    endpoint: endpoint.name.clone(),
+                };
+                self.node_latency.get_or_create(&labels).set(endpoint.latency_ms);
+                self.node_error_rate.get_or_create(&labels).set(endpoint.error_rate);
+                self.node_latest_block.get_or_create(&labels).set(endpoint.latest_block);
+                self.node_block_lag.get_or_create(&labels).set(endpoint.block_lag);
+                self.node_is_healthy.get_or_create(&labels).set(endpoint.is_healthy() as i64);
+            }
+        }
+    }
 }
@@ -1,17 +1,15 @@
 extern crate rocket;
-use std::str::FromStr;
-
+use crate::response::{parse_chain, ApiError, ApiErrorCode};
 use name_resolver::client::Client as NameClient;
-use primitives::chain::Chain;
 use primitives::name::NameRecord;
-use rocket::{response::status::NotFound, serde::json::Json, tokio::sync::Mutex, State};
+use rocket::{serde::json::Json, tokio::sync::Mutex, State};
 
 #[get("/name/resolve/<name>?<chain>")]
-pub async fn get_name_resolve(name: &str, chain: &str, name_client: &State<Mutex<NameClient>>) -> Result<Json<NameRecord>, NotFound<String>> {
-    let chain = Chain::from_str(chain).unwrap();
-    let result = name_client.lock().await.resolve(name, chain).await;
-    match result {
-        Ok(name) => Ok(Json(name)),
-        Err(err) => Err(NotFound(err.to_string())),
-    }
+pub async fn get_name_resolve(name: &str, chain: &str, name_client: &State<Mutex<NameClient>>) -> Result<Json<NameRecord>, ApiError> {
+    let chain = parse_chain(chain)?;
+    let name = name_client.lock().await.resolve(name, chain).await.map_err(|x| {
+        println!("name resolve error: {}", x);
+        ApiError::new(ApiErrorCode::NotFound, "name not found")
+    })?;
+    Ok(Json(name))
 }
@@ -1,23 +1,21 @@
 extern crate rocket;
-use primitives::{Chain, NFTCollectible, NFTCollection};
-use rocket::{response::status::NotFound, serde::json::Json, tokio::sync::Mutex, State};
-use std::str::FromStr;
+use primitives::{NFTCollectible, NFTCollection};
+use rocket::{serde::json::Json, State};
 
-use crate::{nft_client::NFTClient, response::ResponseResults};
+use crate::{
+    nft_client::NFTClient,
+    response::{parse_chain, ApiError, ResponseResults},
+};
 
 // by device
 
 #[get("/nft/collectibles_by_/<device_id>?<wallet_index>")]
-pub async fn get_nft_collections(
-    device_id: &str,
-    wallet_index: i32,
-    client: &State<Mutex<NFTClient>>,
-) -> Result<Json<ResponseResults<NFTCollection>>, NotFound<String>> {
-    let result = client.lock().await.get_nft_collections(device_id, wallet_index).await;
-    match result {
-        Ok(results) => Ok(Json(ResponseResults { results })),
-        Err(err) => Err(NotFound(err.to_string())),
-    }
+pub async fn get_nft_collections(device_id: &str, wallet_index: i32, client: &State<NFTClient>) -> Result<Json<ResponseResults<NFTCollection>>, ApiError> {
+    let results = client
+        .get_nft_collections(device_id, wallet_index)
+        .await
+        .map_err(|x| ApiError::from(x).device_not_found())?;
+    Ok(Json(ResponseResults { results }))
 }
 
 #[get("/nft/collectibles_by_device_id/<device_id>/<collection_id>?<wallet_index>")]
@@ -25,13 +23,13 @@ pub async fn get_nft_collectibles(
     device_id: &str,
     collection_id: &str,
     wallet_index: i32,
-    client: &State<Mutex<NFTClient>>,
-) -> Result<Json<ResponseResults<NFTCollectible>>, NotFound<String>> {
-    let result = client.lock().await.get_nft_collectibles(device_id, collection_id, wallet_index).await;
-    match result {
-        Ok(results) => Ok(Json(ResponseResults { results })),
-        Err(err) => Err(NotFound(err.to_string())),
-    }
+    client: &State<NFTClient>,
+) -> Result<Json<ResponseResults<NFTCollectible>>, ApiError> {
+    let results = client
+        .get_nft_collectibles(device_id, collection_id, wallet_index)
+        .await
+        .map_err(|x| ApiError::from(x).device_not_found())?;
+    Ok(Json(ResponseResults { results }))
 }
 
 // by address. mostly for testing purposes
@@ -40,14 +38,14 @@ pub async fn get_nft_collectibles(
 pub async fn get_nft_collections_by_chain_address(
     chain: &str,
     address: &str,
-    client: &State<Mutex<NFTClient>>,
-) -> Result<Json<ResponseResults<NFTCollection>>, NotFound<String>> {
-    let chain = Chain::from_str(chain).unwrap();
-    let result = client.lock().await.get_nft_collections_by_address(chain, address).await;
-    match result {
-        Ok(results) => Ok(Json(ResponseResults { results })),
-        Err(err) => Err(NotFound(err.to_string())),
-    }
+    client: &State<NFTClient>,
+) -> Result<Json<ResponseResults<NFTCollection>>, ApiError> {
+    let chain = parse_chain(chain)?;
+    let results = client
+        .get_nft_collections_by_address(chain, address)
+        .await
+        .map_err(ApiError::from_provider_error)?;
+    Ok(Json(ResponseResults { results }))
 }
 
 #[get("/nft/collectibles_by_chain_address/<chain>/<collection_id>?<address>")]
@@ -55,12 +53,12 @@ pub async fn get_nft_collectibles_by_chain_address(
     chain: &str,
     collection_id: &str,
     address: &str,
-    client: &State<Mutex<NFTClient>>,
-) -> Result<Json<ResponseResults<NFTCollectible>>, NotFound<String>> {
-    let chain = Chain::from_str(chain).unwrap();
-    let result = client.lock().await.get_nft_collectibles_by_address(chain, collection_id, address).await;
-    match result {
-        Ok(results) => Ok(Json(ResponseResults { results })),
-        Err(err) => Err(NotFound(err.to_string())),
-    }
+    client: &State<NFTClient>,
+) -> Result<Json<ResponseResults<NFTCollectible>>, ApiError> {
+    let chain = parse_chain(chain)?;
+    let results = client
+        .get_nft_collectibles_by_address(chain, collection_id, address)
+        .await
+        .map_err(ApiError::from_provider_error)?;
+    Ok(Json(ResponseResults { results }))
 }
@@ -1,34 +1,85 @@
-use std::error::Error;
+use std::{collections::HashMap, error::Error, sync::Mutex};
 
+use gem_chain_rpc::{
+    nft::{self, NFTScan},
+    ChainNFTProvider, ChainNFTScanProvider,
+};
 use primitives::{Chain, NFTCollectible, NFTCollection};
 use storage::DatabaseClient;
 
+// The database is locked only around queries, provider calls and scans run without holding it
 pub struct NFTClient {
-    database: DatabaseClient,
+    database: Mutex<DatabaseClient>,
+    providers: HashMap<Chain, Box<dyn ChainNFTProvider>>,
+    scan_providers: HashMap<Chain, Box<dyn ChainNFTScanProvider>>,
 }
 
 impl NFTClient {
-    pub async fn new(database_url: &str) -> Self {
+    pub async fn new(
+        database_url: &str,
+        providers: Vec<(Chain, Box<dyn ChainNFTProvider>)>,
+        scan_providers: Vec<(Chain, Box<dyn ChainNFTScanProvider>)>,
+    ) -> Self {
         Self {
-            database: DatabaseClient::new(database_url),
+            database: Mutex::new(DatabaseClient::new(database_url)),
+            providers: providers.into_iter().collect(),
+            scan_providers: scan_providers.into_iter().collect(),
         }
     }
 
-    pub async fn get_nft_collections(&mut self, device_id: &str, wallet_index: i32) -> Result<Vec<NFTCollection>, Box<dyn Error>> {
-        let _subscriptions = self.get_subscriptions(device_id, wallet_index)?;
-        //TODO: subscriptions contains all of the addresses of the user. Fetch across all chains their nft
-        Ok(vec![])
+    fn is_supported(&self, chain: &Chain) -> bool {
+        self.providers.contains_key(chain) || self.scan_providers.contains_key(chain)
     }
 
-    pub async fn get_nft_collectibles(&mut self, device_id: &str, _collection_id: &str, wallet_index: i32) -> Result<Vec<NFTCollectible>, Box<dyn Error>> {
-        let _subscriptions = self.get_subscriptions(device_id, wallet_index)?;
-        //TODO: Get all the collectibles of the user.
-        Ok(vec![])
+    // Subscribed addresses on chains with collectibles support
+    fn get_addresses(&self, device_id: &str, wallet_index: i32) -> Result<Vec<(Chain, String)>, Box<dyn Error + Send + Sync>> {
+        let mut addresses = self
+            .get_subscriptions(device_id, wallet_index)?
+            .into_iter()
+            .filter(|x| self.is_supported(&x.chain))
+            .map(|x| (x.chain, x.address))
+            .collect::<Vec<_>>();
+        addresses.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()).then(a.1.cmp(&b.1)));
+        addresses.dedup();
+        Ok(addresses)
+    }
+
+    pub async fn get_nft_collections(&self, device_id: &str, wallet_index: i32) -> Result<Vec<NFTCollection>, Box<dyn Error + Send + Sync>> {
+        let addresses = self.get_addresses(device_id, wallet_index)?;
+        let results = futures::future::join_all(addresses.iter().map(|(chain, address)| self.get_collections(*chain, address))).await;
+
+        // a failing chain should not hide collectibles from the others
+        Ok(addresses
+            .into_iter()
+            .zip(results)
+            .flat_map(|((chain, address), result)| match result {
+                Ok(collections) => collections,
+                Err(err) => {
+                    println!("nft collections chain: {}, address: {}, error: {:?}", chain.as_ref(), address, err);
+                    vec![]
+                }
+            })
+            .collect())
     }
 
-    pub fn get_subscriptions(&mut self, device_id: &str, wallet_index: i32) -> Result<Vec<primitives::Subscription>, Box<dyn Error>> {
+    pub async fn get_nft_collectibles(
+        &self,
+        device_id: &str,
+        collection_id: &str,
+        wallet_index: i32,
+    ) -> Result<Vec<NFTCollectible>, Box<dyn Error + Send + Sync>> {
+        let addresses = self.get_addresses(device_id, wallet_index)?;
+        let results = futures::future::join_all(addresses.iter().map(|(chain, address)| self.get_collectibles(*chain, collection_id, address))).await;
+
+        // collection ids are chain specific, chains that do not know the id return nothing or an error
+        Ok(results.into_iter().flat_map(|x| x.unwrap_or_default()).collect())
+    }
+
+    pub fn get_subscriptions(&self, device_id: &str, wallet_index: i32) -> Result<Vec<primitives::Subscription>, Box<dyn Error + Send + Sync>> {
         let subscriptions = self
             .database
+            .lock()
+            .unwrap()
             .get_subscriptions_by_device_id_wallet_index(device_id, wallet_index)?
             .into_iter()
             .map(|x| x.as_primitive())
@@ -36,18 +87,76 @@ impl NFTClient {
         Ok(subscriptions)
     }
 
-    pub async fn get_nft_collections_by_address(&mut self, _chain: Chain, _address: &str) -> Result<Vec<NFTCollection>, Box<dyn Error>> {
-        //TODO: Fetching from ChainNFTProvider
-        Ok(vec![])
+    pub async fn get_nft_collections_by_address(&self, chain: Chain, address: &str) -> Result<Vec<NFTCollection>, Box<dyn Error + Send + Sync>> {
+        if !self.is_supported(&chain) {
+            return Err(format!("collectibles are not supported for chain: {}", chain.as_ref()).into());
+        }
+        self.get_collections(chain, address).await
     }
 
     pub async fn get_nft_collectibles_by_address(
-        &mut self,
-        _chain: Chain,
-        _collection_id: &str,
-        _address: &str,
-    ) -> Result<Vec<NFTCollectible>, Box<dyn Error>> {
-        //TODO: Fetching from ChainNFTProvider
-        Ok(vec![])
+        &self,
+        chain: Chain,
+        collection_id: &str,
+        address: &str,
+    ) -> Result<Vec<NFTCollectible>, Box<dyn Error + Send + Sync>> {
+        if !self.is_supported(&chain) {
+            return Err(format!("collectibles are not supported for chain: {}", chain.as_ref()).into());
+        }
+        self.get_collectibles(chain, collection_id, address).await
+    }
+
+    async fn get_collections(&self, chain: Chain, address: &str) -> Result<Vec<NFTCollection>, Box<dyn Error + Send + Sync>> {
+        match self.providers.get(&chain) {
+            Some(provider) => provider.get_nft_collections(address.to_string()).await,
+            None => Ok(nft::group_collections(&self.get_scanned_nfts(chain, address, None).await?)),
+        }
+    }
+
+    async fn get_collectibles(&self, chain: Chain, collection_id: &str, address: &str) -> Result<Vec<NFTCollectible>, Box<dyn Error + Send + Sync>> {
+        match self.providers.get(&chain) {
+            Some(provider) => provider.get_nft_collectibles(collection_id.to_string(), address.to_string()).await,
+            None => Ok(self
+                .get_scanned_nfts(chain, address, Some(collection_id))
+                .await?
+                .into_iter()
+                .map(|(_, collectible)| collectible)
+                .collect()),
+        }
+    }
+
+    // Continues the persisted scan of the address, collectibles of tokens received in blocks that are not scanned yet show up on later calls
+    async fn get_scanned_nfts(
+        &self,
+        chain: Chain,
+        address: &str,
+        collection_id: Option<&str>,
+    ) -> Result<Vec<(NFTCollection, NFTCollectible)>, Box<dyn Error + Send + Sync>> {
+        let provider = self.scan_providers.get(&chain).ok_or("collectibles are not supported")?;
+        let scan = self.database.lock().unwrap().get_nft_scan(chain, address)?.and_then(|x| {
+            Some(NFTScan {
+                from_block: x.from_block,
+                to_block: x.to_block,
+                tokens: serde_json::from_value(x.tokens).ok()?,
+            })
+        });
+        let scan = provider.scan_nfts(address.to_string(), scan).await?;
+        self.database.lock().unwrap().set_nft_scan(storage::models::NFTScan {
+            chain: chain.as_ref().to_string(),
+            address: address.to_string(),
+            from_block: scan.from_block,
+            to_block: scan.to_block,
+            tokens: serde_json::to_value(&scan.tokens)?,
+        })?;
+
+        let tokens = scan
+            .tokens
+            .into_iter()
+            .filter(|x| match collection_id {
+                Some(id) => x.contract.eq_ignore_ascii_case(id),
+                None => true,
+            })
+            .collect();
+        provider.get_nfts(address.to_string(), tokens).await
     }
 }
@@ -1,8 +1,7 @@
 extern crate rocket;
-use std::str::FromStr;
-
 use crate::parser_client::ParserClient;
-use primitives::{Chain, Transaction};
+use crate::response::{parse_chain, ApiError};
+use primitives::Transaction;
 use rocket::serde::json::Json;
 use rocket::tokio::sync::Mutex;
 use rocket::State;
@@ -13,10 +12,15 @@ pub async fn get_parser_block(
     block_number: i64,
     transaction_type: Option<&str>,
     parser_client: &State<Mutex<ParserClient>>,
-) -> Json<Vec<Transaction>> {
-    let chain = Chain::from_str(chain).unwrap();
-    let transactions = parser_client.lock().await.get_block(chain, block_number, transaction_type).await.unwrap();
-    Json(transactions)
+) -> Result<Json<Vec<Transaction>>, ApiError> {
+    let chain = parse_chain(chain)?;
+    let transactions = parser_client
+        .lock()
+        .await
+        .get_block(chain, block_number, transaction_type)
+        .await
+        .map_err(ApiError::provider_unavailable)?;
+    Ok(Json(transactions))
 }
 
 #[get("/parser/chains/<chain>/blocks/<block_number>/finalize?<address>&<transaction_type>")]
@@ -26,20 +30,25 @@ pub async fn get_parser_block_finalize(
     address: &str,
     transaction_type: Option<&str>,
     parser_client: &State<Mutex<ParserClient>>,
-) -> Json<Vec<Transaction>> {
-    let chain = Chain::from_str(chain).unwrap();
+) -> Result<Json<Vec<Transaction>>, ApiError> {
+    let chain = parse_chain(chain)?;
     let transactions = parser_client
         .lock()
         .await
         .get_block_finalize(chain, block_number, vec![address.to_string()], transaction_type)
         .await
-        .unwrap();
-    Json(transactions)
+        .map_err(ApiError::provider_unavailable)?;
+    Ok(Json(transactions))
 }
 
 #[get("/parser/chains/<chain>")]
-pub async fn get_parser_block_number_latest(chain: &str, parser_client: &State<Mutex<ParserClient>>) -> Json<i64> {
-    let chain = Chain::from_str(chain).unwrap();
-    let block_number = parser_client.lock().await.get_block_number_latest(chain).await.unwrap();
-    Json(block_number)
+pub async fn get_parser_block_number_latest(chain: &str, parser_client: &State<Mutex<ParserClient>>) -> Result<Json<i64>, ApiError> {
+    let chain = parse_chain(chain)?;
+    let block_number = parser_client
+        .lock()
+        .await
+        .get_block_number_latest(chain)
+        .await
+        .map_err(ApiError::provider_unavailable)?;
+    Ok(Json(block_number))
 }
@@ -0,0 +1,24 @@
+extern crate rocket;
+use primitives::{ChartPeriod, Portfolio, DEFAULT_FIAT_CURRENCY};
+use rocket::{serde::json::Json, tokio::sync::Mutex, State};
+
+use crate::{portfolio_client::PortfolioClient, response::ApiError};
+
+#[get("/portfolio/<device_id>?<wallet_index>&<currency>&<period>")]
+pub async fn get_portfolio(
+    device_id: &str,
+    wallet_index: i32,
+    currency: Option<String>,
+    period: Option<String>,
+    client: &State<Mutex<PortfolioClient>>,
+) -> Result<Json<Portfolio>, ApiError> {
+    let currency = currency.unwrap_or(DEFAULT_FIAT_CURRENCY.to_string());
+    let period = ChartPeriod::new(period.unwrap_or_default()).unwrap_or(ChartPeriod::Day);
+    let portfolio = client
+        .lock()
+        .await
+        .get_portfolio(device_id, wallet_index, currency.as_str(), period)
+        .await
+        .map_err(|x| ApiError::from(x).device_not_found())?;
+    Ok(Json(portfolio))
+}
@@ -0,0 +1,411 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+};
+
+use gem_chain_rpc::ChainBalanceProvider;
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use pricer::{chart_client::ChartClient, PriceClient};
+use primitives::{
+    AssetBalance, AssetId, Chain, ChartPeriod, ChartValue, Portfolio, PortfolioAsset, Transaction, TransactionState, TransactionSwapMetadata, TransactionType,
+    TransactionsFetchOption,
+};
+use storage::DatabaseClient;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceChange {
+    pub timestamp: i64,
+    pub asset_id: String,
+    pub value: f64,
+}
+
+pub struct PortfolioClient {
+    database: DatabaseClient,
+    price_client: PriceClient,
+    chart_client: ChartClient,
+    providers: HashMap<Chain, Box<dyn ChainBalanceProvider>>,
+}
+
+impl PortfolioClient {
+    pub async fn new(database_url: &str, price_client: PriceClient, chart_client: ChartClient, providers: Vec<(Chain, Box<dyn ChainBalanceProvider>)>) -> Self {
+        Self {
+            database: DatabaseClient::new(database_url),
+            price_client,
+            chart_client,
+            providers: providers.into_iter().collect(),
+        }
+    }
+
+    pub async fn get_portfolio(&mut self, device_id: &str, wallet_index: i32, currency: &str, period: ChartPeriod) -> Result<Portfolio, Box<dyn Error>> {
+        let addresses = self.get_addresses(device_id, wallet_index)?;
+        let balances = self.get_balances(&addresses).await?;
+        let transactions = self.get_transactions(device_id, wallet_index, &addresses)?;
+        // assets no longer held are still part of the history through their balance changes
+        let asset_ids = balances
+            .keys()
+            .cloned()
+            .chain(transaction_asset_ids(&transactions))
+            .collect::<HashSet<_>>()
+            .into_iter()
+            .collect::<Vec<_>>();
+        let decimals = self
+            .database
+            .get_assets(asset_ids.clone())?
+            .into_iter()
+            .map(|x| (x.id, x.decimals))
+            .collect::<HashMap<_, _>>();
+        let prices = self
+            .price_client
+            .get_asset_prices(currency, asset_ids.iter().map(|x| x.as_str()).collect())
+            .await?
+            .prices
+            .into_iter()
+            .map(|x| (x.asset_id, x.price))
+            .collect::<HashMap<_, _>>();
+
+        // assets without metadata or price can not be valued
+        let amounts = balances
+            .iter()
+            .flat_map(|(asset_id, balance)| Some((asset_id.clone(), amount(balance, *decimals.get(asset_id)?))))
+            .filter(|(asset_id, _)| prices.contains_key(asset_id))
+            .collect::<HashMap<_, _>>();
+        let total_value = amounts.iter().map(|(asset_id, amount)| amount * prices[asset_id]).sum::<f64>();
+
+        let mut assets = amounts
+            .iter()
+            .map(|(asset_id, amount)| {
+                let price = prices[asset_id];
+                let value = amount * price;
+                PortfolioAsset {
+                    asset_id: asset_id.clone(),
+                    balance: balances[asset_id].to_string(),
+                    price,
+                    value,
+                    allocation: if total_value > 0.0 { value / total_value * 100.0 } else { 0.0 },
+                }
+            })
+            .collect::<Vec<_>>();
+        assets.sort_by(|a, b| b.value.total_cmp(&a.value).then(a.asset_id.cmp(&b.asset_id)));
+
+        let own_addresses = addresses.iter().map(|x| x.1.clone()).collect::<HashSet<_>>();
+        let changes = balance_changes(&transactions, &own_addresses, &decimals);
+        let from_timestamp = chrono::Utc::now().timestamp() - period.minutes() as i64 * 60;
+        let history_asset_ids = amounts
+            .keys()
+            .cloned()
+            .chain(changes.iter().filter(|x| x.timestamp >= from_timestamp).map(|x| x.asset_id.clone()))
+            .collect::<HashSet<_>>();
+        let charts = self.get_charts(history_asset_ids.into_iter().collect(), period, currency).await;
+        let history = portfolio_history(&amounts, &changes, &charts);
+
+        Ok(Portfolio {
+            currency: currency.to_string(),
+            total_value,
+            assets,
+            history,
+        })
+    }
+
+    // Subscribed addresses on chains with balance support
+    fn get_addresses(&mut self, device_id: &str, wallet_index: i32) -> Result<Vec<(Chain, String)>, Box<dyn Error>> {
+        let mut addresses = self
+            .database
+            .get_subscriptions_by_device_id_wallet_index(device_id, wallet_index)?
+            .into_iter()
+            .map(|x| x.as_primitive())
+            .filter(|x| self.providers.contains_key(&x.chain))
+            .map(|x| (x.chain, x.address))
+            .collect::<Vec<_>>();
+        addresses.sort_by(|a, b| a.0.as_ref().cmp(b.0.as_ref()).then(a.1.cmp(&b.1)));
+        addresses.dedup();
+        Ok(addresses)
+    }
+
+    // Balances summed over addresses by asset id, tokens are the ones seen in the stored transactions
+    async fn get_balances(&mut self, addresses: &[(Chain, String)]) -> Result<HashMap<String, BigUint>, Box<dyn Error>> {
+        let chains = addresses.iter().map(|x| x.0.as_ref().to_string()).collect::<Vec<_>>();
+        let mut token_ids: HashMap<Chain, Vec<String>> = HashMap::new();
+        for asset_id in self
+            .database
+            .get_assets_ids_by_device_id(addresses.iter().map(|x| x.1.clone()).collect(), chains, None)?
+            .iter()
+            .flat_map(|x| AssetId::new(x))
+        {
+            if let Some(token_id) = asset_id.token_id {
+                token_ids.entry(asset_id.chain).or_default().push(token_id);
+            }
+        }
+
+        let results = futures::future::join_all(addresses.iter().map(|(chain, address)| {
+            let token_ids = token_ids.get(chain).cloned().unwrap_or_default();
+            self.providers[chain].get_balances(address.clone(), token_ids)
+        }))
+        .await;
+
+        // a failing chain should not hide balances from the others
+        let mut balances: HashMap<String, BigUint> = HashMap::new();
+        for ((chain, address), result) in addresses.iter().zip(results) {
+            match result {
+                Ok(values) => {
+                    for AssetBalance { asset_id, balance } in values {
+                        if let Ok(balance) = balance.parse::<BigUint>() {
+                            *balances.entry(asset_id.to_string()).or_default() += balance;
+                        }
+                    }
+                }
+                Err(err) => println!("portfolio balances chain: {}, address: {}, error: {:?}", chain.as_ref(), address, err),
+            }
+        }
+        balances.retain(|_, balance| *balance > BigUint::ZERO);
+        Ok(balances)
+    }
+
+    fn get_transactions(&mut self, device_id: &str, wallet_index: i32, addresses: &[(Chain, String)]) -> Result<Vec<Transaction>, Box<dyn Error>> {
+        let options = TransactionsFetchOption {
+            wallet_index,
+            asset_id: None,
+            from_timestamp: None,
+        };
+        let chains = addresses.iter().map(|x| x.0.as_ref().to_string()).collect::<Vec<_>>();
+        let addresses = addresses.iter().map(|x| x.1.clone()).collect::<Vec<_>>();
+        Ok(self
+            .database
+            .get_transactions_by_device_id(device_id, addresses.clone(), chains, options)?
+            .into_iter()
+            .map(|x| x.as_primitive(addresses.clone()))
+            .collect())
+    }
+
+    // Price charts by asset id, assets without a chart are left out of the history
+    async fn get_charts(&mut self, asset_ids: Vec<String>, period: ChartPeriod, currency: &str) -> HashMap<String, Vec<ChartValue>> {
+        let mut charts = HashMap::new();
+        for asset_id in asset_ids {
+            let Ok(coin_id) = self.chart_client.get_coin_id(&asset_id) else {
+                continue;
+            };
+            match self.chart_client.get_charts_prices(&coin_id, period.clone(), currency).await {
+                Ok(values) => {
+                    charts.insert(asset_id, values);
+                }
+                Err(err) => println!("portfolio charts asset_id: {}, error: {:?}", asset_id, err),
+            }
+        }
+        charts
+    }
+}
+
+fn amount(value: &BigUint, decimals: i32) -> f64 {
+    value.to_f64().unwrap_or_default() / 10f64.powi(decimals)
+}
+
+// Assets moved by the transactions, including fees and both sides of swaps
+pub fn transaction_asset_ids(transactions: &[Transaction]) -> HashSet<String> {
+    let mut asset_ids = HashSet::new();
+    for transaction in transactions {
+        asset_ids.insert(transaction.asset_id.to_string());
+        asset_ids.insert(transaction.fee_asset_id.to_string());
+        if let Some(swap) = transaction
+            .metadata
+            .clone()
+            .and_then(|x| serde_json::from_value::<TransactionSwapMetadata>(x).ok())
+        {
+            asset_ids.insert(swap.from_asset.to_string());
+            asset_ids.insert(swap.to_asset.to_string());
+        }
+    }
+    asset_ids
+}
+
+// Confirmed balance changes of the given addresses, swaps use the metadata for both sides
+pub fn balance_changes(transactions: &[Transaction], addresses: &HashSet<String>, decimals: &HashMap<String, i32>) -> Vec<BalanceChange> {
+    let mut changes = vec![];
+    for transaction in transactions.iter().filter(|x| matches!(x.state, TransactionState::Confirmed)) {
+        let timestamp = transaction.created_at.timestamp();
+        let outgoing = addresses.contains(&transaction.from);
+        let incoming = addresses.contains(&transaction.to);
+        let mut add = |asset_id: &AssetId, value: &str, sign: f64| {
+            let asset_id = asset_id.to_string();
+            if let (Some(decimals), Ok(value)) = (decimals.get(&asset_id), value.parse::<BigUint>()) {
+                let value = sign * amount(&value, *decimals);
+                changes.push(BalanceChange { timestamp, asset_id, value });
+            }
+        };
+
+        let swap = transaction
+            .metadata
+            .clone()
+            .filter(|_| matches!(transaction.transaction_type, TransactionType::Swap))
+            .and_then(|x| serde_json::from_value::<TransactionSwapMetadata>(x).ok());
+        match swap {
+            Some(swap) if outgoing => {
+                add(&swap.from_asset, &swap.from_value, -1.0);
+                add(&swap.to_asset, &swap.to_value, 1.0);
+            }
+            _ => {
+                // transfers between own addresses cancel out
+                if outgoing {
+                    add(&transaction.asset_id, &transaction.value, -1.0);
+                }
+                if incoming {
+                    add(&transaction.asset_id, &transaction.value, 1.0);
+                }
+            }
+        }
+        if outgoing {
+            add(&transaction.fee_asset_id, &transaction.fee, -1.0);
+        }
+    }
+    changes
+}
+
+// Portfolio value at every chart timestamp, balances are walked back from the current ones by undoing later changes
+pub fn portfolio_history(amounts: &HashMap<String, f64>, changes: &[BalanceChange], charts: &HashMap<String, Vec<ChartValue>>) -> Vec<ChartValue> {
+    let mut timestamps = charts.values().flatten().map(|x| x.timestamp).collect::<Vec<_>>();
+    timestamps.sort();
+    timestamps.dedup();
+
+    timestamps
+        .into_iter()
+        .map(|timestamp| {
+            let value = charts
+                .iter()
+                .map(|(asset_id, chart)| {
+                    let later = changes
+                        .iter()
+                        .filter(|x| x.asset_id == *asset_id && x.timestamp > timestamp as i64)
+                        .map(|x| x.value)
+                        .sum::<f64>();
+                    // history older than the stored transactions can go negative
+                    let amount = (amounts.get(asset_id).copied().unwrap_or_default() - later).max(0.0);
+                    let price = chart.iter().filter(|x| x.timestamp <= timestamp).last().map(|x| x.value).unwrap_or_default();
+                    amount * price as f64
+                })
+                .sum::<f64>();
+            ChartValue {
+                timestamp,
+                value: value as f32,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::DateTime;
+
+    fn transaction(from: &str, to: &str, value: &str, timestamp: i64) -> Transaction {
+        Transaction::new(
+            "0x1".to_string(),
+            Chain::Ethereum.as_asset_id(),
+            from.to_string(),
+            to.to_string(),
+            None,
+            TransactionType::Transfer,
+            TransactionState::Confirmed,
+            "1".to_string(),
+            "0".to_string(),
+            "10".to_string(),
+            Chain::Ethereum.as_asset_id(),
+            value.to_string(),
+            None,
+            None,
+            DateTime::from_timestamp(timestamp, 0).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_balance_changes() {
+        let addresses = HashSet::from(["0xa".to_string()]);
+        let decimals = HashMap::from([("ethereum".to_string(), 1)]);
+        let transactions = vec![transaction("0xb", "0xa", "100", 10), transaction("0xa", "0xb", "20", 20)];
+
+        assert_eq!(
+            balance_changes(&transactions, &addresses, &decimals),
+            vec![
+                BalanceChange {
+                    timestamp: 10,
+                    asset_id: "ethereum".to_string(),
+                    value: 10.0
+                },
+                BalanceChange {
+                    timestamp: 20,
+                    asset_id: "ethereum".to_string(),
+                    value: -2.0
+                },
+                BalanceChange {
+                    timestamp: 20,
+                    asset_id: "ethereum".to_string(),
+                    value: -1.0
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_portfolio_history() {
+        let amounts = HashMap::from([("ethereum".to_string(), 7.0)]);
+        let changes = vec![
+            BalanceChange {
+                timestamp: 10,
+                asset_id: "ethereum".to_string(),
+                value: 10.0,
+            },
+            BalanceChange {
+                timestamp: 20,
+                asset_id: "ethereum".to_string(),
+                value: -3.0,
+            },
+        ];
+        let charts = HashMap::from([(
+            "ethereum".to_string(),
+            vec![
+                ChartValue { timestamp: 5, value: 2.0 },
+                ChartValue { timestamp: 15, value: 3.0 },
+                ChartValue { timestamp: 25, value: 4.0 },
+            ],
+        )]);
+
+        assert_eq!(
+            portfolio_history(&amounts, &changes, &charts),
+            vec![
+                ChartValue { timestamp: 5, value: 0.0 },
+                ChartValue { timestamp: 15, value: 30.0 },
+                ChartValue { timestamp: 25, value: 28.0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_portfolio_history_sold_asset() {
+        let changes = vec![
+            BalanceChange {
+                timestamp: 10,
+                asset_id: "ethereum".to_string(),
+                value: 3.0,
+            },
+            BalanceChange {
+                timestamp: 20,
+                asset_id: "ethereum".to_string(),
+                value: -3.0,
+            },
+        ];
+        let charts = HashMap::from([(
+            "ethereum".to_string(),
+            vec![ChartValue { timestamp: 15, value: 2.0 }, ChartValue { timestamp: 25, value: 4.0 }],
+        )]);
+
+        assert_eq!(
+            portfolio_history(&HashMap::new(), &changes, &charts),
+            vec![ChartValue { timestamp: 15, value: 6.0 }, ChartValue { timestamp: 25, value: 0.0 }]
+        );
+    }
+
+    #[test]
+    fn test_transaction_asset_ids() {
+        let transactions = vec![transaction("0xa", "0xb", "20", 20)];
+
+        assert_eq!(transaction_asset_ids(&transactions), HashSet::from(["ethereum".to_string()]));
+    }
+}
@@ -1,3 +1,12 @@
+use std::{error::Error, fmt, str::FromStr};
+
+use primitives::Chain;
+use rocket::{
+    http::Status,
+    response::{self, Responder},
+    serde::json::Json,
+    Request, Response,
+};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -5,13 +14,191 @@ pub struct ResponseResults<T> {
     pub results: Vec<T>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiErrorCode {
+    DeviceNotFound,
+    AssetNotFound,
+    NotFound,
+    ProviderUnavailable,
+    InvalidRequest,
+    InternalError,
+}
+
+impl ApiErrorCode {
+    pub fn status(&self) -> Status {
+        match self {
+            Self::DeviceNotFound | Self::AssetNotFound | Self::NotFound => Status::NotFound,
+            Self::ProviderUnavailable => Status::ServiceUnavailable,
+            Self::InvalidRequest => Status::BadRequest,
+            Self::InternalError => Status::InternalServerError,
+        }
+    }
+
+    fn from_status(status: Status) -> Self {
+        match status.code {
+            404 => Self::NotFound,
+            400..=499 => Self::InvalidRequest,
+            503 => Self::ProviderUnavailable,
+            _ => Self::InternalError,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResponseError {
+    pub code: ApiErrorCode,
     pub error: String,
 }
 
-impl From<Box<dyn std::error::Error + Send + Sync>> for ResponseError {
-    fn from(error: Box<dyn std::error::Error + Send + Sync>) -> Self {
-        Self { error: error.to_string() }
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    pub code: ApiErrorCode,
+    pub message: String,
+}
+
+impl ApiError {
+    pub fn new(code: ApiErrorCode, message: impl fmt::Display) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn invalid_request(message: impl fmt::Display) -> Self {
+        Self::new(ApiErrorCode::InvalidRequest, message)
+    }
+
+    // provider details are logged, not returned
+    pub fn provider_unavailable(error: impl fmt::Display) -> Self {
+        println!("api provider error: {}", error);
+        Self::new(ApiErrorCode::ProviderUnavailable, "provider unavailable")
+    }
+
+    // Errors of routes backed by external providers, database errors are still mapped as database errors
+    pub fn from_provider_error(error: Box<dyn Error + Send + Sync>) -> Self {
+        match error.downcast_ref::<diesel::result::Error>() {
+            Some(_) => Self::from_error(error.as_ref()),
+            None => Self::provider_unavailable(error),
+        }
+    }
+
+    // Missing rows are reported as the resource the route looks up
+    pub fn not_found_as(self, code: ApiErrorCode) -> Self {
+        match self.code {
+            ApiErrorCode::NotFound => Self { code, ..self },
+            _ => self,
+        }
+    }
+
+    pub fn device_not_found(self) -> Self {
+        self.not_found_as(ApiErrorCode::DeviceNotFound)
+    }
+
+    pub fn asset_not_found(self) -> Self {
+        self.not_found_as(ApiErrorCode::AssetNotFound)
+    }
+
+    fn from_error(error: &(dyn Error + 'static)) -> Self {
+        match error.downcast_ref::<diesel::result::Error>() {
+            Some(diesel::result::Error::NotFound) => Self::new(ApiErrorCode::NotFound, error),
+            Some(err) => {
+                // database details are logged, not returned
+                println!("api database error: {:?}", err);
+                Self::new(ApiErrorCode::InternalError, "database error")
+            }
+            None => {
+                println!("api error: {:?}", error);
+                Self::new(ApiErrorCode::InternalError, "internal error")
+            }
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}: {}", self.code, self.message)
+    }
+}
+
+impl From<diesel::result::Error> for ApiError {
+    fn from(error: diesel::result::Error) -> Self {
+        Self::from_error(&error)
+    }
+}
+
+impl From<Box<dyn Error>> for ApiError {
+    fn from(error: Box<dyn Error>) -> Self {
+        Self::from_error(error.as_ref())
+    }
+}
+
+impl From<Box<dyn Error + Send + Sync>> for ApiError {
+    fn from(error: Box<dyn Error + Send + Sync>) -> Self {
+        Self::from_error(error.as_ref())
+    }
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, request: &'r Request<'_>) -> response::Result<'static> {
+        let status = self.code.status();
+        let body = ResponseError {
+            code: self.code,
+            error: self.message,
+        };
+        Response::build_from(Json(body).respond_to(request)?).status(status).ok()
+    }
+}
+
+pub fn parse_chain(chain: &str) -> Result<Chain, ApiError> {
+    Chain::from_str(chain).map_err(|_| ApiError::invalid_request(format!("unsupported chain: {}", chain)))
+}
+
+// Unmatched routes and rejected requests (malformed JSON, bad query params) get the same error body
+#[catch(default)]
+pub fn default_catcher(status: Status, _request: &Request) -> (Status, Json<ResponseError>) {
+    let body = ResponseError {
+        code: ApiErrorCode::from_status(status),
+        error: status.reason_lossy().to_string(),
+    };
+    (status, Json(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_api_error_from_database() {
+        let error = ApiError::from(diesel::result::Error::NotFound);
+        assert_eq!(error.code, ApiErrorCode::NotFound);
+        assert_eq!(error.clone().device_not_found().code, ApiErrorCode::DeviceNotFound);
+        assert_eq!(error.asset_not_found().code.status(), Status::NotFound);
+
+        let error = ApiError::from(diesel::result::Error::BrokenTransactionManager).device_not_found();
+        assert_eq!(error.code, ApiErrorCode::InternalError);
+        assert_eq!(error.message, "database error");
+
+        let error: Box<dyn Error + Send + Sync> = "connection refused 10.0.0.1".into();
+        let error = ApiError::from(error);
+        assert_eq!(error.code, ApiErrorCode::InternalError);
+        assert_eq!(error.message, "internal error");
+    }
+
+    #[test]
+    fn test_api_error_from_provider() {
+        let error = ApiError::from_provider_error("timeout https://provider.com?apiKey=secret".into());
+        assert_eq!(error.code, ApiErrorCode::ProviderUnavailable);
+        assert_eq!(error.message, "provider unavailable");
+
+        let error = ApiError::from_provider_error(Box::new(diesel::result::Error::NotFound));
+        assert_eq!(error.code, ApiErrorCode::NotFound);
+    }
+
+    #[test]
+    fn test_api_error_code() {
+        assert_eq!(serde_json::to_value(ApiErrorCode::ProviderUnavailable).unwrap(), "provider_unavailable");
+        assert_eq!(ApiErrorCode::from_status(Status::UnprocessableEntity), ApiErrorCode::InvalidRequest);
+        assert_eq!(parse_chain("unknown").unwrap_err().code, ApiErrorCode::InvalidRequest);
     }
 }
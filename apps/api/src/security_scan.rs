@@ -1,4 +1,5 @@
 extern crate rocket;
+use crate::response::ApiError;
 use primitives::{SecurityMetadata, SecurityResponse};
 use rocket::{futures::future, serde::json::Json, tokio::sync::Mutex, State};
 use security_provider::{AddressTarget, ScanRequest, ScanResult, ScanTarget, SecurityProvider};
@@ -9,9 +10,9 @@ static PROVIDER_NAME: &str = "Gem";
 static REASON: &str = "Moderation";
 
 #[post("/scan/security", data = "<scan_request>")]
-pub async fn scan(scan_request: Json<ScanRequest>, client: &State<Mutex<SecurityScanClient>>) -> Json<SecurityResponse> {
-    let result = client.lock().await.scan_security(scan_request.0).await.unwrap();
-    Json(result)
+pub async fn scan(scan_request: Json<ScanRequest>, client: &State<Mutex<SecurityScanClient>>) -> Result<Json<SecurityResponse>, ApiError> {
+    let result = client.lock().await.scan_security(scan_request.0).await.map_err(ApiError::from_provider_error)?;
+    Ok(Json(result))
 }
 
 pub struct SecurityScanClient {
@@ -52,17 +53,18 @@ impl SecurityScanClient {
             }
         }
 
-        let scanned = future::join_all(self.security_providers.iter().map(|provider| provider.scan(&scan_request.target)))
-            .await
-            .into_iter()
-            .filter_map(|result| match result {
-                Err(e) => {
-                    println!("error scanning: {}", e);
-                    None
-                }
-                Ok(result) => Some(result),
-            });
-        results.extend(scanned);
+        let scanned = future::join_all(self.security_providers.iter().map(|provider| provider.scan(&scan_request.target))).await;
+        // without any result a clean response would hide that nothing was scanned
+        if results.is_empty() && !scanned.is_empty() && scanned.iter().all(|result| result.is_err()) {
+            return Err("security providers are unavailable".into());
+        }
+        results.extend(scanned.into_iter().filter_map(|result| match result {
+            Err(e) => {
+                println!("error scanning: {}", e);
+                None
+            }
+            Ok(result) => Some(result),
+        }));
 
         Ok(SecurityResponse {
             malicious: results.iter().any(|result| result.is_malicious),
@@ -1,11 +1,12 @@
 extern crate rocket;
+use crate::response::ApiError;
 use primitives::fiat_assets::FiatAssets;
 use rocket::serde::json::Json;
 use rocket::tokio::sync::Mutex;
 use rocket::State;
 
 #[get("/swap/assets")]
-pub async fn get_swap_assets(client: &State<Mutex<crate::SwapClient>>) -> Json<FiatAssets> {
-    let quote = client.lock().await.get_swap_assets().await.unwrap();
-    Json(quote)
+pub async fn get_swap_assets(client: &State<Mutex<crate::SwapClient>>) -> Result<Json<FiatAssets>, ApiError> {
+    let quote = client.lock().await.get_swap_assets().await?;
+    Ok(Json(quote))
 }
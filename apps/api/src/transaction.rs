@@ -1,5 +1,6 @@
 extern crate rocket;
 
+use crate::response::ApiError;
 use crate::TransactionsClient;
 use primitives::Transaction;
 use primitives::TransactionsFetchOption;
@@ -14,18 +15,22 @@ pub async fn get_transactions_by_device_id(
     asset_id: Option<String>,
     from_timestamp: Option<u32>,
     client: &State<Mutex<TransactionsClient>>,
-) -> Json<Vec<Transaction>> {
+) -> Result<Json<Vec<Transaction>>, ApiError> {
     let options: TransactionsFetchOption = TransactionsFetchOption {
         wallet_index,
         asset_id,
         from_timestamp,
     };
-    let transactions = client.lock().await.get_transactions_by_device_id(device_id, options).unwrap();
-    Json(transactions)
+    let transactions = client
+        .lock()
+        .await
+        .get_transactions_by_device_id(device_id, options)
+        .map_err(|x| ApiError::from(x).device_not_found())?;
+    Ok(Json(transactions))
 }
 
 #[get("/transactions/by_hash/<hash>")]
-pub async fn get_transactions_by_hash(hash: &str, client: &State<Mutex<TransactionsClient>>) -> Json<Vec<Transaction>> {
-    let transactions = client.lock().await.get_transactions_by_hash(hash).unwrap();
-    Json(transactions)
+pub async fn get_transactions_by_hash(hash: &str, client: &State<Mutex<TransactionsClient>>) -> Result<Json<Vec<Transaction>>, ApiError> {
+    let transactions = client.lock().await.get_transactions_by_hash(hash)?;
+    Ok(Json(transactions))
 }
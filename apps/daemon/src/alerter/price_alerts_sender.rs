@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use api_connector::pusher::model::Notification;
 use api_connector::PusherClient;
 use pricer::price_alert_client::PriceAlertRules;
@@ -27,23 +29,35 @@ impl PriceAlertSender {
 
         let price_alert_notifications = self.price_alert_client.get_devices_to_alert(rules).await?;
 
-        let notifications = self.price_alert_client.get_notifications_for_price_alerts(price_alert_notifications);
-
-        self.notify(notifications).await
+        let notifications = self.price_alert_client.get_notifications_for_price_alerts(price_alert_notifications.clone());
+
+        // alerts of failed pushes stay pending and are sent again on the next run
+        let failed_tokens = self.notify(notifications.clone()).await;
+        let price_alerts = price_alert_notifications
+            .into_iter()
+            .filter(|x| !failed_tokens.contains(&x.device.token))
+            .map(|x| x.price_alert)
+            .collect::<Vec<_>>();
+        self.price_alert_client.set_price_alerts_notified(price_alerts)?;
+
+        Ok(notifications
+            .iter()
+            .filter(|x| !x.tokens.iter().any(|token| failed_tokens.contains(token)))
+            .count())
     }
 
-    pub async fn notify(&mut self, notifications: Vec<Notification>) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    // Returns the tokens the notifications failed to be delivered to
+    pub async fn notify(&mut self, notifications: Vec<Notification>) -> HashSet<String> {
         if notifications.is_empty() {
-            return Ok(0);
+            return HashSet::new();
         }
 
         match self.pusher_client.push_notifications(notifications.clone()).await {
-            Ok(_) => {}
+            Ok(response) => response.logs.into_iter().map(|x| x.token).collect(),
             Err(e) => {
                 println!("alerter failed to send notification: {:?}", e);
+                notifications.into_iter().flat_map(|x| x.tokens).collect()
             }
         }
-
-        Ok(notifications.len())
     }
 }
@@ -12,6 +12,8 @@ impl DeviceUpdater {
     }
 
     pub async fn update(&mut self) -> Result<usize, Box<dyn Error + Send + Sync>> {
-        Ok(self.database.delete_devices_subscriptions_after_days(120)?)
+        let subscriptions = self.database.delete_devices_subscriptions_after_days(120)?;
+        let push_outbox = self.database.delete_push_outbox_after_days(7)?;
+        Ok(subscriptions + push_outbox)
     }
 }
@@ -3,21 +3,25 @@ mod device_updater;
 mod fiat_assets_updater;
 mod pricer;
 mod tokenlist_updater;
+mod transaction_backfill;
 mod transaction_updater;
 mod version_updater;
 
 use crate::device_updater::DeviceUpdater;
 use crate::tokenlist_updater::Client as TokenListClient;
+use crate::transaction_backfill::TransactionBackfill;
 use crate::transaction_updater::TransactionUpdater;
 use crate::version_updater::Client as VersionClient;
 use api_connector::AssetsClient;
 use fiat::FiatProviderFactory;
 use fiat_assets_updater::FiatAssetsUpdater;
 use job_runner::run_job;
+use settings_chain::ProviderFactory;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
+use storage::DatabaseClient;
 
 #[tokio::main]
 pub async fn main() {
@@ -79,6 +83,15 @@ pub async fn main() {
         }
     });
 
+    let transaction_backfill = run_job("transaction backfill", Duration::from_secs(60), {
+        let settings = Arc::new(settings.clone());
+        move || {
+            let providers = ProviderFactory::new_address_history_providers(&settings);
+            let mut transaction_backfill = TransactionBackfill::new(Box::new(DatabaseClient::new(&settings.postgres.url)), providers);
+            async move { transaction_backfill.backfill().await }
+        }
+    });
+
     // Pin the futures when creating the services vector
     let services: Vec<Pin<Box<dyn Future<Output = ()> + Send>>> = match service.as_str() {
         "alerter" => alerter::jobs(settings.clone()).await,
@@ -91,6 +104,7 @@ pub async fn main() {
                 Box::pin(device_updater),
                 Box::pin(token_list_updater),
                 Box::pin(transaction_updater),
+                Box::pin(transaction_backfill),
             ]
         }
     };
@@ -6,8 +6,10 @@ use crate::pricer::asset_updater::AssetUpdater;
 use crate::pricer::charts_updater::ChartsUpdater;
 use crate::pricer::price_updater::PriceUpdater;
 use coingecko::CoinGeckoClient;
+use coinmarketcap::CoinMarketCapClient;
 use job_runner::run_job;
-use pricer::{ChartClient, PriceClient};
+use pricer::providers::{CoinGeckoPriceProvider, CoinMarketCapPriceProvider};
+use pricer::{ChartClient, PriceClient, PriceProvider};
 use settings::Settings;
 use std::future::Future;
 use std::pin::Pin;
@@ -110,5 +112,16 @@ pub async fn jobs(settings: Settings) -> Vec<Pin<Box<dyn Future<Output = ()> + S
 fn price_updater_factory(settings: &Settings) -> PriceUpdater {
     let coingecko_client = CoinGeckoClient::new(&settings.coingecko.key.secret.clone());
     let price_client = PriceClient::new(&settings.redis.url, &settings.postgres.url.clone());
-    PriceUpdater::new(price_client, coingecko_client.clone())
+    PriceUpdater::new(price_client, coingecko_client.clone(), price_providers(settings, coingecko_client))
+}
+
+// CoinGecko is the primary provider, others are enabled when an api key is set
+fn price_providers(settings: &Settings, coingecko_client: CoinGeckoClient) -> Vec<Box<dyn PriceProvider>> {
+    let mut providers: Vec<Box<dyn PriceProvider>> = vec![Box::new(CoinGeckoPriceProvider::new(coingecko_client))];
+    if !settings.coinmarketcap.key.secret.is_empty() {
+        providers.push(Box::new(CoinMarketCapPriceProvider::new(CoinMarketCapClient::new(
+            &settings.coinmarketcap.key.secret,
+        ))));
+    }
+    providers
 }
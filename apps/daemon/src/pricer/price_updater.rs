@@ -1,8 +1,9 @@
 use chain_primitives::format_token_id;
-use chrono::{DateTime, Duration, Utc};
+use chrono::{Duration, Utc};
 use coingecko::mapper::{get_chain_for_coingecko_platform_id, get_coingecko_market_id_for_chain};
-use coingecko::{Coin, CoinGeckoClient, CoinMarket, SimplePrice};
-use pricer::PriceClient;
+use coingecko::{Coin, CoinGeckoClient, CoinMarket};
+use futures::future::join_all;
+use pricer::{merge_prices, PriceClient, PriceProvider, ProviderPrice};
 use primitives::chain::Chain;
 use primitives::DEFAULT_FIAT_CURRENCY;
 use std::collections::{HashMap, HashSet};
@@ -10,16 +11,21 @@ use std::error::Error;
 use storage::models::price::{PriceAsset, PriceCache};
 use storage::models::{FiatRate, Price};
 
+// Prices not refreshed by any provider within this time are marked as stale
+const PRICES_STALE_SECONDS: i64 = 3600;
+
 pub struct PriceUpdater {
     coin_gecko_client: CoinGeckoClient,
     price_client: PriceClient,
+    providers: Vec<Box<dyn PriceProvider>>,
 }
 
 impl PriceUpdater {
-    pub fn new(price_client: PriceClient, coin_gecko_client: CoinGeckoClient) -> Self {
+    pub fn new(price_client: PriceClient, coin_gecko_client: CoinGeckoClient, providers: Vec<Box<dyn PriceProvider>>) -> Self {
         PriceUpdater {
             coin_gecko_client,
             price_client,
+            providers,
         }
     }
 
@@ -62,30 +68,87 @@ impl PriceUpdater {
     pub async fn update_prices_simple_ids(&mut self, ids: Vec<String>) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
         let ids_chunks = ids.chunks(500);
         for ids in ids_chunks {
-            let prices = self.coin_gecko_client.get_prices_by_ids(ids.to_vec(), DEFAULT_FIAT_CURRENCY).await?;
-            let prices = prices
+            let prices = self
+                .get_providers_prices(ids.to_vec())
+                .await?
                 .into_iter()
-                .map(|(id, price)| price_for_simple_price(id.as_str(), price))
-                .collect::<Vec<Price>>();
+                .map(price_for_provider_price)
+                .collect();
 
             let _ = self.price_client.set_prices_simple(prices);
         }
 
+        let stale_before = Utc::now() - Duration::seconds(PRICES_STALE_SECONDS);
+        self.price_client.set_prices_stale(stale_before.naive_utc())?;
+
         Ok(ids.len())
     }
 
+    // Providers are queried in parallel, a failed provider is skipped and assets fall back to the remaining ones
+    async fn get_providers_prices(&self, ids: Vec<String>) -> Result<Vec<ProviderPrice>, Box<dyn Error + Send + Sync>> {
+        let results = join_all(self.providers.iter().map(|x| x.get_prices(ids.clone()))).await;
+
+        let mut prices = Vec::new();
+        for (provider, result) in self.providers.iter().zip(results) {
+            match result {
+                Ok(result) => prices.push(result),
+                Err(err) => println!("price provider {} error: {}", provider.name(), err),
+            }
+        }
+        if prices.is_empty() {
+            return Err("all price providers failed".into());
+        }
+        Ok(merge_prices(prices))
+    }
+
+    // Market data (all time high, rank, supply) comes from CoinGecko markets, prices are merged from all providers.
+    // Coins without market data, or all coins when markets fail, only get their price updated.
     pub async fn update_prices(&mut self, pages: u32) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
-        let coin_markets = self.coin_gecko_client.get_all_coin_markets(250, pages).await?;
+        let mut markets = match self.coin_gecko_client.get_all_coin_markets(250, pages).await {
+            Ok(coin_markets) => coin_markets
+                .into_iter()
+                .map(price_for_market)
+                .filter(|x| x.last_updated_at.is_some())
+                .map(|x| (x.id.clone(), x))
+                .collect::<HashMap<_, _>>(),
+            Err(err) => {
+                println!("coingecko markets error: {}", err);
+                HashMap::new()
+            }
+        };
 
-        let prices = coin_markets
-            .into_iter()
-            .map(|market| price_for_market(market.clone()))
-            .filter(|x| x.last_updated_at.is_some())
-            .collect::<HashSet<Price>>()
+        let mut ids = self
+            .price_client
+            .get_prices_ids()?
             .into_iter()
-            .collect::<Vec<Price>>();
+            .take(pages as usize * 250)
+            .collect::<HashSet<_>>();
+        ids.extend(markets.keys().cloned());
+        let ids = ids.into_iter().collect::<Vec<_>>();
+
+        let mut prices_simple = Vec::new();
+        for ids in ids.chunks(500) {
+            let provider_prices = match self.get_providers_prices(ids.to_vec()).await {
+                Ok(prices) => prices,
+                Err(err) => {
+                    println!("update prices error: {}", err);
+                    continue;
+                }
+            };
+            for provider_price in provider_prices {
+                match markets.get_mut(&provider_price.price_id) {
+                    Some(market) => {
+                        market.price = provider_price.price;
+                        market.price_change_percentage_24h = provider_price.price_change_percentage_24h;
+                        market.last_updated_at = market.last_updated_at.max(provider_price.last_updated_at);
+                    }
+                    None => prices_simple.push(price_for_provider_price(provider_price)),
+                }
+            }
+        }
 
-        self.price_client.set_prices(prices)
+        let prices = markets.into_values().collect::<Vec<_>>();
+        Ok(self.price_client.set_prices(prices)? + self.price_client.set_prices_simple(prices_simple)?)
     }
 
     pub fn get_prices_assets_for_coin(&mut self, coin: Coin) -> Vec<PriceAsset> {
@@ -196,24 +259,23 @@ fn price_for_market(market: CoinMarket) -> Price {
     )
 }
 
-fn price_for_simple_price(id: &str, price: SimplePrice) -> Price {
-    let last_updated_at = price.last_updated_at.and_then(|x| DateTime::from_timestamp(x as i64, 0)).map(|x| x.naive_utc());
-
+fn price_for_provider_price(price: ProviderPrice) -> Price {
     Price::new(
-        id.to_string(),
-        price.usd.unwrap_or_default(),
-        price.usd_24h_change.unwrap_or_default(),
+        price.price_id,
+        price.price,
+        price.price_change_percentage_24h,
         0.0,
         None,
         0.0,
         None,
-        price.usd_market_cap.unwrap_or_default(),
+        price.market_cap,
         0,
+        price.total_volume,
         0.0,
         0.0,
         0.0,
-        0.0,
-        last_updated_at,
+        // providers without an update time report the current price
+        price.last_updated_at.or(Some(Utc::now().naive_utc())),
     )
 }
 
@@ -0,0 +1,180 @@
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::str::FromStr;
+
+use chrono::{Duration, Utc};
+use gem_chain_rpc::ChainAddressHistoryProvider;
+use primitives::Chain;
+use storage::models::{SubscriptionAddressBackfill, Transaction, TransactionAddresses};
+use storage::store::BackfillStore;
+
+const TRANSACTIONS_LIMIT: usize = 50;
+const SUBSCRIPTIONS_MAX_AGE_DAYS: i64 = 1;
+
+pub struct TransactionBackfill {
+    database: Box<dyn BackfillStore>,
+    providers: HashMap<Chain, Box<dyn ChainAddressHistoryProvider>>,
+}
+
+impl TransactionBackfill {
+    pub fn new(database: Box<dyn BackfillStore>, providers: Vec<(Chain, Box<dyn ChainAddressHistoryProvider>)>) -> Self {
+        Self {
+            database,
+            providers: providers.into_iter().collect(),
+        }
+    }
+
+    // Stores recent history of newly subscribed addresses, pushes are not sent for backfilled transactions
+    pub async fn backfill(&mut self) -> Result<HashMap<String, usize>, Box<dyn Error + Send + Sync>> {
+        let after = (Utc::now() - Duration::days(SUBSCRIPTIONS_MAX_AGE_DAYS)).naive_utc();
+        let addresses = self
+            .database
+            .get_subscriptions_without_backfill(after)?
+            .into_iter()
+            .flat_map(|x| Some((Chain::from_str(&x.chain).ok()?, x.address)))
+            .filter(|(chain, _)| self.providers.contains_key(chain))
+            .collect::<HashSet<_>>();
+
+        let mut transactions = 0;
+        let mut failed = 0;
+        for (chain, address) in &addresses {
+            match self.backfill_address(*chain, address).await {
+                Ok(count) => transactions += count,
+                Err(err) => {
+                    // not marked as backfilled, retried on the next run
+                    failed += 1;
+                    println!("backfill chain: {}, address: {}, error: {:?}", chain.as_ref(), address, err);
+                }
+            }
+        }
+
+        Ok(HashMap::from([
+            ("addresses".to_string(), addresses.len()),
+            ("transactions".to_string(), transactions),
+            ("failed".to_string(), failed),
+        ]))
+    }
+
+    async fn backfill_address(&mut self, chain: Chain, address: &str) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        let transactions = self.providers[&chain].get_address_transactions(address.to_string(), TRANSACTIONS_LIMIT).await?;
+
+        // transactions with unknown assets are skipped, same as in the parser
+        let transactions = transactions
+            .into_iter()
+            .filter(|x| {
+                let asset_ids = x.asset_ids();
+                self.database.get_assets(asset_ids.clone()).is_ok_and(|assets| assets.len() == asset_ids.len())
+            })
+            .collect::<Vec<_>>();
+
+        if !transactions.is_empty() {
+            let addresses = transactions.clone().into_iter().flat_map(TransactionAddresses::from_primitive).collect();
+            let values = transactions.clone().into_iter().map(Transaction::from_primitive).collect();
+            self.database.add_transactions(values, addresses)?;
+        }
+
+        self.database.add_subscriptions_addresses_backfill(vec![SubscriptionAddressBackfill {
+            chain: chain.as_ref().to_string(),
+            address: address.to_string(),
+        }])?;
+
+        Ok(transactions.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::{AssetId, TransactionState, TransactionType};
+    use storage::models::{Asset, Subscription};
+    use storage::store::SubscriptionsBackfillStore;
+    use storage::MemoryStore;
+
+    const ADDRESS: &str = "7ezmRn9ZmF2Z3XbC9mJGG3PknwsPWmRUUvjnpAuqT7dR";
+    const FAILING_ADDRESS: &str = "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu";
+
+    struct MockHistory {
+        transactions: Vec<primitives::Transaction>,
+    }
+
+    #[async_trait::async_trait]
+    impl ChainAddressHistoryProvider for MockHistory {
+        async fn get_address_transactions(&self, address: String, _limit: usize) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
+            if address == FAILING_ADDRESS {
+                return Err("history not available".into());
+            }
+            Ok(self.transactions.clone())
+        }
+    }
+
+    fn transfer(hash: &str, asset_id: AssetId) -> primitives::Transaction {
+        primitives::Transaction::new(
+            hash.to_string(),
+            asset_id,
+            ADDRESS.to_string(),
+            FAILING_ADDRESS.to_string(),
+            None,
+            TransactionType::Transfer,
+            TransactionState::Confirmed,
+            "1".to_string(),
+            "0".to_string(),
+            "5000".to_string(),
+            Chain::Solana.as_asset_id(),
+            "1000".to_string(),
+            None,
+            None,
+            Utc::now(),
+        )
+    }
+
+    fn subscription(address: &str) -> Subscription {
+        Subscription {
+            device_id: 1,
+            wallet_index: 0,
+            chain: Chain::Solana.as_ref().to_string(),
+            address: address.to_string(),
+        }
+    }
+
+    fn store() -> MemoryStore {
+        let store = MemoryStore::new();
+        store.add_subscriptions(vec![subscription(ADDRESS), subscription(FAILING_ADDRESS)]);
+        store.add_assets(vec![Asset::from_primitive(primitives::Asset::from_chain(Chain::Solana))]);
+        store
+    }
+
+    fn backfill(store: &MemoryStore) -> TransactionBackfill {
+        let history = MockHistory {
+            transactions: vec![
+                transfer("a", Chain::Solana.as_asset_id()),
+                // unknown token, skipped
+                transfer("b", AssetId::from_token(Chain::Solana, "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo")),
+            ],
+        };
+        TransactionBackfill::new(Box::new(store.clone()), vec![(Chain::Solana, Box::new(history))])
+    }
+
+    #[tokio::test]
+    async fn test_backfill() {
+        let store = store();
+        let mut backfill = backfill(&store);
+
+        let result = backfill.backfill().await.unwrap();
+
+        assert_eq!(result["addresses"], 2);
+        assert_eq!(result["transactions"], 1);
+        assert_eq!(result["failed"], 1);
+        assert_eq!(
+            store.get_transactions().iter().map(|x| x.hash.clone()).collect::<Vec<_>>(),
+            vec!["a".to_string()]
+        );
+
+        // failed address is retried on the next run, backfilled address is not
+        let pending = store.clone().get_subscriptions_without_backfill(Utc::now().naive_utc()).unwrap();
+        assert_eq!(pending.iter().map(|x| x.address.as_str()).collect::<Vec<_>>(), vec![FAILING_ADDRESS]);
+
+        let result = backfill.backfill().await.unwrap();
+        assert_eq!(result["addresses"], 1);
+        assert_eq!(result["failed"], 1);
+    }
+}
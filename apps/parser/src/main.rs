@@ -3,9 +3,7 @@ pub use parser::Parser;
 pub mod parser_options;
 pub use parser_options::ParserOptions;
 pub mod pusher;
-use parser_proxy::{ParserProxy, ParserProxyUrlConfig};
 pub use pusher::Pusher;
-pub mod parser_proxy;
 
 use api_connector::PusherClient;
 use primitives::Chain;
@@ -60,20 +58,54 @@ pub async fn main() {
         parsers.push(parser);
     }
 
+    parsers.push(tokio::spawn(async move {
+        push_outbox_start(settings).await;
+    }));
+
     futures::future::join_all(parsers).await;
 }
 
+// Retries failed pushes, claimed rows are locked so running multiple parser processes is safe
+async fn push_outbox_start(settings: Settings) {
+    let pusher_client = PusherClient::new(settings.pusher.url.clone(), settings.pusher.ios.topic.clone());
+    let mut pusher = Pusher::new(Box::new(DatabaseClient::new(settings.postgres.url.as_str())), pusher_client);
+    loop {
+        match pusher.retry_pending().await {
+            Ok(count) => {
+                if count > 0 {
+                    println!("push outbox delivered: {}", count)
+                }
+            }
+            Err(e) => {
+                println!("push outbox error: {:?}", e);
+            }
+        }
+        tokio::time::sleep(Duration::from_secs(30)).await;
+    }
+}
+
 async fn parser_start(settings: Settings, parser_options: ParserOptions, chain: Chain, node_urls: Vec<String>) {
     let pusher_client = PusherClient::new(settings.pusher.url.clone(), settings.pusher.ios.topic.clone());
-    let pusher = Pusher::new(settings.postgres.url.clone(), pusher_client);
+    let pusher = Pusher::new(Box::new(DatabaseClient::new(settings.postgres.url.as_str())), pusher_client);
     let database_client = DatabaseClient::new(settings.postgres.url.as_str());
 
-    let url = settings_chain::ProviderFactory::url(chain, &settings);
-    let node_urls = if node_urls.is_empty() { vec![url.to_string()] } else { node_urls };
-    let config = ParserProxyUrlConfig { urls: node_urls };
-    let proxy = ParserProxy::new(chain, config);
+    let node_urls = if node_urls.is_empty() {
+        settings_chain::ProviderFactory::urls(chain, &settings)
+    } else {
+        node_urls
+    };
+    let trace = settings_chain::ProviderFactory::trace(chain, &settings);
+    let provider = settings_chain::ProviderFactory::new_failover_provider(chain, &node_urls, trace);
+
+    let health_provider = provider.clone();
+    tokio::spawn(async move {
+        loop {
+            health_provider.check_health().await;
+            tokio::time::sleep(Duration::from_secs(60)).await;
+        }
+    });
 
-    let mut parser = Parser::new(Box::new(proxy), pusher, database_client, parser_options.clone());
+    let mut parser = Parser::new(Box::new(provider), pusher, Box::new(database_client), parser_options.clone());
     loop {
         match parser.start().await {
             Ok(_) => {
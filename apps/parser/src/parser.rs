@@ -6,15 +6,16 @@ use std::{
 };
 
 use crate::{ParserOptions, Pusher};
-use gem_chain_rpc::ChainBlockProvider;
+use gem_chain_rpc::{BlockHeader, ChainBlockProvider};
 use primitives::Chain;
-use storage::DatabaseClient;
+use storage::store::{AssetsStore, DevicesStore, ParserStateStore, SubscriptionsStore, TransactionsStore};
+use storage::{models::ParserState, ParserStore};
 
 pub struct Parser {
     chain: Chain,
     provider: Box<dyn ChainBlockProvider>,
     pusher: Pusher,
-    database: DatabaseClient,
+    database: Box<dyn ParserStore>,
     options: ParserOptions,
 }
 
@@ -25,7 +26,7 @@ pub struct ParserBlocksResult {
 }
 
 impl Parser {
-    pub fn new(provider: Box<dyn ChainBlockProvider>, pusher: Pusher, database: DatabaseClient, options: ParserOptions) -> Self {
+    pub fn new(provider: Box<dyn ChainBlockProvider>, pusher: Pusher, database: Box<dyn ParserStore>, options: ParserOptions) -> Self {
         Self {
             chain: provider.get_chain(),
             provider,
@@ -85,9 +86,46 @@ impl Parser {
                     break;
                 }
 
+                let headers = match self.fetch_block_headers(&state, next_blocks.clone()).await {
+                    Ok(headers) => headers,
+                    Err(err) => {
+                        println!(
+                            "parser block headers chain: {}, blocks: {:?}, error: {:?}",
+                            self.chain.as_ref(),
+                            next_blocks,
+                            err
+                        );
+
+                        tokio::time::sleep(Duration::from_millis(self.options.timeout)).await;
+                        break;
+                    }
+                };
+
+                if let Some(fork_block) = self.find_fork_block(&state, &headers).await? {
+                    let deleted = self.database.rollback_parser_state(self.chain, fork_block as i32)?;
+
+                    println!(
+                        "parser reorg: {}, current_block: {}, fork_block: {}, removed transactions: {}",
+                        self.chain.as_ref(),
+                        state.current_block,
+                        fork_block,
+                        deleted
+                    );
+                    continue;
+                }
+
                 match self.parse_blocks(next_blocks.clone()).await {
                     Ok(result) => {
                         let _ = self.database.set_parser_state_current_block(self.chain, end_block);
+                        if let Err(err) = self.store_block_headers(&state, headers, end_block) {
+                            println!(
+                                "parser store block headers chain: {}, blocks: {}..={}, error: {:?}",
+                                self.chain.as_ref(),
+                                start_block,
+                                end_block,
+                                err
+                            );
+                        }
 
                         println!(
                             "parser block complete: {}, blocks: {:?} transactions: {} of {}, to go blocks: {}, in: {:?}",
@@ -118,6 +156,62 @@ impl Parser {
         }
     }
 
+    async fn fetch_block_headers(&self, state: &ParserState, blocks: Vec<i32>) -> Result<Vec<BlockHeader>, Box<dyn Error + Send + Sync>> {
+        if state.reorg_window == 0 {
+            return Ok(vec![]);
+        }
+        let headers = futures::future::try_join_all(blocks.iter().map(|block| self.provider.get_block_header(*block as i64))).await?;
+        let headers = headers.into_iter().flatten().collect::<Vec<_>>();
+
+        if let Some(header) = find_unlinked_header(&headers) {
+            return Err(format!("block {} does not link to parent {}", header.number, header.parent_number).into());
+        }
+        Ok(headers)
+    }
+
+    // Compares the parent of the first fetched block against stored hashes and walks back to the last block that is still canonical
+    async fn find_fork_block(&mut self, state: &ParserState, headers: &[BlockHeader]) -> Result<Option<i64>, Box<dyn Error + Send + Sync>> {
+        let Some(first) = headers.first() else {
+            return Ok(None);
+        };
+        match self.database.get_parser_state_block(self.chain, first.parent_number as i32)? {
+            Some(stored) if stored.block_hash != first.parent_hash => {}
+            _ => return Ok(None),
+        }
+
+        let min_block = (state.current_block - state.reorg_window) as i64;
+        let mut block = first.parent_number - 1;
+        while block > min_block {
+            if let Some(stored) = self.database.get_parser_state_block(self.chain, block as i32)? {
+                let header = self.provider.get_block_header(block).await?;
+                if header.is_some_and(|x| x.hash == stored.block_hash) {
+                    return Ok(Some(block));
+                }
+            }
+            block -= 1;
+        }
+        Ok(Some(min_block))
+    }
+
+    fn store_block_headers(&mut self, state: &ParserState, headers: Vec<BlockHeader>, end_block: i32) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        if headers.is_empty() {
+            return Ok(0);
+        }
+        let blocks = headers
+            .into_iter()
+            .map(|x| storage::models::ParserStateBlock {
+                chain: self.chain.as_ref().to_string(),
+                block_number: x.number as i32,
+                block_hash: x.hash,
+                parent_hash: x.parent_hash,
+            })
+            .collect::<Vec<_>>();
+
+        let result = self.database.add_parser_state_blocks(blocks)?;
+        self.database.delete_parser_state_blocks_before(self.chain, end_block - state.reorg_window)?;
+        Ok(result)
+    }
+
     async fn fetch_blocks(&mut self, blocks: Vec<i32>) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
         let mut retry_attempts_count = 0;
         loop {
@@ -168,7 +262,7 @@ impl Parser {
                         continue;
                     }
 
-                    match self.pusher.push(device.as_primitive(), transaction, subscription.as_primitive()).await {
+                    match self.pusher.push(device, transaction, subscription.as_primitive()).await {
                         Ok(result) => {
                             println!("push: result: {:?}", result);
                         }
@@ -237,3 +331,211 @@ impl Parser {
         Ok(primitive_transactions.len())
     }
 }
+
+// Returns first header in a batch that does not point to the previous one, node is likely in the middle of a reorg
+fn find_unlinked_header(headers: &[BlockHeader]) -> Option<&BlockHeader> {
+    headers
+        .windows(2)
+        .find(|pair| pair[1].parent_number == pair[0].number && pair[1].parent_hash != pair[0].hash)
+        .map(|pair| &pair[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use api_connector::PusherClient;
+    use primitives::{AssetId, TransactionState, TransactionType};
+    use storage::models::{Asset, Device, ParserStateBlock, Subscription};
+    use storage::MemoryStore;
+
+    const ADDRESS: &str = "0x1111111111111111111111111111111111111111";
+
+    // Local chain serving fixed blocks, block hashes are the block numbers unless overridden
+    struct MockChain {
+        transactions: HashMap<i64, Vec<primitives::Transaction>>,
+        hashes: HashMap<i64, String>,
+    }
+
+    #[async_trait::async_trait]
+    impl ChainBlockProvider for MockChain {
+        fn get_chain(&self) -> Chain {
+            Chain::Ethereum
+        }
+
+        async fn get_latest_block(&self) -> Result<i64, Box<dyn Error + Send + Sync>> {
+            Ok(self.transactions.keys().max().cloned().unwrap_or_default())
+        }
+
+        async fn get_transactions(&self, block_number: i64) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
+            Ok(self.transactions.get(&block_number).cloned().unwrap_or_default())
+        }
+
+        async fn get_block_header(&self, block_number: i64) -> Result<Option<BlockHeader>, Box<dyn Error + Send + Sync>> {
+            let hash = |number: i64| self.hashes.get(&number).cloned().unwrap_or(format!("0x{}", number));
+            Ok(Some(header(block_number, &hash(block_number), block_number - 1, &hash(block_number - 1))))
+        }
+    }
+
+    fn header(number: i64, hash: &str, parent_number: i64, parent_hash: &str) -> BlockHeader {
+        BlockHeader {
+            number,
+            hash: hash.to_string(),
+            parent_number,
+            parent_hash: parent_hash.to_string(),
+        }
+    }
+
+    fn transfer(hash: &str, from: &str, to: &str, block_number: i64) -> primitives::Transaction {
+        primitives::Transaction::new(
+            hash.to_string(),
+            AssetId::from_chain(Chain::Ethereum),
+            from.to_string(),
+            to.to_string(),
+            None,
+            TransactionType::Transfer,
+            TransactionState::Confirmed,
+            block_number.to_string(),
+            "0".to_string(),
+            "21000".to_string(),
+            AssetId::from_chain(Chain::Ethereum),
+            "1000000000000000000".to_string(),
+            None,
+            None,
+            chrono::Utc::now(),
+        )
+    }
+
+    fn parser_state(current_block: i32) -> ParserState {
+        ParserState {
+            chain: Chain::Ethereum.as_ref().to_string(),
+            current_block,
+            latest_block: current_block,
+            await_blocks: 0,
+            timeout_between_blocks: 0,
+            parallel_blocks: 1,
+            is_enabled: true,
+            updated_at: chrono::Utc::now().naive_utc(),
+            reorg_window: 5,
+        }
+    }
+
+    fn store() -> MemoryStore {
+        let store = MemoryStore::new();
+        store.add_parser_state(parser_state(10));
+        store.add_device(Device {
+            id: 1,
+            device_id: "device".to_string(),
+            platform: "ios".to_string(),
+            platform_store: None,
+            token: "token".to_string(),
+            locale: "en".to_string(),
+            currency: "USD".to_string(),
+            is_push_enabled: false,
+            is_price_alerts_enabled: false,
+            version: "1.0".to_string(),
+            subscriptions_version: 1,
+        });
+        store.add_subscriptions(vec![Subscription {
+            device_id: 1,
+            wallet_index: 1,
+            chain: Chain::Ethereum.as_ref().to_string(),
+            address: ADDRESS.to_string(),
+        }]);
+        store.add_assets(vec![Asset {
+            id: Chain::Ethereum.as_ref().to_string(),
+            chain: Chain::Ethereum.as_ref().to_string(),
+            token_id: None,
+            name: "Ethereum".to_string(),
+            symbol: "ETH".to_string(),
+            asset_type: "NATIVE".to_string(),
+            decimals: 18,
+            rank: 100,
+            is_buyable: true,
+            is_sellable: true,
+            is_swappable: true,
+            is_stakeable: false,
+            staking_apr: None,
+        }]);
+        store
+    }
+
+    fn parser(chain: MockChain, store: &MemoryStore) -> Parser {
+        let pusher = Pusher::new(Box::new(store.clone()), PusherClient::new("http://localhost".to_string(), "".to_string()));
+        let options = ParserOptions { timeout: 0, retry: 0 };
+        Parser::new(Box::new(chain), pusher, Box::new(store.clone()), options)
+    }
+
+    #[tokio::test]
+    async fn test_parse_blocks() {
+        let other = "0x2222222222222222222222222222222222222222";
+        let chain = MockChain {
+            transactions: HashMap::from([
+                (11, vec![transfer("0xa", other, ADDRESS, 11), transfer("0xb", other, other, 11)]),
+                (12, vec![transfer("0xc", ADDRESS, other, 12)]),
+            ]),
+            hashes: HashMap::new(),
+        };
+        let store = store();
+        let mut parser = parser(chain, &store);
+
+        let result = parser.parse_blocks(vec![11, 12]).await.unwrap();
+
+        assert_eq!(result.transactions, 3);
+        assert_eq!(result.insert_transactions, 2);
+        let mut hashes = store.get_transactions().into_iter().map(|x| x.hash).collect::<Vec<_>>();
+        hashes.sort();
+        assert_eq!(hashes, vec!["0xa", "0xc"]);
+        assert!(store.get_transactions_addresses().iter().any(|x| x.address == ADDRESS));
+    }
+
+    #[tokio::test]
+    async fn test_parse_blocks_multiple_records() {
+        let other = "0x2222222222222222222222222222222222222222";
+        let records = vec![transfer("0xa", other, ADDRESS, 11), transfer("0xa", other, ADDRESS, 11).with_index(1)];
+        let chain = MockChain {
+            transactions: HashMap::from([(11, records)]),
+            hashes: HashMap::new(),
+        };
+        let store = store();
+        let mut parser = parser(chain, &store);
+
+        let result = parser.parse_blocks(vec![11]).await.unwrap();
+
+        assert_eq!(result.insert_transactions, 2);
+        let mut ids = store.get_transactions().into_iter().map(|x| x.id).collect::<Vec<_>>();
+        ids.sort();
+        assert_eq!(ids, vec!["ethereum_0xa", "ethereum_0xa_1"]);
+        assert_eq!(store.get_transactions_addresses().iter().filter(|x| x.address == ADDRESS).count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_find_fork_block() {
+        let chain = MockChain {
+            transactions: HashMap::new(),
+            hashes: HashMap::from([(9, "0x9b".to_string()), (10, "0x10b".to_string())]),
+        };
+        let mut store = store();
+        let blocks = (6..=10).map(|x| ParserStateBlock {
+            chain: Chain::Ethereum.as_ref().to_string(),
+            block_number: x,
+            block_hash: format!("0x{}", x),
+            parent_hash: format!("0x{}", x - 1),
+        });
+        store.add_parser_state_blocks(blocks.collect()).unwrap();
+        let mut parser = parser(chain, &store);
+
+        let state = parser_state(10);
+        let headers = parser.fetch_block_headers(&state, vec![11]).await.unwrap();
+
+        assert_eq!(parser.find_fork_block(&state, &headers).await.unwrap(), Some(8));
+    }
+
+    #[test]
+    fn test_find_unlinked_header() {
+        let headers = vec![header(10, "0xa", 9, "0x9"), header(11, "0xb", 10, "0xa"), header(13, "0xd", 11, "0xb")];
+        assert_eq!(find_unlinked_header(&headers), None);
+
+        let headers = vec![header(10, "0xa", 9, "0x9"), header(11, "0xb", 10, "0xc")];
+        assert_eq!(find_unlinked_header(&headers), Some(&headers[1]));
+    }
+}
@@ -1,23 +1,29 @@
 use std::error::Error;
 
+use chrono::{Duration, Utc};
+
 use localizer::LanguageLocalizer;
 use primitives::{
     AddressFormatter, BigNumberFormatter, Chain, PushNotification, PushNotificationTransaction, PushNotificationTypes, Subscription, Transaction,
-    TransactionSwapMetadata, TransactionType,
+    TransactionNFTTransferMetadata, TransactionSwapMetadata, TransactionType,
 };
-use storage::DatabaseClient;
+use storage::models::{Device, PushOutbox, PushOutboxAttempt, PushOutboxStatus};
+use storage::store::{AssetsStore, DevicesStore, PushOutboxStore, ScanAddressesStore};
+use storage::PusherStore;
 
-use api_connector::pusher::model::Message;
+use api_connector::pusher::model::{Message, Notification};
 use api_connector::PusherClient;
 
+const PUSH_OUTBOX_RETRY_LIMIT: i64 = 100;
+const PUSH_OUTBOX_LEASE_MINUTES: i64 = 5;
+
 pub struct Pusher {
     client: PusherClient,
-    database_client: DatabaseClient,
+    database_client: Box<dyn PusherStore>,
 }
 
 impl Pusher {
-    pub fn new(database_url: String, pusher_client: PusherClient) -> Self {
-        let database_client = DatabaseClient::new(&database_url);
+    pub fn new(database_client: Box<dyn PusherStore>, pusher_client: PusherClient) -> Self {
         Self {
             client: pusher_client,
             database_client,
@@ -49,6 +55,17 @@ impl Pusher {
 
                 Ok(Message { title, message: Some(message) })
             }
+            TransactionType::TransferNFT => {
+                let metadata = transaction.metadata.ok_or("Missing metadata")?;
+                let metadata: TransactionNFTTransferMetadata = serde_json::from_value(metadata)?;
+                let is_sent = transaction.from == subscription.address;
+                let value = format!("NFT #{}", metadata.token_id);
+
+                Ok(Message {
+                    title: localizer.notification_transfer_title(is_sent, value.as_str()),
+                    message: Some(localizer.notification_transfer_description(is_sent, to_address.as_str(), from_address.as_str())),
+                })
+            }
             TransactionType::TokenApproval => Ok(Message {
                 title: localizer.notification_token_approval_title(asset.symbol.as_str(), to_address.as_str()),
                 message: None,
@@ -96,7 +113,9 @@ impl Pusher {
         format! {"{} {}", amount, symbol}
     }
 
-    pub async fn push(&mut self, device: primitives::Device, transaction: Transaction, subscription: Subscription) -> Result<usize, Box<dyn Error>> {
+    pub async fn push(&mut self, device: Device, transaction: Transaction, subscription: Subscription) -> Result<usize, Box<dyn Error>> {
+        let device_id = device.id;
+        let device = device.as_primitive();
         // only push if push is enabled and token is set
         if !device.is_push_enabled || device.token.is_empty() {
             return Ok(0);
@@ -122,13 +141,170 @@ impl Pusher {
             data,
         );
 
-        let response = self.client.push(notification).await?;
+        // every push is recorded in the outbox, failed ones are picked up by retry_pending
+        let (attempt, counts) = self.deliver(&device.id, notification.clone(), 0).await?;
+        self.database_client
+            .add_push_outbox(attempt.new_push_outbox(device_id, serde_json::to_value(&notification)?))?;
+
+        Ok(counts)
+    }
+
+    // Returns the number of delivered pushes, a failing row is rescheduled without aborting the rest of the batch
+    pub async fn retry_pending(&mut self) -> Result<usize, Box<dyn Error>> {
+        let values = self
+            .database_client
+            .claim_push_outbox_pending(PUSH_OUTBOX_RETRY_LIMIT, Duration::minutes(PUSH_OUTBOX_LEASE_MINUTES))?;
+        let mut delivered = 0;
+        for value in values {
+            match self.retry(&value).await {
+                Ok(counts) => delivered += counts,
+                Err(err) => {
+                    println!("push outbox retry error: {}, id: {}", err, value.id);
+                    let attempt = PushOutboxAttempt::new(value.attempts, PushOutboxStatus::Failed, Some(err.to_string()), Utc::now().naive_utc());
+                    if let Err(err) = self.database_client.update_push_outbox(value.id, attempt) {
+                        println!("push outbox update error: {}, id: {}", err, value.id);
+                    }
+                }
+            }
+        }
+        Ok(delivered)
+    }
 
-        if !response.logs.is_empty() {
-            println!("push logs: {:?}", response.logs);
-            let _ = self.database_client.update_device_is_push_enabled(&device.id, false)?;
+    // The stored notification is sent to the current token of the device, tokens may have been refreshed since it was queued
+    async fn retry(&mut self, value: &PushOutbox) -> Result<usize, Box<dyn Error>> {
+        let device = self.database_client.get_device_by_id(value.device_id)?.as_primitive();
+        if !device.is_push_enabled || device.token.is_empty() {
+            let attempt = PushOutboxAttempt::new(value.attempts, PushOutboxStatus::Disabled, None, Utc::now().naive_utc());
+            self.database_client.update_push_outbox(value.id, attempt)?;
+            return Ok(0);
         }
+        let mut notification: Notification = serde_json::from_value(value.notification.clone())?;
+        notification.tokens = vec![device.token.clone()];
+        notification.platform = device.platform.as_i32();
+
+        let (attempt, counts) = self.deliver(&device.id, notification, value.attempts).await?;
+        self.database_client.update_push_outbox(value.id, attempt)?;
+        Ok(counts)
+    }
+
+    async fn deliver(&mut self, device_id: &str, notification: Notification, attempts: i32) -> Result<(PushOutboxAttempt, usize), Box<dyn Error>> {
+        let now = Utc::now().naive_utc();
+        let (status, error, counts) = match self.client.push(notification).await {
+            Ok(response) if !response.invalid_tokens().is_empty() => {
+                println!("push invalid tokens: {:?}", response.logs);
+                self.database_client.update_device_is_push_enabled(device_id, false)?;
+                (PushOutboxStatus::InvalidToken, response.logs.first().map(|x| x.error.clone()), 0)
+            }
+            Ok(response) if !response.errors().is_empty() => {
+                println!("push logs: {:?}", response.logs);
+                (PushOutboxStatus::Failed, Some(response.errors().join(", ")), 0)
+            }
+            Ok(response) => (PushOutboxStatus::Delivered, None, response.counts as usize),
+            Err(err) => (PushOutboxStatus::Failed, Some(err.to_string()), 0),
+        };
+        self.database_client.add_push_deliveries(status, 1)?;
+        Ok((PushOutboxAttempt::new(attempts, status, error, now), counts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::{AssetId, TransactionState};
+    use storage::models::Asset;
+    use storage::MemoryStore;
+
+    fn store() -> MemoryStore {
+        let store = MemoryStore::new();
+        store.add_device(Device {
+            id: 1,
+            device_id: "device".to_string(),
+            platform: "ios".to_string(),
+            platform_store: None,
+            token: "token".to_string(),
+            locale: "en".to_string(),
+            currency: "USD".to_string(),
+            is_push_enabled: true,
+            is_price_alerts_enabled: false,
+            version: "1.0".to_string(),
+            subscriptions_version: 1,
+        });
+        store.add_assets(vec![Asset {
+            id: Chain::Ethereum.as_ref().to_string(),
+            chain: Chain::Ethereum.as_ref().to_string(),
+            token_id: None,
+            name: "Ethereum".to_string(),
+            symbol: "ETH".to_string(),
+            asset_type: "NATIVE".to_string(),
+            decimals: 18,
+            rank: 100,
+            is_buyable: true,
+            is_sellable: true,
+            is_swappable: true,
+            is_stakeable: false,
+            staking_apr: None,
+        }]);
+        store
+    }
+
+    #[tokio::test]
+    async fn test_push_failed_is_queued() {
+        let store = store();
+        // nothing listens on this port, the push fails and is queued for retry
+        let mut pusher = Pusher::new(Box::new(store.clone()), PusherClient::new("http://127.0.0.1:1".to_string(), "".to_string()));
+        let transaction = Transaction::new(
+            "0xa".to_string(),
+            AssetId::from_chain(Chain::Ethereum),
+            "0x1".to_string(),
+            "0x2".to_string(),
+            None,
+            TransactionType::Transfer,
+            TransactionState::Confirmed,
+            "1".to_string(),
+            "0".to_string(),
+            "21000".to_string(),
+            AssetId::from_chain(Chain::Ethereum),
+            "1000000000000000000".to_string(),
+            None,
+            None,
+            chrono::Utc::now(),
+        );
+        let subscription = Subscription {
+            wallet_index: 1,
+            chain: Chain::Ethereum,
+            address: "0x2".to_string(),
+        };
+
+        let device = store.get_device("device").unwrap();
+        assert_eq!(pusher.push(device, transaction, subscription).await.unwrap(), 0);
+
+        let outbox = store.get_push_outbox();
+        assert_eq!(outbox.len(), 1);
+        assert_eq!(outbox[0].status, PushOutboxStatus::Pending.as_ref());
+        assert_eq!(outbox[0].attempts, 1);
+        assert!(outbox[0].error.is_some());
+        assert!(store.get_device("device").unwrap().is_push_enabled);
+        assert_eq!(store.get_push_deliveries().get("failed"), Some(&1));
+    }
+
+    #[tokio::test]
+    async fn test_retry_pending() {
+        let mut store = store();
+        store.update_device_is_push_enabled("device", false).unwrap();
+
+        let attempt = PushOutboxAttempt::new(0, PushOutboxStatus::Failed, None, chrono::Utc::now().naive_utc() - Duration::hours(1));
+        // missing device, push disabled device
+        store.add_push_outbox(attempt.new_push_outbox(2, serde_json::json!({}))).unwrap();
+        store.add_push_outbox(attempt.new_push_outbox(1, serde_json::json!({}))).unwrap();
+
+        let mut pusher = Pusher::new(Box::new(store.clone()), PusherClient::new("http://127.0.0.1:1".to_string(), "".to_string()));
+        assert_eq!(pusher.retry_pending().await.unwrap(), 0);
 
-        Ok(response.counts as usize)
+        let outbox = store.get_push_outbox();
+        assert_eq!(outbox[0].status, PushOutboxStatus::Pending.as_ref());
+        assert_eq!(outbox[0].attempts, 2);
+        assert!(outbox[0].error.is_some());
+        assert_eq!(outbox[1].status, PushOutboxStatus::Disabled.as_ref());
+        assert!(store.get_push_deliveries().is_empty());
     }
 }
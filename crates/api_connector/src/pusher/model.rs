@@ -1,7 +1,7 @@
 use primitives::PushNotification;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Notification {
     pub tokens: Vec<String>,
     pub platform: i32,
@@ -41,6 +41,16 @@ pub struct Response {
     pub success: String,
 }
 
+impl Response {
+    pub fn invalid_tokens(&self) -> Vec<String> {
+        self.logs.iter().filter(|x| x.is_invalid_token()).map(|x| x.token.clone()).collect()
+    }
+
+    pub fn errors(&self) -> Vec<String> {
+        self.logs.iter().filter(|x| !x.is_invalid_token()).map(|x| x.error.clone()).collect()
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Log {
     #[serde(rename = "type")]
@@ -50,8 +60,50 @@ pub struct Log {
     pub error: String,
 }
 
+// APNs and FCM errors for tokens that will never be delivered again, other errors are treated as transient
+const INVALID_TOKEN_ERRORS: [&str; 7] = [
+    "unregistered",
+    "baddevicetoken",
+    "devicetokennotfortopic",
+    "notregistered",
+    "registration-token-not-registered",
+    "invalidregistration",
+    "requested entity was not found",
+];
+
+impl Log {
+    pub fn is_invalid_token(&self) -> bool {
+        let error = self.error.to_lowercase();
+        INVALID_TOKEN_ERRORS.iter().any(|x| error.contains(x))
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Message {
     pub title: String,
     pub message: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_invalid_tokens() {
+        let response: Response = serde_json::from_str(
+            r#"{
+                "counts": 3,
+                "success": "ok",
+                "logs": [
+                    {"type": "failed-push", "platform": "ios", "token": "1", "error": "Unregistered"},
+                    {"type": "failed-push", "platform": "android", "token": "2", "error": "registration-token-not-registered"},
+                    {"type": "failed-push", "platform": "ios", "token": "3", "error": "TooManyRequests"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(response.invalid_tokens(), vec!["1", "2"]);
+        assert_eq!(response.errors(), vec!["TooManyRequests"]);
+    }
+}
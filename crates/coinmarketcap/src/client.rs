@@ -0,0 +1,45 @@
+use super::model::{QuotesLatest, QuotesLatestQuery};
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::Error;
+
+const COINMARKETCAP_API_URL: &str = "https://pro-api.coinmarketcap.com";
+
+#[derive(Clone)]
+pub struct CoinMarketCapClient {
+    client: reqwest::Client,
+    api_key: String,
+}
+
+impl CoinMarketCapClient {
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert("X-CMC_PRO_API_KEY", HeaderValue::from_str(self.api_key.as_str()).unwrap());
+        headers
+    }
+
+    // Unknown slugs are skipped instead of failing the whole request
+    pub async fn get_quotes_latest(&self, slugs: Vec<String>, currency: &str) -> Result<QuotesLatest, Error> {
+        let url = format!("{}/v2/cryptocurrency/quotes/latest", COINMARKETCAP_API_URL);
+        let query = QuotesLatestQuery {
+            slug: slugs.join(","),
+            convert: currency.to_string(),
+            skip_invalid: true,
+        };
+        self.client
+            .get(&url)
+            .query(&query)
+            .headers(self.headers())
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+    }
+}
@@ -0,0 +1,7 @@
+pub mod client;
+pub mod mapper;
+pub mod model;
+
+pub use self::client::CoinMarketCapClient;
+pub use self::mapper::get_coinmarketcap_slug;
+pub use self::model::{Cryptocurrency, Quote, QuotesLatest};
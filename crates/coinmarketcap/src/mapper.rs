@@ -0,0 +1,17 @@
+// CoinMarketCap slug for a CoinGecko coin id, most ids match and only the known differences are mapped
+pub fn get_coinmarketcap_slug(coingecko_id: &str) -> &str {
+    match coingecko_id {
+        "binancecoin" => "bnb",
+        "ripple" => "xrp",
+        "avalanche-2" => "avalanche",
+        "matic-network" => "polygon",
+        "the-open-network" => "toncoin",
+        "injective-protocol" => "injective",
+        "sei-network" => "sei",
+        "near" => "near-protocol",
+        "polkadot" => "polkadot-new",
+        "optimism" => "optimism-ethereum",
+        "staked-ether" => "steth",
+        _ => coingecko_id,
+    }
+}
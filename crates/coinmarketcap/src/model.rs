@@ -0,0 +1,33 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuotesLatest {
+    pub data: HashMap<String, Cryptocurrency>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Cryptocurrency {
+    pub id: u64,
+    pub name: String,
+    pub symbol: String,
+    pub slug: String,
+    pub quote: HashMap<String, Quote>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Quote {
+    pub price: Option<f64>,
+    pub volume_24h: Option<f64>,
+    pub percent_change_24h: Option<f64>,
+    pub market_cap: Option<f64>,
+    pub last_updated: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct QuotesLatestQuery {
+    pub slug: String,
+    pub convert: String,
+    pub skip_invalid: bool,
+}
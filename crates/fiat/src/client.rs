@@ -84,20 +84,7 @@ impl Client {
             }
         }
 
-        let mut results: Vec<FiatQuote> = join_all(futures)
-            .await
-            .into_iter()
-            .flatten()
-            .map(|quote| {
-                let mut result = quote.clone();
-                result.crypto_amount = precision(quote.crypto_amount, 5);
-                result
-            })
-            .collect();
-
-        results.sort_by(|a, b| b.crypto_amount.partial_cmp(&a.crypto_amount).unwrap());
-
-        Ok(results)
+        map_quotes(join_all(futures).await)
     }
 
     pub async fn get_sell_quotes(&mut self, request: FiatSellRequest) -> Result<Vec<FiatQuote>, Box<dyn Error + Send + Sync>> {
@@ -110,21 +97,30 @@ impl Client {
             }
         }
 
-        let mut results: Vec<FiatQuote> = join_all(futures)
-            .await
-            .into_iter()
-            .flatten()
-            .map(|quote| {
-                let mut result = quote.clone();
-                result.crypto_amount = precision(quote.crypto_amount, 5);
-                result
-            })
-            .collect();
-
-        results.sort_by(|a, b| b.crypto_amount.partial_cmp(&a.crypto_amount).unwrap());
+        map_quotes(join_all(futures).await)
+    }
+}
 
-        Ok(results)
+// Quotes sorted by crypto amount, failed providers are skipped unless every provider failed
+fn map_quotes(results: Vec<Result<FiatQuote, Box<dyn Error + Send + Sync>>>) -> Result<Vec<FiatQuote>, Box<dyn Error + Send + Sync>> {
+    let mut quotes: Vec<FiatQuote> = Vec::new();
+    let mut error = None;
+    for result in results {
+        match result {
+            Ok(quote) => quotes.push(FiatQuote {
+                crypto_amount: precision(quote.crypto_amount, 5),
+                ..quote
+            }),
+            Err(err) => error = Some(err),
+        }
     }
+    if let Some(error) = error.filter(|_| quotes.is_empty()) {
+        return Err(error);
+    }
+
+    quotes.sort_by(|a, b| b.crypto_amount.partial_cmp(&a.crypto_amount).unwrap());
+
+    Ok(quotes)
 }
 
 #[allow(dead_code)]
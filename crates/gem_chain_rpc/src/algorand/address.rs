@@ -0,0 +1,63 @@
+use base64::{engine::general_purpose, Engine as _};
+use sha2::{Digest, Sha512_256};
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+const PUBLIC_KEY_LENGTH: usize = 32;
+const CHECKSUM_LENGTH: usize = 4;
+
+pub struct AlgorandAddress {}
+
+impl AlgorandAddress {
+    // address is the unpadded base32 of the public key followed by the last 4 bytes of its sha512/256
+    pub fn from_public_key(public_key: &[u8]) -> Option<String> {
+        if public_key.len() != PUBLIC_KEY_LENGTH {
+            return None;
+        }
+        let hash = Sha512_256::digest(public_key);
+        let mut bytes = public_key.to_vec();
+        bytes.extend_from_slice(&hash[hash.len() - CHECKSUM_LENGTH..]);
+        Some(Self::base32_encode(&bytes))
+    }
+
+    // block transactions encode public keys as base64
+    pub fn from_base64(value: &str) -> Option<String> {
+        let public_key = general_purpose::STANDARD.decode(value).ok()?;
+        Self::from_public_key(&public_key)
+    }
+
+    fn base32_encode(bytes: &[u8]) -> String {
+        let mut result = String::with_capacity(bytes.len().div_ceil(5) * 8);
+        let mut buffer: u16 = 0;
+        let mut bits = 0;
+        for byte in bytes {
+            buffer = (buffer << 8) | *byte as u16;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                result.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+            }
+        }
+        if bits > 0 {
+            result.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_base64() {
+        assert_eq!(
+            AlgorandAddress::from_base64("x/zNsljw1BicK/i21o7ml1CGQrCtAB8x/LkYw1S6hZo=").unwrap(),
+            "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA".to_string()
+        );
+        assert_eq!(
+            AlgorandAddress::from_base64("/v////////////////////////////////////////8=").unwrap(),
+            "737777777777777777777777777777777777777777777777777UFEJ2CI".to_string()
+        );
+        assert_eq!(AlgorandAddress::from_base64("AAEC"), None);
+    }
+}
@@ -0,0 +1,169 @@
+use std::error::Error;
+
+use crate::{ChainBlockProvider, ChainTokenDataProvider};
+use async_trait::async_trait;
+use base64::{engine::general_purpose, Engine as _};
+use chrono::DateTime;
+use primitives::{chain::Chain, Asset, AssetId, AssetType, TransactionState, TransactionType};
+use reqwest_middleware::ClientWithMiddleware;
+
+use super::address::AlgorandAddress;
+use super::model::{AssetResponse, Block, BlockResponse, BlockTransactionIds, NodeStatus, Transaction};
+
+const TRANSACTION_TYPE_PAY: &str = "pay";
+const TRANSACTION_TYPE_ASSET_TRANSFER: &str = "axfer";
+
+pub struct AlgorandClient {
+    url: String,
+    client: ClientWithMiddleware,
+}
+
+impl AlgorandClient {
+    pub fn new(client: ClientWithMiddleware, url: String) -> Self {
+        Self { url, client }
+    }
+
+    fn decode_note(note: Option<String>) -> Option<String> {
+        let bytes = general_purpose::STANDARD.decode(note?).ok()?;
+        String::from_utf8(bytes).ok().filter(|x| !x.is_empty())
+    }
+
+    pub fn map_transaction(&self, hash: String, transaction: Transaction, block_number: i64, block_timestamp: i64) -> Option<primitives::Transaction> {
+        let (asset_id, to, value) = match transaction.transaction_type.as_str() {
+            TRANSACTION_TYPE_PAY => (self.get_chain().as_asset_id(), transaction.rcv?, transaction.amt),
+            // skip opt-in (zero amount to self) and clawback transfers
+            TRANSACTION_TYPE_ASSET_TRANSFER if transaction.aamt > 0 && transaction.asnd.is_none() => {
+                let asset_id = AssetId::from_token(self.get_chain(), &transaction.xaid?.to_string());
+                (asset_id, transaction.arcv?, transaction.aamt)
+            }
+            _ => return None,
+        };
+        let from = AlgorandAddress::from_base64(&transaction.snd)?;
+        let to = AlgorandAddress::from_base64(&to)?;
+
+        let transaction = primitives::Transaction::new(
+            hash,
+            asset_id,
+            from,
+            to,
+            None,
+            TransactionType::Transfer,
+            TransactionState::Confirmed,
+            block_number.to_string(),
+            0.to_string(),
+            transaction.fee.to_string(),
+            self.get_chain().as_asset_id(),
+            value.to_string(),
+            Self::decode_note(transaction.note),
+            None,
+            DateTime::from_timestamp(block_timestamp, 0)?,
+        );
+        Some(transaction)
+    }
+
+    pub async fn get_node_status(&self) -> Result<NodeStatus, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v2/status", self.url);
+        Ok(self.client.get(url).send().await?.json::<NodeStatus>().await?)
+    }
+
+    pub async fn get_block(&self, block_number: i64) -> Result<Block, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v2/blocks/{}?format=json", self.url, block_number);
+        Ok(self.client.get(url).send().await?.json::<BlockResponse>().await?.block)
+    }
+
+    // Block transactions do not include ids, they are returned in the same order by this endpoint
+    pub async fn get_block_transaction_ids(&self, block_number: i64) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v2/blocks/{}/txids", self.url, block_number);
+        Ok(self.client.get(url).send().await?.json::<BlockTransactionIds>().await?.block_txids)
+    }
+
+    pub async fn get_asset(&self, asset_id: &str) -> Result<AssetResponse, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v2/assets/{}", self.url, asset_id);
+        Ok(self.client.get(url).send().await?.json::<AssetResponse>().await?)
+    }
+}
+
+#[async_trait]
+impl ChainBlockProvider for AlgorandClient {
+    fn get_chain(&self) -> Chain {
+        Chain::Algorand
+    }
+
+    async fn get_latest_block(&self) -> Result<i64, Box<dyn Error + Send + Sync>> {
+        Ok(self.get_node_status().await?.last_round)
+    }
+
+    async fn get_transactions(&self, block_number: i64) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
+        let (block, ids) = futures::try_join!(self.get_block(block_number), self.get_block_transaction_ids(block_number))?;
+        if block.txns.len() != ids.len() {
+            return Err("block transactions and ids mismatch".into());
+        }
+
+        let transactions = ids
+            .into_iter()
+            .zip(block.txns)
+            .flat_map(|(hash, x)| self.map_transaction(hash, x.txn, block_number, block.ts))
+            .collect::<Vec<primitives::Transaction>>();
+        Ok(transactions)
+    }
+}
+
+#[async_trait]
+impl ChainTokenDataProvider for AlgorandClient {
+    async fn get_token_data(&self, chain: Chain, token_id: String) -> Result<Asset, Box<dyn Error + Send + Sync>> {
+        let asset = self.get_asset(&token_id).await?;
+        let symbol = asset.params.unit_name.ok_or("missing asset unit name")?;
+
+        Ok(Asset {
+            id: AssetId::from_token(chain, &asset.index.to_string()),
+            name: asset.params.name.unwrap_or(symbol.clone()),
+            symbol,
+            decimals: asset.params.decimals,
+            asset_type: AssetType::TOKEN,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::algorand::model::{BlockResponse, BlockTransactionIds};
+
+    #[test]
+    fn test_map_transaction() {
+        let block_file = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/algorand/block_46000000.json");
+        let ids_file = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/algorand/block_46000000_txids.json");
+        let block: BlockResponse = serde_json::from_reader(std::fs::File::open(block_file).unwrap()).unwrap();
+        let ids: BlockTransactionIds = serde_json::from_reader(std::fs::File::open(ids_file).unwrap()).unwrap();
+
+        let client = AlgorandClient::new(
+            reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build(),
+            "http://localhost".into(),
+        );
+        let transactions = ids
+            .block_txids
+            .into_iter()
+            .zip(block.block.txns)
+            .flat_map(|(hash, x)| client.map_transaction(hash, x.txn, 46000000, block.block.ts))
+            .collect::<Vec<_>>();
+
+        // opt-in and application call are skipped
+        assert_eq!(transactions.len(), 2);
+
+        let transfer = &transactions[0];
+        assert_eq!(transfer.hash, "4N4VEXZGGWLCNFOKLQDF3IM6OXZHS3HVGQ4YLHIAOBN2OMVJHBMA");
+        assert_eq!(transfer.asset_id, Chain::Algorand.as_asset_id());
+        assert_eq!(transfer.from, "X5QHSQYQNVOBUQP2JQHVXUSDDNWEQ67GUA673B6XEPQEWCFGN55U4PD25M");
+        assert_eq!(transfer.to, "Y76M3MSY6DKBRHBL7C3NNDXGS5IIMQVQVUAB6MP4XEMMGVF2QWNPL226CA");
+        assert_eq!(transfer.value, "5000000");
+        assert_eq!(transfer.fee, "1000");
+        assert_eq!(transfer.memo, Some("gem wallet".to_string()));
+        assert_eq!(transfer.created_at.timestamp(), 1737036000);
+
+        let token_transfer = &transactions[1];
+        assert_eq!(token_transfer.asset_id, AssetId::from_token(Chain::Algorand, "31566704"));
+        assert_eq!(token_transfer.from, "X5QHSQYQNVOBUQP2JQHVXUSDDNWEQ67GUA673B6XEPQEWCFGN55U4PD25M");
+        assert_eq!(token_transfer.to, "737777777777777777777777777777777777777777777777777UFEJ2CI");
+        assert_eq!(token_transfer.value, "2500000");
+    }
+}
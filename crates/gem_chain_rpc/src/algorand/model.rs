@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStatus {
+    #[serde(rename = "last-round")]
+    pub last_round: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockResponse {
+    pub block: Block,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Block {
+    pub ts: i64,
+    #[serde(default)]
+    pub txns: Vec<SignedTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockTransactionIds {
+    #[serde(rename = "blockTxids")]
+    pub block_txids: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedTransaction {
+    pub txn: Transaction,
+}
+
+// Block transactions use the short msgpack field names, zero values are omitted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    #[serde(rename = "type")]
+    pub transaction_type: String,
+    pub snd: String,
+    #[serde(default)]
+    pub fee: u64,
+    pub note: Option<String>,
+    // pay
+    pub rcv: Option<String>,
+    #[serde(default)]
+    pub amt: u64,
+    // axfer
+    pub xaid: Option<u64>,
+    pub arcv: Option<String>,
+    #[serde(default)]
+    pub aamt: u64,
+    pub asnd: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetResponse {
+    pub index: u64,
+    pub params: AssetParams,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetParams {
+    pub name: Option<String>,
+    #[serde(rename = "unit-name")]
+    pub unit_name: Option<String>,
+    pub decimals: i32,
+}
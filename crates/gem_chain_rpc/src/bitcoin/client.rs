@@ -1,11 +1,11 @@
 use std::error::Error;
 
-use crate::{ChainBlockProvider, ChainTokenDataProvider};
+use crate::{ChainAddressHistoryProvider, ChainBlockProvider, ChainTokenDataProvider};
 use async_trait::async_trait;
 use chrono::Utc;
 use primitives::{chain::Chain, transaction_utxo::TransactionInput, Asset, TransactionDirection, TransactionType};
 
-use super::model::{Block, Status, Transaction};
+use super::model::{AddressTransactions, Block, Status, Transaction};
 use reqwest_middleware::ClientWithMiddleware;
 
 pub struct BitcoinClient {
@@ -30,6 +30,11 @@ impl BitcoinClient {
         Ok(block)
     }
 
+    pub async fn get_address_transactions(&self, address: &str, limit: usize) -> Result<AddressTransactions, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/api/v2/address/{}?details=txs&pageSize={}", self.url, address, limit);
+        Ok(self.client.get(url).send().await?.json::<AddressTransactions>().await?)
+    }
+
     pub fn map_transaction(chain: Chain, transaction: &super::model::Transaction, _block_number: i64) -> Option<primitives::Transaction> {
         let inputs: Vec<TransactionInput> = transaction
             .vin
@@ -112,6 +117,19 @@ impl ChainBlockProvider for BitcoinClient {
     }
 }
 
+#[async_trait]
+impl ChainAddressHistoryProvider for BitcoinClient {
+    // Mempool transactions have no block yet and are left to the parser
+    async fn get_address_transactions(&self, address: String, limit: usize) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
+        let transactions = BitcoinClient::get_address_transactions(self, &address, limit).await?.transactions;
+        Ok(transactions
+            .into_iter()
+            .filter(|x| x.block_height > 0)
+            .flat_map(|x| BitcoinClient::map_transaction(self.chain, &x, x.block_height))
+            .collect())
+    }
+}
+
 #[async_trait]
 impl ChainTokenDataProvider for BitcoinClient {
     async fn get_token_data(&self, _chain: Chain, _token_id: String) -> Result<Asset, Box<dyn Error + Send + Sync>> {
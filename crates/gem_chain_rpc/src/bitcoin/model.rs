@@ -19,6 +19,14 @@ pub struct Block {
     pub txs: Vec<Transaction>,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressTransactions {
+    // missing when the address has no transactions
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
@@ -1,48 +1,67 @@
-use super::model::{Block, Transaction, TransactionReciept};
-use crate::ethereum::erc20;
-use crate::{ChainBlockProvider, ChainTokenDataProvider};
-use alloy_core::primitives::hex;
+use super::decoder::{self, LogNativeWrap, LogTransfer};
+use super::model::{Block, BlockHeader, Log, ParityTrace, Transaction, TransactionReciept, TransactionTrace};
+use super::trace::{self, EthereumTrace, InternalTransfer};
+use crate::ethereum::{erc1155, erc20, erc721};
+use crate::nft::{self, NFTMetadataClient, NFTScan, NFTToken};
+use crate::BlockHeader as ChainBlockHeader;
+use crate::{ChainAddressHistoryProvider, ChainBalanceProvider, ChainBlockProvider, ChainNFTScanProvider, ChainTokenDataProvider};
+use alloy_core::primitives::{hex, Address, U256};
 use alloy_core::sol_types::SolCall;
 use async_trait::async_trait;
 use chrono::Utc;
 use gem_evm::address::EthereumAddress;
 use hex::FromHex;
 use jsonrpsee::{
-    core::{client::ClientT, params::BatchRequestBuilder},
+    core::{client::ClientT, params::BatchRequestBuilder, ClientError},
     http_client::{HttpClient, HttpClientBuilder},
     rpc_params,
 };
 use num_bigint::BigUint;
-use num_traits::Num;
-use primitives::{chain::Chain, Asset, AssetId, TransactionState, TransactionSwapMetadata, TransactionType};
+use num_traits::Zero;
+use primitives::{
+    chain::Chain, Asset, AssetBalance, AssetId, BigIntHex, NFTCollectible, NFTCollection, NFTType, TransactionNFTTransferMetadata, TransactionState,
+    TransactionSwapMetadata, TransactionType,
+};
 use serde::de::DeserializeOwned;
 use serde_json::json;
-use std::error::Error;
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    str::FromStr,
+};
 
 const FUNCTION_ERC20_NAME: &str = "0x06fdde03";
 const FUNCTION_ERC20_SYMBOL: &str = "0x95d89b41";
 const FUNCTION_ERC20_DECIMALS: &str = "0x313ce567";
-const FUNCTION_ERC20_TRANSFER: &str = "0xa9059cbb";
-const FUNCTION_ERC20_APPROVE: &str = "0x095ea7b3";
-const FUNCTION_1INCH_SWAP: &str = "0x12aa3caf";
-const CONTRACT_1INCH: &str = "0x1111111254EEB25477B68fb85Ed929f73A960582";
-
-const TOPIC_DEPOSIT: &str = "0xe1fffcc4923d04b559f4d29a8bfc6cda04eb5b0d3c460751c2402c5c5cc9109c";
-//const TOPIC_TRANSFER: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+const RECEIPTS_BATCH_SIZE: usize = 100;
+// Blocks per eth_getLogs request, within the range limit of most providers
+const LOGS_BLOCK_RANGE: i64 = 10_000;
+// Pages of LOGS_BLOCK_RANGE blocks scanned for NFT transfers per call
+const NFT_SCAN_PAGES: i64 = 10;
+// Blocks scanned back from the latest block for token transfers of an address
+const ADDRESS_HISTORY_BLOCKS: i64 = 100_000;
 
 pub struct EthereumClient {
     chain: Chain,
     client: HttpClient,
+    trace: Option<EthereumTrace>,
+    metadata: NFTMetadataClient,
 }
 
 impl EthereumClient {
-    pub fn new(chain: Chain, url: String) -> Self {
+    pub fn new(chain: Chain, url: String, trace: Option<EthereumTrace>) -> Self {
         let client = HttpClientBuilder::default()
             .max_response_size(256 * 1024 * 1024) // 256MB
             .build(url)
             .unwrap();
+        let metadata = NFTMetadataClient::new(reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build());
 
-        Self { chain, client }
+        Self {
+            chain,
+            client,
+            trace,
+            metadata,
+        }
     }
 
     async fn eth_call<T: DeserializeOwned>(&self, contract: &str, data: &str) -> Result<T, Box<dyn Error + Send + Sync>> {
@@ -62,30 +81,39 @@ impl EthereumClient {
         Ok(res)
     }
 
-    async fn get_transaction_reciepts(&self, hashes: Vec<String>) -> Result<Vec<TransactionReciept>, Box<dyn Error + Send + Sync>> {
-        let hashes_chunks: Vec<Vec<String>> = hashes.chunks(10).map(|s| s.into()).collect();
-        let mut results: Vec<TransactionReciept> = Vec::new();
-        for hashes in hashes_chunks {
-            let mut batch = BatchRequestBuilder::default();
-            for hash in hashes.iter() {
-                batch.insert("eth_getTransactionReceipt", vec![json!(hash)])?;
-            }
+    async fn get_native_balance(&self, address: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let balance: BigIntHex = self.client.request("eth_getBalance", rpc_params![address, "latest"]).await?;
+        Ok(balance.value.to_string())
+    }
+
+    async fn get_token_balance(&self, token_id: &str, address: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let account = address.parse::<Address>()?;
+        let data = hex::encode_prefixed(erc20::balanceOfCall { account }.abi_encode());
+        let result: String = self.eth_call(token_id, &data).await?;
+        Ok(erc20::balanceOfCall::abi_decode_returns(&Vec::from_hex(result)?, true)?._0.to_string())
+    }
+
+    async fn get_block_reciepts(&self, block_number: i64, transactions: &[Transaction]) -> Result<Vec<TransactionReciept>, Box<dyn Error + Send + Sync>> {
+        let params = vec![json!(format!("0x{:x}", block_number))];
+        match self.client.request("eth_getBlockReceipts", params).await {
+            Ok(receipts) => Ok(receipts),
+            // nodes without eth_getBlockReceipts reply with an error object, fetch receipts by transaction hash instead
+            Err(ClientError::Call(_)) => self.get_transaction_reciepts(transactions).await,
+            Err(err) => Err(err.into()),
+        }
+    }
 
-            let receipts = self
-                .client
-                .batch_request::<TransactionReciept>(batch)
-                .await?
-                .iter()
-                .filter_map(|r| r.as_ref().ok())
-                .cloned()
-                .collect::<Vec<TransactionReciept>>();
-
-            if receipts.len() != hashes.len() {
-                return Err("Failed to get all transaction reciepts".into());
+    async fn get_transaction_reciepts(&self, transactions: &[Transaction]) -> Result<Vec<TransactionReciept>, Box<dyn Error + Send + Sync>> {
+        let mut receipts = Vec::with_capacity(transactions.len());
+        for chunk in transactions.chunks(RECEIPTS_BATCH_SIZE) {
+            let mut batch = BatchRequestBuilder::new();
+            for transaction in chunk {
+                batch.insert("eth_getTransactionReceipt", rpc_params![transaction.hash.clone()])?;
             }
-            results.extend(receipts);
+            let response = self.client.batch_request::<TransactionReciept>(batch).await?;
+            receipts.extend(response.into_ok().map_err(|_| "Failed to get transaction reciepts")?);
         }
-        Ok(results)
+        Ok(receipts)
     }
 
     async fn get_block(&self, block_number: i64) -> Result<Block, Box<dyn Error + Send + Sync>> {
@@ -93,139 +121,434 @@ impl EthereumClient {
         Ok(self.client.request("eth_getBlockByNumber", params).await?)
     }
 
-    fn map_transaction(&self, transaction: Transaction, receipt: &TransactionReciept) -> Option<primitives::Transaction> {
+    async fn get_block_header_by_number(&self, block_number: i64) -> Result<Option<BlockHeader>, Box<dyn Error + Send + Sync>> {
+        let params = vec![json!(format!("0x{:x}", block_number)), json!(false)];
+        Ok(self.client.request("eth_getBlockByNumber", params).await?)
+    }
+
+    // Internal native transfers by transaction hash, empty when tracing is not enabled for the chain
+    async fn get_internal_transfers(
+        &self,
+        block_number: i64,
+        transactions: &[Transaction],
+    ) -> Result<HashMap<String, Vec<InternalTransfer>>, Box<dyn Error + Send + Sync>> {
+        let block = json!(format!("0x{:x}", block_number));
+        match self.trace {
+            None => Ok(HashMap::new()),
+            Some(EthereumTrace::DebugTraceBlock) => {
+                let params = vec![block, json!({"tracer": "callTracer"})];
+                let traces: Vec<TransactionTrace> = self.client.request(EthereumTrace::DebugTraceBlock.method(), params).await?;
+                if traces.len() != transactions.len() {
+                    return Err("Failed to get all transaction traces".into());
+                }
+                // older clients omit txHash, traces are returned in block order
+                Ok(traces
+                    .into_iter()
+                    .zip(transactions)
+                    .map(|(x, transaction)| (x.tx_hash.unwrap_or(transaction.hash.clone()), trace::call_frame_transfers(&x.result)))
+                    .collect())
+            }
+            Some(EthereumTrace::TraceBlock) => {
+                let traces: Vec<ParityTrace> = self.client.request(EthereumTrace::TraceBlock.method(), vec![block]).await?;
+                Ok(trace::parity_trace_transfers(&traces))
+            }
+        }
+    }
+
+    async fn get_logs(&self, topics: serde_json::Value, from_block: i64, to_block: i64) -> Result<Vec<Log>, Box<dyn Error + Send + Sync>> {
+        let params = vec![json!({
+            "fromBlock": format!("0x{:x}", from_block),
+            "toBlock": format!("0x{:x}", to_block),
+            "topics": topics,
+        })];
+        Ok(self.client.request("eth_getLogs", params).await?)
+    }
+
+    // Blocks produced since the last call are scanned first so new tokens show up, then older blocks back to genesis.
+    // At most NFT_SCAN_PAGES pages are scanned per call, the caller continues the scan on the next call.
+    async fn scan_nft_transfers(&self, address: &EthereumAddress, scan: Option<NFTScan>) -> Result<NFTScan, Box<dyn Error + Send + Sync>> {
+        let latest_block = self.get_latest_block().await?;
+        let mut scan = scan.unwrap_or(NFTScan {
+            from_block: latest_block + 1,
+            to_block: latest_block,
+            tokens: vec![],
+        });
+
+        let mut pages = NFT_SCAN_PAGES;
+        while pages > 0 && scan.to_block < latest_block {
+            let from_block = scan.to_block + 1;
+            let to_block = (from_block + LOGS_BLOCK_RANGE - 1).min(latest_block);
+            for token in self.get_received_nfts(address, from_block, to_block).await? {
+                scan.add_token(token);
+            }
+            scan.to_block = to_block;
+            pages -= 1;
+        }
+        while pages > 0 && scan.from_block > 0 {
+            let to_block = scan.from_block - 1;
+            let from_block = (to_block - LOGS_BLOCK_RANGE + 1).max(0);
+            for token in self.get_received_nfts(address, from_block, to_block).await? {
+                scan.add_token(token);
+            }
+            scan.from_block = from_block;
+            pages -= 1;
+        }
+        Ok(scan)
+    }
+
+    // ERC721 and ERC1155 tokens transferred to the address
+    async fn get_received_nfts(&self, address: &EthereumAddress, from_block: i64, to_block: i64) -> Result<Vec<NFTToken>, Box<dyn Error + Send + Sync>> {
+        let topic = decoder::address_topic(address);
+        let erc1155_topics = json!([decoder::TOPIC_TRANSFER_SINGLE, decoder::TOPIC_TRANSFER_BATCH]);
+        let (erc721, erc1155) = futures::try_join!(
+            self.get_logs(json!([decoder::TOPIC_TRANSFER, null, topic]), from_block, to_block),
+            self.get_logs(json!([erc1155_topics, null, null, topic]), from_block, to_block),
+        )?;
+
+        let address = address.to_checksum();
+        let tokens = |transfers: Vec<LogTransfer>, nft_type: NFTType| {
+            transfers
+                .into_iter()
+                .filter(|x| x.to == address)
+                .filter_map(move |x| {
+                    Some(NFTToken {
+                        contract: x.contract,
+                        token_id: x.token_id?.to_string(),
+                        nft_type: nft_type.clone(),
+                    })
+                })
+                .collect::<Vec<_>>()
+        };
+        Ok([
+            tokens(decoder::decode_transfers(&erc721), NFTType::ERC721),
+            tokens(decoder::decode_transfers(&erc1155), NFTType::ERC1155),
+        ]
+        .concat())
+    }
+
+    // ownerOf for ERC721 and balanceOf for ERC1155
+    async fn is_nft_owner(&self, address: &Address, token: &NFTToken) -> Result<bool, Box<dyn Error + Send + Sync>> {
+        let id = U256::from_str(&token.token_id)?;
+        match token.nft_type {
+            NFTType::ERC1155 => {
+                let data = hex::encode_prefixed(erc1155::balanceOfCall { account: *address, id }.abi_encode());
+                let result: String = self.eth_call(&token.contract, &data).await?;
+                Ok(!erc1155::balanceOfCall::abi_decode_returns(&Vec::from_hex(result)?, true)?._0.is_zero())
+            }
+            _ => {
+                let data = hex::encode_prefixed(erc721::ownerOfCall { tokenId: id }.abi_encode());
+                let result: String = self.eth_call(&token.contract, &data).await?;
+                Ok(erc721::ownerOfCall::abi_decode_returns(&Vec::from_hex(result)?, true)?._0 == *address)
+            }
+        }
+    }
+
+    // Hashes of the latest transactions with ERC20 transfers in or out of the address, newest first.
+    // Native transfers emit no logs and are not found.
+    async fn get_token_transfer_hashes(&self, address: &EthereumAddress, limit: usize) -> Result<Vec<String>, Box<dyn Error + Send + Sync>> {
+        let latest_block = self.get_latest_block().await?;
+        let last_block = (latest_block - ADDRESS_HISTORY_BLOCKS).max(0);
+        let topic = decoder::address_topic(address);
+
+        let mut hashes: Vec<String> = vec![];
+        let mut to_block = latest_block;
+        while to_block >= last_block && hashes.len() < limit {
+            let from_block = (to_block - LOGS_BLOCK_RANGE + 1).max(last_block);
+            let (incoming, outgoing) = futures::try_join!(
+                self.get_logs(json!([decoder::TOPIC_TRANSFER, null, topic]), from_block, to_block),
+                self.get_logs(json!([decoder::TOPIC_TRANSFER, topic]), from_block, to_block),
+            )?;
+            let mut logs = [incoming, outgoing].concat();
+            logs.sort_by_key(|x| std::cmp::Reverse(x.block_number.clone().map(|x| x.value)));
+            for hash in logs.into_iter().filter_map(|x| x.transaction_hash) {
+                if hashes.len() < limit && !hashes.contains(&hash) {
+                    hashes.push(hash);
+                }
+            }
+            to_block = from_block - 1;
+        }
+        Ok(hashes)
+    }
+
+    // Transactions that fail to load are left out
+    async fn get_transactions_by_hashes(&self, hashes: &[String]) -> Result<Vec<(Transaction, TransactionReciept)>, Box<dyn Error + Send + Sync>> {
+        let mut result = vec![];
+        for chunk in hashes.chunks(RECEIPTS_BATCH_SIZE) {
+            let mut transactions_batch = BatchRequestBuilder::new();
+            let mut receipts_batch = BatchRequestBuilder::new();
+            for hash in chunk {
+                transactions_batch.insert("eth_getTransactionByHash", rpc_params![hash])?;
+                receipts_batch.insert("eth_getTransactionReceipt", rpc_params![hash])?;
+            }
+            let (transactions, receipts) = futures::try_join!(
+                self.client.batch_request::<Transaction>(transactions_batch),
+                self.client.batch_request::<TransactionReciept>(receipts_batch),
+            )?;
+            result.extend(
+                transactions
+                    .into_iter()
+                    .zip(receipts)
+                    .filter_map(|(transaction, receipt)| Some((transaction.ok()?, receipt.ok()?))),
+            );
+        }
+        Ok(result)
+    }
+
+    async fn get_token_uri(&self, contract: &str, token_id: &BigUint, nft_type: &NFTType) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let id = U256::from_be_slice(&token_id.to_bytes_be());
+        match nft_type {
+            NFTType::ERC1155 => {
+                let data = hex::encode_prefixed(erc1155::uriCall { id }.abi_encode());
+                let result: String = self.eth_call(contract, &data).await?;
+                let uri = erc1155::uriCall::abi_decode_returns(&Vec::from_hex(result)?, true)?._0;
+                // clients substitute {id} with the lowercase hex token id padded to 64 characters
+                Ok(uri.replace("{id}", &format!("{:0>64}", token_id.to_str_radix(16))))
+            }
+            _ => {
+                let data = hex::encode_prefixed(erc721::tokenURICall { tokenId: id }.abi_encode());
+                let result: String = self.eth_call(contract, &data).await?;
+                Ok(erc721::tokenURICall::abi_decode_returns(&Vec::from_hex(result)?, true)?._0)
+            }
+        }
+    }
+
+    async fn get_contract_name(&self, contract: &str) -> Result<String, Box<dyn Error + Send + Sync>> {
+        let result: String = self.eth_call(contract, FUNCTION_ERC20_NAME).await?;
+        Ok(erc721::nameCall::abi_decode_returns(&Vec::from_hex(result)?, true)?._0)
+    }
+
+    async fn get_nft(&self, contract: String, token_id: BigUint, nft_type: NFTType, collection_name: String) -> (NFTCollection, NFTCollectible) {
+        let uri = self.get_token_uri(&contract, &token_id, &nft_type).await.unwrap_or_default();
+        let metadata = self.metadata.get_metadata_or_default(&uri).await;
+        let explorer_url = nft::explorer_token_url(self.chain, &contract);
+        let image = metadata.image();
+
+        let collection = NFTCollection {
+            id: contract.clone(),
+            name: if collection_name.is_empty() { contract.clone() } else { collection_name },
+            description: String::new(),
+            chain: self.chain,
+            image: image.clone(),
+            explorer_url: explorer_url.clone(),
+            count: 1,
+        };
+        let collectible = NFTCollectible {
+            id: format!("{}_{}", contract, token_id),
+            collection_id: contract,
+            name: metadata.name.clone().unwrap_or(format!("#{}", token_id)),
+            description: metadata.description.clone().unwrap_or_default(),
+            chain: self.chain,
+            image,
+            collectible_type: nft_type,
+            attributes: metadata.attributes(),
+            explorer_url,
+        };
+        (collection, collectible)
+    }
+
+    // Tokens the address no longer holds, or whose ownership check fails, are left out
+    async fn get_owned_nfts(&self, address: &str, tokens: Vec<NFTToken>) -> Result<Vec<(NFTCollection, NFTCollectible)>, Box<dyn Error + Send + Sync>> {
+        let owner = address.parse::<Address>()?;
+        let owned = futures::future::join_all(tokens.iter().map(|x| self.is_nft_owner(&owner, x))).await;
+        let tokens = tokens
+            .into_iter()
+            .zip(owned)
+            .filter(|(_, owned)| matches!(owned, Ok(true)))
+            .filter_map(|(x, _)| Some((x.contract, x.token_id.parse::<BigUint>().ok()?, x.nft_type)))
+            .collect::<Vec<_>>();
+
+        let contracts = tokens.iter().map(|x| x.0.clone()).collect::<HashSet<_>>();
+        let names = futures::future::join_all(contracts.into_iter().map(|contract| async move {
+            let name = self.get_contract_name(&contract).await.unwrap_or_default();
+            (contract, name)
+        }))
+        .await
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+        Ok(futures::future::join_all(tokens.into_iter().map(|(contract, token_id, nft_type)| {
+            let name = names.get(&contract).cloned().unwrap_or_default();
+            self.get_nft(contract, token_id, nft_type, name)
+        }))
+        .await)
+    }
+
+    fn asset_id(&self, contract: &str) -> AssetId {
+        AssetId::from_token(self.chain, contract)
+    }
+
+    fn map_transaction(&self, transaction: Transaction, receipt: &TransactionReciept, internal_transfers: &[InternalTransfer]) -> Vec<primitives::Transaction> {
         let state = if receipt.status == "0x1" {
             TransactionState::Confirmed
         } else {
             TransactionState::Failed
         };
-        let value = transaction.value.value.to_string();
-        let nonce = transaction.nonce.as_i32();
-        let block = transaction.block_number.as_i32();
-        let fee = receipt.get_fee().to_string();
-        let from = EthereumAddress::parse(&transaction.from)?.to_checksum();
-        let to = EthereumAddress::parse(&transaction.to.unwrap_or_default())?.to_checksum();
+        let (Some(from), Some(to)) = (
+            EthereumAddress::parse(&transaction.from),
+            EthereumAddress::parse(&transaction.to.clone().unwrap_or_default()),
+        ) else {
+            return vec![];
+        };
+        let from = from.to_checksum();
+        let to = to.to_checksum();
+        let new_transaction =
+            |asset_id: AssetId, from: String, to: String, transaction_type: TransactionType, value: String, metadata: Option<serde_json::Value>| {
+                primitives::Transaction::new(
+                    transaction.hash.clone(),
+                    asset_id,
+                    from,
+                    to,
+                    None,
+                    transaction_type,
+                    state.clone(),
+                    transaction.block_number.as_i32().to_string(),
+                    transaction.nonce.as_i32().to_string(),
+                    receipt.get_fee().to_string(),
+                    self.chain.as_asset_id(),
+                    value,
+                    None,
+                    metadata,
+                    Utc::now(),
+                )
+            };
 
         // system transfer
         if transaction.input == "0x" {
-            let transaction = primitives::Transaction::new(
-                transaction.hash.clone(),
-                self.chain.as_asset_id(),
-                from,
-                to,
-                None,
-                TransactionType::Transfer,
-                state,
-                block.to_string(),
-                nonce.to_string(),
-                fee.to_string(),
-                self.chain.as_asset_id(),
-                value,
-                None,
-                None,
-                Utc::now(),
-            );
-            return Some(transaction);
+            let value = transaction.value.value.to_string();
+            return vec![new_transaction(self.chain.as_asset_id(), from, to, TransactionType::Transfer, value, None)];
         }
-        // ERC20 transfer. Only add confirmed
-        let input_prefix = transaction.input.chars().take(10).collect::<String>();
-        if (input_prefix.starts_with(FUNCTION_ERC20_TRANSFER) || input_prefix.starts_with(FUNCTION_ERC20_APPROVE)) && state == TransactionState::Confirmed {
-            let transaction_type = match input_prefix.as_str() {
-                FUNCTION_ERC20_TRANSFER => TransactionType::Transfer,
-                FUNCTION_ERC20_APPROVE => TransactionType::TokenApproval,
-                _ => TransactionType::Transfer,
-            };
-            let token_id = to.clone();
-            let asset_id = AssetId {
-                chain: self.chain,
-                token_id: Some(token_id),
-            };
-            let value: String = transaction.input.chars().skip(74).take(64).collect();
-            let to_address: String = transaction.input.chars().skip(34).take(40).collect::<String>();
-            let to_address = EthereumAddress::parse(&to_address)?.to_checksum();
-            let value = BigUint::from_str_radix(value.as_str(), 16).unwrap_or_default();
-
-            let transaction = primitives::Transaction::new(
-                transaction.hash.clone(),
-                asset_id,
-                from,
-                to_address.clone(),
-                None,
-                transaction_type,
-                state,
-                block.to_string(),
-                nonce.to_string(),
-                fee.to_string(),
+        let value = &transaction.value.value;
+        // native value sent along with calldata, like a WETH deposit or a bridge deposit
+        let native_transfer = (*value > BigUint::zero()).then(|| {
+            new_transaction(
                 self.chain.as_asset_id(),
+                from.clone(),
+                to.clone(),
+                TransactionType::Transfer,
                 value.to_string(),
                 None,
-                None,
-                Utc::now(),
-            );
-            return Some(transaction);
+            )
+        });
+        // Token transfers only exist in logs of confirmed transactions
+        if state != TransactionState::Confirmed {
+            return native_transfer.into_iter().collect();
         }
 
-        if input_prefix.starts_with(FUNCTION_1INCH_SWAP) && to == CONTRACT_1INCH && receipt.logs.len() <= 9 {
-            let first_log = receipt.logs.first()?;
-            let last_log = receipt.logs.last()?;
-            let first_log_value = BigUint::from_str_radix(&first_log.clone().data[2..], 16).ok()?.to_string();
-            let last_log_value = BigUint::from_str_radix(&last_log.clone().data[2..], 16).ok()?.to_string();
+        let transfers = decoder::decode_transfers(&receipt.logs);
+        let swap = if decoder::has_swap_event(&receipt.logs) {
+            let wraps = decoder::decode_native_wraps(&receipt.logs);
+            self.map_swap(&from, value, &transfers, &wraps)
+        } else {
+            None
+        };
 
-            let values: (String, String) = if first_log.topics[0] == TOPIC_DEPOSIT {
-                (value, last_log_value.clone())
-            } else {
-                (first_log_value.clone(), last_log_value.clone())
-            };
-            let from_value = values.0.clone();
-            let to_value = values.1.clone();
+        let mut transactions = vec![];
+        match &swap {
+            Some(swap) => {
+                let mut swap_transaction = new_transaction(
+                    swap.from_asset.clone(),
+                    from.clone(),
+                    from.clone(),
+                    TransactionType::Swap,
+                    swap.from_value.clone(),
+                    serde_json::to_value(swap).ok(),
+                );
+                swap_transaction.contract = Some(to.clone());
+                transactions.push(swap_transaction);
+                // value that is not the input of the swap is still recorded
+                if swap.from_asset != self.chain.as_asset_id() {
+                    transactions.extend(native_transfer);
+                }
+            }
+            None => transactions.extend(native_transfer),
+        }
+        // the swap record covers the fungible token and native flows of the sender, transfers between other addresses are kept
+        let is_swap_flow = |transfer_from: &str, transfer_to: &str| swap.is_some() && (transfer_from == from || transfer_to == from);
 
-            let assets = if first_log.topics[0] == TOPIC_DEPOSIT {
-                (
-                    self.chain.as_asset_id(),
-                    AssetId {
-                        chain: self.chain,
-                        token_id: Some(EthereumAddress::parse(&last_log.address)?.to_checksum()),
-                    },
-                )
-            } else {
-                (
-                    AssetId {
-                        chain: self.chain,
-                        token_id: Some(EthereumAddress::parse(&first_log.address)?.to_checksum()),
-                    },
-                    self.chain.as_asset_id(),
-                )
-            };
+        let transfers = transfers
+            .into_iter()
+            .filter(|x| x.value > BigUint::zero())
+            .filter(|x| x.token_id.is_some() || !is_swap_flow(&x.from, &x.to))
+            .map(|transfer| match transfer.token_id {
+                Some(token_id) => {
+                    let metadata = TransactionNFTTransferMetadata {
+                        contract: transfer.contract,
+                        token_id: token_id.to_string(),
+                        value: transfer.value.to_string(),
+                    };
+                    new_transaction(
+                        self.chain.as_asset_id(),
+                        transfer.from,
+                        transfer.to,
+                        TransactionType::TransferNFT,
+                        "0".to_string(),
+                        serde_json::to_value(metadata).ok(),
+                    )
+                }
+                None => new_transaction(
+                    self.asset_id(&transfer.contract),
+                    transfer.from,
+                    transfer.to,
+                    TransactionType::Transfer,
+                    transfer.value.to_string(),
+                    None,
+                ),
+            })
+            .collect::<Vec<_>>();
+        transactions.extend(transfers);
 
-            let swap = TransactionSwapMetadata {
-                from_asset: assets.0.clone(),
-                from_value: from_value.clone(),
-                to_asset: assets.1.clone(),
-                to_value: to_value.clone(),
-            };
-            let asset_id = assets.clone().0;
+        let approvals = decoder::decode_approvals(&receipt.logs).into_iter().filter(|x| x.owner == from).map(|x| {
+            new_transaction(
+                self.asset_id(&x.contract),
+                x.owner,
+                x.spender,
+                TransactionType::TokenApproval,
+                x.value.to_string(),
+                None,
+            )
+        });
+        transactions.extend(approvals);
 
-            let transaction = primitives::Transaction::new(
-                transaction.hash.clone(),
-                asset_id,
-                from.clone(),
-                from.clone(),
-                to.to_string().into(),
-                TransactionType::Swap,
-                state,
-                block.to_string(),
-                nonce.to_string(),
-                fee.to_string(),
+        let internal_transfers = internal_transfers.iter().filter(|x| !is_swap_flow(&x.from, &x.to)).map(|x| {
+            new_transaction(
                 self.chain.as_asset_id(),
-                from_value.clone().to_string(),
+                x.from.clone(),
+                x.to.clone(),
+                TransactionType::Transfer,
+                x.value.to_string(),
                 None,
-                serde_json::to_value(swap).ok(),
-                Utc::now(),
-            );
-            return Some(transaction);
+            )
+        });
+        transactions.extend(internal_transfers);
+        transactions.into_iter().enumerate().map(|(index, x)| x.with_index(index)).collect()
+    }
+
+    // Net token flows of the sender, native value is taken from transaction value or unwrapped by the router
+    fn map_swap(&self, sender: &str, value: &BigUint, transfers: &[LogTransfer], wraps: &[LogNativeWrap]) -> Option<TransactionSwapMetadata> {
+        let fungible = transfers.iter().filter(|x| x.token_id.is_none()).collect::<Vec<_>>();
+
+        let (from_asset, from_value) = match fungible.iter().find(|x| x.from == sender) {
+            Some(sent) => (self.asset_id(&sent.contract), sent.value.clone()),
+            None if *value > BigUint::zero() => (self.chain.as_asset_id(), value.clone()),
+            None => return None,
+        };
+        let (to_asset, to_value) = match fungible.iter().rev().find(|x| x.to == sender) {
+            Some(received) => (self.asset_id(&received.contract), received.value.clone()),
+            None => {
+                let withdrawal = wraps.iter().rev().find(|x| !x.is_deposit)?;
+                (self.chain.as_asset_id(), withdrawal.value.clone())
+            }
+        };
+        if from_asset == to_asset {
+            return None;
         }
 
-        None
+        Some(TransactionSwapMetadata {
+            from_asset,
+            from_value: from_value.to_string(),
+            to_asset,
+            to_value: to_value.to_string(),
+        })
     }
 }
 
@@ -243,31 +566,44 @@ impl ChainBlockProvider for EthereumClient {
 
     async fn get_transactions(&self, block_number: i64) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
         let block = self.get_block(block_number).await?;
-        let transactions = block
-            .transactions
-            .into_iter()
-            .filter(|x| {
-                x.input == "0x"
-                    || x.input.starts_with(FUNCTION_ERC20_TRANSFER)
-                    || x.input.starts_with(FUNCTION_ERC20_APPROVE)
-                    || x.input.starts_with(FUNCTION_1INCH_SWAP)
-            })
-            .collect::<Vec<Transaction>>();
-
-        if transactions.is_empty() {
+        if block.transactions.is_empty() {
             return Ok(vec![]);
         }
+        let receipts = self
+            .get_block_reciepts(block_number, &block.transactions)
+            .await?
+            .into_iter()
+            .map(|x| (x.transaction_hash.clone(), x))
+            .collect::<HashMap<_, _>>();
 
-        let hashes = transactions.clone().into_iter().map(|x| x.hash).collect();
-        let receipts = self.get_transaction_reciepts(hashes).await?;
+        if receipts.len() != block.transactions.len() {
+            return Err("Failed to get all transaction reciepts".into());
+        }
+        let internal_transfers = self.get_internal_transfers(block_number, &block.transactions).await?;
 
-        let transactions = transactions
+        let transactions = block
+            .transactions
             .into_iter()
-            .zip(receipts.iter())
-            .filter_map(|(transaction, receipt)| self.map_transaction(transaction, receipt))
+            .flat_map(|transaction| match receipts.get(&transaction.hash) {
+                Some(receipt) => {
+                    let internal_transfers = internal_transfers.get(&transaction.hash).cloned().unwrap_or_default();
+                    self.map_transaction(transaction, receipt, &internal_transfers)
+                }
+                None => vec![],
+            })
             .collect::<Vec<primitives::Transaction>>();
 
-        return Ok(transactions);
+        Ok(transactions)
+    }
+
+    async fn get_block_header(&self, block_number: i64) -> Result<Option<ChainBlockHeader>, Box<dyn Error + Send + Sync>> {
+        let header = self.get_block_header_by_number(block_number).await?;
+        Ok(header.map(|header| ChainBlockHeader {
+            number: header.number.as_i64(),
+            hash: header.hash,
+            parent_number: header.number.as_i64() - 1,
+            parent_hash: header.parent_hash,
+        }))
     }
 }
 
@@ -294,3 +630,259 @@ impl ChainTokenDataProvider for EthereumClient {
         })
     }
 }
+
+#[async_trait]
+impl ChainBalanceProvider for EthereumClient {
+    // A token whose balanceOf call fails (not a contract, reverts) is left out instead of failing all balances
+    async fn get_balances(&self, address: String, token_ids: Vec<String>) -> Result<Vec<AssetBalance>, Box<dyn Error + Send + Sync>> {
+        let native = self.get_native_balance(&address).await?;
+        let tokens = futures::future::join_all(token_ids.iter().map(|x| self.get_token_balance(x, &address))).await;
+
+        Ok(std::iter::once(AssetBalance::new(self.chain.as_asset_id(), native))
+            .chain(
+                token_ids
+                    .into_iter()
+                    .zip(tokens)
+                    .filter_map(|(token_id, balance)| Some(AssetBalance::new(self.asset_id(&token_id), balance.ok()?))),
+            )
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ChainAddressHistoryProvider for EthereumClient {
+    async fn get_address_transactions(&self, address: String, limit: usize) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
+        let address = EthereumAddress::parse(&address).ok_or("invalid address")?;
+        let hashes = self.get_token_transfer_hashes(&address, limit).await?;
+        let address = address.to_checksum();
+        Ok(self
+            .get_transactions_by_hashes(&hashes)
+            .await?
+            .into_iter()
+            .flat_map(|(transaction, receipt)| self.map_transaction(transaction, &receipt, &[]))
+            .filter(|x| x.addresses().contains(&address))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ChainNFTScanProvider for EthereumClient {
+    async fn scan_nfts(&self, address: String, scan: Option<NFTScan>) -> Result<NFTScan, Box<dyn Error + Send + Sync>> {
+        let address = EthereumAddress::parse(&address).ok_or("invalid address")?;
+        self.scan_nft_transfers(&address, scan).await
+    }
+
+    async fn get_nfts(&self, address: String, tokens: Vec<NFTToken>) -> Result<Vec<(NFTCollection, NFTCollectible)>, Box<dyn Error + Send + Sync>> {
+        self.get_owned_nfts(&address, tokens).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+    const BOB: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+    const USDT: &str = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+    const WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+
+    fn client() -> EthereumClient {
+        EthereumClient::new(Chain::Ethereum, "http://localhost".to_string(), None)
+    }
+
+    fn transfer(contract: &str, from: &str, to: &str, value: u64) -> LogTransfer {
+        LogTransfer {
+            contract: contract.to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            value: BigUint::from(value),
+            token_id: None,
+        }
+    }
+
+    fn transfer_log(contract: &str, from: &str, to: &str, value: u64) -> serde_json::Value {
+        let topic = |address: &str| format!("0x{:0>64}", address.trim_start_matches("0x").to_lowercase());
+        json!({
+            "address": contract,
+            "topics": [decoder::TOPIC_TRANSFER, topic(from), topic(to)],
+            "data": format!("0x{:064x}", value),
+        })
+    }
+
+    #[test]
+    fn test_map_transaction_multiple_transfers() {
+        let hash = "0x5c504ed432cb51138bcf09aa5e8a410dd4a1e204ef84bfed1be16dfba1b22060";
+        let transaction: Transaction = serde_json::from_value(json!({
+            "blockNumber": "0x14a3c26",
+            "from": ALICE,
+            "hash": hash,
+            "input": "0x6a761202",
+            "nonce": "0x7",
+            "to": BOB,
+            "value": "0x0",
+        }))
+        .unwrap();
+        let receipt: TransactionReciept = serde_json::from_value(json!({
+            "transactionHash": hash,
+            "gasUsed": "0x1d4c0",
+            "effectiveGasPrice": "0x3b9aca00",
+            "status": "0x1",
+            "logs": [transfer_log(USDT, ALICE, BOB, 1_000_000), transfer_log(USDC, ALICE, BOB, 2_000_000)],
+        }))
+        .unwrap();
+
+        let transactions = client().map_transaction(transaction, &receipt, &[]);
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].id, format!("ethereum_{}", hash));
+        assert_eq!(transactions[1].id, format!("ethereum_{}_1", hash));
+        assert!(transactions.iter().all(|x| x.hash == hash));
+        assert_eq!(transactions[0].asset_id, AssetId::from_token(Chain::Ethereum, USDT));
+        assert_eq!(transactions[1].asset_id, AssetId::from_token(Chain::Ethereum, USDC));
+        assert_eq!(transactions[1].value, "2000000");
+    }
+
+    #[test]
+    fn test_map_transaction_internal_transfers() {
+        let hash = "0x9e1d4f3d2c0b3a7e6f5d4c3b2a19087f6e5d4c3b2a19087f6e5d4c3b2a190870";
+        let transaction: Transaction = serde_json::from_value(json!({
+            "blockNumber": "0x14a3c26",
+            "from": ALICE,
+            "hash": hash,
+            "input": "0x3593564c",
+            "nonce": "0x8",
+            "to": BOB,
+            "value": "0x0",
+        }))
+        .unwrap();
+        let receipt: TransactionReciept = serde_json::from_value(json!({
+            "transactionHash": hash,
+            "gasUsed": "0x1d4c0",
+            "effectiveGasPrice": "0x3b9aca00",
+            "status": "0x1",
+            "logs": [transfer_log(USDT, ALICE, BOB, 1_000_000)],
+        }))
+        .unwrap();
+        let internal_transfers = vec![InternalTransfer {
+            from: BOB.to_string(),
+            to: ALICE.to_string(),
+            value: BigUint::from(1_000u32),
+        }];
+
+        let transactions = client().map_transaction(transaction, &receipt, &internal_transfers);
+
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].id, format!("ethereum_{}", hash));
+        assert_eq!(transactions[1].id, format!("ethereum_{}_1", hash));
+        assert_eq!(transactions[1].asset_id, Chain::Ethereum.as_asset_id());
+        assert_eq!(transactions[1].value, "1000");
+    }
+
+    #[test]
+    fn test_map_transaction_swap_keeps_other_transfers() {
+        let hash = "0x1f2e3d4c5b6a79880716253443526170899a8b7c6d5e4f30211203f4e5d6c7b8";
+        let transaction: Transaction = serde_json::from_value(json!({
+            "blockNumber": "0x14a3c26",
+            "from": ALICE,
+            "hash": hash,
+            "input": "0x3593564c",
+            "nonce": "0x9",
+            "to": BOB,
+            "value": "0x0",
+        }))
+        .unwrap();
+        let receipt: TransactionReciept = serde_json::from_value(json!({
+            "transactionHash": hash,
+            "gasUsed": "0x1d4c0",
+            "effectiveGasPrice": "0x3b9aca00",
+            "status": "0x1",
+            "logs": [
+                transfer_log(USDC, ALICE, BOB, 1_000_000),
+                transfer_log(USDT, BOB, ALICE, 999_000),
+                // fee taken by the router for a third address
+                transfer_log(USDT, BOB, WETH, 1_000),
+                {"address": BOB, "topics": [decoder::SWAP_TOPICS[0]], "data": "0x"},
+            ],
+        }))
+        .unwrap();
+
+        let transactions = client().map_transaction(transaction, &receipt, &[]);
+
+        assert_eq!(transactions.len(), 2);
+        assert!(matches!(transactions[0].transaction_type, TransactionType::Swap));
+        assert!(matches!(transactions[1].transaction_type, TransactionType::Transfer));
+        assert_eq!(transactions[1].id, format!("ethereum_{}_1", hash));
+        assert_eq!(transactions[1].to, WETH);
+        assert_eq!(transactions[1].value, "1000");
+    }
+
+    #[test]
+    fn test_map_transaction_native_value_with_calldata() {
+        let hash = "0x7a6b5c4d3e2f10011223344556677889900aabbccddeeff0011223344556677";
+        let transaction: Transaction = serde_json::from_value(json!({
+            "blockNumber": "0x14a3c26",
+            "from": ALICE,
+            "hash": hash,
+            "input": "0xd0e30db0",
+            "nonce": "0xa",
+            "to": WETH,
+            "value": "0xde0b6b3a7640000",
+        }))
+        .unwrap();
+        let receipt: TransactionReciept = serde_json::from_value(json!({
+            "transactionHash": hash,
+            "gasUsed": "0xb41c",
+            "effectiveGasPrice": "0x3b9aca00",
+            "status": "0x1",
+            "logs": [],
+        }))
+        .unwrap();
+
+        let transactions = client().map_transaction(transaction, &receipt, &[]);
+
+        assert_eq!(transactions.len(), 1);
+        assert_eq!(transactions[0].asset_id, Chain::Ethereum.as_asset_id());
+        assert_eq!(transactions[0].to, WETH);
+        assert_eq!(transactions[0].value, "1000000000000000000");
+    }
+
+    #[test]
+    fn test_map_swap() {
+        let client = client();
+
+        // token to token, the last transfer back to the sender is the output
+        let transfers = vec![
+            transfer(USDC, ALICE, BOB, 1_000_000),
+            transfer(WETH, BOB, BOB, 5),
+            transfer(USDT, BOB, ALICE, 999_000),
+        ];
+        let swap = client.map_swap(ALICE, &BigUint::zero(), &transfers, &[]).unwrap();
+        assert_eq!(swap.from_asset, AssetId::from_token(Chain::Ethereum, USDC));
+        assert_eq!(swap.from_value, "1000000");
+        assert_eq!(swap.to_asset, AssetId::from_token(Chain::Ethereum, USDT));
+        assert_eq!(swap.to_value, "999000");
+
+        // native in through transaction value
+        let transfers = vec![transfer(USDC, BOB, ALICE, 3_000_000)];
+        let swap = client.map_swap(ALICE, &BigUint::from(1_000u32), &transfers, &[]).unwrap();
+        assert_eq!(swap.from_asset, Chain::Ethereum.as_asset_id());
+        assert_eq!(swap.from_value, "1000");
+        assert_eq!(swap.to_asset, AssetId::from_token(Chain::Ethereum, USDC));
+
+        // native out through an unwrap by the router
+        let transfers = vec![transfer(USDC, ALICE, BOB, 3_000_000)];
+        let wraps = vec![LogNativeWrap {
+            contract: WETH.to_string(),
+            account: BOB.to_string(),
+            value: BigUint::from(900u32),
+            is_deposit: false,
+        }];
+        let swap = client.map_swap(ALICE, &BigUint::zero(), &transfers, &wraps).unwrap();
+        assert_eq!(swap.to_asset, Chain::Ethereum.as_asset_id());
+        assert_eq!(swap.to_value, "900");
+
+        // nothing received
+        assert!(client.map_swap(ALICE, &BigUint::zero(), &transfers, &[]).is_none());
+    }
+}
@@ -0,0 +1,259 @@
+use gem_evm::address::EthereumAddress;
+use num_bigint::BigUint;
+use num_traits::{Num, One, ToPrimitive};
+
+use super::model::Log;
+
+pub const TOPIC_TRANSFER: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+pub const TOPIC_APPROVAL: &str = "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925";
+pub const TOPIC_TRANSFER_SINGLE: &str = "0xc3d58168c5ae7397731d063d5bbf3d657854427343f4c083240f7aacaa2d0f62";
+pub const TOPIC_TRANSFER_BATCH: &str = "0x4a39dc06d4c0dbc64b70af90fd698a233a518aa5d07e595d983b8c0526c8f7fb";
+pub const TOPIC_DEPOSIT: &str = "0xe1fffcc4923d04b559f4d29a8bfc6cda04eb5b0d3c460751c2402c5c5cc9109c";
+pub const TOPIC_WITHDRAWAL: &str = "0x7fcf532c15f0a6db0bd6d0e038bea71d30d808c7d98cb3bf7268a95bf5081b65";
+
+// Swap events emitted by pools, presence of any of them marks a transaction as a swap
+pub const SWAP_TOPICS: [&str; 5] = [
+    "0xd78ad95fa46c994b6551d0da85fc275fe613ce37657fb8d5e3d130840159d822", // Uniswap V2
+    "0xc42079f94a6350d7e6235f29174924f928cc2ac818eb64fed8004e115fbcca67", // Uniswap V3
+    "0x19b47279256b2a23a1665c810c8d55a1758940ee09377d4f8d26497a3577dc83", // PancakeSwap V3
+    "0x8b3e96f2b889fa771c53c981b40daf005f63f637f1869f707052d15a3dd97140", // Curve
+    "0x2170c741c41531aec20e7c107c24eecfdd15e69c9bb0a8dd37b1840b9e0b207b", // Balancer V2
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogTransfer {
+    pub contract: String,
+    pub from: String,
+    pub to: String,
+    pub value: BigUint,
+    // set for ERC721 and ERC1155 transfers
+    pub token_id: Option<BigUint>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogApproval {
+    pub contract: String,
+    pub owner: String,
+    pub spender: String,
+    pub value: BigUint,
+}
+
+// Native value wrapped (deposit) or unwrapped (withdrawal) by WETH like contracts
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogNativeWrap {
+    pub contract: String,
+    pub account: String,
+    pub value: BigUint,
+    pub is_deposit: bool,
+}
+
+fn topic_address(topic: &str) -> Option<String> {
+    let topic = topic.strip_prefix("0x")?;
+    if topic.len() != 64 {
+        return None;
+    }
+    Some(EthereumAddress::parse(&topic[24..])?.to_checksum())
+}
+
+fn topic_value(topic: &str) -> Option<BigUint> {
+    BigUint::from_str_radix(topic.strip_prefix("0x")?, 16).ok()
+}
+
+fn data_words(data: &str) -> Vec<BigUint> {
+    let data = data.strip_prefix("0x").unwrap_or(data);
+    data.as_bytes()
+        .chunks(64)
+        .filter(|x| x.len() == 64)
+        .flat_map(|x| BigUint::from_str_radix(std::str::from_utf8(x).ok()?, 16).ok())
+        .collect()
+}
+
+// Reads dynamic uint256[] located at byte offset inside abi encoded data
+fn data_array(words: &[BigUint], offset: &BigUint) -> Option<Vec<BigUint>> {
+    let index = offset.to_usize()? / 32;
+    let length = words.get(index)?.to_usize()?;
+    words.get(index + 1..index + 1 + length).map(|x| x.to_vec())
+}
+
+fn decode_transfer(log: &Log) -> Option<Vec<LogTransfer>> {
+    let contract = EthereumAddress::parse(&log.address)?.to_checksum();
+    let topics = &log.topics;
+    match (topics.first()?.as_str(), topics.len()) {
+        // ERC20
+        (TOPIC_TRANSFER, 3) => Some(vec![LogTransfer {
+            contract,
+            from: topic_address(&topics[1])?,
+            to: topic_address(&topics[2])?,
+            value: data_words(&log.data).first()?.clone(),
+            token_id: None,
+        }]),
+        // ERC721
+        (TOPIC_TRANSFER, 4) => Some(vec![LogTransfer {
+            contract,
+            from: topic_address(&topics[1])?,
+            to: topic_address(&topics[2])?,
+            value: BigUint::one(),
+            token_id: Some(topic_value(&topics[3])?),
+        }]),
+        // ERC1155
+        (TOPIC_TRANSFER_SINGLE, 4) => {
+            let words = data_words(&log.data);
+            Some(vec![LogTransfer {
+                contract,
+                from: topic_address(&topics[2])?,
+                to: topic_address(&topics[3])?,
+                value: words.get(1)?.clone(),
+                token_id: Some(words.first()?.clone()),
+            }])
+        }
+        (TOPIC_TRANSFER_BATCH, 4) => {
+            let words = data_words(&log.data);
+            let from = topic_address(&topics[2])?;
+            let to = topic_address(&topics[3])?;
+            let ids = data_array(&words, words.first()?)?;
+            let values = data_array(&words, words.get(1)?)?;
+            if ids.len() != values.len() {
+                return None;
+            }
+            Some(
+                ids.into_iter()
+                    .zip(values)
+                    .map(|(id, value)| LogTransfer {
+                        contract: contract.clone(),
+                        from: from.clone(),
+                        to: to.clone(),
+                        value,
+                        token_id: Some(id),
+                    })
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+pub fn decode_transfers(logs: &[Log]) -> Vec<LogTransfer> {
+    logs.iter().flat_map(decode_transfer).flatten().collect()
+}
+
+pub fn decode_approvals(logs: &[Log]) -> Vec<LogApproval> {
+    logs.iter()
+        .filter(|x| x.topics.len() == 3 && x.topics[0] == TOPIC_APPROVAL)
+        .flat_map(|log| {
+            Some(LogApproval {
+                contract: EthereumAddress::parse(&log.address)?.to_checksum(),
+                owner: topic_address(&log.topics[1])?,
+                spender: topic_address(&log.topics[2])?,
+                value: data_words(&log.data).first()?.clone(),
+            })
+        })
+        .collect()
+}
+
+pub fn decode_native_wraps(logs: &[Log]) -> Vec<LogNativeWrap> {
+    logs.iter()
+        .filter(|x| x.topics.len() == 2 && (x.topics[0] == TOPIC_DEPOSIT || x.topics[0] == TOPIC_WITHDRAWAL))
+        .flat_map(|log| {
+            Some(LogNativeWrap {
+                contract: EthereumAddress::parse(&log.address)?.to_checksum(),
+                account: topic_address(&log.topics[1])?,
+                value: data_words(&log.data).first()?.clone(),
+                is_deposit: log.topics[0] == TOPIC_DEPOSIT,
+            })
+        })
+        .collect()
+}
+
+pub fn has_swap_event(logs: &[Log]) -> bool {
+    logs.iter().any(|x| x.topics.first().is_some_and(|topic| SWAP_TOPICS.contains(&topic.as_str())))
+}
+
+// Address left padded to 32 bytes, used to filter logs by an indexed address
+pub fn address_topic(address: &EthereumAddress) -> String {
+    format!("0x{:0>64}", hex::encode(&address.bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALICE: &str = "0x000000000000000000000000f39fd6e51aad88f6f4ce6ab8827279cfffb92266";
+    const BOB: &str = "0x00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8";
+    const TOKEN: &str = "0xdac17f958d2ee523a2206206994597c13d831ec7";
+
+    fn log(topics: Vec<&str>, data: &str) -> Log {
+        Log {
+            address: TOKEN.to_string(),
+            topics: topics.into_iter().map(|x| x.to_string()).collect(),
+            data: data.to_string(),
+            transaction_hash: None,
+            block_number: None,
+        }
+    }
+
+    #[test]
+    fn test_decode_erc20_transfer() {
+        let logs = vec![log(
+            vec![TOPIC_TRANSFER, ALICE, BOB],
+            "0x00000000000000000000000000000000000000000000000000000000000f4240",
+        )];
+        let transfers = decode_transfers(&logs);
+
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].contract, "0xdAC17F958D2ee523a2206206994597C13D831ec7");
+        assert_eq!(transfers[0].from, "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266");
+        assert_eq!(transfers[0].to, "0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+        assert_eq!(transfers[0].value, BigUint::from(1_000_000u32));
+        assert_eq!(transfers[0].token_id, None);
+    }
+
+    #[test]
+    fn test_decode_erc721_transfer() {
+        let token_id = "0x0000000000000000000000000000000000000000000000000000000000000539";
+        let logs = vec![log(vec![TOPIC_TRANSFER, ALICE, BOB, token_id], "0x")];
+        let transfers = decode_transfers(&logs);
+
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].value, BigUint::one());
+        assert_eq!(transfers[0].token_id, Some(BigUint::from(1337u32)));
+    }
+
+    #[test]
+    fn test_decode_erc1155_transfer_batch() {
+        let operator = ALICE;
+        let data = [
+            "0000000000000000000000000000000000000000000000000000000000000040",
+            "00000000000000000000000000000000000000000000000000000000000000a0",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000001",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "0000000000000000000000000000000000000000000000000000000000000002",
+            "000000000000000000000000000000000000000000000000000000000000000a",
+            "0000000000000000000000000000000000000000000000000000000000000014",
+        ]
+        .concat();
+        let logs = vec![log(vec![TOPIC_TRANSFER_BATCH, operator, ALICE, BOB], &format!("0x{}", data))];
+        let transfers = decode_transfers(&logs);
+
+        assert_eq!(transfers.len(), 2);
+        assert_eq!(transfers[0].token_id, Some(BigUint::from(1u32)));
+        assert_eq!(transfers[0].value, BigUint::from(10u32));
+        assert_eq!(transfers[1].token_id, Some(BigUint::from(2u32)));
+        assert_eq!(transfers[1].value, BigUint::from(20u32));
+    }
+
+    #[test]
+    fn test_address_topic() {
+        let alice = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+        assert_eq!(
+            address_topic(&EthereumAddress::parse(alice).unwrap()),
+            "0x000000000000000000000000f39fd6e51aad88f6f4ce6ab8827279cfffb92266"
+        );
+    }
+
+    #[test]
+    fn test_has_swap_event() {
+        assert!(!has_swap_event(&[log(vec![TOPIC_TRANSFER, ALICE, BOB], "0x")]));
+        assert!(has_swap_event(&[log(vec![SWAP_TOPICS[1], ALICE, BOB], "0x")]));
+    }
+}
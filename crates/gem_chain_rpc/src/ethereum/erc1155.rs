@@ -0,0 +1,6 @@
+use alloy_core::sol;
+
+sol!(
+    function uri(uint256 id) public view virtual returns (string memory);
+    function balanceOf(address account, uint256 id) public view virtual returns (uint256);
+);
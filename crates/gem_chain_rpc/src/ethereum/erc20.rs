@@ -4,4 +4,5 @@ sol!(
     function name() public view virtual returns (string memory);
     function symbol() public view virtual returns (string memory);
     function decimals() public view virtual returns (uint8);
+    function balanceOf(address account) public view virtual returns (uint256);
 );
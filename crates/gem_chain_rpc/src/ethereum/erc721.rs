@@ -0,0 +1,7 @@
+use alloy_core::sol;
+
+sol!(
+    function name() public view virtual returns (string memory);
+    function tokenURI(uint256 tokenId) public view virtual returns (string memory);
+    function ownerOf(uint256 tokenId) public view virtual returns (address);
+);
@@ -1,3 +1,7 @@
 pub mod client;
+pub mod decoder;
+pub mod erc1155;
 pub mod erc20;
+pub mod erc721;
 pub mod model;
+pub mod trace;
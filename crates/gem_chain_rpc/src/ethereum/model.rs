@@ -8,6 +8,14 @@ pub struct Block {
     pub transactions: Vec<Transaction>,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHeader {
+    pub number: BigIntHex,
+    pub hash: String,
+    pub parent_hash: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
@@ -29,6 +37,7 @@ pub struct Transaction {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionReciept {
+    pub transaction_hash: String,
     pub gas_used: BigIntHex,
     pub effective_gas_price: BigIntHex,
     pub l1_fee: Option<BigIntHex>,
@@ -51,4 +60,50 @@ pub struct Log {
     pub address: String,
     pub topics: Vec<String>,
     pub data: String,
+    #[serde(default, rename = "transactionHash")]
+    pub transaction_hash: Option<String>,
+    #[serde(default, rename = "blockNumber")]
+    pub block_number: Option<BigIntHex>,
+}
+
+// debug_traceBlockByNumber with callTracer
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionTrace {
+    pub tx_hash: Option<String>,
+    pub result: CallFrame,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    #[serde(rename = "type")]
+    pub call_type: String,
+    pub from: String,
+    pub to: Option<String>,
+    pub value: Option<BigIntHex>,
+    pub error: Option<String>,
+    #[serde(default)]
+    pub calls: Vec<CallFrame>,
+}
+
+// trace_block
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParityTrace {
+    pub action: ParityTraceAction,
+    pub error: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub trace_address: Vec<u64>,
+    #[serde(rename = "type")]
+    pub trace_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ParityTraceAction {
+    pub call_type: Option<String>,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub value: Option<BigIntHex>,
 }
@@ -0,0 +1,177 @@
+use std::collections::HashMap;
+
+use gem_evm::address::EthereumAddress;
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+use super::model::{CallFrame, ParityTrace};
+
+const CALL_TYPE_CALL: &str = "call";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EthereumTrace {
+    // debug_traceBlockByNumber with callTracer (geth, erigon, reth)
+    DebugTraceBlock,
+    // trace_block (erigon, nethermind, reth)
+    TraceBlock,
+}
+
+impl EthereumTrace {
+    pub fn method(&self) -> &'static str {
+        match self {
+            Self::DebugTraceBlock => "debug_traceBlockByNumber",
+            Self::TraceBlock => "trace_block",
+        }
+    }
+}
+
+// Native value moved by a nested call, the top level call is the transaction itself
+#[derive(Debug, Clone, PartialEq)]
+pub struct InternalTransfer {
+    pub from: String,
+    pub to: String,
+    pub value: BigUint,
+}
+
+fn internal_transfer(call_type: &str, from: &str, to: Option<&str>, value: Option<&BigUint>) -> Option<InternalTransfer> {
+    let value = value?;
+    if !call_type.eq_ignore_ascii_case(CALL_TYPE_CALL) || value.is_zero() {
+        return None;
+    }
+    Some(InternalTransfer {
+        from: EthereumAddress::parse(from)?.to_checksum(),
+        to: EthereumAddress::parse(to?)?.to_checksum(),
+        value: value.clone(),
+    })
+}
+
+fn collect_call_frames(frame: &CallFrame, transfers: &mut Vec<InternalTransfer>) {
+    for call in frame.calls.iter().filter(|x| x.error.is_none()) {
+        transfers.extend(internal_transfer(
+            &call.call_type,
+            &call.from,
+            call.to.as_deref(),
+            call.value.as_ref().map(|x| &x.value),
+        ));
+        collect_call_frames(call, transfers);
+    }
+}
+
+pub fn call_frame_transfers(frame: &CallFrame) -> Vec<InternalTransfer> {
+    if frame.error.is_some() {
+        return vec![];
+    }
+    let mut transfers = vec![];
+    collect_call_frames(frame, &mut transfers);
+    transfers
+}
+
+// Groups transfers by transaction hash, skipping traces within reverted subtrees
+pub fn parity_trace_transfers(traces: &[ParityTrace]) -> HashMap<String, Vec<InternalTransfer>> {
+    let reverted = traces
+        .iter()
+        .filter(|x| x.error.is_some())
+        .flat_map(|x| Some((x.transaction_hash.clone()?, x.trace_address.clone())))
+        .collect::<Vec<_>>();
+
+    let mut transfers: HashMap<String, Vec<InternalTransfer>> = HashMap::new();
+    for trace in traces.iter().filter(|x| !x.trace_address.is_empty()) {
+        let Some(hash) = trace.transaction_hash.clone() else {
+            continue;
+        };
+        let is_reverted = reverted
+            .iter()
+            .any(|(reverted_hash, address)| *reverted_hash == hash && trace.trace_address.starts_with(address));
+        if is_reverted || trace.trace_type != CALL_TYPE_CALL {
+            continue;
+        }
+        let action = &trace.action;
+        let transfer = internal_transfer(
+            action.call_type.as_deref().unwrap_or_default(),
+            action.from.as_deref().unwrap_or_default(),
+            action.to.as_deref(),
+            action.value.as_ref().map(|x| &x.value),
+        );
+        if let Some(transfer) = transfer {
+            transfers.entry(hash).or_default().push(transfer);
+        }
+    }
+    transfers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ethereum::model::TransactionTrace;
+
+    #[test]
+    fn test_call_frame_transfers() {
+        let trace: TransactionTrace = serde_json::from_value(serde_json::json!({
+            "txHash": "0x1",
+            "result": {
+                "type": "CALL",
+                "from": "0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266",
+                "to": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                "value": "0x0",
+                "calls": [
+                    {
+                        "type": "CALL",
+                        "from": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "to": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+                        "value": "0xde0b6b3a7640000"
+                    },
+                    {
+                        "type": "DELEGATECALL",
+                        "from": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "to": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+                        "value": "0x1"
+                    },
+                    {
+                        "type": "CALL",
+                        "from": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                        "to": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+                        "value": "0x1",
+                        "error": "execution reverted"
+                    }
+                ]
+            }
+        }))
+        .unwrap();
+        let transfers = call_frame_transfers(&trace.result);
+
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].from, "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2");
+        assert_eq!(transfers[0].to, "0x70997970C51812dc3A010C7d01b50e0d17dc79C8");
+        assert_eq!(transfers[0].value, BigUint::from(1_000_000_000_000_000_000u64));
+    }
+
+    #[test]
+    fn test_parity_trace_transfers() {
+        let trace = |address: Vec<u64>, value: &str, error: Option<&str>| {
+            serde_json::json!({
+                "action": {
+                    "callType": "call",
+                    "from": "0xc02aaa39b223fe8d0a0e5c4f27ead9083c756cc2",
+                    "to": "0x70997970c51812dc3a010c7d01b50e0d17dc79c8",
+                    "value": value
+                },
+                "error": error,
+                "transactionHash": "0x1",
+                "traceAddress": address,
+                "type": "call"
+            })
+        };
+        let traces: Vec<ParityTrace> = serde_json::from_value(serde_json::json!([
+            trace(vec![], "0x5", None),
+            trace(vec![0], "0x2", None),
+            trace(vec![1], "0x0", Some("Reverted")),
+            trace(vec![1, 0], "0x3", None),
+        ]))
+        .unwrap();
+        let transfers = parity_trace_transfers(&traces);
+
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers["0x1"].len(), 1);
+        assert_eq!(transfers["0x1"][0].value, BigUint::from(2u32));
+    }
+}
@@ -1,40 +1,60 @@
 // lib.rs
 
+pub mod algorand;
 pub mod aptos;
 pub mod bitcoin;
 pub mod cosmos;
 pub mod ethereum;
 pub mod near;
+pub mod nft;
 pub mod solana;
+pub mod stellar;
 pub mod sui;
 pub mod ton;
 pub mod tron;
 pub mod xrp;
 
+pub use self::algorand::client::AlgorandClient;
 pub use self::aptos::client::AptosClient;
 pub use self::bitcoin::client::BitcoinClient;
 pub use self::cosmos::client::CosmosClient;
 pub use self::ethereum::client::EthereumClient;
+pub use self::ethereum::trace::EthereumTrace;
 pub use self::near::client::NearClient;
 pub use self::solana::client::SolanaClient;
+pub use self::stellar::client::StellarClient;
 pub use self::sui::client::SuiClient;
 pub use self::ton::client::TonClient;
 pub use self::tron::client::TronClient;
 pub use self::xrp::client::XRPClient;
 
 use async_trait::async_trait;
-use primitives::{chain::Chain, Asset, Transaction};
+use nft::{NFTScan, NFTToken};
+use primitives::{chain::Chain, Asset, AssetBalance, NFTCollectible, NFTCollection, Transaction};
 
-use std::{error::Error, sync::Arc};
+use std::sync::Arc;
 
 pub trait ChainProvider: ChainBlockProvider + ChainTokenDataProvider {}
 impl<T: ChainBlockProvider + ChainTokenDataProvider> ChainProvider for T {}
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockHeader {
+    pub number: i64,
+    pub hash: String,
+    pub parent_number: i64,
+    pub parent_hash: String,
+}
+
 #[async_trait]
 pub trait ChainBlockProvider: Send + Sync {
     fn get_chain(&self) -> Chain;
     async fn get_latest_block(&self) -> Result<i64, Box<dyn std::error::Error + Send + Sync>>;
     async fn get_transactions(&self, block_number: i64) -> Result<Vec<Transaction>, Box<dyn std::error::Error + Send + Sync>>;
+
+    // Used for reorg detection, chains without block hashes (or skipped blocks) return None
+    async fn get_block_header(&self, _block_number: i64) -> Result<Option<BlockHeader>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(None)
+    }
 }
 
 #[async_trait]
@@ -42,6 +62,33 @@ pub trait ChainTokenDataProvider: Send + Sync {
     async fn get_token_data(&self, chain: Chain, token_id: String) -> Result<Asset, Box<dyn std::error::Error + Send + Sync>>;
 }
 
+// Collectibles held by an address, collection ids are the chain specific collection (contract) address
+#[async_trait]
+pub trait ChainNFTProvider: Send + Sync {
+    async fn get_nft_collections(&self, address: String) -> Result<Vec<NFTCollection>, Box<dyn std::error::Error + Send + Sync>>;
+    async fn get_nft_collectibles(&self, collection_id: String, address: String) -> Result<Vec<NFTCollectible>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+// Collectibles of chains without an ownership index, tokens received by the address are found by scanning transfer logs.
+// Every call scans a bounded number of blocks and the caller persists the scan, current ownership is checked on chain.
+#[async_trait]
+pub trait ChainNFTScanProvider: Send + Sync {
+    async fn scan_nfts(&self, address: String, scan: Option<NFTScan>) -> Result<NFTScan, Box<dyn std::error::Error + Send + Sync>>;
+    async fn get_nfts(&self, address: String, tokens: Vec<NFTToken>) -> Result<Vec<(NFTCollection, NFTCollectible)>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+// Recent transactions of an address, used to backfill history of new subscriptions
+#[async_trait]
+pub trait ChainAddressHistoryProvider: Send + Sync {
+    async fn get_address_transactions(&self, address: String, limit: usize) -> Result<Vec<Transaction>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
+// Balances of an address in the smallest unit, the native asset followed by the requested tokens, tokens that fail to load are omitted
+#[async_trait]
+pub trait ChainBalanceProvider: Send + Sync {
+    async fn get_balances(&self, address: String, token_ids: Vec<String>) -> Result<Vec<AssetBalance>, Box<dyn std::error::Error + Send + Sync>>;
+}
+
 #[async_trait]
 impl<T: Send + Sync> ChainBlockProvider for Arc<T>
 where
@@ -58,6 +105,10 @@ where
     async fn get_transactions(&self, block_number: i64) -> Result<Vec<Transaction>, Box<dyn std::error::Error + Send + Sync>> {
         (**self).get_transactions(block_number).await
     }
+
+    async fn get_block_header(&self, block_number: i64) -> Result<Option<BlockHeader>, Box<dyn std::error::Error + Send + Sync>> {
+        (**self).get_block_header(block_number).await
+    }
 }
 
 #[async_trait]
@@ -70,35 +121,50 @@ where
     }
 }
 
-// Temporary mock client until all providers are implemented
-pub struct MockChainBlockClient {
-    pub chain: Chain,
-}
+#[async_trait]
+impl<T: Send + Sync> ChainNFTScanProvider for Arc<T>
+where
+    T: ChainNFTScanProvider + ?Sized,
+{
+    async fn scan_nfts(&self, address: String, scan: Option<NFTScan>) -> Result<NFTScan, Box<dyn std::error::Error + Send + Sync>> {
+        (**self).scan_nfts(address, scan).await
+    }
 
-impl MockChainBlockClient {
-    pub fn new(chain: Chain) -> Self {
-        Self { chain }
+    async fn get_nfts(&self, address: String, tokens: Vec<NFTToken>) -> Result<Vec<(NFTCollection, NFTCollectible)>, Box<dyn std::error::Error + Send + Sync>> {
+        (**self).get_nfts(address, tokens).await
     }
 }
 
 #[async_trait]
-impl ChainBlockProvider for MockChainBlockClient {
-    fn get_chain(&self) -> Chain {
-        self.chain
+impl<T: Send + Sync> ChainNFTProvider for Arc<T>
+where
+    T: ChainNFTProvider + ?Sized,
+{
+    async fn get_nft_collections(&self, address: String) -> Result<Vec<NFTCollection>, Box<dyn std::error::Error + Send + Sync>> {
+        (**self).get_nft_collections(address).await
     }
 
-    async fn get_latest_block(&self) -> Result<i64, Box<dyn Error + Send + Sync>> {
-        Ok(1)
+    async fn get_nft_collectibles(&self, collection_id: String, address: String) -> Result<Vec<NFTCollectible>, Box<dyn std::error::Error + Send + Sync>> {
+        (**self).get_nft_collectibles(collection_id, address).await
     }
+}
 
-    async fn get_transactions(&self, _block_number: i64) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
-        Ok(vec![])
+#[async_trait]
+impl<T: Send + Sync> ChainAddressHistoryProvider for Arc<T>
+where
+    T: ChainAddressHistoryProvider + ?Sized,
+{
+    async fn get_address_transactions(&self, address: String, limit: usize) -> Result<Vec<Transaction>, Box<dyn std::error::Error + Send + Sync>> {
+        (**self).get_address_transactions(address, limit).await
     }
 }
 
 #[async_trait]
-impl ChainTokenDataProvider for MockChainBlockClient {
-    async fn get_token_data(&self, _chain: Chain, _token_id: String) -> Result<Asset, Box<dyn Error + Send + Sync>> {
-        unimplemented!()
+impl<T: Send + Sync> ChainBalanceProvider for Arc<T>
+where
+    T: ChainBalanceProvider + ?Sized,
+{
+    async fn get_balances(&self, address: String, token_ids: Vec<String>) -> Result<Vec<AssetBalance>, Box<dyn std::error::Error + Send + Sync>> {
+        (**self).get_balances(address, token_ids).await
     }
 }
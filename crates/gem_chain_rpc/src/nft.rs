@@ -0,0 +1,199 @@
+use std::error::Error;
+
+use base64::{engine::general_purpose, Engine as _};
+use primitives::{block_explorer::get_block_explorers, Chain, NFTAttrubute, NFTCollectible, NFTCollection, NFTImage, NFTType};
+use reqwest_middleware::ClientWithMiddleware;
+use serde::{Deserialize, Serialize};
+
+const IPFS_SCHEME: &str = "ipfs://";
+const IPFS_GATEWAY: &str = "https://ipfs.io/ipfs/";
+const ARWEAVE_SCHEME: &str = "ar://";
+const ARWEAVE_GATEWAY: &str = "https://arweave.net/";
+const DATA_JSON_BASE64: &str = "data:application/json;base64,";
+const DATA_JSON: &str = "data:application/json,";
+
+// Transfer log scan of an address, blocks from_block..=to_block are scanned
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NFTScan {
+    pub from_block: i64,
+    pub to_block: i64,
+    // tokens received within the scanned blocks, they may have been sent away since
+    pub tokens: Vec<NFTToken>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NFTToken {
+    pub contract: String,
+    pub token_id: String,
+    pub nft_type: NFTType,
+}
+
+impl NFTScan {
+    pub fn add_token(&mut self, token: NFTToken) {
+        if !self.tokens.iter().any(|x| x.contract == token.contract && x.token_id == token.token_id) {
+            self.tokens.push(token);
+        }
+    }
+}
+
+// Off-chain token metadata, shared by ERC721/ERC1155 tokenURI and Metaplex uri
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NFTMetadata {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub image: Option<String>,
+    #[serde(default)]
+    pub attributes: Vec<NFTMetadataAttribute>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NFTMetadataAttribute {
+    pub trait_type: Option<String>,
+    pub value: serde_json::Value,
+}
+
+impl NFTMetadata {
+    pub fn image(&self) -> NFTImage {
+        nft_image(self.image.as_deref().unwrap_or_default())
+    }
+
+    pub fn attributes(&self) -> Vec<NFTAttrubute> {
+        self.attributes
+            .iter()
+            .flat_map(|x| {
+                let value = match &x.value {
+                    serde_json::Value::String(value) => value.clone(),
+                    serde_json::Value::Number(value) => value.to_string(),
+                    serde_json::Value::Bool(value) => value.to_string(),
+                    _ => return None,
+                };
+                Some(NFTAttrubute {
+                    name: x.trait_type.clone()?,
+                    value,
+                })
+            })
+            .collect()
+    }
+}
+
+// Rewrites ipfs:// and ar:// links to public gateways
+pub fn resolve_url(uri: &str) -> String {
+    let uri = uri.trim();
+    if let Some(path) = uri.strip_prefix(IPFS_SCHEME) {
+        return format!("{}{}", IPFS_GATEWAY, path.trim_start_matches("ipfs/"));
+    }
+    if let Some(path) = uri.strip_prefix(ARWEAVE_SCHEME) {
+        return format!("{}{}", ARWEAVE_GATEWAY, path);
+    }
+    uri.to_string()
+}
+
+pub fn nft_image(uri: &str) -> NFTImage {
+    let url = resolve_url(uri);
+    NFTImage {
+        image_url: url.clone(),
+        preview_image_url: url,
+        original_source_url: uri.to_string(),
+    }
+}
+
+pub fn explorer_token_url(chain: Chain, token: &str) -> String {
+    get_block_explorers(chain).first().and_then(|x| x.get_token_url(token)).unwrap_or_default()
+}
+
+fn decode_json(data: &[u8]) -> Result<NFTMetadata, Box<dyn Error + Send + Sync>> {
+    Ok(serde_json::from_slice(data)?)
+}
+
+fn decode_base64_json(data: &str) -> Result<NFTMetadata, Box<dyn Error + Send + Sync>> {
+    decode_json(&general_purpose::STANDARD.decode(data)?)
+}
+
+// Metadata can be inlined as a data uri, mostly for fully on-chain collections
+fn decode_data_uri(uri: &str) -> Option<Result<NFTMetadata, Box<dyn Error + Send + Sync>>> {
+    if let Some(data) = uri.strip_prefix(DATA_JSON_BASE64) {
+        return Some(decode_base64_json(data));
+    }
+    uri.strip_prefix(DATA_JSON).map(|data| decode_json(data.as_bytes()))
+}
+
+pub struct NFTMetadataClient {
+    client: ClientWithMiddleware,
+}
+
+impl NFTMetadataClient {
+    pub fn new(client: ClientWithMiddleware) -> Self {
+        Self { client }
+    }
+
+    pub async fn get_metadata(&self, uri: &str) -> Result<NFTMetadata, Box<dyn Error + Send + Sync>> {
+        let uri = uri.trim_matches(char::from(0)).trim();
+        if let Some(result) = decode_data_uri(uri) {
+            return result;
+        }
+        if uri.is_empty() {
+            return Ok(NFTMetadata::default());
+        }
+        Ok(self.client.get(resolve_url(uri)).send().await?.json::<NFTMetadata>().await?)
+    }
+
+    // Missing or broken metadata should not hide the rest of the collectibles
+    pub async fn get_metadata_or_default(&self, uri: &str) -> NFTMetadata {
+        self.get_metadata(uri).await.unwrap_or_default()
+    }
+}
+
+// Collections with the number of collectibles held, in the order they are first seen
+pub fn group_collections(items: &[(NFTCollection, NFTCollectible)]) -> Vec<NFTCollection> {
+    let mut collections: Vec<NFTCollection> = vec![];
+    for (collection, _) in items {
+        match collections.iter_mut().find(|x| x.id == collection.id) {
+            Some(existing) => existing.count += 1,
+            None => collections.push(NFTCollection {
+                count: 1,
+                ..collection.clone()
+            }),
+        }
+    }
+    collections
+}
+
+pub fn filter_collectibles(items: Vec<(NFTCollection, NFTCollectible)>, collection_id: &str) -> Vec<NFTCollectible> {
+    items
+        .into_iter()
+        .filter(|(collection, _)| collection.id == collection_id)
+        .map(|(_, x)| x)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_url() {
+        assert_eq!(
+            resolve_url("ipfs://QmXUUXRSAJeb4u8p4yKHmXN1iAKtAV7jwLHjw35TNm5jN7/1.png"),
+            "https://ipfs.io/ipfs/QmXUUXRSAJeb4u8p4yKHmXN1iAKtAV7jwLHjw35TNm5jN7/1.png"
+        );
+        assert_eq!(resolve_url("ipfs://ipfs/QmHash"), "https://ipfs.io/ipfs/QmHash");
+        assert_eq!(resolve_url("ar://abc"), "https://arweave.net/abc");
+        assert_eq!(resolve_url("https://example.com/1.json"), "https://example.com/1.json");
+    }
+
+    #[test]
+    fn test_decode_data_uri() {
+        let metadata = decode_data_uri("data:application/json;base64,eyJuYW1lIjoiTm91biAxIiwiaW1hZ2UiOiJpcGZzOi8vUW1IYXNoIiwiYXR0cmlidXRlcyI6W3sidHJhaXRfdHlwZSI6ImhlYWQiLCJ2YWx1ZSI6ImFudCJ9LHsidHJhaXRfdHlwZSI6ImxldmVsIiwidmFsdWUiOjN9XX0=")
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(metadata.name, Some("Noun 1".into()));
+        assert_eq!(metadata.image().image_url, "https://ipfs.io/ipfs/QmHash");
+        let attributes = metadata.attributes();
+        assert_eq!(attributes.len(), 2);
+        assert_eq!(attributes[1].name, "level");
+        assert_eq!(attributes[1].value, "3");
+
+        assert!(decode_data_uri("https://example.com/1.json").is_none());
+    }
+}
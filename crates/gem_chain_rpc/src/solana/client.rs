@@ -1,26 +1,36 @@
 use async_trait::async_trait;
 use chrono::Utc;
 use jsonrpsee::{
-    core::{client::ClientT, ClientError},
+    core::{client::ClientT, params::BatchRequestBuilder, ClientError},
     http_client::{HttpClient, HttpClientBuilder},
     rpc_params,
 };
 use serde::de::DeserializeOwned;
 use serde_json::json;
-use std::{error::Error, str::FromStr};
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    str::FromStr,
+};
 
-use super::model::{BlockTransaction, BlockTransactions, InstructionParsed};
-use crate::{ChainBlockProvider, ChainTokenDataProvider};
+use super::model::{BlockHeader, BlockTransaction, BlockTransactions, InstructionParsed, SignatureInfo, TokenAccount};
+use crate::nft::{self, NFTMetadataClient};
+use crate::BlockHeader as ChainBlockHeader;
+use crate::{ChainAddressHistoryProvider, ChainBalanceProvider, ChainBlockProvider, ChainNFTProvider, ChainTokenDataProvider};
 use gem_solana::{
+    get_token_program_id_by_address,
     jsonrpc::{AccountData, SolanaParsedTokenInfo, ValueResult},
     metaplex::{decode_metadata, metadata::Metadata},
     pubkey::Pubkey,
-    TOKEN_PROGRAM, WSOL_TOKEN_ADDRESS,
+    TOKEN_PROGRAM, TOKEN_PROGRAM_2022, WSOL_TOKEN_ADDRESS,
+};
+use primitives::{
+    chain::Chain, Asset, AssetBalance, AssetId, AssetType, NFTCollectible, NFTCollection, NFTType, Transaction, TransactionSwapMetadata, TransactionType,
 };
-use primitives::{chain::Chain, Asset, AssetId, AssetType, Transaction, TransactionState, TransactionSwapMetadata, TransactionType};
 
 pub struct SolanaClient {
     client: HttpClient,
+    metadata: NFTMetadataClient,
 }
 
 const CLEANUP_BLOCK_ERROR: i32 = -32001;
@@ -29,6 +39,9 @@ const MISSING_OR_SKIPPED_SLOT_ERROR: i32 = -32009;
 const NOT_AVAILABLE_SLOT_ERROR: i32 = -32004;
 const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
 const JUPITER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+const STAKE_PROGRAM_ID: &str = "Stake11111111111111111111111111111111111111";
+const MULTIPLE_ACCOUNTS_LIMIT: usize = 100;
+const TRANSACTIONS_BATCH_SIZE: usize = 10;
 
 impl SolanaClient {
     pub fn new(url: String) -> Self {
@@ -36,8 +49,9 @@ impl SolanaClient {
             .max_response_size(100 * 1024 * 1024) // 100MB
             .build(url)
             .unwrap();
+        let metadata = NFTMetadataClient::new(reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build());
 
-        Self { client }
+        Self { client, metadata }
     }
 
     fn map_transaction(&self, transaction: &BlockTransaction, block_number: i64) -> Option<Transaction> {
@@ -54,7 +68,7 @@ impl SolanaClient {
         let chain = self.get_chain();
         let fee = transaction.meta.fee;
         let sequence = 0.to_string();
-        let state = TransactionState::Confirmed;
+        let state = transaction.meta.get_state();
         let fee_asset_id = chain.as_asset_id();
         // system transfer
         if (account_keys.len() == 2 || account_keys.len() == 3) && account_keys.last()? == SYSTEM_PROGRAM_ID && signatures.len() == 1 {
@@ -146,12 +160,7 @@ impl SolanaClient {
                     .instructions
                     .clone()
                     .into_iter()
-                    .flat_map(|x| {
-                        if let Some(value) = x.parsed {
-                            return Some(value);
-                        }
-                        None
-                    })
+                    .flat_map(|x| x.get_parsed())
                     .collect::<Vec<InstructionParsed>>();
 
                 let transfer_instructions = instructions
@@ -205,6 +214,133 @@ impl SolanaClient {
         None
     }
 
+    fn map_transactions(&self, transaction: &BlockTransaction, block_number: i64) -> Vec<Transaction> {
+        match self.map_transaction(transaction, block_number) {
+            Some(transaction) => vec![transaction],
+            None => self
+                .map_instructions(transaction, block_number)
+                .into_iter()
+                .enumerate()
+                .map(|(index, x)| x.with_index(index))
+                .collect(),
+        }
+    }
+
+    // Stake program instructions and SOL, SPL and Token-2022 transfers, including the ones made through CPI
+    fn map_instructions(&self, transaction: &BlockTransaction, block_number: i64) -> Vec<Transaction> {
+        let Some(hash) = transaction.transaction.signatures.first() else {
+            return vec![];
+        };
+        let chain = self.get_chain();
+        let meta = &transaction.meta;
+        let account_keys = transaction
+            .transaction
+            .message
+            .account_keys
+            .iter()
+            .map(|x| x.pubkey.as_str())
+            .collect::<Vec<_>>();
+        // token account to (mint, owner)
+        let token_accounts = meta
+            .pre_token_balances
+            .iter()
+            .chain(meta.post_token_balances.iter())
+            .flat_map(|x| Some((*account_keys.get(x.account_index as usize)?, (x.mint.clone(), x.owner.clone()))))
+            .collect::<HashMap<_, _>>();
+        let balance = |account: &str| {
+            let index = account_keys.iter().position(|x| *x == account)?;
+            meta.post_balances.get(index).map(|x| x.to_string())
+        };
+        let new_transaction = |asset_id: AssetId, from: String, to: String, transaction_type: TransactionType, value: String| {
+            Transaction::new(
+                hash.clone(),
+                asset_id,
+                from,
+                to,
+                None,
+                transaction_type,
+                meta.get_state(),
+                block_number.to_string(),
+                0.to_string(),
+                meta.fee.to_string(),
+                chain.as_asset_id(),
+                value,
+                None,
+                None,
+                Utc::now(),
+            )
+        };
+
+        transaction
+            .transaction
+            .message
+            .instructions
+            .iter()
+            .chain(meta.inner_instructions.iter().flat_map(|x| &x.instructions))
+            .flat_map(|instruction| {
+                let program_id = instruction.program_id.as_deref()?;
+                let parsed = instruction.get_parsed()?;
+                let info = parsed.info;
+                match (program_id, parsed.instruction_type.as_str()) {
+                    (SYSTEM_PROGRAM_ID, "transfer") => Some(new_transaction(
+                        chain.as_asset_id(),
+                        info.source?,
+                        info.destination?,
+                        TransactionType::Transfer,
+                        info.lamports?.to_string(),
+                    )),
+                    // delegated and deactivated amount is the stake account balance
+                    (STAKE_PROGRAM_ID, "delegate") => Some(new_transaction(
+                        chain.as_asset_id(),
+                        info.stake_authority?,
+                        info.vote_account?,
+                        TransactionType::StakeDelegate,
+                        balance(info.stake_account.as_deref()?)?,
+                    )),
+                    (STAKE_PROGRAM_ID, "deactivate") => {
+                        let stake_account = info.stake_account?;
+                        let value = balance(&stake_account)?;
+                        Some(new_transaction(
+                            chain.as_asset_id(),
+                            info.stake_authority?,
+                            stake_account,
+                            TransactionType::StakeUndelegate,
+                            value,
+                        ))
+                    }
+                    (STAKE_PROGRAM_ID, "withdraw") => Some(new_transaction(
+                        chain.as_asset_id(),
+                        info.withdraw_authority?,
+                        info.destination?,
+                        TransactionType::StakeWithdraw,
+                        info.lamports?.to_string(),
+                    )),
+                    // plain transfer has no mint, it is taken from token balances of the accounts
+                    (program_id, "transfer" | "transferChecked") if get_token_program_id_by_address(program_id).is_some() => {
+                        let source = token_accounts.get(info.source.as_deref()?);
+                        let destination = token_accounts.get(info.destination.as_deref()?);
+                        let mint = info.mint.or(source.or(destination).map(|x| x.0.clone()))?;
+                        let from = source.map(|x| x.1.clone()).or(info.authority)?;
+                        let to = destination.map(|x| x.1.clone()).or(info.destination)?;
+                        let value = info.token_amount.map(|x| x.amount.value).or(info.amount.map(|x| x.value))?;
+                        Some(new_transaction(
+                            self.asset_id_from_program(mint),
+                            from,
+                            to,
+                            TransactionType::Transfer,
+                            value.to_string(),
+                        ))
+                    }
+                    _ => None,
+                }
+            })
+            .collect()
+    }
+
+    fn is_skipped_slot_error(code: i32) -> bool {
+        [MISSING_SLOT_ERROR, MISSING_OR_SKIPPED_SLOT_ERROR, NOT_AVAILABLE_SLOT_ERROR, CLEANUP_BLOCK_ERROR].contains(&code)
+    }
+
     fn asset_id_from_program(&self, program_id: String) -> AssetId {
         if program_id == WSOL_TOKEN_ADDRESS {
             return self.get_chain().as_asset_id();
@@ -242,6 +378,163 @@ impl SolanaClient {
     }
 }
 
+impl SolanaClient {
+    async fn get_balance(&self, address: &str) -> Result<u64, Box<dyn Error + Send + Sync>> {
+        let params = vec![json!(address), json!({ "commitment": "confirmed" })];
+        let result: ValueResult<u64> = self.client.request("getBalance", params).await?;
+        Ok(result.value)
+    }
+
+    async fn get_token_accounts_by_owner(&self, owner: &str, program: &str) -> Result<Vec<TokenAccount>, Box<dyn Error + Send + Sync>> {
+        let params = vec![
+            json!(owner),
+            json!({ "programId": program }),
+            json!({
+                "encoding": "jsonParsed",
+                "commitment": "confirmed",
+            }),
+        ];
+        let result: ValueResult<Vec<TokenAccount>> = self.client.request("getTokenAccountsByOwner", params).await?;
+        Ok(result.value)
+    }
+
+    // Metaplex metadata by mint, mints without a metadata account are left out
+    async fn get_metaplex_metadata(&self, mints: Vec<String>) -> Result<HashMap<String, Metadata>, Box<dyn Error + Send + Sync>> {
+        let mut result = HashMap::new();
+        for chunk in mints.chunks(MULTIPLE_ACCOUNTS_LIMIT) {
+            let keys = chunk
+                .iter()
+                .map(|mint| Pubkey::from_str(mint).ok().and_then(Metadata::find_pda).map(|x| x.0.to_string()))
+                .collect::<Option<Vec<_>>>()
+                .ok_or("invalid mint")?;
+            let params = vec![
+                json!(keys),
+                json!({
+                    "encoding": "base64",
+                    "commitment": "confirmed",
+                }),
+            ];
+            let accounts: ValueResult<Vec<Option<AccountData>>> = self.client.request("getMultipleAccounts", params).await?;
+            for (mint, account) in chunk.iter().zip(accounts.value) {
+                if let Some(metadata) = account.and_then(|x| decode_metadata(x.data.first()?).ok()) {
+                    result.insert(mint.clone(), metadata);
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    async fn get_signatures_for_address(&self, address: &str, limit: usize) -> Result<Vec<SignatureInfo>, Box<dyn Error + Send + Sync>> {
+        let params = vec![
+            json!(address),
+            json!({
+                "limit": limit,
+                "commitment": "confirmed",
+            }),
+        ];
+        Ok(self.client.request("getSignaturesForAddress", params).await?)
+    }
+
+    // Transactions by signature fetched in sequential batches, transactions that fail to load are None
+    async fn get_transactions_by_signatures(&self, signatures: &[String]) -> Result<Vec<Option<BlockTransaction>>, Box<dyn Error + Send + Sync>> {
+        let mut transactions = Vec::with_capacity(signatures.len());
+        for chunk in signatures.chunks(TRANSACTIONS_BATCH_SIZE) {
+            let mut batch = BatchRequestBuilder::new();
+            for signature in chunk {
+                let config = json!({
+                    "encoding": "jsonParsed",
+                    "maxSupportedTransactionVersion": 0,
+                    "commitment": "confirmed",
+                });
+                batch.insert("getTransaction", rpc_params![signature, config])?;
+            }
+            let response = self.client.batch_request::<Option<BlockTransaction>>(batch).await?;
+            transactions.extend(response.into_iter().map(|x| x.ok().flatten()));
+        }
+        Ok(transactions)
+    }
+
+    // NFTs of the SPL Token and Token-2022 programs described by Metaplex metadata, sorted by mint
+    async fn get_nfts(&self, address: &str) -> Result<Vec<(NFTCollection, NFTCollectible)>, Box<dyn Error + Send + Sync>> {
+        let (token_accounts, token_2022_accounts) = futures::try_join!(
+            self.get_token_accounts_by_owner(address, TOKEN_PROGRAM),
+            self.get_token_accounts_by_owner(address, TOKEN_PROGRAM_2022),
+        )?;
+        let mut mints = token_accounts
+            .into_iter()
+            .chain(token_2022_accounts)
+            .map(|x| x.account.data.parsed.info)
+            .filter(|x| x.is_nft())
+            .map(|x| x.mint)
+            .collect::<Vec<_>>();
+        mints.sort();
+        mints.dedup();
+        let mut metadata = self.get_metaplex_metadata(mints.clone()).await?;
+
+        // NFTs without a verified collection are listed as a collection of their own
+        let items = mints
+            .into_iter()
+            .filter_map(|mint| {
+                let collection = metadata.get(&mint)?.collection.as_ref().filter(|x| x.verified).map(|x| x.key.to_string());
+                Some((mint.clone(), collection.unwrap_or(mint)))
+            })
+            .collect::<Vec<_>>();
+        let collection_mints = items
+            .iter()
+            .map(|(_, collection)| collection.clone())
+            .filter(|x| !metadata.contains_key(x))
+            .collect::<HashSet<_>>();
+        let collections_metadata = self.get_metaplex_metadata(collection_mints.into_iter().collect()).await?;
+        metadata.extend(collections_metadata);
+
+        let offchain = futures::future::join_all(
+            metadata
+                .iter()
+                .map(|(mint, x)| async move { (mint.clone(), self.metadata.get_metadata_or_default(&x.data.uri).await) }),
+        )
+        .await
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+        let name = |mint: &str| {
+            metadata
+                .get(mint)
+                .map(|x| x.data.name.trim_matches(char::from(0)).to_string())
+                .unwrap_or_default()
+        };
+        let chain = self.get_chain();
+
+        Ok(items
+            .into_iter()
+            .map(|(mint, collection_mint)| {
+                let collectible_metadata = offchain.get(&mint).cloned().unwrap_or_default();
+                let collection_metadata = offchain.get(&collection_mint).cloned().unwrap_or_default();
+                let collection = NFTCollection {
+                    id: collection_mint.clone(),
+                    name: name(&collection_mint),
+                    description: collection_metadata.description.clone().unwrap_or_default(),
+                    chain,
+                    image: collection_metadata.image(),
+                    explorer_url: nft::explorer_token_url(chain, &collection_mint),
+                    count: 1,
+                };
+                let collectible = NFTCollectible {
+                    id: mint.clone(),
+                    collection_id: collection_mint,
+                    name: name(&mint),
+                    description: collectible_metadata.description.clone().unwrap_or_default(),
+                    chain,
+                    image: collectible_metadata.image(),
+                    collectible_type: NFTType::SPL,
+                    attributes: collectible_metadata.attributes(),
+                    explorer_url: nft::explorer_token_url(chain, &mint),
+                };
+                (collection, collectible)
+            })
+            .collect())
+    }
+}
+
 #[async_trait]
 impl ChainBlockProvider for SolanaClient {
     fn get_chain(&self) -> Chain {
@@ -253,6 +546,29 @@ impl ChainBlockProvider for SolanaClient {
         Ok(block)
     }
 
+    async fn get_block_header(&self, block_number: i64) -> Result<Option<ChainBlockHeader>, Box<dyn Error + Send + Sync>> {
+        let params = vec![
+            json!(block_number),
+            json!({
+                "encoding": "json",
+                "maxSupportedTransactionVersion": 0,
+                "transactionDetails": "none",
+                "rewards": false
+            }),
+        ];
+        let block: Result<BlockHeader, ClientError> = self.client.request("getBlock", params).await;
+        match block {
+            Ok(block) => Ok(Some(ChainBlockHeader {
+                number: block_number,
+                hash: block.blockhash,
+                parent_number: block.parent_slot,
+                parent_hash: block.previous_blockhash,
+            })),
+            Err(ClientError::Call(err)) if Self::is_skipped_slot_error(err.code()) => Ok(None),
+            Err(err) => Err(Box::new(err)),
+        }
+    }
+
     async fn get_transactions(&self, block_number: i64) -> Result<Vec<Transaction>, Box<dyn Error + Send + Sync>> {
         let params = vec![
             json!(block_number),
@@ -269,14 +585,13 @@ impl ChainBlockProvider for SolanaClient {
                 let transactions = block
                     .transactions
                     .into_iter()
-                    .flat_map(|x| self.map_transaction(&x, block_number))
+                    .flat_map(|x| self.map_transactions(&x, block_number))
                     .collect::<Vec<Transaction>>();
                 Ok(transactions)
             }
             Err(err) => match err {
                 ClientError::Call(err) => {
-                    let errors = [MISSING_SLOT_ERROR, MISSING_OR_SKIPPED_SLOT_ERROR, NOT_AVAILABLE_SLOT_ERROR, CLEANUP_BLOCK_ERROR];
-                    if errors.contains(&err.code()) {
+                    if Self::is_skipped_slot_error(err.code()) {
                         return Ok(vec![]);
                     } else {
                         return Err(Box::new(err));
@@ -310,9 +625,61 @@ impl ChainTokenDataProvider for SolanaClient {
     }
 }
 
+#[async_trait]
+impl ChainBalanceProvider for SolanaClient {
+    // Token balances are summed over all token accounts of a mint, missing accounts are a zero balance
+    async fn get_balances(&self, address: String, token_ids: Vec<String>) -> Result<Vec<AssetBalance>, Box<dyn Error + Send + Sync>> {
+        let chain = self.get_chain();
+        let native = self.get_balance(&address).await?;
+        let mut tokens: HashMap<String, u128> = HashMap::new();
+        if !token_ids.is_empty() {
+            for account in self.get_token_accounts_by_owner(&address, TOKEN_PROGRAM).await? {
+                let info = account.account.data.parsed.info;
+                *tokens.entry(info.mint).or_default() += info.token_amount.amount.parse::<u128>().unwrap_or_default();
+            }
+        }
+
+        Ok(std::iter::once(AssetBalance::new(chain.as_asset_id(), native.to_string()))
+            .chain(token_ids.into_iter().map(|token_id| {
+                let balance = tokens.get(&token_id).copied().unwrap_or_default();
+                AssetBalance::new(AssetId::from_token(chain, &token_id), balance.to_string())
+            }))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ChainNFTProvider for SolanaClient {
+    async fn get_nft_collections(&self, address: String) -> Result<Vec<NFTCollection>, Box<dyn Error + Send + Sync>> {
+        Ok(nft::group_collections(&self.get_nfts(&address).await?))
+    }
+
+    async fn get_nft_collectibles(&self, collection_id: String, address: String) -> Result<Vec<NFTCollectible>, Box<dyn Error + Send + Sync>> {
+        Ok(nft::filter_collectibles(self.get_nfts(&address).await?, &collection_id))
+    }
+}
+
+#[async_trait]
+impl ChainAddressHistoryProvider for SolanaClient {
+    async fn get_address_transactions(&self, address: String, limit: usize) -> Result<Vec<Transaction>, Box<dyn Error + Send + Sync>> {
+        let signatures = self.get_signatures_for_address(&address, limit).await?;
+        let transactions = self
+            .get_transactions_by_signatures(&signatures.iter().map(|x| x.signature.clone()).collect::<Vec<_>>())
+            .await?;
+
+        Ok(signatures
+            .into_iter()
+            .zip(transactions)
+            .flat_map(|(signature, transaction)| transaction.map(|x| self.map_transactions(&x, signature.slot)).unwrap_or_default())
+            .filter(|x| x.addresses().contains(&address))
+            .collect())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use primitives::TransactionState;
     use serde::{Deserialize, Serialize};
 
     #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -341,4 +708,150 @@ mod tests {
         assert_eq!(parsed_info.decimals, 6);
         assert_eq!(parsed_info.mint_authority, "BJE5MMbqXjVwjAF7oxwPYXnTXDyspzZyt4vwenNw5ruG");
     }
+
+    #[test]
+    fn test_decode_token_accounts() {
+        let json = json!({
+            "context": {"slot": 300000000},
+            "value": [
+                {
+                    "pubkey": "8ZtNuoSJd3CLdT6GuHdTUyQZmpavNFqJP5W2zVzqfJpS",
+                    "account": {"data": {"parsed": {"info": {
+                        "isNative": false,
+                        "mint": "7Ehc6h7DQ2b8dyX2QX8f8U2x2B3ftWrP7Qw5BzNMw9ix",
+                        "owner": "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+                        "state": "initialized",
+                        "tokenAmount": {"amount": "1", "decimals": 0, "uiAmount": 1.0, "uiAmountString": "1"}
+                    }, "type": "account"}, "program": "spl-token", "space": 165}}
+                },
+                {
+                    "pubkey": "FGETo8T8wMcN2wCjav8VK6eh3dLk63evNDPxzLSJra8B",
+                    "account": {"data": {"parsed": {"info": {
+                        "isNative": false,
+                        "mint": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                        "owner": "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM",
+                        "state": "initialized",
+                        "tokenAmount": {"amount": "1", "decimals": 6, "uiAmount": 0.000001, "uiAmountString": "0.000001"}
+                    }, "type": "account"}, "program": "spl-token", "space": 165}}
+                }
+            ]
+        });
+        let result: ValueResult<Vec<TokenAccount>> = serde_json::from_value(json).unwrap();
+        let nfts = result
+            .value
+            .into_iter()
+            .map(|x| x.account.data.parsed.info)
+            .filter(|x| x.is_nft())
+            .collect::<Vec<_>>();
+
+        assert_eq!(nfts.len(), 1);
+        assert_eq!(nfts[0].mint, "7Ehc6h7DQ2b8dyX2QX8f8U2x2B3ftWrP7Qw5BzNMw9ix");
+    }
+
+    #[test]
+    fn test_map_instructions() {
+        let client = SolanaClient::new("http://localhost".into());
+        let transaction: BlockTransaction = serde_json::from_value(json!({
+            "transaction": {
+                "signatures": ["5h6xBEauJ3PK6SWCZ1PGjBvj8vDdWG3KpwATGy1ARAXFSDwt8GFXM7W5Ncn16wmqokgpiKRLuS83KUxyZyv2sUYv"],
+                "message": {
+                    "accountKeys": [
+                        {"pubkey": "7ezmRn9ZmF2Z3XbC9mJGG3PknwsPWmRUUvjnpAuqT7dR"},
+                        {"pubkey": "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T"},
+                        {"pubkey": "BeWWXqbWcFbvAkGB6pc4HRfw1MxRAbPxMdDS9CTVAXpz"},
+                        {"pubkey": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"},
+                        {"pubkey": "Stake11111111111111111111111111111111111111"}
+                    ],
+                    "instructions": [
+                        {
+                            "programId": "Stake11111111111111111111111111111111111111",
+                            "parsed": {"type": "delegate", "info": {
+                                "stakeAccount": "4Nd1mBQtrMJVYVfKf2PJy9NZUZdTAsp7D4xWLs4gDB4T",
+                                "voteAccount": "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu",
+                                "stakeAuthority": "7ezmRn9ZmF2Z3XbC9mJGG3PknwsPWmRUUvjnpAuqT7dR"
+                            }}
+                        },
+                        {"programId": "MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr", "parsed": "memo"}
+                    ]
+                }
+            },
+            "meta": {
+                "fee": 5000,
+                "preBalances": [1000000000, 0, 0, 0, 1],
+                "postBalances": [499995000, 500000000, 2039280, 2039280, 1],
+                "preTokenBalances": [],
+                "postTokenBalances": [
+                    {"accountIndex": 2, "mint": "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo", "owner": "DRpbCBMxVnDK7maPM5tGv6MvB3v1sRMC86PZ8okm21hy", "uiTokenAmount": {"amount": "0"}},
+                    {"accountIndex": 3, "mint": "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo", "owner": "7ezmRn9ZmF2Z3XbC9mJGG3PknwsPWmRUUvjnpAuqT7dR", "uiTokenAmount": {"amount": "1500000"}}
+                ],
+                "innerInstructions": [{"instructions": [{
+                    "programId": "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb",
+                    "parsed": {"type": "transfer", "info": {
+                        "source": "BeWWXqbWcFbvAkGB6pc4HRfw1MxRAbPxMdDS9CTVAXpz",
+                        "destination": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin",
+                        "authority": "DRpbCBMxVnDK7maPM5tGv6MvB3v1sRMC86PZ8okm21hy",
+                        "amount": "1500000"
+                    }}
+                }]}]
+            }
+        }))
+        .unwrap();
+
+        let result = client.map_transactions(&transaction, 1);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].transaction_type.as_ref(), TransactionType::StakeDelegate.as_ref());
+        assert_eq!(result[0].from, "7ezmRn9ZmF2Z3XbC9mJGG3PknwsPWmRUUvjnpAuqT7dR");
+        assert_eq!(result[0].to, "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu");
+        assert_eq!(result[0].value, "500000000");
+        assert_eq!(
+            result[1].asset_id,
+            AssetId::from_token(Chain::Solana, "2b1kV6DkPAnxd5ixfnxCpjxmKwqjjaYmCZfHsFu24GXo")
+        );
+        assert_eq!(result[1].from, "DRpbCBMxVnDK7maPM5tGv6MvB3v1sRMC86PZ8okm21hy");
+        assert_eq!(result[1].to, "7ezmRn9ZmF2Z3XbC9mJGG3PknwsPWmRUUvjnpAuqT7dR");
+        assert_eq!(result[1].value, "1500000");
+        assert_eq!(result[0].state, TransactionState::Confirmed);
+        assert_ne!(result[0].id, result[1].id);
+    }
+
+    #[test]
+    fn test_map_failed_transaction() {
+        let client = SolanaClient::new("http://localhost".into());
+        let transaction: BlockTransaction = serde_json::from_value(json!({
+            "transaction": {
+                "signatures": ["3Zbg6yFdoY4VKMGx4wJyVfLhBCGQ4Zo8nJd3qK8sYdXLuWDUW5D4tGCm3HFXdtkx9q3Y4DPNnh7zexDfmz7WJJxP"],
+                "message": {
+                    "accountKeys": [
+                        {"pubkey": "7ezmRn9ZmF2Z3XbC9mJGG3PknwsPWmRUUvjnpAuqT7dR"},
+                        {"pubkey": "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu"},
+                        {"pubkey": "11111111111111111111111111111111"}
+                    ],
+                    "instructions": [{
+                        "programId": "11111111111111111111111111111111",
+                        "parsed": {"type": "transfer", "info": {
+                            "source": "7ezmRn9ZmF2Z3XbC9mJGG3PknwsPWmRUUvjnpAuqT7dR",
+                            "destination": "CertusDeBmqN8ZawdkxK5kFGMwBXdudvWHYwtNgNhvLu",
+                            "lamports": 2000000000
+                        }}
+                    }]
+                }
+            },
+            "meta": {
+                "err": {"InstructionError": [0, {"Custom": 1}]},
+                "fee": 5000,
+                "preBalances": [1000000000, 0, 1],
+                "postBalances": [999995000, 0, 1],
+                "preTokenBalances": [],
+                "postTokenBalances": [],
+                "innerInstructions": []
+            }
+        }))
+        .unwrap();
+
+        let result = client.map_transactions(&transaction, 1);
+
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].state, TransactionState::Failed);
+    }
 }
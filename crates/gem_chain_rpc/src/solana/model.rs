@@ -1,5 +1,6 @@
+use gem_solana::jsonrpc::{ParsedData, ParsedInfo, ValueData};
 use num_bigint::BigUint;
-use primitives::BigIntValue;
+use primitives::{BigIntValue, TransactionState};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,9 +9,20 @@ pub struct Block {
     pub blockhash: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHeader {
+    pub blockhash: String,
+    pub previous_blockhash: String,
+    pub parent_slot: i64,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Meta {
+    // instruction error of a failed transaction, null on success
+    #[serde(default)]
+    pub err: Option<serde_json::Value>,
     pub fee: u64,
     pub inner_instructions: Vec<InnerInstruction>,
     pub pre_balances: Vec<u64>,
@@ -28,7 +40,15 @@ pub struct InnerInstruction {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Instruction {
-    pub parsed: Option<InstructionParsed>,
+    pub program_id: Option<String>,
+    // parsed is a plain string for some programs, like memo
+    pub parsed: Option<serde_json::Value>,
+}
+
+impl Instruction {
+    pub fn get_parsed(&self) -> Option<InstructionParsed> {
+        serde_json::from_value(self.parsed.clone()?).ok()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -47,9 +67,22 @@ pub struct InstructionInfo {
     pub destination: Option<String>,
     pub source: Option<String>,
     pub token_amount: Option<TokenAmount>,
+    pub amount: Option<BigIntValue>,
+    pub lamports: Option<u64>,
+    pub stake_account: Option<String>,
+    pub vote_account: Option<String>,
+    pub stake_authority: Option<String>,
+    pub withdraw_authority: Option<String>,
 }
 
 impl Meta {
+    pub fn get_state(&self) -> TransactionState {
+        match self.err {
+            Some(_) => TransactionState::Failed,
+            None => TransactionState::Confirmed,
+        }
+    }
+
     pub fn get_pre_token_balance(&self, account_index: i64) -> Option<TokenBalance> {
         self.pre_token_balances.iter().find(|b| b.account_index == account_index).cloned()
     }
@@ -69,7 +102,7 @@ pub struct Status {
 #[serde(rename_all = "camelCase")]
 pub struct Message {
     pub account_keys: Vec<AccountKey>,
-    //pub instructions: Vec<Instruction>,
+    pub instructions: Vec<Instruction>,
     //pub recent_blockhash: String,
 }
 
@@ -79,14 +112,6 @@ pub struct AccountKey {
     pub pubkey: String,
 }
 
-// #[derive(Debug, Deserialize, Serialize)]
-// #[serde(rename_all = "camelCase")]
-// pub struct Instruction {
-//     pub accounts: Vec<u64>,
-//     pub data: String,
-//     pub program_id_index: u64,
-// }
-
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Transaction {
@@ -101,6 +126,14 @@ pub struct BlockTransaction {
     pub transaction: Transaction,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureInfo {
+    pub signature: String,
+    pub slot: i64,
+    pub err: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockTransactions {
@@ -127,3 +160,30 @@ impl TokenBalance {
 pub struct TokenAmount {
     pub amount: BigIntValue,
 }
+
+// getTokenAccountsByOwner with jsonParsed encoding
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TokenAccount {
+    pub pubkey: String,
+    pub account: ValueData<ParsedData<ParsedInfo<TokenAccountInfo>>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenAccountInfo {
+    pub mint: String,
+    pub token_amount: TokenAccountAmount,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TokenAccountAmount {
+    pub amount: String,
+    pub decimals: i32,
+}
+
+impl TokenAccountInfo {
+    // Metaplex NFTs are mints with supply of one and no decimals
+    pub fn is_nft(&self) -> bool {
+        self.token_amount.amount == "1" && self.token_amount.decimals == 0
+    }
+}
@@ -0,0 +1,262 @@
+use std::error::Error;
+
+use crate::{ChainBlockProvider, ChainTokenDataProvider};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use num_bigint::BigUint;
+use primitives::{chain::Chain, Asset, AssetId, AssetType, TransactionState, TransactionType};
+use reqwest_middleware::ClientWithMiddleware;
+
+use super::model::{AssetRecord, Embedded, NodeStatus, Payment};
+
+const PAYMENTS_LIMIT: usize = 200;
+const ASSET_DECIMALS: i32 = 7;
+const TOKEN_ID_SEPARATOR: &str = "::";
+
+pub struct StellarClient {
+    url: String,
+    client: ClientWithMiddleware,
+}
+
+impl StellarClient {
+    pub fn new(client: ClientWithMiddleware, url: String) -> Self {
+        Self { url, client }
+    }
+
+    // token id is `issuer::code`
+    pub fn token_id(issuer: &str, code: &str) -> String {
+        format!("{}{}{}", issuer, TOKEN_ID_SEPARATOR, code)
+    }
+
+    // Horizon returns amounts as decimals with 7 digits precision
+    pub fn parse_amount(amount: &str) -> Option<String> {
+        let (integer, fraction) = amount.split_once('.').unwrap_or((amount, ""));
+        if fraction.len() > ASSET_DECIMALS as usize {
+            return None;
+        }
+        let value = format!("{}{:0<width$}", integer, fraction, width = ASSET_DECIMALS as usize);
+        Some(value.parse::<BigUint>().ok()?.to_string())
+    }
+
+    pub fn map_transaction(&self, payment: Payment, block_number: i64) -> Option<primitives::Transaction> {
+        let transaction = payment.transaction.clone()?;
+        let (asset_id, from, to, amount) = match payment.payment_type.as_str() {
+            "create_account" => (self.get_chain().as_asset_id(), payment.funder?, payment.account?, payment.starting_balance?),
+            "payment" => {
+                let asset_id = match payment.asset_type?.as_str() {
+                    "native" => self.get_chain().as_asset_id(),
+                    _ => AssetId::from_token(self.get_chain(), &Self::token_id(&payment.asset_issuer?, &payment.asset_code?)),
+                };
+                (asset_id, payment.from?, payment.to?, payment.amount?)
+            }
+            _ => return None,
+        };
+        let state = if payment.transaction_successful {
+            TransactionState::Confirmed
+        } else {
+            TransactionState::Failed
+        };
+        let memo = match transaction.memo_type.as_str() {
+            "text" | "id" => transaction.memo,
+            _ => None,
+        };
+        let created_at = DateTime::parse_from_rfc3339(&payment.created_at).ok()?.with_timezone(&Utc);
+
+        let transaction = primitives::Transaction::new(
+            payment.transaction_hash,
+            asset_id,
+            from,
+            to,
+            None,
+            TransactionType::Transfer,
+            state,
+            block_number.to_string(),
+            transaction.source_account_sequence,
+            transaction.fee_charged,
+            self.get_chain().as_asset_id(),
+            Self::parse_amount(&amount)?,
+            memo,
+            None,
+            created_at,
+        );
+        Some(transaction)
+    }
+
+    // Payments of a transaction share its hash, every payment after the first gets an indexed id
+    pub fn map_transactions(&self, payments: Vec<Payment>, block_number: i64) -> Vec<primitives::Transaction> {
+        let mut groups: Vec<(String, Vec<primitives::Transaction>)> = vec![];
+        for payment in payments {
+            let hash = payment.transaction_hash.clone();
+            let Some(transaction) = self.map_transaction(payment, block_number) else {
+                continue;
+            };
+            match groups.iter_mut().find(|(x, _)| *x == hash) {
+                Some((_, transactions)) => transactions.push(transaction),
+                None => groups.push((hash, vec![transaction])),
+            }
+        }
+        groups
+            .into_iter()
+            .flat_map(|(_, transactions)| transactions.into_iter().enumerate().map(|(index, x)| x.with_index(index)))
+            .collect()
+    }
+
+    pub async fn get_node_status(&self) -> Result<NodeStatus, Box<dyn Error + Send + Sync>> {
+        Ok(self.client.get(self.url.clone()).send().await?.json::<NodeStatus>().await?)
+    }
+
+    pub async fn get_ledger_payments(&self, ledger: i64) -> Result<Vec<Payment>, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/ledgers/{}/payments", self.url, ledger);
+        let mut cursor: Option<String> = None;
+        let mut results: Vec<Payment> = Vec::new();
+        loop {
+            let mut query = vec![
+                ("limit", PAYMENTS_LIMIT.to_string()),
+                ("include_failed", true.to_string()),
+                ("join", "transactions".to_string()),
+            ];
+            if let Some(cursor) = cursor {
+                query.push(("cursor", cursor));
+            }
+            let payments = self
+                .client
+                .get(url.clone())
+                .query(&query)
+                .send()
+                .await?
+                .json::<Embedded<Payment>>()
+                .await?
+                .embedded
+                .records;
+
+            let is_last_page = payments.len() < PAYMENTS_LIMIT;
+            cursor = payments.last().map(|x| x.paging_token.clone());
+            results.extend(payments);
+
+            if is_last_page {
+                return Ok(results);
+            }
+        }
+    }
+
+    pub async fn get_asset(&self, code: &str, issuer: &str) -> Result<AssetRecord, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/assets", self.url);
+        let query = [("asset_code", code), ("asset_issuer", issuer)];
+        let assets = self.client.get(url).query(&query).send().await?.json::<Embedded<AssetRecord>>().await?;
+        Ok(assets.embedded.records.first().cloned().ok_or("asset not found")?)
+    }
+}
+
+#[async_trait]
+impl ChainBlockProvider for StellarClient {
+    fn get_chain(&self) -> Chain {
+        Chain::Stellar
+    }
+
+    async fn get_latest_block(&self) -> Result<i64, Box<dyn Error + Send + Sync>> {
+        Ok(self.get_node_status().await?.history_latest_ledger)
+    }
+
+    async fn get_transactions(&self, block_number: i64) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
+        let payments = self.get_ledger_payments(block_number).await?;
+        Ok(self.map_transactions(payments, block_number))
+    }
+}
+
+#[async_trait]
+impl ChainTokenDataProvider for StellarClient {
+    async fn get_token_data(&self, chain: Chain, token_id: String) -> Result<Asset, Box<dyn Error + Send + Sync>> {
+        let (issuer, code) = token_id.split_once(TOKEN_ID_SEPARATOR).ok_or("invalid token id")?;
+        let asset = self.get_asset(code, issuer).await?;
+
+        Ok(Asset {
+            id: AssetId::from_token(chain, &Self::token_id(&asset.asset_issuer, &asset.asset_code)),
+            name: asset.asset_code.clone(),
+            symbol: asset.asset_code,
+            decimals: ASSET_DECIMALS,
+            asset_type: AssetType::TOKEN,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_amount() {
+        assert_eq!(StellarClient::parse_amount("10.0000000"), Some("100000000".to_string()));
+        assert_eq!(StellarClient::parse_amount("0.0000001"), Some("1".to_string()));
+        assert_eq!(StellarClient::parse_amount("25.5"), Some("255000000".to_string()));
+        assert_eq!(StellarClient::parse_amount("7"), Some("70000000".to_string()));
+        assert_eq!(StellarClient::parse_amount("0.00000001"), None);
+    }
+
+    #[test]
+    fn test_map_transaction() {
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/stellar/ledger_55000000_payments.json");
+        let payments: Embedded<Payment> = serde_json::from_reader(std::fs::File::open(file).unwrap()).unwrap();
+
+        let client = StellarClient::new(
+            reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build(),
+            "http://localhost".into(),
+        );
+        let transactions = payments
+            .embedded
+            .records
+            .into_iter()
+            .flat_map(|x| client.map_transaction(x, 55000000))
+            .collect::<Vec<_>>();
+
+        // path payments are skipped
+        assert_eq!(transactions.len(), 4);
+
+        let create_account = &transactions[0];
+        assert_eq!(create_account.asset_id, Chain::Stellar.as_asset_id());
+        assert_eq!(create_account.from, "GCKFBEIYV2U22IO2BJ4KVJOIP7XPWQGQFKKWXR6DOSJBV7STMAQSMTGG");
+        assert_eq!(create_account.to, "GDQP2KPQGKIHYJGXNUIYOMHARUARCA7DJT5FO2FFOOKY3B2WSQHG4W37");
+        assert_eq!(create_account.value, "20000000");
+        assert_eq!(create_account.memo, None);
+
+        let payment = &transactions[1];
+        assert_eq!(payment.value, "1255000000");
+        assert_eq!(payment.fee, "100");
+        assert_eq!(payment.sequence, "98765432109876545");
+        assert_eq!(payment.memo, Some("1234567".to_string()));
+        assert_eq!(payment.state, TransactionState::Confirmed);
+
+        let token_payment = &transactions[2];
+        assert_eq!(
+            token_payment.asset_id,
+            AssetId::from_token(Chain::Stellar, "GA5ZSEJYB37JRC5AVCIA5MOP4RHTM335X2KGX3IHOJAPP5RE34K4KZVN::USDC")
+        );
+        assert_eq!(token_payment.value, "100000000");
+        assert_eq!(token_payment.memo, Some("invoice 42".to_string()));
+
+        let failed = &transactions[3];
+        assert_eq!(failed.state, TransactionState::Failed);
+        assert_eq!(failed.memo, None);
+    }
+
+    #[test]
+    fn test_map_transactions_multiple_payments() {
+        let file = concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/stellar/ledger_55000001_payments.json");
+        let payments: Embedded<Payment> = serde_json::from_reader(std::fs::File::open(file).unwrap()).unwrap();
+
+        let client = StellarClient::new(
+            reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build(),
+            "http://localhost".into(),
+        );
+        let transactions = client.map_transactions(payments.embedded.records, 55000001);
+        let hash = "5b7e1c9a3d2f4e6a8b0c1d2e3f4a5b6c7d8e9f0a1b2c3d4e5f6a7b8c9d0e1f2a";
+
+        // path payment in the middle of the batch is skipped
+        assert_eq!(transactions.len(), 3);
+        assert_eq!(transactions[0].id, format!("stellar_{}", hash));
+        assert_eq!(transactions[0].value, "500000000");
+        assert_eq!(transactions[1].id, format!("stellar_{}_1", hash));
+        assert_eq!(transactions[1].to, "GCKFBEIYV2U22IO2BJ4KVJOIP7XPWQGQFKKWXR6DOSJBV7STMAQSMTGG");
+        assert_eq!(transactions[1].value, "200000000");
+        assert_eq!(transactions[2].id, "stellar_d1e2f3a4b5c6d7e8f9a0b1c2d3e4f5a6b7c8d9e0f1a2b3c4d5e6f7a8b9c0d1e2");
+    }
+}
@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeStatus {
+    pub history_latest_ledger: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Embedded<T> {
+    #[serde(rename = "_embedded")]
+    pub embedded: Records<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Records<T> {
+    pub records: Vec<T>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Payment {
+    pub paging_token: String,
+    #[serde(rename = "type")]
+    pub payment_type: String,
+    pub transaction_hash: String,
+    pub transaction_successful: bool,
+    pub created_at: String,
+    // payment
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: Option<String>,
+    pub asset_type: Option<String>,
+    pub asset_code: Option<String>,
+    pub asset_issuer: Option<String>,
+    // create_account
+    pub funder: Option<String>,
+    pub account: Option<String>,
+    pub starting_balance: Option<String>,
+    pub transaction: Option<PaymentTransaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentTransaction {
+    pub fee_charged: String,
+    pub source_account_sequence: String,
+    pub memo_type: String,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AssetRecord {
+    pub asset_code: String,
+    pub asset_issuer: String,
+}
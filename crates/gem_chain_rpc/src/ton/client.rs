@@ -1,14 +1,20 @@
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 
-use crate::{ChainBlockProvider, ChainTokenDataProvider};
+use crate::nft;
+use crate::{ChainAddressHistoryProvider, ChainBalanceProvider, ChainBlockProvider, ChainNFTProvider, ChainTokenDataProvider};
 use async_trait::async_trait;
 use chrono::Utc;
 use gem_ton::address::TonAddress;
-use primitives::{chain::Chain, Asset, AssetId, AssetType, TransactionState, TransactionType};
+use primitives::{chain::Chain, Asset, AssetBalance, AssetId, AssetType, NFTCollectible, NFTCollection, NFTImage, NFTType, TransactionState, TransactionType};
 
 use reqwest_middleware::ClientWithMiddleware;
 
-use super::model::{Blocks, Chainhead, JettonInfo, Shards, Transaction, Transactions};
+use super::decoder::{self, MessageBody};
+use super::model::{Account, Blocks, Chainhead, JettonBalances, JettonInfo, JettonWalletData, NftItem, NftItems, Shards, Transaction, Transactions};
+
+const NFT_ITEMS_LIMIT: i32 = 1000;
+const NFT_PREVIEW_RESOLUTION: &str = "500x500";
 
 pub struct TonClient {
     url: String,
@@ -25,44 +31,135 @@ impl TonClient {
         Some(address.to_base64_url())
     }
 
-    pub fn map_transaction(&self, transaction: Transaction) -> Option<primitives::Transaction> {
-        if transaction.transaction_type == "TransOrd" && transaction.out_msgs.len() == 1 && transaction.out_msgs.first()?.op_code.is_none() {
-            let asset_id = self.get_chain().as_asset_id();
-            let out_message = transaction.out_msgs.first()?;
-            let from = Self::parse_address(&out_message.source.address)?;
-            let to: String = match &out_message.destination {
-                Some(destination) => Self::parse_address(&destination.address)?,
-                None => "".into(),
-            };
-            let value = out_message.value.to_string();
-            let state = if transaction.success {
-                TransactionState::Confirmed
-            } else {
-                TransactionState::Failed
-            };
-            //TODO: Implement memo
-            let memo: Option<String> = None; //out_message.decoded_body.clone().text;
-
-            let transaction = primitives::Transaction::new(
-                transaction.hash,
-                asset_id.clone(),
-                from,
-                to,
-                None,
-                TransactionType::Transfer,
-                state,
-                transaction.block.to_string(),
-                0.to_string(),
-                transaction.total_fees.to_string(),
-                asset_id,
-                value,
-                memo,
-                None,
-                Utc::now(),
-            );
-            return Some(transaction);
+    // Wallet transactions with a single out message: TON transfers with an optional comment and jetton transfers
+    pub fn map_transaction(&self, transaction: Transaction, jetton_masters: &HashMap<String, String>) -> Option<primitives::Transaction> {
+        if transaction.transaction_type != "TransOrd" || transaction.out_msgs.len() != 1 {
+            return None;
+        }
+        let chain = self.get_chain();
+        let out_message = transaction.out_msgs.first()?;
+        let from = Self::parse_address(&out_message.source.address)?;
+        let destination: String = match &out_message.destination {
+            Some(destination) => Self::parse_address(&destination.address)?,
+            None => "".into(),
+        };
+
+        let (asset_id, to, value, memo) = match decoder::decode_message_body(out_message.raw_body.as_deref()).ok()? {
+            MessageBody::Empty => (chain.as_asset_id(), destination, out_message.value.to_string(), None),
+            MessageBody::Comment(comment) => (chain.as_asset_id(), destination, out_message.value.to_string(), Some(comment)),
+            // destination of the out message is the sender jetton wallet, the recipient owner is in the body
+            MessageBody::JettonTransfer(transfer) => (
+                AssetId::from_token(chain, jetton_masters.get(&destination)?),
+                transfer.destination.to_base64_url(),
+                transfer.amount.to_string(),
+                transfer.comment,
+            ),
+            // notifications are sent by the recipient jetton wallet for a transfer already indexed on the sender side
+            MessageBody::JettonTransferNotification(_) | MessageBody::Unknown(_) => return None,
+        };
+        let state = if transaction.success {
+            TransactionState::Confirmed
+        } else {
+            TransactionState::Failed
+        };
+
+        let transaction = primitives::Transaction::new(
+            transaction.hash,
+            asset_id,
+            from,
+            to,
+            None,
+            TransactionType::Transfer,
+            state,
+            transaction.block.to_string(),
+            0.to_string(),
+            transaction.total_fees.to_string(),
+            chain.as_asset_id(),
+            value,
+            memo,
+            None,
+            Utc::now(),
+        );
+        Some(transaction)
+    }
+
+    // Internal transfers received by the account: TON transfers with an optional comment and
+    // jetton transfer notifications sent by the jetton wallet of the account
+    pub fn map_incoming_transaction(&self, transaction: Transaction, jetton_masters: &HashMap<String, String>) -> Option<primitives::Transaction> {
+        if transaction.transaction_type != "TransOrd" {
+            return None;
         }
-        None
+        let in_message = transaction.in_msg.as_ref().filter(|x| !x.bounced)?;
+        let chain = self.get_chain();
+        let source = Self::parse_address(&in_message.source.as_ref()?.address)?;
+        let to = Self::parse_address(&in_message.destination.as_ref()?.address)?;
+
+        let (asset_id, from, value, memo) = match decoder::decode_message_body(in_message.raw_body.as_deref()).ok()? {
+            MessageBody::Empty if in_message.value > 0 => (chain.as_asset_id(), source, in_message.value.to_string(), None),
+            MessageBody::Comment(comment) if in_message.value > 0 => (chain.as_asset_id(), source, in_message.value.to_string(), Some(comment)),
+            // source of the message is the recipient jetton wallet, the sender owner is in the body
+            MessageBody::JettonTransferNotification(notification) => (
+                AssetId::from_token(chain, jetton_masters.get(&source)?),
+                notification.sender.to_base64_url(),
+                notification.amount.to_string(),
+                notification.comment,
+            ),
+            _ => return None,
+        };
+        let state = if transaction.success {
+            TransactionState::Confirmed
+        } else {
+            TransactionState::Failed
+        };
+
+        Some(primitives::Transaction::new(
+            transaction.hash,
+            asset_id,
+            from,
+            to,
+            None,
+            TransactionType::Transfer,
+            state,
+            transaction.block.to_string(),
+            0.to_string(),
+            transaction.total_fees.to_string(),
+            chain.as_asset_id(),
+            value,
+            memo,
+            None,
+            Utc::now(),
+        ))
+    }
+
+    // Jetton wallets receiving a transfer op from their owner or notifying their owner of a received transfer
+    fn get_jetton_wallets(transactions: &[Transaction]) -> HashSet<String> {
+        let senders = transactions
+            .iter()
+            .flat_map(|x| &x.out_msgs)
+            .filter(|x| matches!(decoder::decode_message_body(x.raw_body.as_deref()), Ok(MessageBody::JettonTransfer(_))))
+            .flat_map(|x| Self::parse_address(&x.destination.as_ref()?.address));
+        let recipients = transactions
+            .iter()
+            .flat_map(|x| &x.in_msg)
+            .filter(|x| {
+                matches!(
+                    decoder::decode_message_body(x.raw_body.as_deref()),
+                    Ok(MessageBody::JettonTransferNotification(_))
+                )
+            })
+            .flat_map(|x| Self::parse_address(&x.source.as_ref()?.address));
+        senders.chain(recipients).collect()
+    }
+
+    // Jetton master by jetton wallet address, wallets that fail to resolve are left out
+    async fn get_jetton_masters(&self, transactions: &[Transaction]) -> HashMap<String, String> {
+        let wallets = Self::get_jetton_wallets(transactions).into_iter().collect::<Vec<_>>();
+        let results = futures::future::join_all(wallets.iter().map(|x| self.get_jetton_wallet_data(x))).await;
+        wallets
+            .into_iter()
+            .zip(results)
+            .flat_map(|(wallet, result)| Some((wallet, Self::parse_address(&result.ok()?.decoded.jetton)?)))
+            .collect()
     }
 
     pub async fn get_master_head(&self) -> Result<Chainhead, Box<dyn Error + Send + Sync>> {
@@ -97,10 +194,86 @@ impl TonClient {
         Ok(response)
     }
 
+    pub async fn get_account_transactions(&self, address: &str, limit: usize) -> Result<Transactions, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v2/blockchain/accounts/{}/transactions?limit={}", self.url, address, limit);
+        Ok(self.client.get(url).send().await?.json::<Transactions>().await?)
+    }
+
     pub async fn get_token_info(&self, token_id: String) -> Result<JettonInfo, Box<dyn Error + Send + Sync>> {
         let url = format!("{}/v2/jettons/{}", self.url, token_id);
         Ok(self.client.get(url).send().await?.json::<JettonInfo>().await?)
     }
+
+    pub async fn get_jetton_wallet_data(&self, address: &str) -> Result<JettonWalletData, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v2/blockchain/accounts/{}/methods/get_wallet_data", self.url, address);
+        Ok(self.client.get(url).send().await?.json::<JettonWalletData>().await?)
+    }
+
+    pub async fn get_account(&self, address: &str) -> Result<Account, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v2/accounts/{}", self.url, address);
+        Ok(self.client.get(url).send().await?.json::<Account>().await?)
+    }
+
+    pub async fn get_jetton_balances(&self, address: &str) -> Result<JettonBalances, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v2/accounts/{}/jettons", self.url, address);
+        Ok(self.client.get(url).send().await?.json::<JettonBalances>().await?)
+    }
+
+    pub async fn get_nft_items(&self, address: &str) -> Result<NftItems, Box<dyn Error + Send + Sync>> {
+        let url = format!(
+            "{}/v2/accounts/{}/nfts?limit={}&offset=0&indirect_ownership=false",
+            self.url, address, NFT_ITEMS_LIMIT
+        );
+        Ok(self.client.get(url).send().await?.json::<NftItems>().await?)
+    }
+
+    // Items outside of a collection are listed as a collection of their own
+    pub fn map_nft(&self, item: NftItem) -> Option<(NFTCollection, NFTCollectible)> {
+        let chain = self.get_chain();
+        let address = Self::parse_address(&item.address)?;
+        let collection_address = match &item.collection {
+            Some(collection) => Self::parse_address(&collection.address)?,
+            None => address.clone(),
+        };
+        let image = item.metadata.image();
+        let image = NFTImage {
+            preview_image_url: item
+                .previews
+                .iter()
+                .find(|x| x.resolution == NFT_PREVIEW_RESOLUTION)
+                .map(|x| x.url.clone())
+                .unwrap_or(image.preview_image_url),
+            ..image
+        };
+        let name = item.metadata.name.clone().unwrap_or_default();
+
+        let collection = NFTCollection {
+            id: collection_address.clone(),
+            name: item.collection.as_ref().map(|x| x.name.clone()).unwrap_or(name.clone()),
+            description: item.collection.as_ref().map(|x| x.description.clone()).unwrap_or_default(),
+            chain,
+            image: image.clone(),
+            explorer_url: nft::explorer_token_url(chain, &collection_address),
+            count: 1,
+        };
+        let collectible = NFTCollectible {
+            id: address.clone(),
+            collection_id: collection_address,
+            name,
+            description: item.metadata.description.clone().unwrap_or_default(),
+            chain,
+            image,
+            collectible_type: NFTType::TON,
+            attributes: item.metadata.attributes(),
+            explorer_url: nft::explorer_token_url(chain, &address),
+        };
+        Some((collection, collectible))
+    }
+
+    async fn get_nfts(&self, address: &str) -> Result<Vec<(NFTCollection, NFTCollectible)>, Box<dyn Error + Send + Sync>> {
+        let items = self.get_nft_items(address).await?.nft_items;
+        Ok(items.into_iter().flat_map(|x| self.map_nft(x)).collect())
+    }
 }
 
 #[async_trait]
@@ -128,15 +301,14 @@ impl ChainBlockProvider for TonClient {
         //     .collect::<Vec<Transactions>>()
         //     .into_iter()
         //     .flat_map(|x| x.transactions)
-        //     .flat_map(|x| self.map_transaction(x))
+        //     .flat_map(|x| self.map_transaction(x, &jetton_masters))
         //     .collect::<Vec<primitives::Transaction>>();
 
-        let transactions = self
-            .get_transactions_in_all_blocks(block.to_string())
-            .await?
-            .transactions
+        let transactions = self.get_transactions_in_all_blocks(block.to_string()).await?.transactions;
+        let jetton_masters = self.get_jetton_masters(&transactions).await;
+        let transactions = transactions
             .into_iter()
-            .flat_map(|x| self.map_transaction(x))
+            .flat_map(|x| self.map_transaction(x, &jetton_masters))
             .collect::<Vec<primitives::Transaction>>();
 
         Ok(transactions)
@@ -160,3 +332,265 @@ impl ChainTokenDataProvider for TonClient {
         })
     }
 }
+
+#[async_trait]
+impl ChainBalanceProvider for TonClient {
+    async fn get_balances(&self, address: String, token_ids: Vec<String>) -> Result<Vec<AssetBalance>, Box<dyn Error + Send + Sync>> {
+        let chain = self.get_chain();
+        let native = self.get_account(&address).await?.balance;
+        let jettons = if token_ids.is_empty() {
+            HashMap::new()
+        } else {
+            // jetton masters are returned in raw form, token ids are user friendly addresses
+            self.get_jetton_balances(&address)
+                .await?
+                .balances
+                .into_iter()
+                .flat_map(|x| Some((Self::parse_address(&x.jetton.address)?, x.balance)))
+                .collect::<HashMap<_, _>>()
+        };
+
+        Ok(std::iter::once(AssetBalance::new(chain.as_asset_id(), native.to_string()))
+            .chain(token_ids.into_iter().map(|token_id| {
+                let balance = jettons.get(&token_id).cloned().unwrap_or("0".to_string());
+                AssetBalance::new(AssetId::from_token(chain, &token_id), balance)
+            }))
+            .collect())
+    }
+}
+
+#[async_trait]
+impl ChainNFTProvider for TonClient {
+    async fn get_nft_collections(&self, address: String) -> Result<Vec<NFTCollection>, Box<dyn Error + Send + Sync>> {
+        Ok(nft::group_collections(&self.get_nfts(&address).await?))
+    }
+
+    async fn get_nft_collectibles(&self, collection_id: String, address: String) -> Result<Vec<NFTCollectible>, Box<dyn Error + Send + Sync>> {
+        Ok(nft::filter_collectibles(self.get_nfts(&address).await?, &collection_id))
+    }
+}
+
+#[async_trait]
+impl ChainAddressHistoryProvider for TonClient {
+    // Transfers sent by the account from its out message, received ones from its in message
+    async fn get_address_transactions(&self, address: String, limit: usize) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
+        let transactions = self.get_account_transactions(&address, limit).await?.transactions;
+        let jetton_masters = self.get_jetton_masters(&transactions).await;
+        Ok(transactions
+            .into_iter()
+            .flat_map(|x| {
+                self.map_transaction(x.clone(), &jetton_masters)
+                    .or_else(|| self.map_incoming_transaction(x, &jetton_masters))
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gem_ton::cell::{BagOfCells, CellBuilder};
+    use num_bigint::BigUint;
+
+    #[test]
+    fn test_map_nft() {
+        let client = TonClient::new(reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build(), "".into());
+        let items: NftItems = serde_json::from_value(serde_json::json!({
+            "nft_items": [{
+                "address": "0:7f1a8c38a4f6e4f0fd24e5b64de1a4f4b8cde71e93f0a1c27a6f9b3c8e1f2d4a",
+                "index": 42,
+                "owner": {"address": "0:83dfd552e63729b472fcbcc8c45ebcc6691702558b68ec7527e1ba403a0f31a8"},
+                "collection": {
+                    "address": "0:0e41dc1dc3c9067ed24248580e12b3359818d83dee0304fabcf80845eafafdb2",
+                    "name": "TON DNS Domains",
+                    "description": "*.ton domains"
+                },
+                "verified": true,
+                "metadata": {
+                    "name": "gem.ton",
+                    "image": "https://dns.ton.org/preview/gem.png",
+                    "attributes": [{"trait_type": "length", "value": 3}]
+                },
+                "previews": [
+                    {"resolution": "100x100", "url": "https://cache.tonapi.io/100.png"},
+                    {"resolution": "500x500", "url": "https://cache.tonapi.io/500.png"}
+                ]
+            }]
+        }))
+        .unwrap();
+
+        let (collection, collectible) = client.map_nft(items.nft_items.into_iter().next().unwrap()).unwrap();
+
+        assert_eq!(
+            collection.id,
+            TonClient::parse_address("0:0e41dc1dc3c9067ed24248580e12b3359818d83dee0304fabcf80845eafafdb2").unwrap()
+        );
+        assert_eq!(collection.name, "TON DNS Domains");
+        assert_eq!(collectible.collection_id, collection.id);
+        assert_eq!(collectible.name, "gem.ton");
+        assert_eq!(collectible.image.image_url, "https://dns.ton.org/preview/gem.png");
+        assert_eq!(collectible.image.preview_image_url, "https://cache.tonapi.io/500.png");
+        assert_eq!(collectible.attributes[0].value, "3");
+    }
+
+    #[test]
+    fn test_map_transaction() {
+        let client = TonClient::new(reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build(), "".into());
+        let owner = "0:83dfd552e63729b472fcbcc8c45ebcc6691702558b68ec7527e1ba403a0f31a8";
+        let jetton_wallet = "0:0e41dc1dc3c9067ed24248580e12b3359818d83dee0304fabcf80845eafafdb2";
+        let jetton_master = "0:b113a994b5024a16719f69139328eb759596c38a25f59028b146fecdc3621dfe";
+        let recipient = TonAddress::from_hex_str("0:7f1a8c38a4f6e4f0fd24e5b64de1a4f4b8cde71e93f0a1c27a6f9b3c8e1f2d4a").unwrap();
+        let encode = |cell| hex::encode(BagOfCells::from_root(cell).serialize(true).unwrap());
+        let comment = CellBuilder::new()
+            .store_u32(32, decoder::OP_TEXT_COMMENT)
+            .unwrap()
+            .store_string("memo")
+            .unwrap()
+            .build()
+            .unwrap();
+        let jetton_transfer = CellBuilder::new()
+            .store_u32(32, decoder::OP_JETTON_TRANSFER)
+            .unwrap()
+            .store_u64(64, 0)
+            .unwrap()
+            .store_coins(&BigUint::from(2_000_000u32))
+            .unwrap()
+            .store_address(&recipient)
+            .unwrap()
+            .store_address(&TonAddress::NULL)
+            .unwrap()
+            .store_bit(false)
+            .unwrap()
+            .store_coins(&BigUint::from(1u32))
+            .unwrap()
+            .store_bit(true)
+            .unwrap()
+            .store_child(comment.clone())
+            .unwrap()
+            .build()
+            .unwrap();
+        let transaction = |destination: &str, raw_body: String| -> Transaction {
+            serde_json::from_value(serde_json::json!({
+                "hash": "abc",
+                "block": "(0,8000000000000000,1)",
+                "transaction_type": "TransOrd",
+                "total_fees": 100,
+                "success": true,
+                "out_msgs": [{
+                    "source": {"address": owner},
+                    "destination": {"address": destination},
+                    "value": 50000000,
+                    "raw_body": raw_body
+                }]
+            }))
+            .unwrap()
+        };
+        let jetton_masters = HashMap::from([(
+            TonClient::parse_address(jetton_wallet).unwrap(),
+            TonClient::parse_address(jetton_master).unwrap(),
+        )]);
+
+        let result = client.map_transaction(transaction(owner, encode(comment)), &jetton_masters).unwrap();
+
+        assert_eq!(result.asset_id, Chain::Ton.as_asset_id());
+        assert_eq!(result.value, "50000000");
+        assert_eq!(result.memo, Some("memo".into()));
+
+        let result = client
+            .map_transaction(transaction(jetton_wallet, encode(jetton_transfer.clone())), &jetton_masters)
+            .unwrap();
+
+        assert_eq!(
+            result.asset_id,
+            AssetId::from_token(Chain::Ton, &TonClient::parse_address(jetton_master).unwrap())
+        );
+        assert_eq!(result.from, TonClient::parse_address(owner).unwrap());
+        assert_eq!(result.to, recipient.to_base64_url());
+        assert_eq!(result.value, "2000000");
+        assert_eq!(result.memo, Some("memo".into()));
+        assert_eq!(result.fee_asset_id, Chain::Ton.as_asset_id());
+        // unresolved jetton master
+        assert!(client
+            .map_transaction(transaction(jetton_wallet, encode(jetton_transfer)), &HashMap::new())
+            .is_none());
+    }
+
+    #[test]
+    fn test_map_incoming_transaction() {
+        let client = TonClient::new(reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build(), "".into());
+        let owner = "0:83dfd552e63729b472fcbcc8c45ebcc6691702558b68ec7527e1ba403a0f31a8";
+        let sender = "0:7f1a8c38a4f6e4f0fd24e5b64de1a4f4b8cde71e93f0a1c27a6f9b3c8e1f2d4a";
+        let jetton_wallet = "0:0e41dc1dc3c9067ed24248580e12b3359818d83dee0304fabcf80845eafafdb2";
+        let jetton_master = "0:b113a994b5024a16719f69139328eb759596c38a25f59028b146fecdc3621dfe";
+        let encode = |cell| hex::encode(BagOfCells::from_root(cell).serialize(true).unwrap());
+        let comment = CellBuilder::new()
+            .store_u32(32, decoder::OP_TEXT_COMMENT)
+            .unwrap()
+            .store_string("memo")
+            .unwrap()
+            .build()
+            .unwrap();
+        let notification = CellBuilder::new()
+            .store_u32(32, decoder::OP_JETTON_TRANSFER_NOTIFICATION)
+            .unwrap()
+            .store_u64(64, 0)
+            .unwrap()
+            .store_coins(&BigUint::from(3_000_000u32))
+            .unwrap()
+            .store_address(&TonAddress::from_hex_str(sender).unwrap())
+            .unwrap()
+            .store_bit(false)
+            .unwrap()
+            .build()
+            .unwrap();
+        let transaction = |source: &str, value: i64, raw_body: String| -> Transaction {
+            serde_json::from_value(serde_json::json!({
+                "hash": "abc",
+                "block": "(0,8000000000000000,1)",
+                "transaction_type": "TransOrd",
+                "total_fees": 100,
+                "success": true,
+                "in_msg": {
+                    "source": {"address": source},
+                    "destination": {"address": owner},
+                    "value": value,
+                    "raw_body": raw_body
+                },
+                "out_msgs": []
+            }))
+            .unwrap()
+        };
+        let jetton_masters = HashMap::from([(
+            TonClient::parse_address(jetton_wallet).unwrap(),
+            TonClient::parse_address(jetton_master).unwrap(),
+        )]);
+
+        let incoming = transaction(sender, 50000000, encode(comment));
+        assert!(client.map_transaction(incoming.clone(), &jetton_masters).is_none());
+        let result = client.map_incoming_transaction(incoming, &jetton_masters).unwrap();
+        assert_eq!(result.asset_id, Chain::Ton.as_asset_id());
+        assert_eq!(result.from, TonClient::parse_address(sender).unwrap());
+        assert_eq!(result.to, TonClient::parse_address(owner).unwrap());
+        assert_eq!(result.value, "50000000");
+        assert_eq!(result.memo, Some("memo".into()));
+
+        let incoming = transaction(jetton_wallet, 1, encode(notification));
+        assert_eq!(
+            TonClient::get_jetton_wallets(std::slice::from_ref(&incoming)),
+            HashSet::from([TonClient::parse_address(jetton_wallet).unwrap()])
+        );
+        let result = client.map_incoming_transaction(incoming, &jetton_masters).unwrap();
+        assert_eq!(
+            result.asset_id,
+            AssetId::from_token(Chain::Ton, &TonClient::parse_address(jetton_master).unwrap())
+        );
+        assert_eq!(result.from, TonAddress::from_hex_str(sender).unwrap().to_base64_url());
+        assert_eq!(result.to, TonClient::parse_address(owner).unwrap());
+        assert_eq!(result.value, "3000000");
+
+        // external message of an outgoing transaction has no source
+        let mut outgoing = transaction(sender, 0, "".into());
+        outgoing.in_msg.as_mut().unwrap().source = None;
+        assert!(client.map_incoming_transaction(outgoing, &jetton_masters).is_none());
+    }
+}
@@ -0,0 +1,204 @@
+use gem_ton::address::TonAddress;
+use gem_ton::cell::{ArcCell, BagOfCells, Cell, CellParser, TonCellError};
+use num_bigint::BigUint;
+
+pub const OP_TEXT_COMMENT: u32 = 0x00000000;
+pub const OP_JETTON_TRANSFER: u32 = 0x0f8a7ea5;
+pub const OP_JETTON_TRANSFER_NOTIFICATION: u32 = 0x7362d09c;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JettonTransfer {
+    pub amount: BigUint,
+    pub destination: TonAddress,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JettonTransferNotification {
+    pub amount: BigUint,
+    pub sender: TonAddress,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MessageBody {
+    Empty,
+    Comment(String),
+    JettonTransfer(JettonTransfer),
+    JettonTransferNotification(JettonTransferNotification),
+    Unknown(u32),
+}
+
+// Message body as hex encoded BoC, missing body is an empty one
+pub fn decode_message_body(raw_body: Option<&str>) -> Result<MessageBody, TonCellError> {
+    match raw_body {
+        Some(raw_body) if !raw_body.is_empty() => decode_cell(BagOfCells::parse_hex(raw_body)?.single_root()?),
+        _ => Ok(MessageBody::Empty),
+    }
+}
+
+pub fn decode_cell(cell: &Cell) -> Result<MessageBody, TonCellError> {
+    let mut parser = cell.parser();
+    if parser.remaining_bits() < 32 {
+        return Ok(MessageBody::Empty);
+    }
+    let op = parser.load_u32(32)?;
+    match op {
+        OP_TEXT_COMMENT => Ok(MessageBody::Comment(load_text(cell, &mut parser)?)),
+        OP_JETTON_TRANSFER => {
+            // transfer#0f8a7ea5 query_id:uint64 amount:Coins destination:MsgAddress response_destination:MsgAddress
+            // custom_payload:(Maybe ^Cell) forward_ton_amount:Coins forward_payload:(Either Cell ^Cell)
+            let mut references = cell.references.iter();
+            parser.load_u64(64)?;
+            let amount = parser.load_coins()?;
+            let destination = parser.load_address()?;
+            parser.load_address()?;
+            if parser.load_bit()? {
+                references.next();
+            }
+            parser.load_coins()?;
+            let comment = load_forward_payload(&mut parser, references.next())?;
+            Ok(MessageBody::JettonTransfer(JettonTransfer { amount, destination, comment }))
+        }
+        OP_JETTON_TRANSFER_NOTIFICATION => {
+            // transfer_notification#7362d09c query_id:uint64 amount:Coins sender:MsgAddress forward_payload:(Either Cell ^Cell)
+            parser.load_u64(64)?;
+            let amount = parser.load_coins()?;
+            let sender = parser.load_address()?;
+            let comment = load_forward_payload(&mut parser, cell.references.first())?;
+            Ok(MessageBody::JettonTransferNotification(JettonTransferNotification { amount, sender, comment }))
+        }
+        _ => Ok(MessageBody::Unknown(op)),
+    }
+}
+
+// Text comment carried in the forward payload, either inline or in a reference
+fn load_forward_payload(parser: &mut CellParser, reference: Option<&ArcCell>) -> Result<Option<String>, TonCellError> {
+    if parser.remaining_bits() == 0 {
+        return Ok(None);
+    }
+    let body = if parser.load_bit()? {
+        match reference {
+            Some(cell) => decode_cell(cell)?,
+            None => return Ok(None),
+        }
+    } else {
+        if parser.remaining_bits() < 32 || parser.load_u32(32)? != OP_TEXT_COMMENT {
+            return Ok(None);
+        }
+        let remaining_bytes = parser.remaining_bytes();
+        MessageBody::Comment(parser.load_utf8_lossy(remaining_bytes)?)
+    };
+    match body {
+        MessageBody::Comment(comment) if !comment.is_empty() => Ok(Some(comment)),
+        _ => Ok(None),
+    }
+}
+
+// Snake encoded string, the rest of the cell followed by the chain of first references
+fn load_text(cell: &Cell, parser: &mut CellParser) -> Result<String, TonCellError> {
+    let remaining_bytes = parser.remaining_bytes();
+    let mut bytes = parser.load_bytes(remaining_bytes)?;
+    let mut next = cell.references.first();
+    while let Some(cell) = next {
+        bytes.extend(cell.data.iter().take(cell.bit_len / 8));
+        next = cell.references.first();
+    }
+    Ok(String::from_utf8_lossy(&bytes).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gem_ton::cell::CellBuilder;
+
+    const DESTINATION: &str = "0:83dfd552e63729b472fcbcc8c45ebcc6691702558b68ec7527e1ba403a0f31a8";
+
+    fn comment_cell(comment: &str) -> Cell {
+        CellBuilder::new()
+            .store_u32(32, OP_TEXT_COMMENT)
+            .unwrap()
+            .store_string(comment)
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn encode(cell: Cell) -> String {
+        hex::encode(BagOfCells::from_root(cell).serialize(true).unwrap())
+    }
+
+    #[test]
+    fn test_decode_comment() {
+        let body = encode(comment_cell("memo 123"));
+
+        assert_eq!(decode_message_body(Some(&body)).unwrap(), MessageBody::Comment("memo 123".into()));
+        assert_eq!(decode_message_body(None).unwrap(), MessageBody::Empty);
+    }
+
+    #[test]
+    fn test_decode_jetton_transfer() {
+        let destination = TonAddress::from_hex_str(DESTINATION).unwrap();
+        let cell = CellBuilder::new()
+            .store_u32(32, OP_JETTON_TRANSFER)
+            .unwrap()
+            .store_u64(64, 1)
+            .unwrap()
+            .store_coins(&BigUint::from(1_500_000u32))
+            .unwrap()
+            .store_address(&destination)
+            .unwrap()
+            .store_address(&TonAddress::NULL)
+            .unwrap()
+            .store_bit(false)
+            .unwrap()
+            .store_coins(&BigUint::from(1u32))
+            .unwrap()
+            .store_bit(true)
+            .unwrap()
+            .store_child(comment_cell("order 42"))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            decode_message_body(Some(&encode(cell))).unwrap(),
+            MessageBody::JettonTransfer(JettonTransfer {
+                amount: BigUint::from(1_500_000u32),
+                destination,
+                comment: Some("order 42".into()),
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_jetton_transfer_notification() {
+        let sender = TonAddress::from_hex_str(DESTINATION).unwrap();
+        let cell = CellBuilder::new()
+            .store_u32(32, OP_JETTON_TRANSFER_NOTIFICATION)
+            .unwrap()
+            .store_u64(64, 1)
+            .unwrap()
+            .store_coins(&BigUint::from(10u32))
+            .unwrap()
+            .store_address(&sender)
+            .unwrap()
+            .store_bit(false)
+            .unwrap()
+            .store_u32(32, OP_TEXT_COMMENT)
+            .unwrap()
+            .store_string("hi")
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            decode_message_body(Some(&encode(cell))).unwrap(),
+            MessageBody::JettonTransferNotification(JettonTransferNotification {
+                amount: BigUint::from(10u32),
+                sender,
+                comment: Some("hi".into()),
+            })
+        );
+    }
+}
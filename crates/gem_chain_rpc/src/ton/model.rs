@@ -1,3 +1,4 @@
+use crate::nft::NFTMetadata;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,10 +36,24 @@ pub struct Transaction {
     pub block: String,
     pub transaction_type: String,
     pub total_fees: i64,
+    #[serde(default)]
+    pub in_msg: Option<InMessage>,
     pub out_msgs: Vec<OutMessage>,
     pub success: bool,
 }
 
+// external messages sent to a wallet have no source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InMessage {
+    pub source: Option<Address>,
+    pub destination: Option<Address>,
+    pub value: i64,
+    #[serde(default)]
+    pub bounced: bool,
+    // hex encoded BoC
+    pub raw_body: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OutMessage {
     pub source: Address,
@@ -46,6 +61,8 @@ pub struct OutMessage {
     pub value: i64,
     pub op_code: Option<String>,
     pub decoded_op_name: Option<String>,
+    // hex encoded BoC
+    pub raw_body: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -64,3 +81,58 @@ pub struct JettonInfoMetadata {
     pub symbol: String,
     pub decimals: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JettonWalletData {
+    pub decoded: JettonWalletDataDecoded,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JettonWalletDataDecoded {
+    pub owner: String,
+    pub jetton: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub balance: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JettonBalances {
+    pub balances: Vec<JettonBalance>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JettonBalance {
+    pub balance: String,
+    pub jetton: Address,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NftItems {
+    pub nft_items: Vec<NftItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NftItem {
+    pub address: String,
+    pub collection: Option<NftItemCollection>,
+    #[serde(default)]
+    pub metadata: NFTMetadata,
+    #[serde(default)]
+    pub previews: Vec<NftPreview>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NftItemCollection {
+    pub address: String,
+    pub name: String,
+    pub description: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NftPreview {
+    pub resolution: String,
+    pub url: String,
+}
@@ -1,20 +1,27 @@
 use std::error::Error;
 
-use crate::{ChainBlockProvider, ChainTokenDataProvider};
+use crate::{ChainAddressHistoryProvider, ChainBlockProvider, ChainTokenDataProvider};
 use async_trait::async_trait;
 use chrono::Utc;
 use num_bigint::BigUint;
-use num_traits::Num;
+use num_traits::{Num, Zero};
 use primitives::{chain::Chain, Asset, AssetId, TransactionState, TransactionType};
 
 use super::{
     address::TronAddress,
-    model::{Block, BlockTransactions, BlockTransactionsInfo, Transaction, TransactionReceiptData},
+    model::{AccountTransactions, Block, BlockTransactions, BlockTransactionsInfo, Transaction, TransactionReceiptData, TronLog},
 };
 use reqwest_middleware::ClientWithMiddleware;
 
 const TRANSFER_CONTRACT: &str = "TransferContract";
 const TRIGGER_SMART_CONTRACT: &str = "TriggerSmartContract";
+const FREEZE_BALANCE_V2_CONTRACT: &str = "FreezeBalanceV2Contract";
+const UNFREEZE_BALANCE_V2_CONTRACT: &str = "UnfreezeBalanceV2Contract";
+const VOTE_WITNESS_CONTRACT: &str = "VoteWitnessContract";
+const WITHDRAW_BALANCE_CONTRACT: &str = "WithdrawBalanceContract";
+const WITHDRAW_EXPIRE_UNFREEZE_CONTRACT: &str = "WithdrawExpireUnfreezeContract";
+const TOPIC_TRANSFER: &str = "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+const RECEIPTS_CONCURRENCY: usize = 10;
 
 pub struct TronClient {
     url: String,
@@ -44,79 +51,141 @@ impl TronClient {
         Ok(response)
     }
 
-    pub fn map_transaction(&self, transaction: Transaction, receipt: TransactionReceiptData) -> Option<primitives::Transaction> {
-        if let (Some(value), Some(contract_result)) = (transaction.raw_data.contract.first().cloned(), transaction.ret.first().cloned()) {
-            let state: TransactionState = if contract_result.contract_ret.clone() == "SUCCESS" {
-                TransactionState::Confirmed
-            } else {
-                TransactionState::Failed
-            };
-            let fee = receipt.fee.unwrap_or_default().to_string();
-            if value.contract_type == TRANSFER_CONTRACT && !transaction.ret.is_empty() {
-                let from = TronAddress::from_hex(value.parameter.value.owner_address.unwrap_or_default().as_str()).unwrap_or_default();
-                let to = TronAddress::from_hex(value.parameter.value.to_address.unwrap_or_default().as_str()).unwrap_or_default();
-
-                let transaction = primitives::Transaction::new(
-                    transaction.tx_id,
-                    self.get_chain().as_asset_id(),
-                    from,
-                    to,
-                    None,
-                    TransactionType::Transfer,
-                    state,
-                    receipt.block_number.to_string(),
-                    0.to_string(),
-                    fee,
-                    self.get_chain().as_asset_id(),
-                    value.parameter.value.amount.unwrap_or_default().to_string(),
-                    None,
-                    None,
-                    Utc::now(),
-                );
-                return Some(transaction);
+    // Confirmed transactions sent or received by the account, TRC20 transfers triggered by other accounts are not included
+    pub async fn get_account_transactions(&self, address: &str, limit: usize) -> Result<AccountTransactions, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/v1/accounts/{}/transactions?limit={}&only_confirmed=true", self.url, address, limit);
+        Ok(self.client.get(url).send().await?.json::<AccountTransactions>().await?)
+    }
+
+    pub async fn get_transaction_reciept(&self, transaction_id: &str) -> Result<TransactionReceiptData, Box<dyn Error + Send + Sync>> {
+        let url = format!("{}/walletsolidity/gettransactioninfobyid?value={}", self.url, transaction_id);
+        Ok(self.client.get(url).send().await?.json::<TransactionReceiptData>().await?)
+    }
+
+    pub fn map_transaction(&self, transaction: Transaction, receipt: TransactionReceiptData) -> Vec<primitives::Transaction> {
+        let (Some(contract), Some(contract_result)) = (transaction.raw_data.contract.first(), transaction.ret.first()) else {
+            return vec![];
+        };
+        let state: TransactionState = if contract_result.contract_ret == "SUCCESS" {
+            TransactionState::Confirmed
+        } else {
+            TransactionState::Failed
+        };
+        let fee = receipt.fee.unwrap_or_default().to_string();
+        let chain = self.get_chain();
+        let new_transaction = |asset_id: AssetId, from: String, to: String, transaction_type: TransactionType, value: String| {
+            primitives::Transaction::new(
+                transaction.tx_id.clone(),
+                asset_id,
+                from,
+                to,
+                None,
+                transaction_type,
+                state.clone(),
+                receipt.block_number.to_string(),
+                0.to_string(),
+                fee.clone(),
+                chain.as_asset_id(),
+                value,
+                None,
+                None,
+                Utc::now(),
+            )
+        };
+        let value = &contract.parameter.value;
+        let address = |hex_value: Option<&str>| TronAddress::from_hex(hex_value.unwrap_or_default()).unwrap_or_default();
+        let owner = address(value.owner_address.as_deref());
+
+        let transactions = match contract.contract_type.as_str() {
+            TRANSFER_CONTRACT => vec![new_transaction(
+                chain.as_asset_id(),
+                owner,
+                address(value.to_address.as_deref()),
+                TransactionType::Transfer,
+                value.amount.unwrap_or_default().to_string(),
+            )],
+            // TRC20 transfers only exist in logs of confirmed transactions
+            TRIGGER_SMART_CONTRACT if state == TransactionState::Confirmed => decode_transfers(&receipt.log.clone().unwrap_or_default())
+                .into_iter()
+                .map(|(token_id, from, to, value)| {
+                    new_transaction(AssetId::from_token(chain, &token_id), from, to, TransactionType::Transfer, value.to_string())
+                })
+                .collect(),
+            FREEZE_BALANCE_V2_CONTRACT => vec![new_transaction(
+                chain.as_asset_id(),
+                owner.clone(),
+                owner,
+                TransactionType::StakeDelegate,
+                value.frozen_balance.unwrap_or_default().to_string(),
+            )],
+            UNFREEZE_BALANCE_V2_CONTRACT => vec![new_transaction(
+                chain.as_asset_id(),
+                owner.clone(),
+                owner,
+                TransactionType::StakeUndelegate,
+                value.unfreeze_balance.unwrap_or_default().to_string(),
+            )],
+            // one record per witness, votes move no funds, the frozen TRX backing them is recorded by FreezeBalanceV2
+            VOTE_WITNESS_CONTRACT => value
+                .votes
+                .clone()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|vote| {
+                    new_transaction(
+                        chain.as_asset_id(),
+                        owner.clone(),
+                        address(Some(&vote.vote_address)),
+                        TransactionType::StakeDelegate,
+                        "0".to_string(),
+                    )
+                })
+                .collect(),
+            WITHDRAW_BALANCE_CONTRACT => vec![new_transaction(
+                chain.as_asset_id(),
+                owner.clone(),
+                owner,
+                TransactionType::StakeRewards,
+                receipt.withdraw_amount.unwrap_or_default().to_string(),
+            )],
+            WITHDRAW_EXPIRE_UNFREEZE_CONTRACT => vec![new_transaction(
+                chain.as_asset_id(),
+                owner.clone(),
+                owner,
+                TransactionType::StakeWithdraw,
+                receipt.withdraw_expire_amount.unwrap_or_default().to_string(),
+            )],
+            _ => vec![],
+        };
+        transactions.into_iter().enumerate().map(|(index, x)| x.with_index(index)).collect()
+    }
+}
+
+// Tron addresses in logs and topics are missing the 41 prefix
+fn log_address(hex_value: &str) -> Option<String> {
+    TronAddress::from_hex(format!("41{}", hex_value).as_str())
+}
+
+// TRC20 Transfer(from, to, value) events as (contract, from, to, value)
+fn decode_transfers(logs: &[TronLog]) -> Vec<(String, String, String, BigUint)> {
+    logs.iter()
+        .flat_map(|log| {
+            let topics = log.topics.clone().unwrap_or_default();
+            if topics.len() != 3 || topics[0] != TOPIC_TRANSFER {
+                return None;
             }
-            let logs = receipt.log.unwrap_or_default();
-            // TRC20 transfers
-            if value.contract_type == TRIGGER_SMART_CONTRACT
-                && logs.len() == 1
-                && logs.first()?.topics.clone().unwrap_or_default().len() == 3
-                && logs.first()?.topics.clone().unwrap_or_default().first()? == "ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"
-            {
-                let log = logs.first()?;
-                let from_string = format!("41{}", log.topics.clone().unwrap_or_default()[1].clone().chars().skip(24).collect::<String>());
-                let to_string = format!("41{}", log.topics.clone().unwrap_or_default()[2].clone().chars().skip(24).collect::<String>());
-                let token_id = TronAddress::from_hex(value.parameter.value.contract_address?.as_str()).unwrap_or_default();
-                let from = TronAddress::from_hex(from_string.as_str()).unwrap_or_default();
-                let to = TronAddress::from_hex(to_string.as_str()).unwrap_or_default();
-                let value = BigUint::from_str_radix(&log.data.clone().unwrap_or_default(), 16).unwrap();
-                let asset_id = AssetId {
-                    chain: self.get_chain(),
-                    token_id: Some(token_id),
-                };
-
-                let transaction = primitives::Transaction::new(
-                    transaction.tx_id,
-                    asset_id,
-                    from,
-                    to,
-                    None,
-                    TransactionType::Transfer,
-                    state,
-                    receipt.block_number.to_string(),
-                    0.to_string(),
-                    fee,
-                    self.get_chain().as_asset_id(),
-                    value.to_string(),
-                    None,
-                    None,
-                    Utc::now(),
-                );
-
-                return Some(transaction);
+            let value = BigUint::from_str_radix(log.data.as_deref()?, 16).ok()?;
+            if value.is_zero() {
+                return None;
             }
-        }
-        None
-    }
+            Some((
+                log_address(log.address.as_deref()?)?,
+                log_address(topics[1].get(24..)?)?,
+                log_address(topics[2].get(24..)?)?,
+                value,
+            ))
+        })
+        .collect()
 }
 
 #[async_trait]
@@ -138,16 +207,128 @@ impl ChainBlockProvider for TronClient {
         let transactions = transactions
             .into_iter()
             .zip(reciepts.iter())
-            .filter_map(|(transaction, receipt)| self.map_transaction(transaction, receipt.clone()))
+            .flat_map(|(transaction, receipt)| self.map_transaction(transaction, receipt.clone()))
             .collect::<Vec<primitives::Transaction>>();
 
         Ok(transactions)
     }
 }
 
+#[async_trait]
+impl ChainAddressHistoryProvider for TronClient {
+    // Transactions whose receipt fails to load are left out
+    async fn get_address_transactions(&self, address: String, limit: usize) -> Result<Vec<primitives::Transaction>, Box<dyn Error + Send + Sync>> {
+        let transactions = self.get_account_transactions(&address, limit).await?.data;
+        let mut result = vec![];
+        for chunk in transactions.chunks(RECEIPTS_CONCURRENCY) {
+            let receipts = futures::future::join_all(chunk.iter().map(|x| self.get_transaction_reciept(&x.tx_id))).await;
+            for (transaction, receipt) in chunk.iter().zip(receipts) {
+                if let Ok(receipt) = receipt {
+                    result.extend(self.map_transaction(transaction.clone(), receipt));
+                }
+            }
+        }
+        Ok(result.into_iter().filter(|x| x.addresses().contains(&address)).collect())
+    }
+}
+
 #[async_trait]
 impl ChainTokenDataProvider for TronClient {
     async fn get_token_data(&self, _chain: Chain, _token_id: String) -> Result<Asset, Box<dyn Error + Send + Sync>> {
         unimplemented!()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: &str = "4159f3440fd40722f716144e4490a4de162d3b3fcb";
+    const WITNESS: &str = "41357a7401a0f0c2d4a44a1881a0c622f15d986291";
+
+    fn transaction(contract_type: &str, value: serde_json::Value) -> Transaction {
+        serde_json::from_value(serde_json::json!({
+            "txID": "abc",
+            "ret": [{"contractRet": "SUCCESS"}],
+            "raw_data": {"contract": [{"type": contract_type, "parameter": {"type_url": "", "value": value}}]}
+        }))
+        .unwrap()
+    }
+
+    fn receipt(value: serde_json::Value) -> TransactionReceiptData {
+        let mut receipt = serde_json::json!({"id": "abc", "fee": 100, "blockNumber": 1, "receipt": {}});
+        receipt.as_object_mut().unwrap().extend(value.as_object().unwrap().clone());
+        serde_json::from_value(receipt).unwrap()
+    }
+
+    #[test]
+    fn test_map_stake_transactions() {
+        let client = TronClient::new(reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build(), "".into());
+        let owner = TronAddress::from_hex(OWNER).unwrap();
+
+        let result = client.map_transaction(
+            transaction(
+                FREEZE_BALANCE_V2_CONTRACT,
+                serde_json::json!({"owner_address": OWNER, "frozen_balance": 5000000}),
+            ),
+            receipt(serde_json::json!({})),
+        );
+        assert_eq!(result[0].transaction_type.as_ref(), TransactionType::StakeDelegate.as_ref());
+        assert_eq!(result[0].value, "5000000");
+
+        let result = client.map_transaction(
+            transaction(
+                VOTE_WITNESS_CONTRACT,
+                serde_json::json!({"owner_address": OWNER, "votes": [{"vote_address": WITNESS, "vote_count": 3}, {"vote_address": OWNER, "vote_count": 2}]}),
+            ),
+            receipt(serde_json::json!({})),
+        );
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].transaction_type.as_ref(), TransactionType::StakeDelegate.as_ref());
+        assert_eq!(result[0].to, TronAddress::from_hex(WITNESS).unwrap());
+        assert_eq!(result[0].value, "0");
+        assert_eq!(result[1].to, owner);
+        assert_eq!(result[1].value, "0");
+        assert_ne!(result[0].id, result[1].id);
+
+        let result = client.map_transaction(
+            transaction(WITHDRAW_BALANCE_CONTRACT, serde_json::json!({"owner_address": OWNER})),
+            receipt(serde_json::json!({"withdraw_amount": 42})),
+        );
+        assert_eq!(result[0].transaction_type.as_ref(), TransactionType::StakeRewards.as_ref());
+        assert_eq!(result[0].from, owner);
+        assert_eq!(result[0].value, "42");
+    }
+
+    #[test]
+    fn test_map_trc20_transfers() {
+        let client = TronClient::new(reqwest_middleware::ClientBuilder::new(reqwest::Client::new()).build(), "".into());
+        let log = |from: &str, to: &str, value: &str| {
+            serde_json::json!({
+                "address": "a614f803b6fd780986a42c78ec9c7f77e6ded13c",
+                "topics": [TOPIC_TRANSFER, format!("000000000000000000000000{}", &from[2..]), format!("000000000000000000000000{}", &to[2..])],
+                "data": value
+            })
+        };
+
+        let result = client.map_transaction(
+            transaction(
+                TRIGGER_SMART_CONTRACT,
+                serde_json::json!({"owner_address": OWNER, "contract_address": "41a614f803b6fd780986a42c78ec9c7f77e6ded13c"}),
+            ),
+            receipt(serde_json::json!({"log": [
+                log(OWNER, WITNESS, "00000000000000000000000000000000000000000000000000000000000f4240"),
+                log(WITNESS, OWNER, "0000000000000000000000000000000000000000000000000000000000000001"),
+            ]})),
+        );
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].asset_id, AssetId::from_token(Chain::Tron, "TR7NHqjeKQxGTCi8q8ZY4pL8otSzgjLj6t"));
+        assert_eq!(result[0].from, TronAddress::from_hex(OWNER).unwrap());
+        assert_eq!(result[0].to, TronAddress::from_hex(WITNESS).unwrap());
+        assert_eq!(result[0].value, "1000000");
+        assert_eq!(result[1].value, "1");
+        assert_eq!(result[0].id, "tron_abc");
+        assert_eq!(result[1].id, "tron_abc_1");
+    }
+}
@@ -21,6 +21,11 @@ pub struct BlockHeaderData {
     pub number: i64,
 }
 
+#[derive(Debug, Deserialize, Serialize)]
+pub struct AccountTransactions {
+    pub data: Vec<Transaction>,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Transaction {
     #[serde(rename = "txID")]
@@ -59,6 +64,15 @@ pub struct ContractParameterValue {
     pub owner_address: Option<String>,
     pub to_address: Option<String>,
     pub contract_address: Option<String>,
+    pub frozen_balance: Option<i64>,
+    pub unfreeze_balance: Option<i64>,
+    pub votes: Option<Vec<Vote>>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Vote {
+    pub vote_address: String,
+    pub vote_count: i64,
 }
 
 pub type BlockTransactionsInfo = Vec<TransactionReceiptData>;
@@ -71,6 +85,8 @@ pub struct TransactionReceiptData {
     pub block_number: i64,
     pub receipt: TransactionReceipt,
     pub log: Option<Vec<TronLog>>,
+    pub withdraw_amount: Option<i64>,
+    pub withdraw_expire_amount: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -80,6 +96,7 @@ pub struct TransactionReceipt {
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct TronLog {
+    pub address: Option<String>,
     pub topics: Option<Vec<String>>,
     pub data: Option<String>,
 }
@@ -0,0 +1,16 @@
+use alloy_core::sol;
+
+sol! {
+    interface IERC1155 {
+        event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
+        event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values);
+        // same signature for ERC721 operators
+        event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+
+        function setApprovalForAll(address operator, bool approved) external;
+        function supportsInterface(bytes4 interfaceId) external view returns (bool);
+    }
+}
+
+// ERC165 interface id of ERC1155
+pub const ERC1155_INTERFACE_ID: [u8; 4] = [0xd9, 0xb6, 0x7a, 0x26];
@@ -73,6 +73,9 @@ pub enum EthereumRpc {
     GasPrice,
     GetBalance(&'static str),
     Call(TransactionObject, BlockParameter),
+    GetTransactionReceipt(String),
+    // block count and reward percentiles, ending at the latest block
+    FeeHistory { blocks: u64, reward_percentiles: Vec<i64> },
 }
 
 impl EthereumRpc {
@@ -81,10 +84,28 @@ impl EthereumRpc {
             EthereumRpc::GasPrice => "eth_gasPrice",
             EthereumRpc::GetBalance(_) => "eth_getBalance",
             EthereumRpc::Call(_, _) => "eth_call",
+            EthereumRpc::GetTransactionReceipt(_) => "eth_getTransactionReceipt",
+            EthereumRpc::FeeHistory { .. } => "eth_feeHistory",
         }
     }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    // 0x1 success, 0x0 reverted
+    pub status: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthereumFeeHistory {
+    pub oldest_block: String,
+    // one more entry than blocks, the last one is the base fee of the next block
+    pub base_fee_per_gas: Vec<String>,
+    // priority fees per block for each requested percentile
+    pub reward: Vec<Vec<String>>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
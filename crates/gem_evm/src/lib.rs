@@ -1,7 +1,10 @@
 pub mod address;
+pub mod erc1155;
 pub mod erc20;
 pub mod erc2612;
 pub mod jsonrpc;
 pub mod lido;
+pub mod multicall3;
 pub mod permit2;
+pub mod thorchain;
 pub mod uniswap;
@@ -0,0 +1,29 @@
+use alloy_core::sol;
+use primitives::EVMChain;
+
+// https://github.com/mds1/multicall
+sol! {
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+
+        struct Result {
+            bool success;
+            bytes returnData;
+        }
+
+        function aggregate3(Call3[] calldata calls) external payable returns (Result[] memory returnData);
+        function getEthBalance(address addr) external view returns (uint256 balance);
+    }
+}
+
+pub fn deployment_by_chain(chain: &EVMChain) -> &'static str {
+    match chain {
+        // https://era.zksync.io/docs/dev/building-on-zksync/useful-address.html
+        EVMChain::ZkSync => "0xF9cda624FBC7e059355ce98a31693d299FACd963",
+        _ => "0xcA11bde05977b3631167028862bE2a173976CA11",
+    }
+}
@@ -0,0 +1,8 @@
+use alloy_core::sol;
+
+// https://gitlab.com/thorchain/ethereum/eth-router
+sol! {
+    interface RouterInterface {
+        function depositWithExpiry(address vault, address asset, uint256 amount, string memo, uint256 expiration) external payable;
+    }
+}
@@ -9,6 +9,10 @@ pub enum SolanaRpc {
     GetAccountInfo(String),
     GetMultipleAccounts(Vec<String>),
     GetEpochInfo,
+    GetLatestBlockhash,
+    GetSignatureStatuses(Vec<String>),
+    // writable accounts of the transaction, empty for the global fees
+    GetRecentPrioritizationFees(Vec<String>),
 }
 
 impl Display for SolanaRpc {
@@ -18,6 +22,9 @@ impl Display for SolanaRpc {
             SolanaRpc::GetAccountInfo(_) => write!(f, "getAccountInfo"),
             SolanaRpc::GetMultipleAccounts(_) => write!(f, "getMultipleAccounts"),
             SolanaRpc::GetEpochInfo => write!(f, "getEpochInfo"),
+            SolanaRpc::GetLatestBlockhash => write!(f, "getLatestBlockhash"),
+            SolanaRpc::GetSignatureStatuses(_) => write!(f, "getSignatureStatuses"),
+            SolanaRpc::GetRecentPrioritizationFees(_) => write!(f, "getRecentPrioritizationFees"),
         }
     }
 }
@@ -69,11 +76,33 @@ pub struct AccountData {
     pub space: u64,
 }
 
+// Micro lamports per compute unit paid in a recent slot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrioritizationFee {
+    pub slot: u64,
+    pub prioritization_fee: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValueResult<T> {
     pub value: T,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatestBlockhash {
+    pub blockhash: String,
+    pub last_valid_block_height: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignatureStatus {
+    pub err: Option<serde_json::Value>,
+    pub confirmation_status: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValueData<T> {
     pub data: T,
@@ -2,6 +2,7 @@ pub mod hash;
 pub mod jsonrpc;
 pub mod metaplex;
 pub mod pubkey;
+pub mod transaction;
 
 pub const WSOL_TOKEN_ADDRESS: &str = "So11111111111111111111111111111111111111112";
 pub const USDC_TOKEN_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
@@ -10,6 +11,8 @@ pub const USDT_TOKEN_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BenwNYB"
 pub const METAPLEX_PROGRAM: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
 pub const TOKEN_PROGRAM: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 pub const TOKEN_PROGRAM_2022: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+pub const SYSTEM_PROGRAM: &str = "11111111111111111111111111111111";
+pub const ASSOCIATED_TOKEN_PROGRAM: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
 
 use primitives::{AssetId, SolanaTokenProgramId};
 use pubkey::Pubkey;
@@ -38,3 +41,24 @@ pub fn get_asset_address(asset_id: &AssetId) -> Option<Pubkey> {
         None => Pubkey::from_str(WSOL_TOKEN_ADDRESS).ok(),
     }
 }
+
+pub fn get_associated_token_address(owner: &Pubkey, mint: &Pubkey, token_program: &Pubkey) -> Option<Pubkey> {
+    let seeds = &[owner.as_ref(), token_program.as_ref(), mint.as_ref()];
+    let program = Pubkey::from_str(ASSOCIATED_TOKEN_PROGRAM).ok()?;
+    Pubkey::try_find_program_address(seeds, &program).map(|x| x.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_get_associated_token_address() {
+        let owner = Pubkey::from_str("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM").unwrap();
+        let mint = Pubkey::from_str(USDC_TOKEN_MINT).unwrap();
+        let token_program = Pubkey::from_str(TOKEN_PROGRAM).unwrap();
+        let address = get_associated_token_address(&owner, &mint, &token_program).unwrap();
+
+        assert_eq!(address.to_string(), "FGETo8T8wMcN2wCjav8VK6eh3dLk63evNDPxzLSJra8B");
+    }
+}
@@ -0,0 +1,177 @@
+// Minimal legacy transaction encoding, see https://solana.com/docs/core/transactions
+
+use crate::{hash::Hash, pubkey::Pubkey};
+
+const SIGNATURE_BYTES: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountMeta {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl AccountMeta {
+    pub fn new(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable: true,
+        }
+    }
+
+    pub fn new_readonly(pubkey: Pubkey, is_signer: bool) -> Self {
+        Self {
+            pubkey,
+            is_signer,
+            is_writable: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Instruction {
+    pub program_id: Pubkey,
+    pub accounts: Vec<AccountMeta>,
+    pub data: Vec<u8>,
+}
+
+impl Instruction {
+    pub fn new(program_id: Pubkey, accounts: Vec<AccountMeta>, data: Vec<u8>) -> Self {
+        Self { program_id, accounts, data }
+    }
+}
+
+fn encode_length(bytes: &mut Vec<u8>, length: usize) {
+    let mut value = length;
+    loop {
+        let mut byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes.push(byte);
+            return;
+        }
+        byte |= 0x80;
+        bytes.push(byte);
+    }
+}
+
+// Account keys ordered as: writable signers, readonly signers, writable and readonly non signers
+fn compile_accounts(payer: &Pubkey, instructions: &[Instruction]) -> Vec<AccountMeta> {
+    let mut accounts: Vec<AccountMeta> = vec![AccountMeta::new(payer.clone(), true)];
+    let metas = instructions
+        .iter()
+        .flat_map(|x| x.accounts.iter().cloned().chain([AccountMeta::new_readonly(x.program_id.clone(), false)]));
+    for meta in metas {
+        match accounts.iter_mut().find(|x| x.pubkey == meta.pubkey) {
+            Some(account) => {
+                account.is_signer |= meta.is_signer;
+                account.is_writable |= meta.is_writable;
+            }
+            None => accounts.push(meta),
+        }
+    }
+    accounts.sort_by_key(|x| (!x.is_signer, !x.is_writable));
+    accounts
+}
+
+#[derive(Debug, Clone)]
+pub struct Transaction {
+    pub payer: Pubkey,
+    pub instructions: Vec<Instruction>,
+    pub recent_blockhash: Hash,
+}
+
+impl Transaction {
+    pub fn new(payer: Pubkey, instructions: Vec<Instruction>, recent_blockhash: Hash) -> Self {
+        Self {
+            payer,
+            instructions,
+            recent_blockhash,
+        }
+    }
+
+    pub fn message_data(&self) -> Vec<u8> {
+        let accounts = compile_accounts(&self.payer, &self.instructions);
+        let index = |pubkey: &Pubkey| accounts.iter().position(|x| x.pubkey == *pubkey).unwrap_or_default() as u8;
+
+        let mut bytes = vec![
+            accounts.iter().filter(|x| x.is_signer).count() as u8,
+            accounts.iter().filter(|x| x.is_signer && !x.is_writable).count() as u8,
+            accounts.iter().filter(|x| !x.is_signer && !x.is_writable).count() as u8,
+        ];
+        encode_length(&mut bytes, accounts.len());
+        accounts.iter().for_each(|x| bytes.extend_from_slice(x.pubkey.as_ref()));
+        bytes.extend_from_slice(self.recent_blockhash.as_ref());
+
+        encode_length(&mut bytes, self.instructions.len());
+        for instruction in &self.instructions {
+            bytes.push(index(&instruction.program_id));
+            encode_length(&mut bytes, instruction.accounts.len());
+            instruction.accounts.iter().for_each(|x| bytes.push(index(&x.pubkey)));
+            encode_length(&mut bytes, instruction.data.len());
+            bytes.extend_from_slice(&instruction.data);
+        }
+        bytes
+    }
+
+    // Serialized with empty signatures, to be signed by the wallet
+    pub fn serialize_unsigned(&self) -> Vec<u8> {
+        let message = self.message_data();
+        let signatures = message[0] as usize;
+
+        let mut bytes = vec![];
+        encode_length(&mut bytes, signatures);
+        bytes.extend(vec![0u8; signatures * SIGNATURE_BYTES]);
+        bytes.extend(message);
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_encode_length() {
+        let encode = |length: usize| {
+            let mut bytes = vec![];
+            encode_length(&mut bytes, length);
+            bytes
+        };
+        assert_eq!(encode(0), vec![0]);
+        assert_eq!(encode(127), vec![0x7f]);
+        assert_eq!(encode(128), vec![0x80, 0x01]);
+        assert_eq!(encode(16384), vec![0x80, 0x80, 0x01]);
+    }
+
+    #[test]
+    fn test_compile_accounts() {
+        let payer = Pubkey::from([1; 32]);
+        let program = Pubkey::from([2; 32]);
+        let readonly = Pubkey::from([3; 32]);
+        let writable = Pubkey::from([4; 32]);
+        let instruction = Instruction::new(
+            program.clone(),
+            vec![
+                AccountMeta::new_readonly(readonly.clone(), false),
+                AccountMeta::new(writable.clone(), false),
+                AccountMeta::new(payer.clone(), true),
+            ],
+            vec![],
+        );
+        let accounts = compile_accounts(&payer, &[instruction.clone()]);
+
+        assert_eq!(
+            accounts.iter().map(|x| x.pubkey.clone()).collect::<Vec<_>>(),
+            vec![payer.clone(), writable, readonly, program]
+        );
+
+        let blockhash = Hash::from_str("EkSnNWid2cvwEVnVx9aBqawnmiCNiDgp3gUdkDPTKN1N").unwrap();
+        let data = Transaction::new(payer, vec![instruction], blockhash).serialize_unsigned();
+
+        assert_eq!(data[0], 1);
+        assert_eq!(&data[65..68], &[1, 0, 2]);
+    }
+}
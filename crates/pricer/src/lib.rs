@@ -1,7 +1,10 @@
 pub mod chart_client;
 pub mod price_alert_client;
 pub mod price_client;
+pub mod price_provider;
+pub mod providers;
 
 pub use chart_client::ChartClient;
 pub use price_alert_client::{PriceAlertClient, PriceAlertNotification, PriceAlertRules};
 pub use price_client::PriceClient;
+pub use price_provider::{merge_prices, PriceProvider, ProviderPrice};
@@ -21,6 +21,8 @@ pub struct PriceAlertNotification {
     pub price: Price,
     pub alert_type: PriceAlertType,
     pub price_alert: PriceAlert,
+    // currency of price
+    pub currency: String,
 }
 
 #[derive(Clone, Debug)]
@@ -48,20 +50,68 @@ impl PriceAlertClient {
 
     pub async fn add_price_alerts(&mut self, device_id: &str, price_alerts: PriceAlerts) -> Result<usize, Box<dyn Error>> {
         let device = self.database.get_device(device_id)?;
-        let values = price_alerts.into_iter().map(|x| PriceAlert::new_price_alert(x, device.id)).collect::<_>();
+        for currency in price_alerts.iter().flat_map(|x| x.currency.clone()).collect::<HashSet<_>>() {
+            if self.database.get_fiat_rate(&currency).is_err() {
+                return Err(format!("Unsupported currency: {}", currency).into());
+            }
+        }
+        let values = price_alerts
+            .into_iter()
+            .map(|x| Ok(PriceAlert::new_price_alert(self.with_reference_price(x)?, device.id)))
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
         Ok(self.database.add_price_alerts(values)?)
     }
 
+    // Percentage alerts without a price are relative to the current price
+    fn with_reference_price(&mut self, price_alert: primitives::PriceAlert) -> Result<primitives::PriceAlert, Box<dyn Error>> {
+        if price_alert.price_percent_change.is_none() || price_alert.price.is_some() {
+            return Ok(price_alert);
+        }
+        let price = self.database.get_price(&price_alert.asset_id)?;
+        let currency = price_alert.currency.clone().unwrap_or(DEFAULT_FIAT_CURRENCY.to_string());
+        let base_rate = self.database.get_fiat_rate(DEFAULT_FIAT_CURRENCY)?;
+        let rate = self.database.get_fiat_rate(&currency)?;
+
+        Ok(primitives::PriceAlert {
+            price: Some(price.as_price_primitive().new_with_rate(base_rate.rate, rate.rate).price),
+            ..price_alert
+        })
+    }
+
     pub async fn delete_price_alerts(&mut self, device_id: &str, price_alerts: PriceAlerts) -> Result<usize, Box<dyn Error>> {
         let device = self.database.get_device(device_id)?;
-        let asset_ids: Vec<_> = price_alerts.iter().map(|x| x.asset_id.as_str()).collect::<HashSet<_>>().into_iter().collect();
+        let alerts = self.database.get_price_alerts_for_device_id(device.id)?;
+        let ids = Self::matching_price_alert_ids(&alerts, &price_alerts);
+
+        Ok(self.database.delete_price_alerts_by_ids(ids)?)
+    }
 
-        Ok(self.database.delete_price_alerts(device.id, asset_ids)?)
+    // Alerts on the same asset are told apart by currency, price, percent change and direction
+    fn matching_price_alert_ids(alerts: &[PriceAlert], price_alerts: &[primitives::PriceAlert]) -> Vec<i32> {
+        alerts
+            .iter()
+            .filter(|alert| price_alerts.iter().any(|x| Self::is_matching_price_alert(alert, x)))
+            .map(|x| x.id)
+            .collect()
     }
 
+    fn is_matching_price_alert(alert: &PriceAlert, price_alert: &primitives::PriceAlert) -> bool {
+        let stored = alert.as_primitive();
+        // percentage alerts added without a price store the reference price at the time they were added
+        let is_price_matching = match price_alert.price {
+            Some(price) => stored.price == Some(price),
+            None => stored.price.is_none() || price_alert.price_percent_change.is_some(),
+        };
+        stored.asset_id == price_alert.asset_id
+            && Self::price_alert_currency(alert) == price_alert.currency.clone().unwrap_or(DEFAULT_FIAT_CURRENCY.to_string())
+            && is_price_matching
+            && stored.price_percent_change == price_alert.price_percent_change
+            && stored.price_direction == price_alert.price_direction
+    }
+
+    // Alerts are not marked as notified here, call set_price_alerts_notified once the notifications are delivered
     pub async fn get_devices_to_alert(&mut self, rules: PriceAlertRules) -> Result<Vec<PriceAlertNotification>, Box<dyn Error + Send + Sync>> {
         let now = chrono::Utc::now().naive_utc();
-        let after_notified_at = now - chrono::Duration::hours(24);
 
         let prices = self.database.get_prices()?;
 
@@ -71,53 +121,83 @@ impl PriceAlertClient {
             map
         });
 
-        let price_alerts = self.database.get_price_alerts(after_notified_at)?;
+        let rates: HashMap<String, f64> = self.database.get_fiat_rates()?.into_iter().map(|x| (x.symbol, x.rate)).collect();
+        let base_rate = *rates.get(DEFAULT_FIAT_CURRENCY).ok_or("missing base fiat rate")?;
+
+        let price_alerts = self.database.get_price_alerts(now)?;
 
         let mut results: Vec<PriceAlertNotification> = Vec::new();
-        let mut price_alert_ids: HashSet<i32> = HashSet::new();
 
         for price in prices {
             if let Some(asset_ids) = prices_assets_map.get(&price.id) {
                 for price_alert in price_alerts.clone() {
                     if asset_ids.clone().contains(&price_alert.asset_id) {
-                        if let Some(alert) = self.get_price_alert_type(&price_alert, price.clone(), rules.clone()) {
+                        let Some(rate) = rates.get(&Self::price_alert_currency(&price_alert)) else {
+                            continue;
+                        };
+                        let alert_price = price.price * rate / base_rate;
+                        if let Some(alert) = Self::get_price_alert_type(&price_alert, price.clone(), alert_price, rules.clone()) {
                             let notification: PriceAlertNotification = self.price_alert_notification(price.as_price_primitive(), price_alert.clone(), alert)?;
-                            price_alert_ids.insert(price_alert.id);
                             results.push(notification);
                         }
                     }
                 }
             }
         }
-        self.database.update_price_alerts_set_notified_at(price_alert_ids.into_iter().collect(), now)?;
         Ok(results)
     }
 
-    fn get_price_alert_type(&self, price_alert: &PriceAlert, price: storage::models::Price, rules: PriceAlertRules) -> Option<PriceAlertType> {
-        if let Some(price_alert_price) = price_alert.price {
+    // One time alerts are deleted, recurring alerts wait for their cooldown
+    pub fn set_price_alerts_notified(&mut self, price_alerts: Vec<PriceAlert>) -> Result<usize, Box<dyn Error + Send + Sync>> {
+        let (once, recurring): (Vec<_>, Vec<_>) = price_alerts.into_iter().partition(|x| x.is_once());
+        let once_ids = once.into_iter().map(|x| x.id).collect::<HashSet<_>>();
+        let recurring_ids = recurring.into_iter().map(|x| x.id).collect::<HashSet<_>>();
+        let now = Utc::now().naive_utc();
+        Ok(self.database.update_price_alerts_set_notified_at(recurring_ids.into_iter().collect(), now)?
+            + self.database.delete_price_alerts_by_ids(once_ids.into_iter().collect())?)
+    }
+
+    // Currency the alert price is set in, alerts without a price follow the 24h change rules
+    fn price_alert_currency(price_alert: &PriceAlert) -> String {
+        price_alert.currency.clone().unwrap_or(DEFAULT_FIAT_CURRENCY.to_string())
+    }
+
+    // alert_price is the current price in the alert currency
+    fn get_price_alert_type(price_alert: &PriceAlert, price: storage::models::Price, alert_price: f64, rules: PriceAlertRules) -> Option<PriceAlertType> {
+        let direction = price_alert.as_primitive().price_direction;
+        match (price_alert.price, price_alert.price_percent_change) {
+            // moves by a percentage from the price the alert was set at
+            (Some(reference_price), Some(price_percent_change)) => {
+                if reference_price <= 0.0 {
+                    return None;
+                }
+                let change = (alert_price - reference_price) / reference_price * 100.0;
+                if change >= price_percent_change && direction != Some(PriceAlertDirection::Down) {
+                    return Some(PriceAlertType::PricePercentChangeUp);
+                }
+                if change <= -price_percent_change && direction != Some(PriceAlertDirection::Up) {
+                    return Some(PriceAlertType::PricePercentChangeDown);
+                }
+                None
+            }
             // price goes up/down
-            if let Some(direction) = price_alert.as_primitive().price_direction {
-                match direction {
-                    PriceAlertDirection::Up => {
-                        if price.clone().price > price_alert_price {
-                            return Some(PriceAlertType::PriceUp);
-                        }
-                    }
-                    PriceAlertDirection::Down => {
-                        if price.clone().price < price_alert_price {
-                            return Some(PriceAlertType::PriceDown);
-                        }
-                    }
+            (Some(price_alert_price), None) => match direction {
+                Some(PriceAlertDirection::Up) if alert_price > price_alert_price => Some(PriceAlertType::PriceUp),
+                Some(PriceAlertDirection::Down) if alert_price < price_alert_price => Some(PriceAlertType::PriceDown),
+                _ => None,
+            },
+            (None, _) => {
+                if price.price_change_percentage_24h > rules.price_change_increase {
+                    Some(PriceAlertType::PriceChangesUp)
+                } else if price.price_change_percentage_24h < -rules.price_change_decrease {
+                    Some(PriceAlertType::PriceChangesDown)
+                } else if Self::is_within_past(price.all_time_high_date, Duration::hours(12)) {
+                    Some(PriceAlertType::AllTimeHigh)
+                } else {
+                    None
                 }
             }
-        } else if price.clone().price_change_percentage_24h > rules.price_change_increase {
-            return Some(PriceAlertType::PriceChangesUp);
-        } else if price.clone().price_change_percentage_24h < -rules.price_change_decrease {
-            return Some(PriceAlertType::PriceChangesDown);
-        } else if Self::is_within_past(price.clone().all_time_high_date, Duration::hours(12)) {
-            return Some(PriceAlertType::AllTimeHigh);
         }
-        None
     }
 
     fn is_within_past(date_time: Option<NaiveDateTime>, duration: Duration) -> bool {
@@ -135,8 +215,12 @@ impl PriceAlertClient {
     ) -> Result<PriceAlertNotification, Box<dyn Error + Send + Sync>> {
         let asset = self.database.get_asset(&price_alert.asset_id)?.as_primitive();
         let device = self.database.get_device_by_id(price_alert.device_id)?.as_primitive();
+        let currency = match price_alert.price {
+            Some(_) => Self::price_alert_currency(&price_alert),
+            None => device.currency.clone(),
+        };
         let base_rate = self.database.get_fiat_rate(DEFAULT_FIAT_CURRENCY)?;
-        let rate = self.database.get_fiat_rate(&device.currency)?;
+        let rate = self.database.get_fiat_rate(&currency)?;
         let price = price.new_with_rate(base_rate.rate, rate.rate);
 
         let notification = PriceAlertNotification {
@@ -145,6 +229,7 @@ impl PriceAlertClient {
             price,
             alert_type,
             price_alert,
+            currency,
         };
         Ok(notification)
     }
@@ -155,25 +240,32 @@ impl PriceAlertClient {
         let formatter = NumberFormatter::new();
 
         for price_alert in notifications {
-            let price = formatter.currency(price_alert.price.price, &price_alert.device.currency);
+            let price = formatter.currency(price_alert.price.price, &price_alert.currency);
             if price.is_none() {
-                println!("Unknown currency symbol: {}", &price_alert.device.currency);
+                println!("Unknown currency symbol: {}", &price_alert.currency);
                 continue;
             }
+            let price = price.unwrap();
             let price_change = formatter.percent(price_alert.price.price_change_percentage_24h, price_alert.device.locale.as_str());
+            // change since the alert was set
+            let reference_price = price_alert.price_alert.price.unwrap_or_default();
+            let price_change_from_reference = if reference_price > 0.0 {
+                formatter.percent(
+                    (price_alert.price.price - reference_price) / reference_price * 100.0,
+                    price_alert.device.locale.as_str(),
+                )
+            } else {
+                price_change.clone()
+            };
 
             let language_localizer = LanguageLocalizer::new_with_language(&price_alert.device.locale);
+            let asset_name = price_alert.asset.full_name();
             let notification_message: LanguageNotification = match price_alert.alert_type {
-                PriceAlertType::PriceChangesUp => {
-                    language_localizer.price_alert_up(&price_alert.asset.full_name(), price.unwrap().as_str(), price_change.as_str())
-                }
-                PriceAlertType::PriceChangesDown => {
-                    language_localizer.price_alert_down(&price_alert.asset.full_name(), price.unwrap().as_str(), price_change.as_str())
-                }
-                PriceAlertType::AllTimeHigh => language_localizer.price_alert_all_time_high(&price_alert.asset.name, price.unwrap().as_str()),
-                PriceAlertType::PriceUp | PriceAlertType::PriceDown | PriceAlertType::PricePercentChangeUp | PriceAlertType::PricePercentChangeDown => {
-                    unimplemented!()
-                }
+                PriceAlertType::PriceChangesUp | PriceAlertType::PriceUp => language_localizer.price_alert_up(&asset_name, &price, &price_change),
+                PriceAlertType::PriceChangesDown | PriceAlertType::PriceDown => language_localizer.price_alert_down(&asset_name, &price, &price_change),
+                PriceAlertType::PricePercentChangeUp => language_localizer.price_alert_up(&asset_name, &price, &price_change_from_reference),
+                PriceAlertType::PricePercentChangeDown => language_localizer.price_alert_down(&asset_name, &price, &price_change_from_reference),
+                PriceAlertType::AllTimeHigh => language_localizer.price_alert_all_time_high(&price_alert.asset.name, &price),
             };
             let price_alert_data = PushNotificationPriceAlert {
                 asset_id: price_alert.asset.id.to_string(),
@@ -194,3 +286,132 @@ impl PriceAlertClient {
         results
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price_alert(price: Option<f64>, price_percent_change: Option<f64>, price_direction: Option<PriceAlertDirection>) -> PriceAlert {
+        PriceAlert {
+            id: 1,
+            device_id: 1,
+            asset_id: "ethereum".to_string(),
+            price_direction: price_direction.map(|x| x.as_ref().to_string()),
+            price,
+            price_percent_change,
+            last_notified_at: None,
+            currency: Some("EUR".to_string()),
+            repeat: "once".to_string(),
+            cooldown: 3600,
+        }
+    }
+
+    fn price(price_change_percentage_24h: f64) -> storage::models::Price {
+        storage::models::Price::new(
+            "ethereum".to_string(),
+            1000.0,
+            price_change_percentage_24h,
+            0.0,
+            None,
+            0.0,
+            None,
+            0.0,
+            0,
+            0.0,
+            0.0,
+            0.0,
+            0.0,
+            None,
+        )
+    }
+
+    fn rules() -> PriceAlertRules {
+        PriceAlertRules {
+            price_change_increase: 5.0,
+            price_change_decrease: 5.0,
+        }
+    }
+
+    fn alert_type(price_alert: PriceAlert, alert_price: f64) -> Option<PriceAlertType> {
+        PriceAlertClient::get_price_alert_type(&price_alert, price(0.0), alert_price, rules())
+    }
+
+    #[test]
+    fn test_get_price_alert_type_percent_change() {
+        assert_eq!(
+            alert_type(price_alert(Some(100.0), Some(5.0), None), 105.0),
+            Some(PriceAlertType::PricePercentChangeUp)
+        );
+        assert_eq!(
+            alert_type(price_alert(Some(100.0), Some(5.0), None), 94.0),
+            Some(PriceAlertType::PricePercentChangeDown)
+        );
+        assert_eq!(alert_type(price_alert(Some(100.0), Some(5.0), None), 103.0), None);
+        assert_eq!(alert_type(price_alert(Some(100.0), Some(5.0), Some(PriceAlertDirection::Up)), 94.0), None);
+        assert_eq!(
+            alert_type(price_alert(Some(100.0), Some(5.0), Some(PriceAlertDirection::Down)), 90.0),
+            Some(PriceAlertType::PricePercentChangeDown)
+        );
+    }
+
+    #[test]
+    fn test_get_price_alert_type_price() {
+        assert_eq!(
+            alert_type(price_alert(Some(100.0), None, Some(PriceAlertDirection::Up)), 101.0),
+            Some(PriceAlertType::PriceUp)
+        );
+        assert_eq!(alert_type(price_alert(Some(100.0), None, Some(PriceAlertDirection::Down)), 101.0), None);
+        assert_eq!(alert_type(price_alert(Some(100.0), None, None), 101.0), None);
+        assert_eq!(
+            PriceAlertClient::get_price_alert_type(&price_alert(None, None, None), price(-6.0), 1000.0, rules()),
+            Some(PriceAlertType::PriceChangesDown)
+        );
+    }
+
+    #[test]
+    fn test_matching_price_alert_ids() {
+        let alerts = vec![
+            price_alert(Some(100.0), None, Some(PriceAlertDirection::Up)),
+            PriceAlert {
+                id: 2,
+                ..price_alert(Some(90.0), None, Some(PriceAlertDirection::Down))
+            },
+        ];
+        let delete = primitives::PriceAlert {
+            asset_id: "ethereum".to_string(),
+            price_direction: Some(PriceAlertDirection::Down),
+            price: Some(90.0),
+            price_percent_change: None,
+            currency: Some("EUR".to_string()),
+            repeat: None,
+            cooldown: None,
+        };
+
+        assert_eq!(PriceAlertClient::matching_price_alert_ids(&alerts, &[delete.clone()]), vec![2]);
+        assert_eq!(
+            PriceAlertClient::matching_price_alert_ids(
+                &alerts,
+                &[primitives::PriceAlert {
+                    currency: None,
+                    ..delete
+                }]
+            ),
+            Vec::<i32>::new()
+        );
+    }
+
+    #[test]
+    fn test_new_price_alert_cooldown() {
+        let price_alert = primitives::PriceAlert {
+            asset_id: "ethereum".to_string(),
+            price_direction: None,
+            price: None,
+            price_percent_change: None,
+            currency: None,
+            repeat: None,
+            cooldown: Some(0),
+        };
+        let new_price_alert = PriceAlert::new_price_alert(price_alert, 1);
+        assert_eq!(new_price_alert.cooldown, storage::models::price_alert::PRICE_ALERT_MIN_COOLDOWN);
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+
+// Provider prices further than this from the primary provider are treated as a wrong id mapping
+const PRICE_MAX_DEVIATION: f64 = 0.25;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProviderPrice {
+    pub price_id: String,
+    pub price: f64,
+    pub price_change_percentage_24h: f64,
+    pub market_cap: f64,
+    pub total_volume: f64,
+    pub last_updated_at: Option<NaiveDateTime>,
+}
+
+#[async_trait]
+pub trait PriceProvider: Send + Sync {
+    fn name(&self) -> &'static str;
+    // USD prices for CoinGecko coin ids, ids unknown to the provider are omitted
+    async fn get_prices(&self, price_ids: Vec<String>) -> Result<Vec<ProviderPrice>, Box<dyn Error + Send + Sync>>;
+}
+
+// Merges provider results ordered by priority, each price is the median of the providers that returned it
+pub fn merge_prices(results: Vec<Vec<ProviderPrice>>) -> Vec<ProviderPrice> {
+    let mut prices_map: HashMap<String, Vec<ProviderPrice>> = HashMap::new();
+    for price in results.into_iter().flatten().filter(|x| x.price > 0.0) {
+        prices_map.entry(price.price_id.clone()).or_default().push(price);
+    }
+
+    prices_map
+        .into_values()
+        .map(|prices| {
+            let primary = prices[0].price;
+            let prices = prices
+                .into_iter()
+                .filter(|x| ((x.price - primary) / primary).abs() <= PRICE_MAX_DEVIATION)
+                .collect::<Vec<_>>();
+
+            ProviderPrice {
+                price_id: prices[0].price_id.clone(),
+                price: median(prices.iter().map(|x| x.price).collect()),
+                price_change_percentage_24h: median(prices.iter().map(|x| x.price_change_percentage_24h).collect()),
+                market_cap: median(prices.iter().map(|x| x.market_cap).filter(|x| *x > 0.0).collect()),
+                total_volume: median(prices.iter().map(|x| x.total_volume).filter(|x| *x > 0.0).collect()),
+                last_updated_at: prices.iter().flat_map(|x| x.last_updated_at).max(),
+            }
+        })
+        .collect()
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        (values[middle - 1] + values[middle]) / 2.0
+    } else {
+        values[middle]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn price(price_id: &str, price: f64, last_updated_at: i64) -> ProviderPrice {
+        ProviderPrice {
+            price_id: price_id.to_string(),
+            price,
+            price_change_percentage_24h: 1.0,
+            market_cap: 0.0,
+            total_volume: 0.0,
+            last_updated_at: chrono::DateTime::from_timestamp(last_updated_at, 0).map(|x| x.naive_utc()),
+        }
+    }
+
+    #[test]
+    fn test_merge_prices() {
+        let results = vec![
+            vec![price("bitcoin", 100.0, 1), price("ethereum", 10.0, 1)],
+            vec![price("bitcoin", 102.0, 2), price("ethereum", 30.0, 2)],
+            vec![price("bitcoin", 101.0, 3), price("solana", 5.0, 3)],
+        ];
+
+        let prices = merge_prices(results).into_iter().map(|x| (x.price_id.clone(), x)).collect::<HashMap<_, _>>();
+
+        assert_eq!(prices["bitcoin"].price, 101.0);
+        assert_eq!(prices["bitcoin"].last_updated_at, price("bitcoin", 0.0, 3).last_updated_at);
+        // second provider is out of range for ethereum and ignored
+        assert_eq!(prices["ethereum"].price, 10.0);
+        assert_eq!(prices["ethereum"].last_updated_at, price("ethereum", 0.0, 1).last_updated_at);
+        // missing in the primary provider, fallback provider is used
+        assert_eq!(prices["solana"].price, 5.0);
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(median(vec![]), 0.0);
+        assert_eq!(median(vec![3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(vec![4.0, 1.0, 2.0, 3.0]), 2.5);
+    }
+}
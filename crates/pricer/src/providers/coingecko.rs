@@ -0,0 +1,43 @@
+use std::error::Error;
+
+use async_trait::async_trait;
+use chrono::DateTime;
+use coingecko::CoinGeckoClient;
+use primitives::DEFAULT_FIAT_CURRENCY;
+
+use crate::price_provider::{PriceProvider, ProviderPrice};
+
+pub struct CoinGeckoPriceProvider {
+    client: CoinGeckoClient,
+}
+
+impl CoinGeckoPriceProvider {
+    pub fn new(client: CoinGeckoClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl PriceProvider for CoinGeckoPriceProvider {
+    fn name(&self) -> &'static str {
+        "coingecko"
+    }
+
+    async fn get_prices(&self, price_ids: Vec<String>) -> Result<Vec<ProviderPrice>, Box<dyn Error + Send + Sync>> {
+        let prices = self.client.get_prices_by_ids(price_ids, DEFAULT_FIAT_CURRENCY).await?;
+
+        Ok(prices
+            .into_iter()
+            .flat_map(|(id, price)| {
+                Some(ProviderPrice {
+                    price_id: id,
+                    price: price.usd?,
+                    price_change_percentage_24h: price.usd_24h_change.unwrap_or_default(),
+                    market_cap: price.usd_market_cap.unwrap_or_default(),
+                    total_volume: price.usd_24h_vol.unwrap_or_default(),
+                    last_updated_at: price.last_updated_at.and_then(|x| DateTime::from_timestamp(x as i64, 0)).map(|x| x.naive_utc()),
+                })
+            })
+            .collect())
+    }
+}
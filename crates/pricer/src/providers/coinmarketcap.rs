@@ -0,0 +1,49 @@
+use std::collections::HashMap;
+use std::error::Error;
+
+use async_trait::async_trait;
+use coinmarketcap::{get_coinmarketcap_slug, CoinMarketCapClient};
+use primitives::DEFAULT_FIAT_CURRENCY;
+
+use crate::price_provider::{PriceProvider, ProviderPrice};
+
+pub struct CoinMarketCapPriceProvider {
+    client: CoinMarketCapClient,
+}
+
+impl CoinMarketCapPriceProvider {
+    pub fn new(client: CoinMarketCapClient) -> Self {
+        Self { client }
+    }
+}
+
+#[async_trait]
+impl PriceProvider for CoinMarketCapPriceProvider {
+    fn name(&self) -> &'static str {
+        "coinmarketcap"
+    }
+
+    async fn get_prices(&self, price_ids: Vec<String>) -> Result<Vec<ProviderPrice>, Box<dyn Error + Send + Sync>> {
+        let slugs_map: HashMap<String, String> = price_ids.iter().map(|x| (get_coinmarketcap_slug(x).to_string(), x.clone())).collect();
+        let quotes = self
+            .client
+            .get_quotes_latest(slugs_map.keys().cloned().collect(), DEFAULT_FIAT_CURRENCY)
+            .await?;
+
+        Ok(quotes
+            .data
+            .into_values()
+            .flat_map(|coin| {
+                let quote = coin.quote.get(DEFAULT_FIAT_CURRENCY)?;
+                Some(ProviderPrice {
+                    price_id: slugs_map.get(&coin.slug)?.clone(),
+                    price: quote.price?,
+                    price_change_percentage_24h: quote.percent_change_24h.unwrap_or_default(),
+                    market_cap: quote.market_cap.unwrap_or_default(),
+                    total_volume: quote.volume_24h.unwrap_or_default(),
+                    last_updated_at: quote.last_updated.map(|x| x.naive_utc()),
+                })
+            })
+            .collect())
+    }
+}
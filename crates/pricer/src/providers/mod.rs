@@ -0,0 +1,5 @@
+pub mod coingecko;
+pub mod coinmarketcap;
+
+pub use self::coingecko::CoinGeckoPriceProvider;
+pub use self::coinmarketcap::CoinMarketCapPriceProvider;
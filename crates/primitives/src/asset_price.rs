@@ -10,6 +10,8 @@ pub struct AssetPrice {
     pub asset_id: String,
     pub price: f64,
     pub price_change_percentage_24h: f64,
+    #[serde(default)]
+    pub is_stale: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use strum::{AsRefStr, EnumIter, EnumString};
 use typeshare::typeshare;
 
-#[derive(Debug, Clone, Serialize, Deserialize, AsRefStr, EnumString, EnumIter)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, AsRefStr, EnumString, EnumIter)]
 #[typeshare(swift = "Equatable, Sendable, CaseIterable")]
 #[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "camelCase")]
@@ -73,7 +73,7 @@ pub mod security_scan;
 pub use self::security_scan::SecurityMetadata;
 pub use self::security_scan::SecurityResponse;
 pub mod transaction_metadata_types;
-pub use self::transaction_metadata_types::TransactionSwapMetadata;
+pub use self::transaction_metadata_types::{TransactionNFTTransferMetadata, TransactionSwapMetadata};
 pub mod big_number_formatter;
 pub use self::big_number_formatter::BigNumberFormatter;
 pub mod number_formatter;
@@ -81,9 +81,11 @@ pub use self::number_formatter::NumberFormatter;
 pub mod wallet_connect;
 pub use self::wallet_connect::WallletConnectCAIP2;
 pub mod nft;
-pub use self::nft::{NFTAttrubute, NFTCollectible, NFTCollection, NFTImage};
+pub use self::nft::{NFTAttrubute, NFTCollectible, NFTCollection, NFTImage, NFTType};
 pub mod price_alert;
-pub use self::price_alert::{PriceAlert, PriceAlertDirection, PriceAlertType, PriceAlerts};
+pub use self::price_alert::{PriceAlert, PriceAlertDirection, PriceAlertRepeat, PriceAlertType, PriceAlerts};
+pub mod portfolio;
+pub use self::portfolio::{AssetBalance, Portfolio, PortfolioAsset};
 
 pub mod chain_cosmos;
 pub use self::chain_cosmos::CosmosDenom;
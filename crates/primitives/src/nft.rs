@@ -58,4 +58,6 @@ pub enum NFTType {
     ERC721,
     ERC1155,
     SPL,
+    // TON NFT items (TEP-62)
+    TON,
 }
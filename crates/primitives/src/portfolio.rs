@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::{AssetId, ChartValue};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetBalance {
+    pub asset_id: AssetId,
+    pub balance: String,
+}
+
+impl AssetBalance {
+    pub fn new(asset_id: AssetId, balance: String) -> Self {
+        Self { asset_id, balance }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[typeshare(swift = "Sendable")]
+#[serde(rename_all = "camelCase")]
+pub struct Portfolio {
+    pub currency: String,
+    pub total_value: f64,
+    pub assets: Vec<PortfolioAsset>,
+    pub history: Vec<ChartValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[typeshare(swift = "Sendable")]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioAsset {
+    pub asset_id: String,
+    pub balance: String,
+    pub price: f64,
+    pub value: f64,
+    // share of the total value in percent
+    pub allocation: f64,
+}
@@ -9,6 +9,9 @@ use crate::{Asset, AssetLink, AssetMarket, PriceAlert};
 pub struct Price {
     pub price: f64,
     pub price_change_percentage_24h: f64,
+    // not refreshed by any price provider recently
+    #[serde(default)]
+    pub is_stale: bool,
 }
 
 impl Price {
@@ -19,6 +22,7 @@ impl Price {
         Price {
             price: price_value,
             price_change_percentage_24h: self.price_change_percentage_24h,
+            is_stale: self.is_stale,
         }
     }
 }
@@ -53,6 +57,7 @@ mod tests {
         let price = Price {
             price: 100.0,
             price_change_percentage_24h: 5.0,
+            is_stale: false,
         };
 
         let new_price = price.new_with_rate(1.0, 2.0);
@@ -12,6 +12,11 @@ pub struct PriceAlert {
     pub price: Option<f64>,
     pub price_percent_change: Option<f64>,
     pub price_direction: Option<PriceAlertDirection>,
+    // currency of price, defaults to USD
+    pub currency: Option<String>,
+    pub repeat: Option<PriceAlertRepeat>,
+    // minimum seconds between notifications of a recurring alert, at least one hour
+    pub cooldown: Option<i32>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -32,7 +37,16 @@ pub enum PriceAlertDirection {
     Down,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize, AsRefStr, EnumString, PartialEq)]
+#[typeshare(swift = "Equatable, Hashable, Sendable")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum PriceAlertRepeat {
+    Once,
+    Recurring,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum PriceAlertType {
     PriceChangesUp,
     PriceChangesDown,
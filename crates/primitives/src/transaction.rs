@@ -138,6 +138,21 @@ impl Transaction {
         format!("{}_{}", chain.as_ref(), hash)
     }
 
+    pub fn id_from_index(chain: Chain, hash: String, index: usize) -> String {
+        match index {
+            0 => Self::id_from(chain, hash),
+            _ => format!("{}_{}_{}", chain.as_ref(), hash, index),
+        }
+    }
+
+    // Transactions decoded into several records keep the hash id for the first record and suffix the others with their index
+    pub fn with_index(self, index: usize) -> Self {
+        Self {
+            id: Self::id_from_index(self.asset_id.chain, self.hash.clone(), index),
+            ..self
+        }
+    }
+
     pub fn is_utxo_tx(&self) -> bool {
         !self.utxo_inputs.is_empty() && !self.utxo_outputs.is_empty()
     }
@@ -260,7 +275,8 @@ impl Transaction {
                 .and_then(|metadata| serde_json::from_value::<TransactionSwapMetadata>(metadata).ok())
                 .map(|metadata| vec![metadata.from_asset.to_string(), metadata.to_asset.to_string()])
                 .unwrap_or_default(),
-            TransactionType::TokenApproval
+            TransactionType::TransferNFT
+            | TransactionType::TokenApproval
             | TransactionType::StakeDelegate
             | TransactionType::StakeUndelegate
             | TransactionType::StakeRewards
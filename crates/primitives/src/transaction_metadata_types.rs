@@ -12,3 +12,12 @@ pub struct TransactionSwapMetadata {
     pub to_asset: AssetId,
     pub to_value: String,
 }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[typeshare(swift = "Sendable")]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionNFTTransferMetadata {
+    pub contract: String,
+    pub token_id: String,
+    pub value: String,
+}
@@ -17,6 +17,7 @@ pub struct Settings {
     pub banxa: Banxa,
     pub kado: Kado,
     pub coingecko: CoinGecko,
+    pub coinmarketcap: CoinMarketCap,
     pub pricer: Pricer,
     pub charter: Charter,
     pub name: Name,
@@ -112,6 +113,12 @@ pub struct CoinGecko {
     pub key: KeySecret,
 }
 
+#[derive(Debug, Deserialize, Clone)]
+#[allow(unused)]
+pub struct CoinMarketCap {
+    pub key: KeySecret,
+}
+
 #[derive(Debug, Deserialize, Clone)]
 #[allow(unused)]
 pub struct Pricer {
@@ -214,6 +221,18 @@ pub struct Chains {
 #[allow(unused)]
 pub struct Chain {
     pub url: String,
+    // Fallback endpoints, requests go to the healthiest of url and urls
+    pub urls: Option<Vec<String>>,
+    // EVM only, enables internal native transfers
+    pub trace: Option<ChainTrace>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq)]
+pub enum ChainTrace {
+    #[serde(rename = "debug_traceBlockByNumber")]
+    DebugTraceBlock,
+    #[serde(rename = "trace_block")]
+    TraceBlock,
 }
 
 #[derive(Debug, Deserialize, Clone)]
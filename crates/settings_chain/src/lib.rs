@@ -1,29 +1,41 @@
 use core::str;
 
+mod failover;
+pub use failover::{ChainHealth, EndpointHealth, FailoverClients, FailoverProvider};
+
 use gem_chain_rpc::{
-    AptosClient, BitcoinClient, ChainProvider, CosmosClient, EthereumClient, MockChainBlockClient, NearClient, SolanaClient, SuiClient, TonClient, TronClient,
-    XRPClient,
+    AlgorandClient, AptosClient, BitcoinClient, ChainAddressHistoryProvider, ChainBalanceProvider, ChainBlockProvider, ChainNFTProvider, ChainNFTScanProvider,
+    ChainProvider, ChainTokenDataProvider, CosmosClient, EthereumClient, EthereumTrace, NearClient, SolanaClient, StellarClient, SuiClient, TonClient,
+    TronClient, XRPClient,
 };
-use primitives::{Asset, Chain};
+use primitives::{Asset, Chain, EVMChain};
 use reqwest_middleware::ClientBuilder;
 use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
-use settings::Settings;
+use settings::{Chain as ChainSettings, ChainTrace, Settings};
 
 pub struct ProviderFactory {}
 
 impl ProviderFactory {
     pub fn new_from_settings(chain: Chain, settings: &Settings) -> Box<dyn ChainProvider> {
-        let url = Self::url(chain, settings);
-        Self::new_provider(chain, url)
+        Box::new(Self::new_failover_provider(chain, &Self::urls(chain, settings), Self::trace(chain, settings)))
     }
 
     pub fn new_providers(settings: &Settings) -> ChainProviders {
-        let providers = Chain::all().iter().map(|x| Self::new_from_settings(*x, &settings.clone())).collect();
+        let providers = Chain::all()
+            .into_iter()
+            .map(|x| Self::new_failover_provider(x, &Self::urls(x, settings), Self::trace(x, settings)))
+            .collect();
         ChainProviders::new(providers)
     }
 
-    pub fn new_provider(chain: Chain, url: &str) -> Box<dyn ChainProvider> {
-        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(5);
+    pub fn new_failover_provider(chain: Chain, urls: &[String], trace: Option<EthereumTrace>) -> FailoverProvider {
+        let providers = urls.iter().map(|url| (url.clone(), Self::new_provider(chain, url, trace))).collect();
+        FailoverProvider::new(chain, providers)
+    }
+
+    pub fn new_provider(chain: Chain, url: &str, trace: Option<EthereumTrace>) -> Box<dyn ChainProvider> {
+        // a single retry for transient errors, FailoverProvider moves on to the next endpoint after that
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(1);
         let client = ClientBuilder::new(reqwest::Client::new())
             .with(RetryTransientMiddleware::new_with_policy(retry_policy))
             .build();
@@ -48,7 +60,7 @@ impl ProviderFactory {
             | Chain::Mantle
             | Chain::Celo
             | Chain::World
-            | Chain::Sonic => Box::new(EthereumClient::new(chain, url)),
+            | Chain::Sonic => Box::new(EthereumClient::new(chain, url, trace)),
             Chain::Cosmos | Chain::Osmosis | Chain::Celestia | Chain::Thorchain | Chain::Injective | Chain::Noble | Chain::Sei => {
                 Box::new(CosmosClient::new(chain, client, url))
             }
@@ -59,62 +71,187 @@ impl ProviderFactory {
             Chain::Sui => Box::new(SuiClient::new(url)),
             Chain::Xrp => Box::new(XRPClient::new(client, url)),
             Chain::Near => Box::new(NearClient::new(url)),
-            Chain::Stellar | Chain::Algorand => Box::new(MockChainBlockClient::new(chain)),
+            Chain::Stellar => Box::new(StellarClient::new(client, url)),
+            Chain::Algorand => Box::new(AlgorandClient::new(client, url)),
+        }
+    }
+
+    // Chains with collectibles support by owner, Solana (Metaplex) and TON
+    pub fn new_nft_provider(chain: Chain, settings: &Settings) -> Option<Box<dyn ChainNFTProvider>> {
+        Self::new_failover_clients(chain, settings, Self::new_nft_client).map(|x| Box::new(x) as Box<dyn ChainNFTProvider>)
+    }
+
+    fn new_nft_client(chain: Chain, url: String) -> Option<Box<dyn ChainNFTProvider>> {
+        match chain {
+            Chain::Solana => Some(Box::new(SolanaClient::new(url))),
+            Chain::Ton => Some(Box::new(TonClient::new(ClientBuilder::new(reqwest::Client::new()).build(), url))),
+            _ => None,
+        }
+    }
+
+    pub fn new_nft_providers(settings: &Settings) -> Vec<(Chain, Box<dyn ChainNFTProvider>)> {
+        Chain::all()
+            .into_iter()
+            .flat_map(|chain| Some((chain, Self::new_nft_provider(chain, settings)?)))
+            .collect()
+    }
+
+    // Chains with collectibles found by scanning transfer logs, EVM (ERC721, ERC1155)
+    pub fn new_nft_scan_provider(chain: Chain, settings: &Settings) -> Option<Box<dyn ChainNFTScanProvider>> {
+        Self::new_failover_clients(chain, settings, Self::new_nft_scan_client).map(|x| Box::new(x) as Box<dyn ChainNFTScanProvider>)
+    }
+
+    fn new_nft_scan_client(chain: Chain, url: String) -> Option<Box<dyn ChainNFTScanProvider>> {
+        EVMChain::from_chain(chain).map(|_| Box::new(EthereumClient::new(chain, url, None)) as Box<dyn ChainNFTScanProvider>)
+    }
+
+    pub fn new_nft_scan_providers(settings: &Settings) -> Vec<(Chain, Box<dyn ChainNFTScanProvider>)> {
+        Chain::all()
+            .into_iter()
+            .flat_map(|chain| Some((chain, Self::new_nft_scan_provider(chain, settings)?)))
+            .collect()
+    }
+
+    // Chains with address history, Solana (getSignaturesForAddress), TON (tonapi), Bitcoin based (blockbook), Tron (TronGrid) and EVM (ERC20 transfer logs)
+    pub fn new_address_history_provider(chain: Chain, settings: &Settings) -> Option<Box<dyn ChainAddressHistoryProvider>> {
+        Self::new_failover_clients(chain, settings, Self::new_address_history_client).map(|x| Box::new(x) as Box<dyn ChainAddressHistoryProvider>)
+    }
+
+    fn new_address_history_client(chain: Chain, url: String) -> Option<Box<dyn ChainAddressHistoryProvider>> {
+        let client = ClientBuilder::new(reqwest::Client::new()).build();
+        match chain {
+            Chain::Solana => Some(Box::new(SolanaClient::new(url))),
+            Chain::Ton => Some(Box::new(TonClient::new(client, url))),
+            Chain::Bitcoin | Chain::Litecoin | Chain::Doge => Some(Box::new(BitcoinClient::new(chain, client, url))),
+            Chain::Tron => Some(Box::new(TronClient::new(client, url))),
+            _ => EVMChain::from_chain(chain).map(|_| Box::new(EthereumClient::new(chain, url, None)) as Box<dyn ChainAddressHistoryProvider>),
         }
     }
 
+    pub fn new_address_history_providers(settings: &Settings) -> Vec<(Chain, Box<dyn ChainAddressHistoryProvider>)> {
+        Chain::all()
+            .into_iter()
+            .flat_map(|chain| Some((chain, Self::new_address_history_provider(chain, settings)?)))
+            .collect()
+    }
+
+    // Chains with balance support, EVM (native and ERC20), Solana (native and SPL) and TON (native and jettons)
+    pub fn new_balance_provider(chain: Chain, settings: &Settings) -> Option<Box<dyn ChainBalanceProvider>> {
+        Self::new_failover_clients(chain, settings, Self::new_balance_client).map(|x| Box::new(x) as Box<dyn ChainBalanceProvider>)
+    }
+
+    fn new_balance_client(chain: Chain, url: String) -> Option<Box<dyn ChainBalanceProvider>> {
+        match chain {
+            Chain::Solana => Some(Box::new(SolanaClient::new(url))),
+            Chain::Ton => Some(Box::new(TonClient::new(ClientBuilder::new(reqwest::Client::new()).build(), url))),
+            _ => EVMChain::from_chain(chain).map(|_| Box::new(EthereumClient::new(chain, url, None)) as Box<dyn ChainBalanceProvider>),
+        }
+    }
+
+    pub fn new_balance_providers(settings: &Settings) -> Vec<(Chain, Box<dyn ChainBalanceProvider>)> {
+        Chain::all()
+            .into_iter()
+            .flat_map(|chain| Some((chain, Self::new_balance_provider(chain, settings)?)))
+            .collect()
+    }
+
+    // A client for every endpoint of the chain, None for chains the client does not support
+    fn new_failover_clients<T: ?Sized + Send + Sync>(
+        chain: Chain,
+        settings: &Settings,
+        new_client: fn(Chain, String) -> Option<Box<T>>,
+    ) -> Option<FailoverClients<T>> {
+        let clients = Self::urls(chain, settings)
+            .into_iter()
+            .map(|url| Some((url.clone(), new_client(chain, url)?)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(FailoverClients::new(chain, clients))
+    }
+
     pub fn url(chain: Chain, settings: &Settings) -> &str {
+        Self::chain_settings(chain, settings).url.as_str()
+    }
+
+    // Primary url first, followed by the fallback urls
+    pub fn urls(chain: Chain, settings: &Settings) -> Vec<String> {
+        let chain_settings = Self::chain_settings(chain, settings);
+        let mut urls = vec![chain_settings.url.clone()];
+        for url in chain_settings.urls.clone().unwrap_or_default() {
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    }
+
+    pub fn trace(chain: Chain, settings: &Settings) -> Option<EthereumTrace> {
+        Self::chain_settings(chain, settings).trace.map(|trace| match trace {
+            ChainTrace::DebugTraceBlock => EthereumTrace::DebugTraceBlock,
+            ChainTrace::TraceBlock => EthereumTrace::TraceBlock,
+        })
+    }
+
+    fn chain_settings(chain: Chain, settings: &Settings) -> &ChainSettings {
         match chain {
-            Chain::Bitcoin => settings.chains.bitcoin.url.as_str(),
-            Chain::Litecoin => settings.chains.litecoin.url.as_str(),
-            Chain::Ethereum => settings.chains.ethereum.url.as_str(),
-            Chain::SmartChain => settings.chains.smartchain.url.as_str(),
-            Chain::Solana => settings.chains.solana.url.as_str(),
-            Chain::Polygon => settings.chains.polygon.url.as_str(),
-            Chain::Thorchain => settings.chains.thorchain.url.as_str(),
-            Chain::Cosmos => settings.chains.cosmos.url.as_str(),
-            Chain::Osmosis => settings.chains.osmosis.url.as_str(),
-            Chain::Arbitrum => settings.chains.arbitrum.url.as_str(),
-            Chain::Ton => settings.chains.ton.url.as_str(),
-            Chain::Tron => settings.chains.tron.url.as_str(),
-            Chain::Doge => settings.chains.doge.url.as_str(),
-            Chain::Optimism => settings.chains.optimism.url.as_str(),
-            Chain::Aptos => settings.chains.aptos.url.as_str(),
-            Chain::Base => settings.chains.base.url.as_str(),
-            Chain::AvalancheC => settings.chains.avalanchec.url.as_str(),
-            Chain::Sui => settings.chains.sui.url.as_str(),
-            Chain::Xrp => settings.chains.xrp.url.as_str(),
-            Chain::OpBNB => settings.chains.opbnb.url.as_str(),
-            Chain::Fantom => settings.chains.fantom.url.as_str(),
-            Chain::Gnosis => settings.chains.gnosis.url.as_str(),
-            Chain::Celestia => settings.chains.celestia.url.as_str(),
-            Chain::Injective => settings.chains.injective.url.as_str(),
-            Chain::Sei => settings.chains.sei.url.as_str(),
-            Chain::Manta => settings.chains.manta.url.as_str(),
-            Chain::Blast => settings.chains.blast.url.as_str(),
-            Chain::Noble => settings.chains.noble.url.as_str(),
-            Chain::ZkSync => settings.chains.zksync.url.as_str(),
-            Chain::Linea => settings.chains.linea.url.as_str(),
-            Chain::Mantle => settings.chains.mantle.url.as_str(),
-            Chain::Celo => settings.chains.celo.url.as_str(),
-            Chain::Near => settings.chains.near.url.as_str(),
-            Chain::World => settings.chains.world.url.as_str(),
-            Chain::Stellar => settings.chains.stellar.url.as_str(),
-            Chain::Sonic => settings.chains.sonic.url.as_str(),
-            Chain::Algorand => settings.chains.algorand.url.as_str(),
+            Chain::Bitcoin => &settings.chains.bitcoin,
+            Chain::Litecoin => &settings.chains.litecoin,
+            Chain::Ethereum => &settings.chains.ethereum,
+            Chain::SmartChain => &settings.chains.smartchain,
+            Chain::Solana => &settings.chains.solana,
+            Chain::Polygon => &settings.chains.polygon,
+            Chain::Thorchain => &settings.chains.thorchain,
+            Chain::Cosmos => &settings.chains.cosmos,
+            Chain::Osmosis => &settings.chains.osmosis,
+            Chain::Arbitrum => &settings.chains.arbitrum,
+            Chain::Ton => &settings.chains.ton,
+            Chain::Tron => &settings.chains.tron,
+            Chain::Doge => &settings.chains.doge,
+            Chain::Optimism => &settings.chains.optimism,
+            Chain::Aptos => &settings.chains.aptos,
+            Chain::Base => &settings.chains.base,
+            Chain::AvalancheC => &settings.chains.avalanchec,
+            Chain::Sui => &settings.chains.sui,
+            Chain::Xrp => &settings.chains.xrp,
+            Chain::OpBNB => &settings.chains.opbnb,
+            Chain::Fantom => &settings.chains.fantom,
+            Chain::Gnosis => &settings.chains.gnosis,
+            Chain::Celestia => &settings.chains.celestia,
+            Chain::Injective => &settings.chains.injective,
+            Chain::Sei => &settings.chains.sei,
+            Chain::Manta => &settings.chains.manta,
+            Chain::Blast => &settings.chains.blast,
+            Chain::Noble => &settings.chains.noble,
+            Chain::ZkSync => &settings.chains.zksync,
+            Chain::Linea => &settings.chains.linea,
+            Chain::Mantle => &settings.chains.mantle,
+            Chain::Celo => &settings.chains.celo,
+            Chain::Near => &settings.chains.near,
+            Chain::World => &settings.chains.world,
+            Chain::Stellar => &settings.chains.stellar,
+            Chain::Sonic => &settings.chains.sonic,
+            Chain::Algorand => &settings.chains.algorand,
         }
     }
 }
 
+#[derive(Clone)]
 pub struct ChainProviders {
-    providers: Vec<Box<dyn ChainProvider>>,
+    providers: Vec<FailoverProvider>,
 }
 
 impl ChainProviders {
-    pub fn new(providers: Vec<Box<dyn ChainProvider>>) -> Self {
+    pub fn new(providers: Vec<FailoverProvider>) -> Self {
         Self { providers }
     }
 
+    pub fn health(&self) -> Vec<ChainHealth> {
+        self.providers.iter().map(|x| x.health()).collect()
+    }
+
+    pub async fn check_health(&self) {
+        futures::future::join_all(self.providers.iter().map(|x| x.check_health())).await;
+    }
+
     pub async fn get_token_data(&self, chain: Chain, token_id: String) -> Result<Asset, Box<dyn std::error::Error + Send + Sync>> {
         self.providers
             .iter()
@@ -138,6 +138,7 @@ impl DatabaseClient {
                 total_supply.eq(excluded(total_supply)),
                 max_supply.eq(excluded(max_supply)),
                 last_updated_at.eq(excluded(last_updated_at)),
+                is_stale.eq(excluded(is_stale)),
             ))
             .execute(&mut self.connection)
     }
@@ -154,10 +155,23 @@ impl DatabaseClient {
                 market_cap.eq(excluded(market_cap)),
                 total_volume.eq(excluded(total_volume)),
                 last_updated_at.eq(excluded(last_updated_at)),
+                is_stale.eq(excluded(is_stale)),
             ))
             .execute(&mut self.connection)
     }
 
+    // Prices without an update time were never confirmed by a provider and are stale as well
+    pub fn set_prices_stale(&mut self, before: NaiveDateTime) -> Result<usize, diesel::result::Error> {
+        use crate::schema::prices::dsl::*;
+        diesel::update(
+            prices
+                .filter(is_stale.eq(false))
+                .filter(last_updated_at.lt(before).or(last_updated_at.is_null())),
+        )
+        .set(is_stale.eq(true))
+        .execute(&mut self.connection)
+    }
+
     pub fn set_prices_assets(&mut self, values: Vec<PriceAsset>) -> Result<usize, diesel::result::Error> {
         use crate::schema::prices_assets::dsl::*;
         diesel::insert_into(prices_assets)
@@ -325,6 +339,62 @@ impl DatabaseClient {
             .execute(&mut self.connection)
     }
 
+    pub fn get_parser_state_block(&mut self, _chain: Chain, block: i32) -> Result<Option<ParserStateBlock>, diesel::result::Error> {
+        use crate::schema::parser_state_blocks::dsl::*;
+        parser_state_blocks
+            .filter(chain.eq(_chain.as_ref()))
+            .filter(block_number.eq(block))
+            .select(ParserStateBlock::as_select())
+            .first(&mut self.connection)
+            .optional()
+    }
+
+    pub fn add_parser_state_blocks(&mut self, values: Vec<ParserStateBlock>) -> Result<usize, diesel::result::Error> {
+        use crate::schema::parser_state_blocks::dsl::*;
+        diesel::insert_into(parser_state_blocks)
+            .values(&values)
+            .on_conflict((chain, block_number))
+            .do_update()
+            .set((block_hash.eq(excluded(block_hash)), parent_hash.eq(excluded(parent_hash))))
+            .execute(&mut self.connection)
+    }
+
+    pub fn delete_parser_state_blocks_before(&mut self, _chain: Chain, block: i32) -> Result<usize, diesel::result::Error> {
+        use crate::schema::parser_state_blocks::dsl::*;
+        diesel::delete(parser_state_blocks.filter(chain.eq(_chain.as_ref())).filter(block_number.lt(block))).execute(&mut self.connection)
+    }
+
+    // Removes everything parsed after the fork block and moves the parser back to it, returns number of deleted transactions
+    pub fn rollback_parser_state(&mut self, _chain: Chain, fork_block: i32) -> Result<usize, diesel::result::Error> {
+        self.connection
+            .build_transaction()
+            .read_write()
+            .run::<_, diesel::result::Error, _>(|conn: &mut PgConnection| {
+                use crate::schema::transactions::dsl::*;
+
+                let transaction_ids = transactions.filter(chain.eq(_chain.as_ref())).filter(block_number.gt(fork_block)).select(id);
+
+                diesel::delete(transactions_addresses::table.filter(transactions_addresses::transaction_id.eq_any(transaction_ids))).execute(conn)?;
+
+                let deleted = diesel::delete(transactions.filter(chain.eq(_chain.as_ref())).filter(block_number.gt(fork_block))).execute(conn)?;
+
+                use crate::schema::parser_state_blocks::dsl as blocks;
+                diesel::delete(
+                    blocks::parser_state_blocks
+                        .filter(blocks::chain.eq(_chain.as_ref()))
+                        .filter(blocks::block_number.gt(fork_block)),
+                )
+                .execute(conn)?;
+
+                use crate::schema::parser_state::dsl as state;
+                diesel::update(state::parser_state.find(_chain.as_ref()))
+                    .set(state::current_block.eq(fork_block))
+                    .execute(conn)?;
+
+                Ok(deleted)
+            })
+    }
+
     pub fn get_subscriptions_by_device_id(&mut self, _device_id: &str) -> Result<Vec<Subscription>, diesel::result::Error> {
         use crate::schema::subscriptions::dsl::*;
         subscriptions
@@ -379,6 +449,116 @@ impl DatabaseClient {
             .load(&mut self.connection)
     }
 
+    // Subscriptions added after a given time for addresses without backfilled history
+    pub fn get_subscriptions_without_backfill(&mut self, after: NaiveDateTime) -> Result<Vec<Subscription>, diesel::result::Error> {
+        use crate::schema::subscriptions::dsl::*;
+        use crate::schema::subscriptions_addresses_backfill as backfill;
+        subscriptions
+            .left_join(backfill::table.on(backfill::chain.eq(chain).and(backfill::address.eq(address))))
+            .filter(created_at.gt(after))
+            .filter(backfill::address.nullable().is_null())
+            .select(Subscription::as_select())
+            .load(&mut self.connection)
+    }
+
+    pub fn add_subscriptions_addresses_backfill(&mut self, values: Vec<SubscriptionAddressBackfill>) -> Result<usize, diesel::result::Error> {
+        use crate::schema::subscriptions_addresses_backfill::dsl::*;
+        diesel::insert_into(subscriptions_addresses_backfill)
+            .values(values)
+            .on_conflict_do_nothing()
+            .execute(&mut self.connection)
+    }
+
+    pub fn add_push_outbox(&mut self, value: NewPushOutbox) -> Result<usize, diesel::result::Error> {
+        use crate::schema::push_outbox::dsl::*;
+        diesel::insert_into(push_outbox).values(value).execute(&mut self.connection)
+    }
+
+    pub fn update_push_outbox(&mut self, _id: i32, attempt: PushOutboxAttempt) -> Result<usize, diesel::result::Error> {
+        use crate::schema::push_outbox::dsl::*;
+        diesel::update(push_outbox.find(_id)).set(attempt).execute(&mut self.connection)
+    }
+
+    // Pending pushes due for retry, claimed rows are moved forward by the lease so concurrent parsers skip them
+    pub fn claim_push_outbox_pending(&mut self, limit: i64, lease: Duration) -> Result<Vec<PushOutbox>, diesel::result::Error> {
+        self.connection
+            .build_transaction()
+            .read_write()
+            .run::<_, diesel::result::Error, _>(|conn: &mut PgConnection| {
+                use crate::schema::push_outbox::dsl::*;
+                let now = Utc::now().naive_utc();
+                let values = push_outbox
+                    .filter(status.eq(PushOutboxStatus::Pending.as_ref()))
+                    .filter(next_attempt_at.le(now))
+                    .order(next_attempt_at.asc())
+                    .limit(limit)
+                    .select(PushOutbox::as_select())
+                    .for_update()
+                    .skip_locked()
+                    .load(conn)?;
+
+                let ids = values.iter().map(|x| x.id).collect::<Vec<_>>();
+                diesel::update(push_outbox.filter(id.eq_any(ids)))
+                    .set(next_attempt_at.eq(now + lease))
+                    .execute(conn)?;
+
+                Ok(values)
+            })
+    }
+
+    // Delivery attempt outcomes are counted by status, the counts are kept when outbox rows are deleted
+    pub fn get_nft_scan(&mut self, _chain: Chain, _address: &str) -> Result<Option<NFTScan>, diesel::result::Error> {
+        use crate::schema::nft_scans::dsl::*;
+        nft_scans
+            .filter(chain.eq(_chain.as_ref()))
+            .filter(address.eq(_address))
+            .select(NFTScan::as_select())
+            .first(&mut self.connection)
+            .optional()
+    }
+
+    pub fn set_nft_scan(&mut self, value: NFTScan) -> Result<usize, diesel::result::Error> {
+        use crate::schema::nft_scans::dsl::*;
+        diesel::insert_into(nft_scans)
+            .values(&value)
+            .on_conflict((chain, address))
+            .do_update()
+            .set(&value)
+            .execute(&mut self.connection)
+    }
+
+    pub fn add_push_deliveries(&mut self, _status: &str, _count: i64) -> Result<usize, diesel::result::Error> {
+        use crate::schema::push_deliveries::dsl;
+        diesel::insert_into(dsl::push_deliveries)
+            .values((dsl::status.eq(_status), dsl::count.eq(_count)))
+            .on_conflict(dsl::status)
+            .do_update()
+            .set(dsl::count.eq(dsl::count + _count))
+            .execute(&mut self.connection)
+    }
+
+    pub fn get_push_deliveries(&mut self) -> Result<Vec<(String, i64)>, diesel::result::Error> {
+        use crate::schema::push_deliveries::dsl;
+        dsl::push_deliveries.select((dsl::status, dsl::count)).load(&mut self.connection)
+    }
+
+    pub fn get_push_outbox_counts(&mut self) -> Result<Vec<(String, i64)>, diesel::result::Error> {
+        use crate::schema::push_outbox::dsl::*;
+        push_outbox.group_by(status).select((status, count(id))).load(&mut self.connection)
+    }
+
+    // Delete delivered and failed pushes, pending ones are kept until retries are exhausted
+    pub fn delete_push_outbox_after_days(&mut self, days: i64) -> Result<usize, diesel::result::Error> {
+        use crate::schema::push_outbox::dsl::*;
+        let cutoff_date = Utc::now() - Duration::days(days);
+        diesel::delete(
+            push_outbox
+                .filter(status.ne(PushOutboxStatus::Pending.as_ref()))
+                .filter(created_at.lt(cutoff_date.naive_utc())),
+        )
+        .execute(&mut self.connection)
+    }
+
     pub fn add_subscriptions(&mut self, _subscriptions: Vec<Subscription>) -> Result<usize, diesel::result::Error> {
         use crate::schema::subscriptions::dsl::*;
         diesel::insert_into(subscriptions)
@@ -399,7 +579,7 @@ impl DatabaseClient {
                 use crate::schema::transactions::dsl::*;
                 let query1 = diesel::insert_into(transactions::table())
                     .values(transactions_values)
-                    .on_conflict((chain, hash))
+                    .on_conflict(id)
                     .do_update()
                     .set((
                         block_number.eq(excluded(block_number)),
@@ -676,10 +856,17 @@ impl DatabaseClient {
     }
 
     // price alerts
-    pub fn get_price_alerts(&mut self, after_notified_at: NaiveDateTime) -> Result<Vec<PriceAlert>, diesel::result::Error> {
+    // Alerts never notified or notified longer than their cooldown before now
+    pub fn get_price_alerts(&mut self, now: NaiveDateTime) -> Result<Vec<PriceAlert>, diesel::result::Error> {
         use crate::schema::price_alerts::dsl::*;
+        use diesel::dsl::sql;
+        use diesel::sql_types::{Bool, Timestamp};
         price_alerts
-            .filter(last_notified_at.lt(after_notified_at).or(last_notified_at.is_null()))
+            .filter(
+                last_notified_at
+                    .is_null()
+                    .or(sql::<Bool>("last_notified_at + make_interval(secs => cooldown) <= ").bind::<Timestamp, _>(now)),
+            )
             .select(PriceAlert::as_select())
             .load(&mut self.connection)
     }
@@ -700,9 +887,9 @@ impl DatabaseClient {
             .execute(&mut self.connection)
     }
 
-    pub fn delete_price_alerts(&mut self, _device_id: i32, asset_ids: Vec<&str>) -> Result<usize, diesel::result::Error> {
+    pub fn delete_price_alerts_by_ids(&mut self, ids: Vec<i32>) -> Result<usize, diesel::result::Error> {
         use crate::schema::price_alerts::dsl::*;
-        diesel::delete(price_alerts.filter(device_id.eq(_device_id).and(asset_id.eq_any(asset_ids)))).execute(&mut self.connection)
+        diesel::delete(price_alerts.filter(id.eq_any(ids))).execute(&mut self.connection)
     }
 
     pub fn update_price_alerts_set_notified_at(&mut self, ids: Vec<i32>, _last_notified_at: NaiveDateTime) -> Result<usize, diesel::result::Error> {
@@ -7,8 +7,11 @@ pub use self::database::DatabaseClient;
 pub mod clickhouse;
 pub mod models;
 pub mod schema;
+pub mod store;
 
 pub use self::clickhouse::clickhouse_database::ClickhouseClient;
+pub use self::store::memory::MemoryStore;
+pub use self::store::{ParserStore, PusherStore};
 
 pub struct RedisClient {
     conn: MultiplexedConnection,
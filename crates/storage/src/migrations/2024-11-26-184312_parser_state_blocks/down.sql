@@ -0,0 +1,3 @@
+drop table parser_state_blocks;
+
+ALTER TABLE parser_state DROP COLUMN reorg_window;
\ No newline at end of file
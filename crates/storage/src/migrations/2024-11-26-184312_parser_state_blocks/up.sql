@@ -0,0 +1,12 @@
+ALTER TABLE parser_state ADD COLUMN reorg_window INTEGER NOT NULL default 0;
+
+UPDATE parser_state SET reorg_window = 64 WHERE chain IN ('polygon', 'smartchain', 'solana');
+
+CREATE TABLE parser_state_blocks (
+    chain VARCHAR NOT NULL REFERENCES chains (id) ON DELETE CASCADE,
+    block_number INTEGER NOT NULL,
+    block_hash VARCHAR(128) NOT NULL,
+    parent_hash VARCHAR(128) NOT NULL,
+    created_at timestamp NOT NULL default current_timestamp,
+    PRIMARY KEY (chain, block_number)
+);
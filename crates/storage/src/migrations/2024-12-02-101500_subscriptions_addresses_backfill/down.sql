@@ -0,0 +1 @@
+drop table subscriptions_addresses_backfill;
@@ -0,0 +1,6 @@
+CREATE TABLE subscriptions_addresses_backfill (
+    chain VARCHAR(32) NOT NULL REFERENCES chains (id) ON DELETE CASCADE,
+    address VARCHAR(256) NOT NULL,
+    created_at timestamp NOT NULL default current_timestamp,
+    PRIMARY KEY (chain, address)
+);
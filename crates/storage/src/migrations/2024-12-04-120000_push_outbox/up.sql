@@ -0,0 +1,17 @@
+CREATE TABLE push_outbox (
+    id SERIAL PRIMARY KEY,
+
+    device_id       INTEGER NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+    notification    jsonb NOT NULL,
+    status          VARCHAR(16) NOT NULL,
+    attempts        INTEGER NOT NULL DEFAULT 0,
+    error           VARCHAR(512),
+    next_attempt_at timestamp NOT NULL default current_timestamp,
+
+    updated_at      timestamp NOT NULL default current_timestamp,
+    created_at      timestamp NOT NULL default current_timestamp
+);
+
+CREATE INDEX push_outbox_status_next_attempt_at_idx ON push_outbox (status, next_attempt_at);
+
+SELECT diesel_manage_updated_at('push_outbox');
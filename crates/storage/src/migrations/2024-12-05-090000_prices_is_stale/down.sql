@@ -0,0 +1 @@
+ALTER TABLE prices DROP COLUMN is_stale;
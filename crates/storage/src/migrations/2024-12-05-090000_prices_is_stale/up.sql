@@ -0,0 +1 @@
+ALTER TABLE prices ADD COLUMN is_stale boolean NOT NULL DEFAULT false;
@@ -0,0 +1,6 @@
+ALTER TABLE price_alerts DROP CONSTRAINT IF EXISTS price_alerts_unique;
+ALTER TABLE price_alerts ADD CONSTRAINT price_alerts_device_id_asset_id_price_price_percent_change_key UNIQUE NULLS NOT DISTINCT (device_id, asset_id, price, price_percent_change);
+
+ALTER TABLE price_alerts DROP COLUMN currency;
+ALTER TABLE price_alerts DROP COLUMN repeat;
+ALTER TABLE price_alerts DROP COLUMN cooldown;
@@ -0,0 +1,6 @@
+ALTER TABLE price_alerts ADD COLUMN currency VARCHAR(8);
+ALTER TABLE price_alerts ADD COLUMN repeat VARCHAR(16) NOT NULL DEFAULT 'recurring';
+ALTER TABLE price_alerts ADD COLUMN cooldown INTEGER NOT NULL DEFAULT 86400;
+
+ALTER TABLE price_alerts DROP CONSTRAINT IF EXISTS price_alerts_device_id_asset_id_price_price_percent_change_key;
+ALTER TABLE price_alerts ADD CONSTRAINT price_alerts_unique UNIQUE NULLS NOT DISTINCT (device_id, asset_id, currency, price, price_percent_change, price_direction);
@@ -0,0 +1,2 @@
+DROP INDEX IF EXISTS transactions_chain_hash_idx;
+ALTER TABLE transactions ADD CONSTRAINT transactions_chain_hash_key UNIQUE (chain, hash);
@@ -0,0 +1,2 @@
+ALTER TABLE transactions DROP CONSTRAINT IF EXISTS transactions_chain_hash_key;
+CREATE INDEX transactions_chain_hash_idx ON transactions (chain, hash);
@@ -0,0 +1,9 @@
+CREATE TABLE push_deliveries (
+    status          VARCHAR(16) PRIMARY KEY NOT NULL,
+    count           BIGINT NOT NULL DEFAULT 0,
+
+    updated_at      timestamp NOT NULL default current_timestamp,
+    created_at      timestamp NOT NULL default current_timestamp
+);
+
+SELECT diesel_manage_updated_at('push_deliveries');
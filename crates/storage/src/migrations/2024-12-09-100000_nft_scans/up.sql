@@ -0,0 +1,13 @@
+CREATE TABLE nft_scans (
+    chain           VARCHAR(32) NOT NULL REFERENCES chains (id) ON DELETE CASCADE,
+    address         VARCHAR(256) NOT NULL,
+    from_block      BIGINT NOT NULL,
+    to_block        BIGINT NOT NULL,
+    tokens          JSONB NOT NULL DEFAULT '[]',
+
+    updated_at      timestamp NOT NULL default current_timestamp,
+    created_at      timestamp NOT NULL default current_timestamp,
+    PRIMARY KEY (chain, address)
+);
+
+SELECT diesel_manage_updated_at('nft_scans');
@@ -4,10 +4,12 @@ pub mod chain;
 pub mod chart;
 pub mod device;
 pub mod fiat;
+pub mod nft_scan;
 pub mod node;
 pub mod parser_state;
 pub mod price;
 pub mod price_alert;
+pub mod push_outbox;
 pub mod release;
 pub mod scan;
 pub mod subscription;
@@ -22,13 +24,15 @@ pub use self::chart::{CreateChart, GetChart};
 pub use self::device::Device;
 pub use self::device::UpdateDevice;
 pub use self::fiat::{FiatAsset, FiatProvider, FiatRate, FiatTransaction, FiatTransactionUpdate};
+pub use self::nft_scan::NFTScan;
 pub use self::node::Node;
-pub use self::parser_state::ParserState;
+pub use self::parser_state::{ParserState, ParserStateBlock};
 pub use self::price::{Price, PriceAsset, PriceCache};
 pub use self::price_alert::{NewPriceAlert, PriceAlert};
+pub use self::push_outbox::{NewPushOutbox, PushOutbox, PushOutboxAttempt, PushOutboxStatus};
 pub use self::release::Release;
 pub use self::scan::ScanAddress;
-pub use self::subscription::{Subscription, SubscriptionAddressExclude};
+pub use self::subscription::{Subscription, SubscriptionAddressBackfill, SubscriptionAddressExclude};
 pub use self::tokenlist::TokenList;
 pub use self::transaction::Transaction;
 pub use self::transaction_addresses::{AddressChainIdResult, TransactionAddresses};
@@ -0,0 +1,14 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+// Progress of the NFT transfer log scan of an address, tokens are the received tokens found so far
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = crate::schema::nft_scans)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NFTScan {
+    pub chain: String,
+    pub address: String,
+    pub from_block: i64,
+    pub to_block: i64,
+    pub tokens: serde_json::Value,
+}
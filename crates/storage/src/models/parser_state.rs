@@ -14,4 +14,15 @@ pub struct ParserState {
     pub parallel_blocks: i32,
     pub is_enabled: bool,
     pub updated_at: NaiveDateTime,
+    pub reorg_window: i32,
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Insertable, Clone)]
+#[diesel(table_name = crate::schema::parser_state_blocks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ParserStateBlock {
+    pub chain: String,
+    pub block_number: i32,
+    pub block_hash: String,
+    pub parent_hash: String,
 }
@@ -24,6 +24,9 @@ pub struct Price {
     pub total_supply: f64,
     pub max_supply: f64,
     pub last_updated_at: Option<NaiveDateTime>,
+    // not refreshed by any price provider recently
+    #[serde(default)]
+    pub is_stale: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -37,6 +40,7 @@ impl PriceCache {
         primitives::Price {
             price: self.price.price,
             price_change_percentage_24h: self.price.price_change_percentage_24h,
+            is_stale: self.price.is_stale,
         }
     }
     pub fn as_asset_price_primitive(&self) -> primitives::AssetPrice {
@@ -44,6 +48,7 @@ impl PriceCache {
             asset_id: self.asset_id.clone(),
             price: self.price.price,
             price_change_percentage_24h: self.price.price_change_percentage_24h,
+            is_stale: self.price.is_stale,
         }
     }
 
@@ -118,6 +123,7 @@ impl Price {
             circulating_supply,
             total_supply,
             max_supply,
+            is_stale: false,
         }
     }
 
@@ -136,6 +142,7 @@ impl Price {
         primitives::Price {
             price: self.price,
             price_change_percentage_24h: self.price_change_percentage_24h,
+            is_stale: self.is_stale,
         }
     }
 
@@ -1,7 +1,12 @@
 use chrono::NaiveDateTime;
 use diesel::prelude::*;
+use primitives::PriceAlertRepeat;
 use serde::{Deserialize, Serialize};
 
+pub const PRICE_ALERT_DEFAULT_COOLDOWN: i32 = 86400;
+// Recurring alerts are not notified more often than this
+pub const PRICE_ALERT_MIN_COOLDOWN: i32 = 3600;
+
 #[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Insertable, AsChangeset, Clone)]
 #[diesel(table_name = crate::schema::price_alerts)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
@@ -13,6 +18,9 @@ pub struct PriceAlert {
     pub price: Option<f64>,
     pub price_percent_change: Option<f64>,
     pub last_notified_at: Option<NaiveDateTime>,
+    pub currency: Option<String>,
+    pub repeat: String,
+    pub cooldown: i32,
 }
 
 #[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Insertable, AsChangeset, Clone)]
@@ -24,6 +32,9 @@ pub struct NewPriceAlert {
     pub price_direction: Option<String>,
     pub price: Option<f64>,
     pub price_percent_change: Option<f64>,
+    pub currency: Option<String>,
+    pub repeat: String,
+    pub cooldown: i32,
 }
 
 impl PriceAlert {
@@ -33,6 +44,9 @@ impl PriceAlert {
             price_direction: self.price_direction.as_deref().and_then(|value| value.parse().ok()),
             price: self.price,
             price_percent_change: self.price_percent_change,
+            currency: self.currency.clone(),
+            repeat: self.repeat.parse().ok(),
+            cooldown: Some(self.cooldown),
         }
     }
 
@@ -43,6 +57,13 @@ impl PriceAlert {
             price_direction: primitive.price_direction.map(|value| value.as_ref().to_string()),
             price: primitive.price,
             price_percent_change: primitive.price_percent_change,
+            currency: primitive.currency,
+            repeat: primitive.repeat.unwrap_or(PriceAlertRepeat::Recurring).as_ref().to_string(),
+            cooldown: primitive.cooldown.unwrap_or(PRICE_ALERT_DEFAULT_COOLDOWN).max(PRICE_ALERT_MIN_COOLDOWN),
         }
     }
+
+    pub fn is_once(&self) -> bool {
+        self.repeat == PriceAlertRepeat::Once.as_ref()
+    }
 }
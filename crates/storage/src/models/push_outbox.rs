@@ -0,0 +1,131 @@
+use chrono::{Duration, NaiveDateTime};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+
+pub const PUSH_OUTBOX_MAX_ATTEMPTS: i32 = 6;
+const PUSH_OUTBOX_BACKOFF_SECONDS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PushOutboxStatus {
+    Pending,
+    Delivered,
+    InvalidToken,
+    // push was disabled for the device before a retry
+    Disabled,
+    Failed,
+}
+
+impl PushOutboxStatus {
+    pub fn all() -> Vec<PushOutboxStatus> {
+        vec![Self::Pending, Self::Delivered, Self::InvalidToken, Self::Disabled, Self::Failed]
+    }
+}
+
+impl AsRef<str> for PushOutboxStatus {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Pending => "pending",
+            Self::Delivered => "delivered",
+            Self::InvalidToken => "invalid_token",
+            Self::Disabled => "disabled",
+            Self::Failed => "failed",
+        }
+    }
+}
+
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Clone)]
+#[diesel(table_name = crate::schema::push_outbox)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PushOutbox {
+    pub id: i32,
+    pub device_id: i32,
+    pub notification: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+#[derive(Debug, Insertable, Clone)]
+#[diesel(table_name = crate::schema::push_outbox)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct NewPushOutbox {
+    pub device_id: i32,
+    pub notification: serde_json::Value,
+    pub status: String,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+#[derive(Debug, AsChangeset, Clone, PartialEq)]
+#[diesel(table_name = crate::schema::push_outbox)]
+#[diesel(treat_none_as_null = true)]
+pub struct PushOutboxAttempt {
+    pub status: String,
+    pub attempts: i32,
+    pub error: Option<String>,
+    pub next_attempt_at: NaiveDateTime,
+}
+
+impl PushOutboxAttempt {
+    // Result of a delivery attempt, failed pushes are retried with exponential backoff until max attempts
+    pub fn new(attempts: i32, status: PushOutboxStatus, error: Option<String>, now: NaiveDateTime) -> Self {
+        let attempts = attempts + 1;
+        let status = match status {
+            PushOutboxStatus::Pending | PushOutboxStatus::Failed if attempts >= PUSH_OUTBOX_MAX_ATTEMPTS => PushOutboxStatus::Failed,
+            PushOutboxStatus::Pending | PushOutboxStatus::Failed => PushOutboxStatus::Pending,
+            status => status,
+        };
+        let next_attempt_at = match status {
+            PushOutboxStatus::Pending => now + Duration::seconds(PUSH_OUTBOX_BACKOFF_SECONDS << (attempts - 1)),
+            _ => now,
+        };
+        Self {
+            status: status.as_ref().to_string(),
+            attempts,
+            error: error.map(|x| x.chars().take(512).collect()),
+            next_attempt_at,
+        }
+    }
+
+    pub fn new_push_outbox(&self, device_id: i32, notification: serde_json::Value) -> NewPushOutbox {
+        NewPushOutbox {
+            device_id,
+            notification,
+            status: self.status.clone(),
+            attempts: self.attempts,
+            error: self.error.clone(),
+            next_attempt_at: self.next_attempt_at,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_outbox_attempt() {
+        let now = NaiveDateTime::default();
+
+        let attempt = PushOutboxAttempt::new(0, PushOutboxStatus::Delivered, None, now);
+        assert_eq!(attempt.status, "delivered");
+        assert_eq!(attempt.attempts, 1);
+
+        let attempt = PushOutboxAttempt::new(0, PushOutboxStatus::Failed, Some("timeout".to_string()), now);
+        assert_eq!(attempt.status, "pending");
+        assert_eq!(attempt.next_attempt_at, now + Duration::seconds(30));
+
+        let attempt = PushOutboxAttempt::new(3, PushOutboxStatus::Failed, None, now);
+        assert_eq!(attempt.next_attempt_at, now + Duration::seconds(240));
+
+        let attempt = PushOutboxAttempt::new(PUSH_OUTBOX_MAX_ATTEMPTS - 1, PushOutboxStatus::Failed, None, now);
+        assert_eq!(attempt.status, "failed");
+        assert_eq!(attempt.next_attempt_at, now);
+
+        let attempt = PushOutboxAttempt::new(1, PushOutboxStatus::Disabled, None, now);
+        assert_eq!(attempt.status, "disabled");
+        assert_eq!(attempt.next_attempt_at, now);
+    }
+}
@@ -21,6 +21,15 @@ pub struct SubscriptionAddressExclude {
     pub chain: String,
 }
 
+// Addresses with transaction history already backfilled
+#[derive(Debug, Queryable, Selectable, Serialize, Deserialize, Insertable, AsChangeset, Clone)]
+#[diesel(table_name = crate::schema::subscriptions_addresses_backfill)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SubscriptionAddressBackfill {
+    pub chain: String,
+    pub address: String,
+}
+
 impl Subscription {
     pub fn as_primitive(&self) -> primitives::Subscription {
         primitives::Subscription {
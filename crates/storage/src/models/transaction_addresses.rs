@@ -17,6 +17,7 @@ impl TransactionAddresses {
         let transaction_id = transaction.clone().id;
         match transaction.transaction_type {
             primitives::TransactionType::Transfer
+            | primitives::TransactionType::TransferNFT
             | primitives::TransactionType::TokenApproval
             | primitives::TransactionType::StakeDelegate
             | primitives::TransactionType::StakeUndelegate
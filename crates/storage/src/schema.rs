@@ -160,6 +160,20 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    nft_scans (chain, address) {
+        #[max_length = 32]
+        chain -> Varchar,
+        #[max_length = 256]
+        address -> Varchar,
+        from_block -> Int8,
+        to_block -> Int8,
+        tokens -> Jsonb,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     nodes (id) {
         id -> Int4,
@@ -183,6 +197,19 @@ diesel::table! {
         is_enabled -> Bool,
         updated_at -> Timestamp,
         created_at -> Timestamp,
+        reorg_window -> Int4,
+    }
+}
+
+diesel::table! {
+    parser_state_blocks (chain, block_number) {
+        chain -> Varchar,
+        block_number -> Int4,
+        #[max_length = 128]
+        block_hash -> Varchar,
+        #[max_length = 128]
+        parent_hash -> Varchar,
+        created_at -> Timestamp,
     }
 }
 
@@ -199,6 +226,11 @@ diesel::table! {
         last_notified_at -> Nullable<Timestamp>,
         updated_at -> Timestamp,
         created_at -> Timestamp,
+        #[max_length = 8]
+        currency -> Nullable<Varchar>,
+        #[max_length = 16]
+        repeat -> Varchar,
+        cooldown -> Int4,
     }
 }
 
@@ -221,6 +253,7 @@ diesel::table! {
         all_time_low_date -> Nullable<Timestamp>,
         all_time_high -> Float8,
         all_time_low -> Float8,
+        is_stale -> Bool,
     }
 }
 
@@ -235,6 +268,32 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    push_deliveries (status) {
+        #[max_length = 16]
+        status -> Varchar,
+        count -> Int8,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
+diesel::table! {
+    push_outbox (id) {
+        id -> Int4,
+        device_id -> Int4,
+        notification -> Jsonb,
+        #[max_length = 16]
+        status -> Varchar,
+        attempts -> Int4,
+        #[max_length = 512]
+        error -> Nullable<Varchar>,
+        next_attempt_at -> Timestamp,
+        updated_at -> Timestamp,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     releases (id) {
         id -> Int4,
@@ -278,6 +337,16 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    subscriptions_addresses_backfill (chain, address) {
+        #[max_length = 32]
+        chain -> Varchar,
+        #[max_length = 256]
+        address -> Varchar,
+        created_at -> Timestamp,
+    }
+}
+
 diesel::table! {
     subscriptions_addresses_exclude (address) {
         #[max_length = 128]
@@ -358,15 +427,19 @@ diesel::joinable!(fiat_assets -> assets (asset_id));
 diesel::joinable!(fiat_assets -> fiat_providers (provider));
 diesel::joinable!(fiat_transactions -> assets (asset_id));
 diesel::joinable!(fiat_transactions -> fiat_providers (provider_id));
+diesel::joinable!(nft_scans -> chains (chain));
 diesel::joinable!(nodes -> chains (chain));
 diesel::joinable!(parser_state -> chains (chain));
+diesel::joinable!(parser_state_blocks -> chains (chain));
 diesel::joinable!(price_alerts -> assets (asset_id));
 diesel::joinable!(price_alerts -> devices (device_id));
 diesel::joinable!(prices_assets -> assets (asset_id));
 diesel::joinable!(prices_assets -> prices (price_id));
+diesel::joinable!(push_outbox -> devices (device_id));
 diesel::joinable!(scan_addresses -> chains (chain));
 diesel::joinable!(subscriptions -> chains (chain));
 diesel::joinable!(subscriptions -> devices (device_id));
+diesel::joinable!(subscriptions_addresses_backfill -> chains (chain));
 diesel::joinable!(subscriptions_addresses_exclude -> chains (chain));
 diesel::joinable!(tokenlists -> chains (chain));
 diesel::joinable!(transactions -> chains (chain));
@@ -384,14 +457,19 @@ diesel::allow_tables_to_appear_in_same_query!(
     fiat_providers,
     fiat_rates,
     fiat_transactions,
+    nft_scans,
     nodes,
     parser_state,
+    parser_state_blocks,
     price_alerts,
     prices,
     prices_assets,
+    push_deliveries,
+    push_outbox,
     releases,
     scan_addresses,
     subscriptions,
+    subscriptions_addresses_backfill,
     subscriptions_addresses_exclude,
     tokenlists,
     transactions,
@@ -0,0 +1,375 @@
+use super::*;
+use diesel::result::Error::NotFound;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[derive(Default)]
+struct MemoryState {
+    parser_states: HashMap<String, ParserState>,
+    parser_state_blocks: BTreeMap<(String, i32), ParserStateBlock>,
+    subscriptions: Vec<Subscription>,
+    subscriptions_exclude_addresses: HashSet<String>,
+    subscriptions_addresses_backfill: HashSet<(String, String)>,
+    devices: Vec<Device>,
+    assets: Vec<Asset>,
+    scan_addresses: Vec<ScanAddress>,
+    transactions: Vec<Transaction>,
+    transactions_addresses: Vec<TransactionAddresses>,
+    push_outbox: Vec<PushOutbox>,
+    push_deliveries: HashMap<String, i64>,
+}
+
+// In-memory store for running the parser and pusher without Postgres, clones share the same state
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    state: Arc<Mutex<MemoryState>>,
+}
+
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, MemoryState> {
+        self.state.lock().unwrap()
+    }
+
+    pub fn add_parser_state(&self, value: ParserState) {
+        self.state().parser_states.insert(value.chain.clone(), value);
+    }
+
+    pub fn add_subscriptions(&self, values: Vec<Subscription>) {
+        self.state().subscriptions.extend(values);
+    }
+
+    pub fn add_subscriptions_exclude_addresses(&self, values: Vec<String>) {
+        self.state().subscriptions_exclude_addresses.extend(values);
+    }
+
+    pub fn add_device(&self, value: Device) {
+        self.state().devices.push(value);
+    }
+
+    pub fn add_assets(&self, values: Vec<Asset>) {
+        self.state().assets.extend(values);
+    }
+
+    pub fn add_scan_addresses(&self, values: Vec<ScanAddress>) {
+        self.state().scan_addresses.extend(values);
+    }
+
+    pub fn get_transactions(&self) -> Vec<Transaction> {
+        self.state().transactions.clone()
+    }
+
+    pub fn get_transactions_addresses(&self) -> Vec<TransactionAddresses> {
+        self.state().transactions_addresses.clone()
+    }
+
+    pub fn get_device(&self, device_id: &str) -> Option<Device> {
+        self.state().devices.iter().find(|x| x.device_id == device_id).cloned()
+    }
+
+    pub fn get_push_outbox(&self) -> Vec<PushOutbox> {
+        self.state().push_outbox.clone()
+    }
+
+    pub fn get_push_deliveries(&self) -> HashMap<String, i64> {
+        self.state().push_deliveries.clone()
+    }
+}
+
+impl ParserStateStore for MemoryStore {
+    fn get_parser_state(&mut self, chain: Chain) -> Result<ParserState, Error> {
+        self.state().parser_states.get(chain.as_ref()).cloned().ok_or(NotFound)
+    }
+
+    fn set_parser_state_latest_block(&mut self, chain: Chain, block: i32) -> Result<usize, Error> {
+        Ok(self.state().parser_states.get_mut(chain.as_ref()).map(|x| x.latest_block = block).is_some() as usize)
+    }
+
+    fn set_parser_state_current_block(&mut self, chain: Chain, block: i32) -> Result<usize, Error> {
+        Ok(self.state().parser_states.get_mut(chain.as_ref()).map(|x| x.current_block = block).is_some() as usize)
+    }
+
+    fn get_parser_state_block(&mut self, chain: Chain, block: i32) -> Result<Option<ParserStateBlock>, Error> {
+        Ok(self.state().parser_state_blocks.get(&(chain.as_ref().to_string(), block)).cloned())
+    }
+
+    fn add_parser_state_blocks(&mut self, values: Vec<ParserStateBlock>) -> Result<usize, Error> {
+        let mut state = self.state();
+        let count = values.len();
+        for value in values {
+            state.parser_state_blocks.insert((value.chain.clone(), value.block_number), value);
+        }
+        Ok(count)
+    }
+
+    fn delete_parser_state_blocks_before(&mut self, chain: Chain, block: i32) -> Result<usize, Error> {
+        let mut state = self.state();
+        let count = state.parser_state_blocks.len();
+        state
+            .parser_state_blocks
+            .retain(|(block_chain, block_number), _| block_chain != chain.as_ref() || *block_number >= block);
+        Ok(count - state.parser_state_blocks.len())
+    }
+
+    fn rollback_parser_state(&mut self, chain: Chain, fork_block: i32) -> Result<usize, Error> {
+        let mut state = self.state();
+        let is_reverted = |x: &Transaction| x.chain == chain.as_ref() && x.block_number > fork_block;
+
+        let transaction_ids = state
+            .transactions
+            .iter()
+            .filter(|x| is_reverted(x))
+            .map(|x| x.id.clone())
+            .collect::<HashSet<_>>();
+        state.transactions_addresses.retain(|x| !transaction_ids.contains(&x.transaction_id));
+        state.transactions.retain(|x| !is_reverted(x));
+        state
+            .parser_state_blocks
+            .retain(|(block_chain, block_number), _| block_chain != chain.as_ref() || *block_number <= fork_block);
+        if let Some(parser_state) = state.parser_states.get_mut(chain.as_ref()) {
+            parser_state.current_block = fork_block;
+        }
+        Ok(transaction_ids.len())
+    }
+}
+
+impl SubscriptionsStore for MemoryStore {
+    fn get_subscriptions(&mut self, chain: Chain, addresses: Vec<String>) -> Result<Vec<Subscription>, Error> {
+        let state = self.state();
+        let mut seen = HashSet::new();
+        Ok(state
+            .subscriptions
+            .iter()
+            .filter(|x| x.chain == chain.as_ref() && addresses.contains(&x.address))
+            .filter(|x| !state.subscriptions_exclude_addresses.contains(&x.address))
+            .filter(|x| seen.insert((x.device_id, x.address.clone())))
+            .cloned()
+            .collect())
+    }
+}
+
+// Subscriptions carry no creation time in memory, every subscription without a backfill is returned
+impl SubscriptionsBackfillStore for MemoryStore {
+    fn get_subscriptions_without_backfill(&mut self, _after: NaiveDateTime) -> Result<Vec<Subscription>, Error> {
+        let state = self.state();
+        Ok(state
+            .subscriptions
+            .iter()
+            .filter(|x| !state.subscriptions_addresses_backfill.contains(&(x.chain.clone(), x.address.clone())))
+            .cloned()
+            .collect())
+    }
+
+    fn add_subscriptions_addresses_backfill(&mut self, values: Vec<SubscriptionAddressBackfill>) -> Result<usize, Error> {
+        let mut state = self.state();
+        Ok(values
+            .into_iter()
+            .filter(|x| state.subscriptions_addresses_backfill.insert((x.chain.clone(), x.address.clone())))
+            .count())
+    }
+}
+
+impl DevicesStore for MemoryStore {
+    fn get_device_by_id(&mut self, id: i32) -> Result<Device, Error> {
+        self.state().devices.iter().find(|x| x.id == id).cloned().ok_or(NotFound)
+    }
+
+    fn update_device_is_push_enabled(&mut self, device_id: &str, value: bool) -> Result<usize, Error> {
+        let mut state = self.state();
+        let devices = state.devices.iter_mut().filter(|x| x.device_id == device_id);
+        Ok(devices.map(|x| x.is_push_enabled = value).count())
+    }
+}
+
+impl AssetsStore for MemoryStore {
+    fn get_asset(&mut self, asset_id: &str) -> Result<Asset, Error> {
+        self.state().assets.iter().find(|x| x.id == asset_id).cloned().ok_or(NotFound)
+    }
+
+    fn get_assets(&mut self, asset_ids: Vec<String>) -> Result<Vec<Asset>, Error> {
+        Ok(self.state().assets.iter().filter(|x| asset_ids.contains(&x.id)).cloned().collect())
+    }
+}
+
+impl ScanAddressesStore for MemoryStore {
+    fn get_scan_address(&mut self, chain: Chain, address: &str) -> Result<ScanAddress, Error> {
+        self.state()
+            .scan_addresses
+            .iter()
+            .find(|x| x.chain == chain.as_ref() && x.address == address)
+            .cloned()
+            .ok_or(NotFound)
+    }
+}
+
+impl PushOutboxStore for MemoryStore {
+    fn add_push_outbox(&mut self, value: NewPushOutbox) -> Result<usize, Error> {
+        let mut state = self.state();
+        let id = state.push_outbox.len() as i32 + 1;
+        state.push_outbox.push(PushOutbox {
+            id,
+            device_id: value.device_id,
+            notification: value.notification,
+            status: value.status,
+            attempts: value.attempts,
+            error: value.error,
+            next_attempt_at: value.next_attempt_at,
+        });
+        Ok(1)
+    }
+
+    fn update_push_outbox(&mut self, id: i32, attempt: PushOutboxAttempt) -> Result<usize, Error> {
+        let mut state = self.state();
+        let values = state.push_outbox.iter_mut().filter(|x| x.id == id);
+        Ok(values
+            .map(|x| {
+                x.status = attempt.status.clone();
+                x.attempts = attempt.attempts;
+                x.error = attempt.error.clone();
+                x.next_attempt_at = attempt.next_attempt_at;
+            })
+            .count())
+    }
+
+    fn claim_push_outbox_pending(&mut self, limit: i64, lease: Duration) -> Result<Vec<PushOutbox>, Error> {
+        let mut state = self.state();
+        let now = chrono::Utc::now().naive_utc();
+        let values = state
+            .push_outbox
+            .iter_mut()
+            .filter(|x| x.status == PushOutboxStatus::Pending.as_ref() && x.next_attempt_at <= now)
+            .take(limit as usize)
+            .map(|x| {
+                let value = x.clone();
+                x.next_attempt_at = now + lease;
+                value
+            })
+            .collect();
+        Ok(values)
+    }
+
+    fn add_push_deliveries(&mut self, status: PushOutboxStatus, count: i64) -> Result<usize, Error> {
+        *self.state().push_deliveries.entry(status.as_ref().to_string()).or_default() += count;
+        Ok(1)
+    }
+}
+
+impl TransactionsStore for MemoryStore {
+    fn add_transactions(&mut self, transactions: Vec<Transaction>, addresses: Vec<TransactionAddresses>) -> Result<bool, Error> {
+        let mut state = self.state();
+        for transaction in transactions {
+            match state.transactions.iter_mut().find(|x| x.id == transaction.id) {
+                Some(existing) => {
+                    existing.block_number = transaction.block_number;
+                    existing.sequence = transaction.sequence;
+                    existing.fee = transaction.fee;
+                    existing.fee_asset_id = transaction.fee_asset_id;
+                    existing.memo = transaction.memo;
+                }
+                None => state.transactions.push(transaction),
+            }
+        }
+        for address in addresses {
+            let exists = state
+                .transactions_addresses
+                .iter()
+                .any(|x| x.transaction_id == address.transaction_id && x.address == address.address && x.asset_id == address.asset_id);
+            if !exists {
+                state.transactions_addresses.push(address);
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDateTime;
+
+    fn block(number: i32) -> ParserStateBlock {
+        ParserStateBlock {
+            chain: Chain::Ethereum.as_ref().to_string(),
+            block_number: number,
+            block_hash: format!("0x{}", number),
+            parent_hash: format!("0x{}", number - 1),
+        }
+    }
+
+    fn transaction(hash: &str, block_number: i32) -> Transaction {
+        Transaction {
+            id: format!("{}_{}", Chain::Ethereum.as_ref(), hash),
+            chain: Chain::Ethereum.as_ref().to_string(),
+            hash: hash.to_string(),
+            memo: None,
+            asset_id: Chain::Ethereum.as_ref().to_string(),
+            value: Some("1".to_string()),
+            fee: Some("1".to_string()),
+            fee_asset_id: Chain::Ethereum.as_ref().to_string(),
+            block_number,
+            sequence: None,
+            from_address: Some("0x1".to_string()),
+            to_address: Some("0x2".to_string()),
+            kind: "transfer".to_string(),
+            state: "confirmed".to_string(),
+            created_at: NaiveDateTime::default(),
+            utxo_inputs: None,
+            utxo_outputs: None,
+            metadata: None,
+        }
+    }
+
+    #[test]
+    fn test_rollback_parser_state() {
+        let mut store = MemoryStore::new();
+        store.add_parser_state(ParserState {
+            chain: Chain::Ethereum.as_ref().to_string(),
+            current_block: 12,
+            latest_block: 12,
+            await_blocks: 0,
+            timeout_between_blocks: 0,
+            parallel_blocks: 1,
+            is_enabled: true,
+            updated_at: NaiveDateTime::default(),
+            reorg_window: 3,
+        });
+        store.add_parser_state_blocks(vec![block(10), block(11), block(12)]).unwrap();
+        store
+            .add_transactions(vec![transaction("0xa", 10), transaction("0xb", 12), transaction("0xb", 12)], vec![])
+            .unwrap();
+        assert_eq!(store.get_transactions().len(), 2);
+
+        assert_eq!(store.rollback_parser_state(Chain::Ethereum, 11).unwrap(), 1);
+        assert_eq!(store.get_parser_state(Chain::Ethereum).unwrap().current_block, 11);
+        assert_eq!(store.get_transactions().iter().map(|x| x.hash.as_str()).collect::<Vec<_>>(), vec!["0xa"]);
+        assert!(store.get_parser_state_block(Chain::Ethereum, 12).unwrap().is_none());
+
+        assert_eq!(store.delete_parser_state_blocks_before(Chain::Ethereum, 11).unwrap(), 1);
+        assert!(store.get_parser_state_block(Chain::Ethereum, 11).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_get_subscriptions() {
+        let mut store = MemoryStore::new();
+        let subscription = |device_id: i32, wallet_index: i32, address: &str| Subscription {
+            device_id,
+            wallet_index,
+            chain: Chain::Ethereum.as_ref().to_string(),
+            address: address.to_string(),
+        };
+        store.add_subscriptions(vec![
+            subscription(1, 1, "0x1"),
+            subscription(1, 2, "0x1"),
+            subscription(2, 1, "0x1"),
+            subscription(2, 1, "0x3"),
+        ]);
+        store.add_subscriptions_exclude_addresses(vec!["0x3".to_string()]);
+
+        let result = store.get_subscriptions(Chain::Ethereum, vec!["0x1".to_string(), "0x3".to_string()]).unwrap();
+        assert_eq!(result.iter().map(|x| x.device_id).collect::<Vec<_>>(), vec![1, 2]);
+        assert!(store.get_subscriptions(Chain::Bitcoin, vec!["0x1".to_string()]).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,161 @@
+pub mod memory;
+
+use crate::models::*;
+use crate::DatabaseClient;
+use chrono::{Duration, NaiveDateTime};
+use diesel::result::Error;
+use primitives::Chain;
+
+// Storage used by the parser and pusher, implemented by DatabaseClient and MemoryStore
+
+pub trait ParserStateStore {
+    fn get_parser_state(&mut self, chain: Chain) -> Result<ParserState, Error>;
+    fn set_parser_state_latest_block(&mut self, chain: Chain, block: i32) -> Result<usize, Error>;
+    fn set_parser_state_current_block(&mut self, chain: Chain, block: i32) -> Result<usize, Error>;
+    fn get_parser_state_block(&mut self, chain: Chain, block: i32) -> Result<Option<ParserStateBlock>, Error>;
+    fn add_parser_state_blocks(&mut self, values: Vec<ParserStateBlock>) -> Result<usize, Error>;
+    fn delete_parser_state_blocks_before(&mut self, chain: Chain, block: i32) -> Result<usize, Error>;
+    fn rollback_parser_state(&mut self, chain: Chain, fork_block: i32) -> Result<usize, Error>;
+}
+
+pub trait SubscriptionsStore {
+    fn get_subscriptions(&mut self, chain: Chain, addresses: Vec<String>) -> Result<Vec<Subscription>, Error>;
+}
+
+pub trait SubscriptionsBackfillStore {
+    fn get_subscriptions_without_backfill(&mut self, after: NaiveDateTime) -> Result<Vec<Subscription>, Error>;
+    fn add_subscriptions_addresses_backfill(&mut self, values: Vec<SubscriptionAddressBackfill>) -> Result<usize, Error>;
+}
+
+pub trait DevicesStore {
+    fn get_device_by_id(&mut self, id: i32) -> Result<Device, Error>;
+    fn update_device_is_push_enabled(&mut self, device_id: &str, value: bool) -> Result<usize, Error>;
+}
+
+pub trait AssetsStore {
+    fn get_asset(&mut self, asset_id: &str) -> Result<Asset, Error>;
+    fn get_assets(&mut self, asset_ids: Vec<String>) -> Result<Vec<Asset>, Error>;
+}
+
+pub trait ScanAddressesStore {
+    fn get_scan_address(&mut self, chain: Chain, address: &str) -> Result<ScanAddress, Error>;
+}
+
+pub trait PushOutboxStore {
+    fn add_push_outbox(&mut self, value: NewPushOutbox) -> Result<usize, Error>;
+    fn update_push_outbox(&mut self, id: i32, attempt: PushOutboxAttempt) -> Result<usize, Error>;
+    fn claim_push_outbox_pending(&mut self, limit: i64, lease: Duration) -> Result<Vec<PushOutbox>, Error>;
+    fn add_push_deliveries(&mut self, status: PushOutboxStatus, count: i64) -> Result<usize, Error>;
+}
+
+pub trait TransactionsStore {
+    fn add_transactions(&mut self, transactions: Vec<Transaction>, addresses: Vec<TransactionAddresses>) -> Result<bool, Error>;
+}
+
+pub trait ParserStore: ParserStateStore + SubscriptionsStore + DevicesStore + AssetsStore + TransactionsStore + Send {}
+
+impl<T: ParserStateStore + SubscriptionsStore + DevicesStore + AssetsStore + TransactionsStore + Send> ParserStore for T {}
+
+pub trait PusherStore: DevicesStore + AssetsStore + ScanAddressesStore + PushOutboxStore + Send {}
+
+impl<T: DevicesStore + AssetsStore + ScanAddressesStore + PushOutboxStore + Send> PusherStore for T {}
+
+pub trait BackfillStore: SubscriptionsBackfillStore + AssetsStore + TransactionsStore + Send {}
+
+impl<T: SubscriptionsBackfillStore + AssetsStore + TransactionsStore + Send> BackfillStore for T {}
+
+impl ParserStateStore for DatabaseClient {
+    fn get_parser_state(&mut self, chain: Chain) -> Result<ParserState, Error> {
+        DatabaseClient::get_parser_state(self, chain)
+    }
+
+    fn set_parser_state_latest_block(&mut self, chain: Chain, block: i32) -> Result<usize, Error> {
+        DatabaseClient::set_parser_state_latest_block(self, chain, block)
+    }
+
+    fn set_parser_state_current_block(&mut self, chain: Chain, block: i32) -> Result<usize, Error> {
+        DatabaseClient::set_parser_state_current_block(self, chain, block)
+    }
+
+    fn get_parser_state_block(&mut self, chain: Chain, block: i32) -> Result<Option<ParserStateBlock>, Error> {
+        DatabaseClient::get_parser_state_block(self, chain, block)
+    }
+
+    fn add_parser_state_blocks(&mut self, values: Vec<ParserStateBlock>) -> Result<usize, Error> {
+        DatabaseClient::add_parser_state_blocks(self, values)
+    }
+
+    fn delete_parser_state_blocks_before(&mut self, chain: Chain, block: i32) -> Result<usize, Error> {
+        DatabaseClient::delete_parser_state_blocks_before(self, chain, block)
+    }
+
+    fn rollback_parser_state(&mut self, chain: Chain, fork_block: i32) -> Result<usize, Error> {
+        DatabaseClient::rollback_parser_state(self, chain, fork_block)
+    }
+}
+
+impl SubscriptionsStore for DatabaseClient {
+    fn get_subscriptions(&mut self, chain: Chain, addresses: Vec<String>) -> Result<Vec<Subscription>, Error> {
+        DatabaseClient::get_subscriptions(self, chain, addresses)
+    }
+}
+
+impl SubscriptionsBackfillStore for DatabaseClient {
+    fn get_subscriptions_without_backfill(&mut self, after: NaiveDateTime) -> Result<Vec<Subscription>, Error> {
+        DatabaseClient::get_subscriptions_without_backfill(self, after)
+    }
+
+    fn add_subscriptions_addresses_backfill(&mut self, values: Vec<SubscriptionAddressBackfill>) -> Result<usize, Error> {
+        DatabaseClient::add_subscriptions_addresses_backfill(self, values)
+    }
+}
+
+impl DevicesStore for DatabaseClient {
+    fn get_device_by_id(&mut self, id: i32) -> Result<Device, Error> {
+        DatabaseClient::get_device_by_id(self, id)
+    }
+
+    fn update_device_is_push_enabled(&mut self, device_id: &str, value: bool) -> Result<usize, Error> {
+        DatabaseClient::update_device_is_push_enabled(self, device_id, value)
+    }
+}
+
+impl AssetsStore for DatabaseClient {
+    fn get_asset(&mut self, asset_id: &str) -> Result<Asset, Error> {
+        DatabaseClient::get_asset(self, asset_id)
+    }
+
+    fn get_assets(&mut self, asset_ids: Vec<String>) -> Result<Vec<Asset>, Error> {
+        DatabaseClient::get_assets(self, asset_ids)
+    }
+}
+
+impl ScanAddressesStore for DatabaseClient {
+    fn get_scan_address(&mut self, chain: Chain, address: &str) -> Result<ScanAddress, Error> {
+        DatabaseClient::get_scan_address(self, chain, address)
+    }
+}
+
+impl PushOutboxStore for DatabaseClient {
+    fn add_push_outbox(&mut self, value: NewPushOutbox) -> Result<usize, Error> {
+        DatabaseClient::add_push_outbox(self, value)
+    }
+
+    fn update_push_outbox(&mut self, id: i32, attempt: PushOutboxAttempt) -> Result<usize, Error> {
+        DatabaseClient::update_push_outbox(self, id, attempt)
+    }
+
+    fn claim_push_outbox_pending(&mut self, limit: i64, lease: Duration) -> Result<Vec<PushOutbox>, Error> {
+        DatabaseClient::claim_push_outbox_pending(self, limit, lease)
+    }
+
+    fn add_push_deliveries(&mut self, status: PushOutboxStatus, count: i64) -> Result<usize, Error> {
+        DatabaseClient::add_push_deliveries(self, status.as_ref(), count)
+    }
+}
+
+impl TransactionsStore for DatabaseClient {
+    fn add_transactions(&mut self, transactions: Vec<Transaction>, addresses: Vec<TransactionAddresses>) -> Result<bool, Error> {
+        DatabaseClient::add_transactions(self, transactions, addresses)
+    }
+}
@@ -0,0 +1,88 @@
+use alloy_core::{
+    primitives::{Address, U256},
+    sol_types::SolCall,
+};
+use gem_evm::{
+    erc20::IERC20,
+    multicall3::IMulticall3::{self, Call3},
+};
+
+// Calls per aggregate3 request, keeps eth_call under common node gas caps
+pub const MULTICALL_BATCH_SIZE: usize = 200;
+
+// Native balance through the multicall contract itself, followed by balanceOf for every token
+pub fn balance_calls(multicall: Address, owner: Address, tokens: &[Address]) -> Vec<Call3> {
+    let native = Call3 {
+        target: multicall,
+        allowFailure: true,
+        callData: IMulticall3::getEthBalanceCall { addr: owner }.abi_encode().into(),
+    };
+    let tokens = tokens.iter().map(|token| Call3 {
+        target: *token,
+        allowFailure: true,
+        callData: IERC20::balanceOfCall { account: owner }.abi_encode().into(),
+    });
+    std::iter::once(native).chain(tokens).collect()
+}
+
+pub fn encode_aggregate3(calls: Vec<Call3>) -> Vec<u8> {
+    IMulticall3::aggregate3Call { calls }.abi_encode()
+}
+
+pub fn decode_aggregate3(data: &[u8]) -> Result<Vec<IMulticall3::Result>, alloy_core::sol_types::Error> {
+    Ok(IMulticall3::aggregate3Call::abi_decode_returns(data, false)?.returnData)
+}
+
+// None when the call reverted, calls to addresses without code succeed with empty data
+pub fn decode_balance(result: &IMulticall3::Result) -> Option<U256> {
+    if !result.success || result.returnData.len() != 32 {
+        return None;
+    }
+    Some(U256::from_be_slice(&result.returnData))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_core::primitives::Bytes;
+
+    #[test]
+    fn test_balance_calls() {
+        let multicall: Address = "0xcA11bde05977b3631167028862bE2a173976CA11".parse().unwrap();
+        let owner: Address = "0x46340b20830761efd32832a74d7169b29feb9758".parse().unwrap();
+        let usdc: Address = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48".parse().unwrap();
+        let calls = balance_calls(multicall, owner, &[usdc]);
+
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].target, multicall);
+        assert_eq!(
+            calls[0].callData.to_string(),
+            "0x4d2301cc00000000000000000000000046340b20830761efd32832a74d7169b29feb9758"
+        );
+        assert_eq!(calls[1].target, usdc);
+        assert_eq!(
+            calls[1].callData.to_string(),
+            "0x70a0823100000000000000000000000046340b20830761efd32832a74d7169b29feb9758"
+        );
+    }
+
+    #[test]
+    fn test_decode_balance() {
+        let success = IMulticall3::Result {
+            success: true,
+            returnData: U256::from(1_000_000u64).to_be_bytes_vec().into(),
+        };
+        let reverted = IMulticall3::Result {
+            success: false,
+            returnData: Bytes::new(),
+        };
+        let no_code = IMulticall3::Result {
+            success: true,
+            returnData: Bytes::new(),
+        };
+
+        assert_eq!(decode_balance(&success), Some(U256::from(1_000_000u64)));
+        assert_eq!(decode_balance(&reverted), None);
+        assert_eq!(decode_balance(&no_code), None);
+    }
+}
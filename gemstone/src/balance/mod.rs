@@ -0,0 +1,238 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::network::{jsonrpc::batch_jsonrpc_call, AlienError, AlienProvider, JsonRpcError, JsonRpcResult};
+use alloy_core::{hex::decode as HexDecode, primitives::Address};
+use gem_evm::{
+    jsonrpc::{BlockParameter, EthereumRpc, TransactionObject},
+    multicall3::{self, IMulticall3},
+};
+use primitives::{AssetId, Chain, EVMChain};
+
+pub mod evm;
+
+#[derive(Debug, Clone, PartialEq, uniffi::Error, thiserror::Error)]
+pub enum BalanceError {
+    #[error("Not supported chain")]
+    NotSupportedChain,
+    #[error("Invalid address {address}")]
+    InvalidAddress { address: String },
+    #[error("RPC error: {msg}")]
+    NetworkError { msg: String },
+    #[error("ABI error: {msg}")]
+    ABIError { msg: String },
+}
+
+impl From<AlienError> for BalanceError {
+    fn from(err: AlienError) -> Self {
+        Self::NetworkError { msg: err.to_string() }
+    }
+}
+
+impl From<JsonRpcError> for BalanceError {
+    fn from(err: JsonRpcError) -> Self {
+        Self::NetworkError { msg: err.message }
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct EvmBalance {
+    pub asset_id: AssetId,
+    // smallest unit
+    pub balance: String,
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct EvmBalances {
+    // native balance first, followed by the tokens in request order
+    pub balances: Vec<EvmBalance>,
+    // invalid token addresses, reverted balanceOf calls, addresses without a contract and assets of failed multicall batches
+    pub failed: Vec<AssetId>,
+}
+
+/// EVM balances through Multicall3
+#[derive(Debug, uniffi::Object)]
+pub struct EvmBalanceFetcher {
+    provider: Arc<dyn AlienProvider>,
+}
+
+#[uniffi::export]
+impl EvmBalanceFetcher {
+    #[uniffi::constructor]
+    fn new(provider: Arc<dyn AlienProvider>) -> Self {
+        Self { provider }
+    }
+
+    pub async fn get_balances(&self, chain: Chain, address: String, token_ids: Vec<String>) -> Result<EvmBalances, BalanceError> {
+        let evm_chain = EVMChain::from_chain(chain).ok_or(BalanceError::NotSupportedChain)?;
+        let owner: Address = address.parse().map_err(|_| BalanceError::InvalidAddress { address: address.clone() })?;
+        let multicall_address = multicall3::deployment_by_chain(&evm_chain);
+        let multicall: Address = multicall_address.parse().map_err(|_| BalanceError::InvalidAddress {
+            address: multicall_address.to_string(),
+        })?;
+
+        let mut assets = vec![AssetId::from_chain(chain)];
+        let mut tokens = vec![];
+        let mut failed = vec![];
+        for token_id in token_ids {
+            match token_id.parse::<Address>() {
+                Ok(token) => {
+                    assets.push(AssetId::from_token(chain, &token_id));
+                    tokens.push(token);
+                }
+                Err(_) => failed.push(AssetId::from_token(chain, &token_id)),
+            }
+        }
+
+        let calls = evm::balance_calls(multicall, owner, &tokens)
+            .chunks(evm::MULTICALL_BATCH_SIZE)
+            .map(|chunk| {
+                let data = evm::encode_aggregate3(chunk.to_vec());
+                EthereumRpc::Call(TransactionObject::new_call(multicall_address, data), BlockParameter::Latest)
+            })
+            .collect::<Vec<_>>();
+        let responses = batch_jsonrpc_call(calls, self.provider.clone(), &chain).await?;
+        // batch responses are not guaranteed to keep the request order, ids start at 1 in request order
+        let mut responses = responses
+            .into_iter()
+            .map(|x| match &x {
+                JsonRpcResult::Value(value) => (value.id, x),
+                JsonRpcResult::Error(error) => (error.id, x),
+            })
+            .collect::<HashMap<_, _>>();
+
+        // a failed multicall only fails the assets of its chunk
+        let mut balances = vec![];
+        for (index, assets) in assets.chunks(evm::MULTICALL_BATCH_SIZE).enumerate() {
+            let results = match responses.remove(&(index as u64 + 1)).map(Self::decode_response) {
+                Some(Ok(results)) if results.len() == assets.len() => results,
+                _ => {
+                    failed.extend(assets.iter().cloned());
+                    continue;
+                }
+            };
+            for (asset_id, result) in assets.iter().zip(results) {
+                match evm::decode_balance(&result) {
+                    Some(balance) => balances.push(EvmBalance {
+                        asset_id: asset_id.clone(),
+                        balance: balance.to_string(),
+                    }),
+                    None => failed.push(asset_id.clone()),
+                }
+            }
+        }
+        Ok(EvmBalances { balances, failed })
+    }
+}
+
+impl EvmBalanceFetcher {
+    fn decode_response(response: JsonRpcResult<String>) -> Result<Vec<IMulticall3::Result>, BalanceError> {
+        let data = HexDecode(response.take()?).map_err(|err| BalanceError::NetworkError { msg: err.to_string() })?;
+        evm::decode_aggregate3(&data).map_err(|err| BalanceError::ABIError { msg: err.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::mock::AlienProviderMock;
+    use alloy_core::{
+        hex::encode_prefixed as HexEncode,
+        primitives::{Bytes, U256},
+        sol_types::SolCall,
+    };
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_get_balances() {
+        let results = vec![
+            IMulticall3::Result {
+                success: true,
+                returnData: U256::from(2_000_000_000_000_000_000u128).to_be_bytes_vec().into(),
+            },
+            IMulticall3::Result {
+                success: true,
+                returnData: U256::from(150_000_000u64).to_be_bytes_vec().into(),
+            },
+            IMulticall3::Result {
+                success: false,
+                returnData: Bytes::new(),
+            },
+        ];
+        let data = IMulticall3::aggregate3Call::abi_encode_returns(&(results,));
+        let response = serde_json::json!([{"jsonrpc": "2.0", "id": 1, "result": HexEncode(data)}]);
+        let fetcher = EvmBalanceFetcher::new(Arc::new(AlienProviderMock {
+            response: response.to_string(),
+            timeout: Duration::from_millis(100),
+        }));
+
+        let usdc = "0xA0b86991c6218b36c1D19D4a2e9Eb0cE3606eB48".to_string();
+        let reverting = "0x0000000000000000000000000000000000000001".to_string();
+        let balances = fetcher
+            .get_balances(
+                Chain::Ethereum,
+                "0x46340b20830761efd32832a74d7169b29feb9758".into(),
+                vec![usdc.clone(), "invalid".into(), reverting.clone()],
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(
+            balances.balances,
+            vec![
+                EvmBalance {
+                    asset_id: AssetId::from_chain(Chain::Ethereum),
+                    balance: "2000000000000000000".into(),
+                },
+                EvmBalance {
+                    asset_id: AssetId::from_token(Chain::Ethereum, &usdc),
+                    balance: "150000000".into(),
+                },
+            ]
+        );
+        assert_eq!(
+            balances.failed,
+            vec![
+                AssetId::from_token(Chain::Ethereum, "invalid"),
+                AssetId::from_token(Chain::Ethereum, &reverting)
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_balances_failed_chunk() {
+        let result = IMulticall3::Result {
+            success: true,
+            returnData: U256::from(1u64).to_be_bytes_vec().into(),
+        };
+        let data = IMulticall3::aggregate3Call::abi_encode_returns(&(vec![result; evm::MULTICALL_BATCH_SIZE],));
+        let response = serde_json::json!([
+            {"jsonrpc": "2.0", "id": 2, "error": {"code": -32000, "message": "execution reverted"}},
+            {"jsonrpc": "2.0", "id": 1, "result": HexEncode(data)},
+        ]);
+        let fetcher = EvmBalanceFetcher::new(Arc::new(AlienProviderMock {
+            response: response.to_string(),
+            timeout: Duration::from_millis(100),
+        }));
+
+        // native and the first tokens fill the first chunk, the last token is in the failed second chunk
+        let token_ids = (1..=evm::MULTICALL_BATCH_SIZE).map(|x| format!("0x{:040x}", x)).collect::<Vec<_>>();
+        let balances = fetcher
+            .get_balances(Chain::Ethereum, "0x46340b20830761efd32832a74d7169b29feb9758".into(), token_ids.clone())
+            .await
+            .unwrap();
+
+        assert_eq!(balances.balances.len(), evm::MULTICALL_BATCH_SIZE);
+        assert_eq!(balances.failed, vec![AssetId::from_token(Chain::Ethereum, token_ids.last().unwrap())]);
+    }
+
+    #[tokio::test]
+    async fn test_get_balances_not_supported_chain() {
+        let fetcher = EvmBalanceFetcher::new(Arc::new(AlienProviderMock {
+            response: String::new(),
+            timeout: Duration::from_millis(100),
+        }));
+        let result = fetcher.get_balances(Chain::Solana, String::new(), vec![]).await;
+
+        assert_eq!(result, Err(BalanceError::NotSupportedChain));
+    }
+}
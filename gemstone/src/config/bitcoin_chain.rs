@@ -2,14 +2,14 @@ use primitives::BitcoinChain;
 
 #[derive(uniffi::Record, Debug, Clone, PartialEq)]
 pub struct BitcoinChainConfig {
-    blocks_fee_priority: BlocksFeePriority,
+    pub blocks_fee_priority: BlocksFeePriority,
 }
 
 #[derive(uniffi::Record, Debug, Clone, PartialEq)]
 pub struct BlocksFeePriority {
-    normal: i32,
-    slow: i32,
-    fast: i32,
+    pub normal: i32,
+    pub slow: i32,
+    pub fast: i32,
 }
 
 pub fn get_bitcoin_chain_config(chain: BitcoinChain) -> BitcoinChainConfig {
@@ -13,6 +13,7 @@ pub enum PublicUrl {
 }
 
 pub const ASSETS_URL: &str = "https://assets.gemwallet.com";
+pub const API_URL: &str = "https://api.gemwallet.com";
 
 pub fn get_public_url(item: PublicUrl) -> &'static str {
     match item {
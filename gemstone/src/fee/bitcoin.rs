@@ -0,0 +1,121 @@
+use std::{str::FromStr, sync::Arc};
+
+use super::{FeeError, FeeRate, FEE_PRIORITIES};
+use crate::config::bitcoin_chain::get_bitcoin_chain_config;
+use crate::network::{AlienHttpMethod, AlienProvider, AlienTarget};
+use primitives::{BitcoinChain, Chain, FeePriority};
+use serde::Deserialize;
+
+const SATOSHI_DECIMALS: usize = 8;
+const MIN_FEE_PER_BYTE: u128 = 1;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BlockbookFeeEstimate {
+    // coins per kilobyte, -1 when the node has no estimate
+    pub result: String,
+}
+
+// Blockbook estimatefee for the target blocks of each priority
+pub async fn fetch_fee_rates(chain: Chain, vsize: u64, provider: Arc<dyn AlienProvider>) -> Result<Vec<FeeRate>, FeeError> {
+    let bitcoin_chain = BitcoinChain::from_str(chain.as_ref()).map_err(|_| FeeError::NotSupportedChain)?;
+    let blocks = get_bitcoin_chain_config(bitcoin_chain).blocks_fee_priority;
+    let endpoint = provider.get_endpoint(chain)?;
+    let targets = FEE_PRIORITIES
+        .iter()
+        .map(|priority| {
+            let blocks = match priority {
+                FeePriority::Slow => blocks.slow,
+                FeePriority::Normal => blocks.normal,
+                FeePriority::Fast => blocks.fast,
+            };
+            AlienTarget {
+                url: format!("{}/api/v2/estimatefee/{}", endpoint, blocks),
+                method: AlienHttpMethod::Get,
+                headers: None,
+                body: None,
+            }
+        })
+        .collect();
+
+    let responses = provider.batch_request(targets).await?;
+    let estimates = responses
+        .iter()
+        .map(|x| serde_json::from_slice::<BlockbookFeeEstimate>(x).map_err(|err| FeeError::InvalidResponse { msg: err.to_string() }))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    fee_rates(&estimates, vsize)
+}
+
+pub fn fee_rates(estimates: &[BlockbookFeeEstimate], vsize: u64) -> Result<Vec<FeeRate>, FeeError> {
+    if estimates.len() != FEE_PRIORITIES.len() {
+        return Err(FeeError::InvalidResponse {
+            msg: format!("expected {} estimates, got {}", FEE_PRIORITIES.len(), estimates.len()),
+        });
+    }
+    let fees_per_byte = estimates
+        .iter()
+        .map(|x| satoshis(&x.result).map(|x| x.div_ceil(1000).max(MIN_FEE_PER_BYTE)))
+        .collect::<Vec<_>>();
+
+    FEE_PRIORITIES
+        .iter()
+        .enumerate()
+        .map(|(index, priority)| {
+            let fee_per_byte = fallback_fee(&fees_per_byte, index).ok_or(FeeError::InvalidResponse {
+                msg: "no fee estimate available".into(),
+            })?;
+            Ok(FeeRate::new(priority, fee_per_byte.to_string(), None, fee_per_byte * vsize as u128))
+        })
+        .collect()
+}
+
+// Estimate of the priority, or of the nearest faster priority and then the nearest slower one when the node has none
+fn fallback_fee(fees_per_byte: &[Option<u128>], index: usize) -> Option<u128> {
+    fees_per_byte[index..].iter().chain(fees_per_byte[..index].iter().rev()).find_map(|x| *x)
+}
+
+// Decimal coin amount to satoshis, None for negative or malformed values
+fn satoshis(value: &str) -> Option<u128> {
+    let (integer, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if fraction.len() > SATOSHI_DECIMALS {
+        return None;
+    }
+    let integer = if integer.is_empty() { 0 } else { integer.parse::<u128>().ok()? };
+    let fraction = format!("{:0<width$}", fraction, width = SATOSHI_DECIMALS).parse::<u128>().ok()?;
+    Some(integer * 10u128.pow(SATOSHI_DECIMALS as u32) + fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn estimate(result: &str) -> BlockbookFeeEstimate {
+        BlockbookFeeEstimate { result: result.into() }
+    }
+
+    #[test]
+    fn test_satoshis() {
+        assert_eq!(satoshis("0.00012"), Some(12_000));
+        assert_eq!(satoshis("1"), Some(100_000_000));
+        assert_eq!(satoshis("-1"), None);
+        assert_eq!(satoshis("0.000000001"), None);
+    }
+
+    #[test]
+    fn test_fee_rates() {
+        let rates = fee_rates(&[estimate("-1"), estimate("0.00012"), estimate("0.0002051")], 141).unwrap();
+        let values = rates.iter().map(|x| (x.fee_per_unit.as_str(), x.total_fee.as_str())).collect::<Vec<_>>();
+
+        assert_eq!(values, vec![("12", "1692"), ("12", "1692"), ("21", "2961")]);
+        assert!(fee_rates(&[estimate("0.0001")], 141).is_err());
+    }
+
+    #[test]
+    fn test_fee_rates_missing_estimates() {
+        let rates = fee_rates(&[estimate("0.00012"), estimate("-1"), estimate("-1")], 141).unwrap();
+        let values = rates.iter().map(|x| x.fee_per_unit.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(values, vec!["12", "12", "12"]);
+        assert!(fee_rates(&[estimate("-1"), estimate("-1"), estimate("-1")], 141).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+use super::{format_units, FeeError, FeeRate, FEE_PRIORITIES};
+use primitives::Chain;
+
+// Thorchain charges a flat 0.02 RUNE native fee regardless of gas
+const THORCHAIN_NATIVE_FEE: u128 = 2_000_000;
+
+// Minimum gas price in thousandths of the smallest unit of the fee denom
+fn gas_price_milli(chain: Chain) -> Option<u128> {
+    match chain {
+        Chain::Cosmos => Some(5),                  // 0.005 uatom
+        Chain::Osmosis => Some(25),                // 0.025 uosmo
+        Chain::Celestia => Some(2),                // 0.002 utia
+        Chain::Noble => Some(100),                 // 0.1 uusdc
+        Chain::Sei => Some(100),                   // 0.1 usei
+        Chain::Injective => Some(500_000_000_000), // 500000000 inj
+        _ => None,
+    }
+}
+
+// Cosmos chains have no fee market, every priority pays the minimum gas price
+pub fn fee_rates(chain: Chain, gas_limit: u64) -> Result<Vec<FeeRate>, FeeError> {
+    if chain == Chain::Thorchain {
+        return Ok(FEE_PRIORITIES
+            .iter()
+            .map(|priority| FeeRate::new(priority, THORCHAIN_NATIVE_FEE.to_string(), None, THORCHAIN_NATIVE_FEE))
+            .collect());
+    }
+    let gas_price = gas_price_milli(chain).ok_or(FeeError::NotSupportedChain)?;
+    let total_fee = (gas_price * gas_limit as u128).div_ceil(1000);
+
+    Ok(FEE_PRIORITIES
+        .iter()
+        .map(|priority| FeeRate::new(priority, format_units(gas_price, 3), None, total_fee))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_rates() {
+        let rates = fee_rates(Chain::Cosmos, 200_000).unwrap();
+        assert_eq!(rates[0].fee_per_unit, "0.005");
+        assert_eq!(rates[2].total_fee, "1000");
+
+        let rates = fee_rates(Chain::Injective, 200_000).unwrap();
+        assert_eq!(rates[1].fee_per_unit, "500000000");
+        assert_eq!(rates[1].total_fee, "100000000000000");
+
+        let rates = fee_rates(Chain::Thorchain, 200_000).unwrap();
+        assert_eq!(rates[1].total_fee, "2000000");
+
+        assert_eq!(fee_rates(Chain::Ethereum, 21_000), Err(FeeError::NotSupportedChain));
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use super::{format_units, FeeError, FeeRate, FEE_PRIORITIES};
+use crate::config::evm_chain::get_evm_chain_config;
+use crate::network::{jsonrpc::jsonrpc_call, AlienProvider, JsonRpcResult};
+use gem_evm::jsonrpc::{EthereumFeeHistory, EthereumRpc};
+use primitives::{Chain, EVMChain};
+
+const GWEI_DECIMALS: u32 = 9;
+
+// Blocks of fee history the priority fees are averaged over
+const FEE_HISTORY_BLOCKS: u64 = 10;
+
+pub async fn fetch_fee_rates(chain: Chain, gas_limit: u64, provider: Arc<dyn AlienProvider>) -> Result<Vec<FeeRate>, FeeError> {
+    let evm_chain = EVMChain::from_chain(chain).ok_or(FeeError::NotSupportedChain)?;
+    let config = get_evm_chain_config(evm_chain);
+    let percentiles = config.rewards_percentiles;
+    let call = EthereumRpc::FeeHistory {
+        blocks: FEE_HISTORY_BLOCKS,
+        reward_percentiles: vec![percentiles.slow, percentiles.normal, percentiles.fast],
+    };
+    let response: JsonRpcResult<EthereumFeeHistory> = jsonrpc_call(&call, provider, &chain).await?;
+
+    fee_rates(&response.take()?, config.min_priority_fee as u128, gas_limit)
+}
+
+// Base fee of the next block plus the average priority fee paid at each percentile, OP Stack L1 data fees are not included
+// Fees per gas are in gwei to match FeeUnitType::Gwei, the total fee stays in wei
+pub fn fee_rates(history: &EthereumFeeHistory, min_priority_fee: u128, gas_limit: u64) -> Result<Vec<FeeRate>, FeeError> {
+    let base_fee = history.base_fee_per_gas.last().map(|x| hex_value(x)).ok_or(FeeError::InvalidResponse {
+        msg: "missing base fee".into(),
+    })??;
+
+    FEE_PRIORITIES
+        .iter()
+        .enumerate()
+        .map(|(index, priority)| {
+            let rewards = history
+                .reward
+                .iter()
+                .filter_map(|x| x.get(index))
+                .map(|x| hex_value(x))
+                .collect::<Result<Vec<_>, _>>()?;
+            let average = if rewards.is_empty() {
+                0
+            } else {
+                rewards.iter().sum::<u128>() / rewards.len() as u128
+            };
+            let priority_fee = average.max(min_priority_fee);
+            let fee_per_gas = base_fee + priority_fee;
+
+            Ok(FeeRate::new(
+                priority,
+                format_units(fee_per_gas, GWEI_DECIMALS),
+                Some(format_units(priority_fee, GWEI_DECIMALS)),
+                fee_per_gas * gas_limit as u128,
+            ))
+        })
+        .collect()
+}
+
+fn hex_value(value: &str) -> Result<u128, FeeError> {
+    u128::from_str_radix(value.trim_start_matches("0x"), 16).map_err(|_| FeeError::InvalidResponse {
+        msg: format!("invalid hex value: {}", value),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use primitives::FeePriority;
+
+    #[test]
+    fn test_fee_rates() {
+        let history: EthereumFeeHistory = serde_json::from_value(serde_json::json!({
+            "oldestBlock": "0x14a3c26",
+            "baseFeePerGas": ["0x3b9aca00", "0x4a817c800"],
+            "reward": [
+                ["0x3b9aca00", "0x77359400", "0xb2d05e00"],
+                ["0x0", "0x3b9aca00", "0x77359400"]
+            ]
+        }))
+        .unwrap();
+        let rates = fee_rates(&history, 1_000_000_000, 21_000).unwrap();
+
+        assert_eq!(
+            rates,
+            vec![
+                FeeRate {
+                    priority: FeePriority::Slow,
+                    fee_per_unit: "21".into(),
+                    priority_fee: Some("1".into()),
+                    total_fee: "441000000000000".into(),
+                },
+                FeeRate {
+                    priority: FeePriority::Normal,
+                    fee_per_unit: "21.5".into(),
+                    priority_fee: Some("1.5".into()),
+                    total_fee: "451500000000000".into(),
+                },
+                FeeRate {
+                    priority: FeePriority::Fast,
+                    fee_per_unit: "22.5".into(),
+                    priority_fee: Some("2.5".into()),
+                    total_fee: "472500000000000".into(),
+                },
+            ]
+        );
+    }
+}
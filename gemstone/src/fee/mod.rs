@@ -0,0 +1,146 @@
+use std::sync::Arc;
+
+use crate::chain::fee_unit_type;
+use crate::network::{AlienError, AlienProvider, JsonRpcError};
+use primitives::{Chain, ChainType, FeePriority};
+
+pub mod bitcoin;
+pub mod cosmos;
+pub mod evm;
+pub mod solana;
+pub mod sui;
+
+pub const FEE_PRIORITIES: [FeePriority; 3] = [FeePriority::Slow, FeePriority::Normal, FeePriority::Fast];
+
+#[derive(Debug, Clone, PartialEq, uniffi::Error, thiserror::Error)]
+pub enum FeeError {
+    #[error("Not supported chain")]
+    NotSupportedChain,
+    #[error("RPC error: {msg}")]
+    NetworkError { msg: String },
+    #[error("Invalid response: {msg}")]
+    InvalidResponse { msg: String },
+}
+
+impl From<AlienError> for FeeError {
+    fn from(err: AlienError) -> Self {
+        Self::NetworkError { msg: err.to_string() }
+    }
+}
+
+impl From<JsonRpcError> for FeeError {
+    fn from(err: JsonRpcError) -> Self {
+        Self::NetworkError { msg: err.message }
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct FeeRate {
+    pub priority: FeePriority,
+    // decimal amount in the unit type of the estimate: gwei per gas, sat per vB, lamports per compute unit, smallest denom per gas on Cosmos and Sui
+    pub fee_per_unit: String,
+    // included in fee_per_unit, EVM priority fee per gas and Solana prioritization fee
+    pub priority_fee: Option<String>,
+    // smallest native unit
+    pub total_fee: String,
+}
+
+impl FeeRate {
+    pub fn new(priority: &FeePriority, fee_per_unit: String, priority_fee: Option<String>, total_fee: u128) -> Self {
+        Self {
+            priority: priority.clone(),
+            fee_per_unit,
+            priority_fee,
+            total_fee: total_fee.to_string(),
+        }
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct FeeEstimate {
+    pub chain: Chain,
+    // FeeUnitType
+    pub unit_type: String,
+    // gas limit, virtual size or compute units the total fee is calculated for
+    pub units: u64,
+    // slow, normal and fast
+    pub rates: Vec<FeeRate>,
+}
+
+// Decimal string of a value with the given number of decimals, trailing zeros removed
+pub fn format_units(value: u128, decimals: u32) -> String {
+    let base = 10u128.pow(decimals);
+    let fraction = format!("{:0width$}", value % base, width = decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        (value / base).to_string()
+    } else {
+        format!("{}.{}", value / base, fraction)
+    }
+}
+
+// Units of a native transfer, used when the caller does not know the transaction size yet
+pub fn default_units(chain: Chain) -> u64 {
+    match chain.chain_type() {
+        ChainType::Ethereum => 21_000,
+        // one input, two outputs, P2PKH for Doge and P2WPKH otherwise
+        ChainType::Bitcoin => match chain {
+            Chain::Doge => 226,
+            _ => 141,
+        },
+        // default compute unit limit of an instruction
+        ChainType::Solana => 200_000,
+        ChainType::Cosmos => 200_000,
+        ChainType::Sui => 25_000,
+        _ => 0,
+    }
+}
+
+/// FeeEstimator
+#[derive(Debug, uniffi::Object)]
+pub struct FeeEstimator {
+    provider: Arc<dyn AlienProvider>,
+}
+
+#[uniffi::export]
+impl FeeEstimator {
+    #[uniffi::constructor]
+    fn new(provider: Arc<dyn AlienProvider>) -> Self {
+        Self { provider }
+    }
+
+    pub async fn estimate_fee(&self, chain: Chain, units: Option<u64>) -> Result<FeeEstimate, FeeError> {
+        let units = units.unwrap_or(default_units(chain));
+        let provider = self.provider.clone();
+        let rates = match chain.chain_type() {
+            ChainType::Ethereum => evm::fetch_fee_rates(chain, units, provider).await?,
+            ChainType::Solana => solana::fetch_fee_rates(units, provider).await?,
+            ChainType::Bitcoin => bitcoin::fetch_fee_rates(chain, units, provider).await?,
+            ChainType::Cosmos => cosmos::fee_rates(chain, units)?,
+            ChainType::Sui => sui::fetch_fee_rates(units, provider).await?,
+            ChainType::Ton | ChainType::Tron | ChainType::Aptos | ChainType::Xrp | ChainType::Near | ChainType::Stellar | ChainType::Algorand => {
+                return Err(FeeError::NotSupportedChain)
+            }
+        };
+
+        Ok(FeeEstimate {
+            chain,
+            unit_type: fee_unit_type(chain).as_ref().to_string(),
+            units,
+            rates,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(21_500_000_000, 9), "21.5");
+        assert_eq!(format_units(1_000_000_000, 9), "1");
+        assert_eq!(format_units(10_000, 6), "0.01");
+        assert_eq!(format_units(0, 6), "0");
+    }
+}
@@ -0,0 +1,81 @@
+use std::sync::Arc;
+
+use super::{format_units, FeeError, FeeRate, FEE_PRIORITIES};
+use crate::network::{jsonrpc::jsonrpc_call, AlienProvider, JsonRpcResult};
+use gem_solana::jsonrpc::{PrioritizationFee, SolanaRpc};
+use primitives::{Chain, FeePriority};
+
+// Lamports per signature
+const BASE_FEE: u128 = 5_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+const MICRO_LAMPORTS_DECIMALS: u32 = 6;
+// Percentiles of the recent non zero prioritization fees for slow, normal and fast
+const FEE_PERCENTILES: [usize; 3] = [25, 50, 75];
+
+pub async fn fetch_fee_rates(compute_units: u64, provider: Arc<dyn AlienProvider>) -> Result<Vec<FeeRate>, FeeError> {
+    let call = SolanaRpc::GetRecentPrioritizationFees(vec![]);
+    let response: JsonRpcResult<Vec<PrioritizationFee>> = jsonrpc_call(&call, provider, &Chain::Solana).await?;
+
+    Ok(fee_rates(&response.take()?, compute_units))
+}
+
+pub fn fee_rates(fees: &[PrioritizationFee], compute_units: u64) -> Vec<FeeRate> {
+    FEE_PRIORITIES
+        .iter()
+        .map(|priority| {
+            let priority_fee = priority_fee(fees, priority);
+            let total_fee = BASE_FEE + (priority_fee * compute_units as u128).div_ceil(MICRO_LAMPORTS_PER_LAMPORT);
+            // lamports per compute unit to match FeeUnitType::Native
+            let fee_per_unit = format_units(priority_fee, MICRO_LAMPORTS_DECIMALS);
+            FeeRate::new(priority, fee_per_unit.clone(), Some(fee_per_unit), total_fee)
+        })
+        .collect()
+}
+
+// Micro lamports per compute unit at the percentile of the priority
+pub fn priority_fee(fees: &[PrioritizationFee], priority: &FeePriority) -> u128 {
+    let mut values = fees.iter().map(|x| x.prioritization_fee as u128).filter(|x| *x > 0).collect::<Vec<_>>();
+    values.sort();
+    let percentile = match priority {
+        FeePriority::Slow => FEE_PERCENTILES[0],
+        FeePriority::Normal => FEE_PERCENTILES[1],
+        FeePriority::Fast => FEE_PERCENTILES[2],
+    };
+    percentile_value(&values, percentile)
+}
+
+fn percentile_value(sorted: &[u128], percentile: usize) -> u128 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    sorted[(sorted.len() - 1) * percentile / 100]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_rates() {
+        let fees: Vec<PrioritizationFee> = serde_json::from_value(serde_json::json!([
+            {"slot": 1, "prioritizationFee": 0},
+            {"slot": 2, "prioritizationFee": 50000},
+            {"slot": 3, "prioritizationFee": 1000},
+            {"slot": 4, "prioritizationFee": 20000},
+            {"slot": 5, "prioritizationFee": 10000},
+            {"slot": 6, "prioritizationFee": 100000}
+        ]))
+        .unwrap();
+        let rates = fee_rates(&fees, 200_000);
+
+        let values = rates.iter().map(|x| (x.fee_per_unit.as_str(), x.total_fee.as_str())).collect::<Vec<_>>();
+        assert_eq!(values, vec![("0.01", "7000"), ("0.02", "9000"), ("0.05", "15000")]);
+    }
+
+    #[test]
+    fn test_fee_rates_empty() {
+        let rates = fee_rates(&[], 200_000);
+
+        assert!(rates.iter().all(|x| x.total_fee == "5000"));
+    }
+}
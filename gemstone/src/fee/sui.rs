@@ -0,0 +1,45 @@
+use std::sync::Arc;
+
+use super::{FeeError, FeeRate, FEE_PRIORITIES};
+use crate::network::{jsonrpc_call, AlienProvider, JsonRpcRequest, JsonRpcRequestConvert, JsonRpcResult};
+use primitives::Chain;
+
+struct ReferenceGasPriceRequest;
+
+impl JsonRpcRequestConvert for ReferenceGasPriceRequest {
+    fn to_req(&self, id: u64) -> JsonRpcRequest {
+        JsonRpcRequest::new(id, "suix_getReferenceGasPrice", vec![])
+    }
+}
+
+pub async fn fetch_fee_rates(gas_units: u64, provider: Arc<dyn AlienProvider>) -> Result<Vec<FeeRate>, FeeError> {
+    let response: JsonRpcResult<String> = jsonrpc_call(&ReferenceGasPriceRequest, provider, &Chain::Sui).await?;
+    let gas_price = response.take()?;
+    let gas_price = gas_price.parse::<u128>().map_err(|_| FeeError::InvalidResponse {
+        msg: format!("invalid gas price: {}", gas_price),
+    })?;
+
+    Ok(fee_rates(gas_price, gas_units))
+}
+
+// Validators process transactions at the reference gas price of the epoch, paying more does not speed them up
+pub fn fee_rates(gas_price: u128, gas_units: u64) -> Vec<FeeRate> {
+    let gas_budget = gas_price * gas_units as u128;
+    FEE_PRIORITIES
+        .iter()
+        .map(|priority| FeeRate::new(priority, gas_price.to_string(), None, gas_budget))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fee_rates() {
+        let rates = fee_rates(750, 25_000);
+
+        assert_eq!(rates.len(), 3);
+        assert!(rates.iter().all(|x| x.fee_per_unit == "750" && x.total_fee == "18750000"));
+    }
+}
@@ -4,14 +4,18 @@ pub mod lido;
 use gem_bsc::stake_hub;
 use std::str::FromStr;
 pub mod asset;
+pub mod balance;
 pub mod bsc;
 pub mod config;
 pub mod solana;
 use solana::MplMetadata;
 pub mod block_explorer;
 pub mod chain;
+pub mod fee;
 pub mod network;
+pub mod node_monitor;
 pub mod payment;
+pub mod simulation;
 pub mod sui;
 pub mod swapper;
 pub mod ton;
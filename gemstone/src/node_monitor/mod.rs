@@ -0,0 +1,159 @@
+use std::{sync::Arc, time::Instant};
+
+use crate::config::node::{Node, NodePriority};
+use crate::network::AlienProvider;
+use primitives::Chain;
+
+pub mod probe;
+use probe::{parse_probe, probe_targets, NodeProbe};
+
+// Nodes further behind the best node than this are considered out of sync
+const MAX_BLOCK_DELAY_MS: i64 = 60_000;
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct NodeStatus {
+    pub url: String,
+    pub priority: NodePriority,
+    pub latency_ms: u64,
+    pub block_number: Option<u64>,
+    // blocks behind the most advanced node of the same check
+    pub block_lag: Option<u64>,
+    pub chain_id: Option<String>,
+    pub is_chain_id_valid: bool,
+    pub is_syncing: bool,
+    pub error: Option<String>,
+}
+
+impl NodeStatus {
+    pub fn is_healthy(&self) -> bool {
+        self.error.is_none() && self.is_chain_id_valid && !self.is_syncing && self.priority != NodePriority::Inactive
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct NodeCheckResult {
+    // best node first, unhealthy nodes last
+    pub nodes: Vec<NodeStatus>,
+    pub recommended: Option<Node>,
+}
+
+/// NodeMonitor
+#[derive(Debug, uniffi::Object)]
+pub struct NodeMonitor {
+    provider: Arc<dyn AlienProvider>,
+}
+
+#[uniffi::export]
+impl NodeMonitor {
+    #[uniffi::constructor]
+    fn new(provider: Arc<dyn AlienProvider>) -> Self {
+        Self { provider }
+    }
+
+    pub async fn check_node(&self, chain: Chain, node: Node) -> NodeStatus {
+        let start = Instant::now();
+        let result = self.provider.batch_request(probe_targets(chain, &node.url)).await;
+        let latency_ms = start.elapsed().as_millis() as u64;
+
+        match result.and_then(|responses| parse_probe(chain, &responses)) {
+            Ok(probe) => node_status(chain, node, latency_ms, probe),
+            Err(err) => NodeStatus {
+                url: node.url,
+                priority: node.priority,
+                latency_ms,
+                block_number: None,
+                block_lag: None,
+                chain_id: None,
+                is_chain_id_valid: false,
+                is_syncing: false,
+                error: Some(err.to_string()),
+            },
+        }
+    }
+
+    pub async fn check_nodes(&self, chain: Chain, nodes: Vec<Node>) -> NodeCheckResult {
+        let statuses = futures::future::join_all(nodes.into_iter().map(|node| self.check_node(chain, node))).await;
+        rank_nodes(chain, statuses)
+    }
+}
+
+fn node_status(chain: Chain, node: Node, latency_ms: u64, probe: NodeProbe) -> NodeStatus {
+    // chains without a queryable chain id are trusted
+    let is_chain_id_valid = probe.chain_id.as_ref().map(|x| x == chain.network_id()).unwrap_or(true);
+    NodeStatus {
+        url: node.url,
+        priority: node.priority,
+        latency_ms,
+        block_number: Some(probe.block_number),
+        block_lag: None,
+        chain_id: probe.chain_id,
+        is_chain_id_valid,
+        is_syncing: probe.is_syncing,
+        error: None,
+    }
+}
+
+// Shared node selection: healthy nodes within the block lag first, then by priority and latency
+pub fn rank_nodes(chain: Chain, mut nodes: Vec<NodeStatus>) -> NodeCheckResult {
+    let best_block = nodes.iter().filter(|x| x.is_healthy()).filter_map(|x| x.block_number).max();
+    let max_block_lag = (MAX_BLOCK_DELAY_MS / chain.block_time()).max(1) as u64;
+
+    for node in nodes.iter_mut() {
+        node.block_lag = best_block.zip(node.block_number).map(|(best, block)| best.saturating_sub(block));
+        if node.block_lag.unwrap_or_default() > max_block_lag {
+            node.is_syncing = true;
+        }
+    }
+
+    nodes.sort_by(|a, b| {
+        b.is_healthy()
+            .cmp(&a.is_healthy())
+            .then((b.priority.clone() as i32).cmp(&(a.priority.clone() as i32)))
+            .then(a.latency_ms.cmp(&b.latency_ms))
+    });
+
+    let recommended = nodes.first().filter(|x| x.is_healthy()).map(|x| Node::new(&x.url, x.priority.clone()));
+    NodeCheckResult { nodes, recommended }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(url: &str, priority: NodePriority, latency_ms: u64, block_number: u64, chain_id: &str) -> NodeStatus {
+        let probe = NodeProbe {
+            block_number,
+            chain_id: Some(chain_id.into()),
+            is_syncing: false,
+        };
+        node_status(Chain::Ethereum, Node::new(url, priority), latency_ms, probe)
+    }
+
+    #[test]
+    fn test_rank_nodes() {
+        let nodes = vec![
+            status("slow", NodePriority::High, 400, 1000, "1"),
+            status("fast", NodePriority::High, 100, 1000, "1"),
+            status("low", NodePriority::Low, 50, 1000, "1"),
+            status("behind", NodePriority::High, 10, 990, "1"),
+            status("wrong_chain", NodePriority::High, 10, 1001, "56"),
+        ];
+        let result = rank_nodes(Chain::Ethereum, nodes);
+        let urls = result.nodes.iter().map(|x| x.url.as_str()).collect::<Vec<_>>();
+
+        assert_eq!(urls, vec!["fast", "slow", "low", "behind", "wrong_chain"]);
+        assert_eq!(result.recommended, Some(Node::new("fast", NodePriority::High)));
+        assert_eq!(result.nodes[3].block_lag, Some(10));
+        assert!(result.nodes[3].is_syncing);
+        assert!(!result.nodes[4].is_chain_id_valid);
+    }
+
+    #[test]
+    fn test_rank_nodes_unhealthy() {
+        let mut node = status("down", NodePriority::High, 10, 0, "1");
+        node.error = Some("timeout".into());
+        let result = rank_nodes(Chain::Ethereum, vec![node]);
+
+        assert_eq!(result.recommended, None);
+    }
+}
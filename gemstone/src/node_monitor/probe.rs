@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+
+use crate::network::{batch_into_target, AlienError, AlienHttpMethod, AlienTarget, Data, JsonRpcRequest, JsonRpcResult};
+use primitives::{Chain, ChainType};
+use serde_json::Value;
+
+// State reported by a node, chain_id is None for chains without a way to query it
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeProbe {
+    pub block_number: u64,
+    pub chain_id: Option<String>,
+    pub is_syncing: bool,
+}
+
+// Requests sent to a node to probe it, responses are parsed by parse_probe in the same order
+pub fn probe_targets(chain: Chain, url: &str) -> Vec<AlienTarget> {
+    let url = url.trim_end_matches('/');
+    match chain.chain_type() {
+        ChainType::Ethereum => vec![batch_into_target(
+            &[
+                JsonRpcRequest::new(1, "eth_chainId", vec![]),
+                JsonRpcRequest::new(2, "eth_blockNumber", vec![]),
+                JsonRpcRequest::new(3, "eth_syncing", vec![]),
+            ],
+            url,
+        )],
+        ChainType::Solana => vec![batch_into_target(
+            &[
+                JsonRpcRequest::new(1, "getGenesisHash", vec![]),
+                JsonRpcRequest::new(2, "getSlot", vec![]),
+                JsonRpcRequest::new(3, "getHealth", vec![]),
+            ],
+            url,
+        )],
+        ChainType::Sui => vec![batch_into_target(
+            &[
+                JsonRpcRequest::new(1, "sui_getChainIdentifier", vec![]),
+                JsonRpcRequest::new(2, "sui_getLatestCheckpointSequenceNumber", vec![]),
+            ],
+            url,
+        )],
+        ChainType::Near => vec![batch_into_target(&JsonRpcRequest::new(1, "status", vec![]), url)],
+        ChainType::Xrp => vec![batch_into_target(&JsonRpcRequest::new(1, "server_info", vec![serde_json::json!({})]), url)],
+        ChainType::Cosmos => vec![
+            get_target(&format!("{}/cosmos/base/tendermint/v1beta1/blocks/latest", url)),
+            get_target(&format!("{}/cosmos/base/tendermint/v1beta1/syncing", url)),
+        ],
+        ChainType::Ton => vec![get_target(&format!("{}/api/v2/getMasterchainInfo", url))],
+        ChainType::Tron => vec![AlienTarget {
+            url: format!("{}/wallet/getnowblock", url),
+            method: AlienHttpMethod::Post,
+            headers: Some(HashMap::from([("Content-Type".into(), "application/json".into())])),
+            body: None,
+        }],
+        ChainType::Aptos => vec![get_target(&format!("{}/v1", url))],
+        ChainType::Bitcoin => vec![get_target(&format!("{}/api/", url))],
+        ChainType::Stellar => vec![get_target(url)],
+        ChainType::Algorand => vec![get_target(&format!("{}/v2/status", url)), get_target(&format!("{}/versions", url))],
+    }
+}
+
+pub fn parse_probe(chain: Chain, responses: &[Data]) -> Result<NodeProbe, AlienError> {
+    let values = responses
+        .iter()
+        .map(|x| serde_json::from_slice::<Value>(x).map_err(|err| AlienError::ResponseError { msg: err.to_string() }))
+        .collect::<Result<Vec<_>, _>>()?;
+    let value = |index: usize| values.get(index).ok_or(AlienError::ResponseError { msg: "No result".into() });
+
+    match chain.chain_type() {
+        ChainType::Ethereum => {
+            let results = jsonrpc_results(value(0)?, 3)?;
+            Ok(NodeProbe {
+                block_number: hex_number(&results[1].take()?)?,
+                chain_id: Some(hex_number(&results[0].take()?)?.to_string()),
+                // false once synced, otherwise an object with the sync progress
+                is_syncing: results[2].take()? != Value::Bool(false),
+            })
+        }
+        ChainType::Solana => {
+            let results = jsonrpc_results(value(0)?, 3)?;
+            Ok(NodeProbe {
+                block_number: number(&results[1].take()?)?,
+                chain_id: results[0].take()?.as_str().map(|x| x.to_string()),
+                // getHealth returns an error while the node is behind
+                is_syncing: results[2].take().is_err(),
+            })
+        }
+        ChainType::Sui => {
+            let results = jsonrpc_results(value(0)?, 2)?;
+            Ok(NodeProbe {
+                block_number: number(&results[1].take()?)?,
+                chain_id: results[0].take()?.as_str().map(|x| x.to_string()),
+                is_syncing: false,
+            })
+        }
+        ChainType::Near => {
+            let result = jsonrpc_result(value(0)?)?;
+            Ok(NodeProbe {
+                block_number: number(&result["sync_info"]["latest_block_height"])?,
+                chain_id: result["chain_id"].as_str().map(|x| x.to_string()),
+                is_syncing: result["sync_info"]["syncing"].as_bool().unwrap_or_default(),
+            })
+        }
+        ChainType::Xrp => {
+            let info = &jsonrpc_result(value(0)?)?["info"];
+            Ok(NodeProbe {
+                block_number: number(&info["validated_ledger"]["seq"])?,
+                chain_id: None,
+                is_syncing: !matches!(info["server_state"].as_str(), Some("full" | "proposing" | "validating")),
+            })
+        }
+        ChainType::Cosmos => {
+            let header = &value(0)?["block"]["header"];
+            Ok(NodeProbe {
+                block_number: number(&header["height"])?,
+                chain_id: header["chain_id"].as_str().map(|x| x.to_string()),
+                is_syncing: value(1)?["syncing"].as_bool().unwrap_or_default(),
+            })
+        }
+        ChainType::Ton => {
+            let result = &value(0)?["result"];
+            Ok(NodeProbe {
+                block_number: number(&result["last"]["seqno"])?,
+                chain_id: result["init"]["root_hash"].as_str().map(|x| x.to_string()),
+                is_syncing: false,
+            })
+        }
+        ChainType::Tron => Ok(NodeProbe {
+            block_number: number(&value(0)?["block_header"]["raw_data"]["number"])?,
+            chain_id: None,
+            is_syncing: false,
+        }),
+        ChainType::Aptos => {
+            let result = value(0)?;
+            Ok(NodeProbe {
+                block_number: number(&result["block_height"])?,
+                chain_id: Some(number(&result["chain_id"])?.to_string()),
+                is_syncing: false,
+            })
+        }
+        ChainType::Bitcoin => {
+            let blockbook = &value(0)?["blockbook"];
+            Ok(NodeProbe {
+                block_number: number(&blockbook["bestHeight"])?,
+                chain_id: None,
+                is_syncing: !blockbook["inSync"].as_bool().unwrap_or_default(),
+            })
+        }
+        ChainType::Stellar => {
+            let result = value(0)?;
+            Ok(NodeProbe {
+                block_number: number(&result["history_latest_ledger"])?,
+                chain_id: result["network_passphrase"].as_str().map(|x| x.to_string()),
+                is_syncing: false,
+            })
+        }
+        ChainType::Algorand => {
+            let status = value(0)?;
+            Ok(NodeProbe {
+                block_number: number(&status["last-round"])?,
+                chain_id: value(1)?["genesis_id"].as_str().map(|x| x.to_string()),
+                // time since the node started catching up, zero once synced
+                is_syncing: status["catchup-time"].as_u64().unwrap_or_default() > 0,
+            })
+        }
+    }
+}
+
+fn get_target(url: &str) -> AlienTarget {
+    AlienTarget {
+        url: url.to_string(),
+        method: AlienHttpMethod::Get,
+        headers: None,
+        body: None,
+    }
+}
+
+fn jsonrpc_result(value: &Value) -> Result<Value, AlienError> {
+    let result: JsonRpcResult<Value> = serde_json::from_value(value.clone()).map_err(|err| AlienError::ResponseError { msg: err.to_string() })?;
+    Ok(result.take()?)
+}
+
+// Batch responses are not guaranteed to keep the request order
+fn jsonrpc_results(value: &Value, count: usize) -> Result<Vec<JsonRpcResult<Value>>, AlienError> {
+    let mut results: Vec<JsonRpcResult<Value>> = serde_json::from_value(value.clone()).map_err(|err| AlienError::ResponseError { msg: err.to_string() })?;
+    if results.len() < count {
+        return Err(AlienError::ResponseError {
+            msg: format!("expected {} results, got {}", count, results.len()),
+        });
+    }
+    results.sort_by_key(|x| match x {
+        JsonRpcResult::Value(value) => value.id,
+        JsonRpcResult::Error(error) => error.id,
+    });
+    Ok(results)
+}
+
+fn hex_number(value: &Value) -> Result<u64, AlienError> {
+    value
+        .as_str()
+        .and_then(|x| u64::from_str_radix(x.trim_start_matches("0x"), 16).ok())
+        .ok_or(AlienError::ResponseError {
+            msg: format!("invalid hex number: {}", value),
+        })
+}
+
+// Nodes return heights either as numbers or as strings
+fn number(value: &Value) -> Result<u64, AlienError> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|x| x.parse().ok()))
+        .ok_or(AlienError::ResponseError {
+            msg: format!("invalid number: {}", value),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn data(value: Value) -> Data {
+        serde_json::to_vec(&value).unwrap()
+    }
+
+    #[test]
+    fn test_probe_targets() {
+        let targets = probe_targets(Chain::Cosmos, "https://cosmos-rest.publicnode.com/");
+        assert_eq!(targets.len(), 2);
+        assert_eq!(
+            targets[0].url,
+            "https://cosmos-rest.publicnode.com/cosmos/base/tendermint/v1beta1/blocks/latest"
+        );
+        assert_eq!(targets[0].method, AlienHttpMethod::Get);
+
+        let targets = probe_targets(Chain::Ethereum, "https://ethereum.publicnode.com");
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].method, AlienHttpMethod::Post);
+    }
+
+    #[test]
+    fn test_parse_ethereum() {
+        let response = data(serde_json::json!([
+            {"jsonrpc": "2.0", "id": 2, "result": "0x14a3c2f"},
+            {"jsonrpc": "2.0", "id": 1, "result": "0x1"},
+            {"jsonrpc": "2.0", "id": 3, "result": false}
+        ]));
+        let probe = parse_probe(Chain::Ethereum, &[response]).unwrap();
+
+        assert_eq!(
+            probe,
+            NodeProbe {
+                block_number: 21642287,
+                chain_id: Some("1".into()),
+                is_syncing: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_solana_behind() {
+        let response = data(serde_json::json!([
+            {"jsonrpc": "2.0", "id": 1, "result": "5eykt4UsFv8P8NJdTREpY1vzqKqZKvdpKuc147dw2N9d"},
+            {"jsonrpc": "2.0", "id": 2, "result": 311038563},
+            {"jsonrpc": "2.0", "id": 3, "error": {"code": -32005, "message": "Node is behind by 42 slots"}}
+        ]));
+        let probe = parse_probe(Chain::Solana, &[response]).unwrap();
+
+        assert_eq!(probe.block_number, 311038563);
+        assert_eq!(probe.chain_id.as_deref(), Some(Chain::Solana.network_id()));
+        assert!(probe.is_syncing);
+    }
+
+    #[test]
+    fn test_parse_cosmos() {
+        let block = data(serde_json::json!({"block": {"header": {"chain_id": "cosmoshub-4", "height": "23891042"}}}));
+        let syncing = data(serde_json::json!({"syncing": true}));
+        let probe = parse_probe(Chain::Cosmos, &[block, syncing]).unwrap();
+
+        assert_eq!(probe.block_number, 23891042);
+        assert_eq!(probe.chain_id.as_deref(), Some("cosmoshub-4"));
+        assert!(probe.is_syncing);
+    }
+}
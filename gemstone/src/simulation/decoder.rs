@@ -0,0 +1,434 @@
+use alloy_core::{
+    hex::decode as HexDecode,
+    primitives::{Address, B256, U256},
+    sol_types::{SolCall, SolEvent},
+};
+use gem_evm::{erc1155::IERC1155, erc20::IERC20};
+use serde::Deserialize;
+
+use super::{SimulationApproval, SimulationAssetType, SimulationBalanceChange};
+
+const UINT96_MAX: u128 = u128::MAX >> 32;
+
+// Frame of the debug_traceCall callTracer, logs are included with withLog
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallFrame {
+    pub from: String,
+    pub to: Option<String>,
+    pub value: Option<String>,
+    pub gas_used: Option<String>,
+    pub error: Option<String>,
+    pub revert_reason: Option<String>,
+    #[serde(default)]
+    pub calls: Vec<CallFrame>,
+    #[serde(default)]
+    pub logs: Vec<CallLog>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CallLog {
+    pub address: String,
+    #[serde(default)]
+    pub topics: Vec<String>,
+    pub data: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Transfer {
+    asset_type: SimulationAssetType,
+    contract: Option<Address>,
+    token_id: Option<U256>,
+    from: Address,
+    to: Address,
+    value: U256,
+}
+
+// Logs and native value transfers of frames that did not revert, in execution order
+fn collect(frame: &CallFrame, logs: &mut Vec<CallLog>, transfers: &mut Vec<Transfer>) {
+    if frame.error.is_some() {
+        return;
+    }
+    let value = frame.value.as_deref().and_then(hex_u256).unwrap_or_default();
+    if let (false, Some(from), Some(to)) = (value.is_zero(), parse_address(&frame.from), frame.to.as_deref().and_then(parse_address)) {
+        transfers.push(Transfer {
+            asset_type: SimulationAssetType::Native,
+            contract: None,
+            token_id: None,
+            from,
+            to,
+            value,
+        });
+    }
+    logs.extend(frame.logs.iter().cloned());
+    for call in &frame.calls {
+        collect(call, logs, transfers);
+    }
+}
+
+pub fn decode_trace(owner: &Address, frame: &CallFrame) -> (Vec<SimulationBalanceChange>, Vec<SimulationApproval>) {
+    let mut logs = vec![];
+    let mut transfers = vec![];
+    collect(frame, &mut logs, &mut transfers);
+    transfers.extend(logs.iter().flat_map(decode_transfer).flatten());
+
+    let approvals = logs.iter().flat_map(|log| decode_approval(owner, log)).collect();
+    (balance_changes(owner, &transfers), approvals)
+}
+
+fn decode_transfer(log: &CallLog) -> Option<Vec<Transfer>> {
+    let contract = parse_address(&log.address)?;
+    let topics = log.topics.iter().map(|x| x.parse::<B256>().ok()).collect::<Option<Vec<_>>>()?;
+    let data = HexDecode(&log.data).ok()?;
+
+    let topic = *topics.first()?;
+    match topics.len() {
+        3 if topic == IERC20::Transfer::SIGNATURE_HASH => Some(vec![Transfer {
+            asset_type: SimulationAssetType::ERC20,
+            contract: Some(contract),
+            token_id: None,
+            from: Address::from_word(topics[1]),
+            to: Address::from_word(topics[2]),
+            value: U256::try_from_be_slice(data.get(..32)?)?,
+        }]),
+        4 if topic == IERC20::Transfer::SIGNATURE_HASH => Some(vec![Transfer {
+            asset_type: SimulationAssetType::ERC721,
+            contract: Some(contract),
+            token_id: Some(U256::from_be_bytes(topics[3].0)),
+            from: Address::from_word(topics[1]),
+            to: Address::from_word(topics[2]),
+            value: U256::from(1),
+        }]),
+        4 if topic == IERC1155::TransferSingle::SIGNATURE_HASH => {
+            let (id, value) = IERC1155::TransferSingle::abi_decode_data(&data, false).ok()?;
+            Some(vec![Transfer {
+                asset_type: SimulationAssetType::ERC1155,
+                contract: Some(contract),
+                token_id: Some(id),
+                from: Address::from_word(topics[2]),
+                to: Address::from_word(topics[3]),
+                value,
+            }])
+        }
+        4 if topic == IERC1155::TransferBatch::SIGNATURE_HASH => {
+            let (ids, values) = IERC1155::TransferBatch::abi_decode_data(&data, false).ok()?;
+            if ids.len() != values.len() {
+                return None;
+            }
+            Some(
+                ids.into_iter()
+                    .zip(values)
+                    .map(|(id, value)| Transfer {
+                        asset_type: SimulationAssetType::ERC1155,
+                        contract: Some(contract),
+                        token_id: Some(id),
+                        from: Address::from_word(topics[2]),
+                        to: Address::from_word(topics[3]),
+                        value,
+                    })
+                    .collect(),
+            )
+        }
+        _ => None,
+    }
+}
+
+fn decode_approval(owner: &Address, log: &CallLog) -> Option<SimulationApproval> {
+    let contract = parse_address(&log.address)?;
+    let topics = log.topics.iter().map(|x| x.parse::<B256>().ok()).collect::<Option<Vec<_>>>()?;
+    if topics.len() < 3 || Address::from_word(topics[1]) != *owner {
+        return None;
+    }
+    let spender = Address::from_word(topics[2]);
+    let data = HexDecode(&log.data).ok()?;
+
+    let topic = topics[0];
+    match topics.len() {
+        3 if topic == IERC20::Approval::SIGNATURE_HASH => {
+            let value = U256::try_from_be_slice(data.get(..32)?)?;
+            Some(erc20_approval(contract, spender, value))
+        }
+        // zero spender clears the approval of the token id
+        4 if topic == IERC20::Approval::SIGNATURE_HASH && !spender.is_zero() => Some(SimulationApproval {
+            asset_type: SimulationAssetType::ERC721,
+            contract: contract.to_checksum(None),
+            spender: spender.to_checksum(None),
+            token_id: Some(U256::from_be_bytes(topics[3].0).to_string()),
+            amount: None,
+            is_unlimited: false,
+        }),
+        3 if topic == IERC1155::ApprovalForAll::SIGNATURE_HASH => {
+            let approved = IERC1155::ApprovalForAll::abi_decode_data(&data, false).ok()?.0;
+            approved.then(|| operator_approval(contract, spender))
+        }
+        _ => None,
+    }
+}
+
+// Without a trace only the top level call is known, covers native value and direct token calls.
+// Transfers and approvals of nested calls, like the output of a swap, are missing from the result
+pub fn decode_calldata(owner: &Address, to: &Address, value: U256, data: &[u8]) -> (Vec<SimulationBalanceChange>, Vec<SimulationApproval>) {
+    let mut transfers = vec![];
+    let mut approvals = vec![];
+    if !value.is_zero() {
+        transfers.push(Transfer {
+            asset_type: SimulationAssetType::Native,
+            contract: None,
+            token_id: None,
+            from: *owner,
+            to: *to,
+            value,
+        });
+    }
+
+    let erc20_transfer = |from: Address, recipient: Address, value: U256| Transfer {
+        asset_type: SimulationAssetType::ERC20,
+        contract: Some(*to),
+        token_id: None,
+        from,
+        to: recipient,
+        value,
+    };
+    match data.get(..4).and_then(|x| <[u8; 4]>::try_from(x).ok()) {
+        Some(IERC20::approveCall::SELECTOR) => {
+            if let Ok(call) = IERC20::approveCall::abi_decode(data, false) {
+                approvals.push(erc20_approval(*to, call.spender, call.value));
+            }
+        }
+        Some(IERC1155::setApprovalForAllCall::SELECTOR) => {
+            if let Ok(call) = IERC1155::setApprovalForAllCall::abi_decode(data, false) {
+                if call.approved {
+                    approvals.push(operator_approval(*to, call.operator));
+                }
+            }
+        }
+        Some(IERC20::transferCall::SELECTOR) => {
+            if let Ok(call) = IERC20::transferCall::abi_decode(data, false) {
+                transfers.push(erc20_transfer(*owner, call.to, call.value));
+            }
+        }
+        Some(IERC20::transferFromCall::SELECTOR) => {
+            if let Ok(call) = IERC20::transferFromCall::abi_decode(data, false) {
+                transfers.push(erc20_transfer(call.from, call.to, call.value));
+            }
+        }
+        _ => {}
+    }
+    (balance_changes(owner, &transfers), approvals)
+}
+
+fn erc20_approval(contract: Address, spender: Address, value: U256) -> SimulationApproval {
+    SimulationApproval {
+        asset_type: SimulationAssetType::ERC20,
+        contract: contract.to_checksum(None),
+        spender: spender.to_checksum(None),
+        token_id: None,
+        amount: Some(value.to_string()),
+        // max uint256 and max uint96 like allowances are effectively unlimited
+        is_unlimited: value >= U256::from(UINT96_MAX),
+    }
+}
+
+// ERC721 and ERC1155 operators can move every token of the collection, both emit the same ApprovalForAll
+// so the collection is labelled ERC721 until the simulator checks the contract with ERC165
+fn operator_approval(contract: Address, operator: Address) -> SimulationApproval {
+    SimulationApproval {
+        asset_type: SimulationAssetType::ERC721,
+        contract: contract.to_checksum(None),
+        spender: operator.to_checksum(None),
+        token_id: None,
+        amount: None,
+        is_unlimited: true,
+    }
+}
+
+// Net change of every asset for the owner, in first seen order
+fn balance_changes(owner: &Address, transfers: &[Transfer]) -> Vec<SimulationBalanceChange> {
+    let mut balances: Vec<(&Transfer, U256, U256)> = vec![];
+    for transfer in transfers {
+        let (incoming, outgoing) = match (transfer.to == *owner, transfer.from == *owner) {
+            (true, false) => (transfer.value, U256::ZERO),
+            (false, true) => (U256::ZERO, transfer.value),
+            _ => continue,
+        };
+        let key = |x: &Transfer| (x.asset_type.clone(), x.contract, x.token_id);
+        match balances.iter_mut().find(|(x, _, _)| key(x) == key(transfer)) {
+            Some((_, total_in, total_out)) => {
+                *total_in += incoming;
+                *total_out += outgoing;
+            }
+            None => balances.push((transfer, incoming, outgoing)),
+        }
+    }
+
+    balances
+        .into_iter()
+        .filter(|(_, incoming, outgoing)| incoming != outgoing)
+        .map(|(transfer, incoming, outgoing)| SimulationBalanceChange {
+            asset_type: transfer.asset_type.clone(),
+            contract: transfer.contract.map(|x| x.to_checksum(None)),
+            token_id: transfer.token_id.map(|x| x.to_string()),
+            amount: incoming.abs_diff(outgoing).to_string(),
+            is_incoming: incoming > outgoing,
+        })
+        .collect()
+}
+
+pub fn parse_address(value: &str) -> Option<Address> {
+    value.parse().ok()
+}
+
+pub fn hex_u256(value: &str) -> Option<U256> {
+    U256::from_str_radix(value.trim_start_matches("0x"), 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OWNER: &str = "0x46340b20830761efd32832a74d7169b29feb9758";
+    const ROUTER: &str = "0x3fc91a3afd70395cd496c647d5a6cc9d4b2b7fad";
+    const USDC: &str = "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48";
+    const TRANSFER: &str = "0xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef";
+    const APPROVAL: &str = "0x8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925";
+    const APPROVAL_FOR_ALL: &str = "0x17307eab39ab6107e8899845ad3d59bd9653f200f220920489ca2b5937696c31";
+
+    fn topic(address: &str) -> String {
+        format!("0x{:0>64}", address.trim_start_matches("0x"))
+    }
+
+    fn word(value: u64) -> String {
+        format!("{:064x}", value)
+    }
+
+    fn log(address: &str, topics: Vec<String>, data: String) -> CallLog {
+        CallLog {
+            address: address.into(),
+            topics,
+            data: format!("0x{}", data),
+        }
+    }
+
+    fn frame(from: &str, to: &str, value: Option<&str>, logs: Vec<CallLog>, calls: Vec<CallFrame>) -> CallFrame {
+        CallFrame {
+            from: from.into(),
+            to: Some(to.into()),
+            value: value.map(|x| x.into()),
+            gas_used: None,
+            error: None,
+            revert_reason: None,
+            calls,
+            logs,
+        }
+    }
+
+    #[test]
+    fn test_decode_trace_swap() {
+        let owner: Address = OWNER.parse().unwrap();
+        // 1 ETH in, 2500 USDC out, the reverted call is ignored
+        let mut reverted = frame(ROUTER, OWNER, Some("0x64"), vec![], vec![]);
+        reverted.error = Some("execution reverted".into());
+        let trace = frame(
+            OWNER,
+            ROUTER,
+            Some("0xde0b6b3a7640000"),
+            vec![],
+            vec![
+                frame(
+                    ROUTER,
+                    USDC,
+                    None,
+                    vec![log(USDC, vec![TRANSFER.into(), topic(ROUTER), topic(OWNER)], word(2_500_000_000))],
+                    vec![],
+                ),
+                reverted,
+            ],
+        );
+        let (changes, approvals) = decode_trace(&owner, &trace);
+
+        assert_eq!(
+            changes,
+            vec![
+                SimulationBalanceChange {
+                    asset_type: SimulationAssetType::Native,
+                    contract: None,
+                    token_id: None,
+                    amount: "1000000000000000000".into(),
+                    is_incoming: false,
+                },
+                SimulationBalanceChange {
+                    asset_type: SimulationAssetType::ERC20,
+                    contract: Some("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".into()),
+                    token_id: None,
+                    amount: "2500000000".into(),
+                    is_incoming: true,
+                },
+            ]
+        );
+        assert!(approvals.is_empty());
+    }
+
+    #[test]
+    fn test_decode_trace_approvals() {
+        let owner: Address = OWNER.parse().unwrap();
+        let trace = frame(
+            OWNER,
+            USDC,
+            None,
+            vec![
+                log(USDC, vec![APPROVAL.into(), topic(OWNER), topic(ROUTER)], "ff".repeat(32)),
+                log(ROUTER, vec![APPROVAL_FOR_ALL.into(), topic(OWNER), topic(USDC)], word(1)),
+                log(ROUTER, vec![TRANSFER.into(), topic(OWNER), topic(USDC), word(42)], String::new()),
+            ],
+            vec![],
+        );
+        let (changes, approvals) = decode_trace(&owner, &trace);
+
+        assert_eq!(approvals.len(), 2);
+        assert_eq!(approvals[0].asset_type, SimulationAssetType::ERC20);
+        assert_eq!(approvals[0].spender, "0x3fC91A3afd70395Cd496C647d5a6CC9D4B2b7FAD");
+        assert!(approvals[0].is_unlimited);
+        assert_eq!(approvals[1].asset_type, SimulationAssetType::ERC721);
+        assert!(approvals[1].is_unlimited);
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].asset_type, SimulationAssetType::ERC721);
+        assert_eq!(changes[0].token_id, Some("42".into()));
+        assert!(!changes[0].is_incoming);
+    }
+
+    #[test]
+    fn test_decode_calldata() {
+        let owner: Address = OWNER.parse().unwrap();
+        let usdc: Address = USDC.parse().unwrap();
+        let router: Address = ROUTER.parse().unwrap();
+
+        let data = IERC20::approveCall {
+            spender: router,
+            value: U256::from(1_000_000),
+        }
+        .abi_encode();
+        let (changes, approvals) = decode_calldata(&owner, &usdc, U256::ZERO, &data);
+        assert!(changes.is_empty());
+        assert_eq!(approvals[0].amount, Some("1000000".into()));
+        assert!(!approvals[0].is_unlimited);
+
+        let data = IERC20::approveCall {
+            spender: router,
+            value: U256::from(UINT96_MAX),
+        }
+        .abi_encode();
+        let (_, approvals) = decode_calldata(&owner, &usdc, U256::ZERO, &data);
+        assert!(approvals[0].is_unlimited);
+
+        let data = IERC20::transferCall {
+            to: router,
+            value: U256::from(5_000_000),
+        }
+        .abi_encode();
+        let (changes, approvals) = decode_calldata(&owner, &usdc, U256::ZERO, &data);
+        assert!(approvals.is_empty());
+        assert_eq!(changes[0].amount, "5000000");
+        assert!(!changes[0].is_incoming);
+    }
+}
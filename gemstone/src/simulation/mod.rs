@@ -0,0 +1,351 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::config::public::API_URL;
+use crate::network::{
+    jsonrpc_call, AlienError, AlienHttpMethod, AlienProvider, AlienTarget, JsonRpcError, JsonRpcRequest, JsonRpcRequestConvert, JsonRpcResult,
+};
+use alloy_core::{hex::decode as HexDecode, primitives::Address, sol_types::SolCall};
+use gem_evm::{
+    erc1155::{ERC1155_INTERFACE_ID, IERC1155},
+    jsonrpc::{BlockParameter, EthereumRpc, TransactionObject},
+};
+use primitives::{Chain, EVMChain, SecurityResponse};
+
+pub mod decoder;
+use decoder::{decode_calldata, decode_trace, hex_u256, parse_address, CallFrame};
+
+const METHOD_NOT_FOUND_CODE: i32 = -32601;
+// Balance the sender is given in the eth_call fallback so the value and gas are always covered
+const SENDER_BALANCE_OVERRIDE: &str = "0xffffffffffffffffffffffffffffffff";
+const UNTRACED_WARNING: &str = "Nested calls are not simulated, balance changes and approvals only cover the top level call";
+
+#[derive(Debug, Clone, PartialEq, uniffi::Error, thiserror::Error)]
+pub enum SimulationError {
+    #[error("Not supported chain")]
+    NotSupportedChain,
+    #[error("Invalid address: {address}")]
+    InvalidAddress { address: String },
+    #[error("Invalid transaction: {msg}")]
+    InvalidTransaction { msg: String },
+    #[error("RPC error: {msg}")]
+    NetworkError { msg: String },
+}
+
+impl From<AlienError> for SimulationError {
+    fn from(err: AlienError) -> Self {
+        Self::NetworkError { msg: err.to_string() }
+    }
+}
+
+impl From<JsonRpcError> for SimulationError {
+    fn from(err: JsonRpcError) -> Self {
+        Self::NetworkError { msg: err.message }
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct SimulationTransaction {
+    pub from: String,
+    pub to: String,
+    // hex encoded wei
+    pub value: Option<String>,
+    // hex encoded calldata
+    pub data: Option<String>,
+    // hex encoded gas limit
+    pub gas: Option<String>,
+}
+
+#[derive(uniffi::Enum, Debug, Clone, PartialEq)]
+pub enum SimulationAssetType {
+    Native,
+    ERC20,
+    ERC721,
+    ERC1155,
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct SimulationBalanceChange {
+    pub asset_type: SimulationAssetType,
+    // token contract, none for the native asset
+    pub contract: Option<String>,
+    // ERC721 and ERC1155 token id
+    pub token_id: Option<String>,
+    // net change in the smallest unit
+    pub amount: String,
+    pub is_incoming: bool,
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct SimulationApproval {
+    pub asset_type: SimulationAssetType,
+    pub contract: String,
+    // spender or operator
+    pub spender: String,
+    // ERC721 approval of a single token
+    pub token_id: Option<String>,
+    // ERC20 allowance
+    pub amount: Option<String>,
+    // unlimited ERC20 allowance or operator of the whole collection
+    pub is_unlimited: bool,
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct SimulationSecurity {
+    pub is_malicious: bool,
+    pub reason: String,
+    pub provider: String,
+}
+
+impl From<SecurityResponse> for SimulationSecurity {
+    fn from(value: SecurityResponse) -> Self {
+        Self {
+            is_malicious: value.malicious,
+            reason: value.reason,
+            provider: value.provider,
+        }
+    }
+}
+
+#[derive(uniffi::Record, Debug, Clone, PartialEq)]
+pub struct SimulationResult {
+    pub success: bool,
+    // revert reason when the transaction fails
+    pub error: Option<String>,
+    // hex encoded, only available from a trace
+    pub gas_used: Option<String>,
+    pub balance_changes: Vec<SimulationBalanceChange>,
+    pub approvals: Vec<SimulationApproval>,
+    // false when the node does not support debug_traceCall, the result then comes from eth_call and decoding
+    // the calldata of the top level call, so balance changes and approvals of nested calls are missing
+    pub is_traced: bool,
+    // set when the result is partial, explains what is missing
+    pub warning: Option<String>,
+    // risk flags of the called contract, none when the scan is not available
+    pub security: Option<SimulationSecurity>,
+}
+
+// eth_call with the sender balance overridden, the transaction should not fail only because the sender lacks funds
+struct CallRequest {
+    transaction: TransactionObject,
+}
+
+impl JsonRpcRequestConvert for CallRequest {
+    fn to_req(&self, id: u64) -> JsonRpcRequest {
+        let sender = self.transaction.from.clone().unwrap_or_default();
+        let overrides = serde_json::json!({ sender: { "balance": SENDER_BALANCE_OVERRIDE } });
+        JsonRpcRequest::new(
+            id,
+            "eth_call",
+            vec![serde_json::to_value(&self.transaction).unwrap(), (&BlockParameter::Latest).into(), overrides],
+        )
+    }
+}
+
+struct TraceCallRequest {
+    transaction: TransactionObject,
+}
+
+impl JsonRpcRequestConvert for TraceCallRequest {
+    fn to_req(&self, id: u64) -> JsonRpcRequest {
+        let tracer = serde_json::json!({
+            "tracer": "callTracer",
+            "tracerConfig": { "withLog": true },
+        });
+        JsonRpcRequest::new(
+            id,
+            "debug_traceCall",
+            vec![serde_json::to_value(&self.transaction).unwrap(), (&BlockParameter::Latest).into(), tracer],
+        )
+    }
+}
+
+/// TransactionSimulator
+#[derive(Debug, uniffi::Object)]
+pub struct TransactionSimulator {
+    provider: Arc<dyn AlienProvider>,
+}
+
+#[uniffi::export]
+impl TransactionSimulator {
+    #[uniffi::constructor]
+    fn new(provider: Arc<dyn AlienProvider>) -> Self {
+        Self { provider }
+    }
+
+    pub async fn simulate(&self, chain: Chain, transaction: SimulationTransaction) -> Result<SimulationResult, SimulationError> {
+        EVMChain::from_chain(chain).ok_or(SimulationError::NotSupportedChain)?;
+        let owner = parse_address(&transaction.from).ok_or(SimulationError::InvalidAddress {
+            address: transaction.from.clone(),
+        })?;
+        let to = parse_address(&transaction.to).ok_or(SimulationError::InvalidAddress {
+            address: transaction.to.clone(),
+        })?;
+        let value = match &transaction.value {
+            Some(value) => hex_u256(value).ok_or(SimulationError::InvalidTransaction {
+                msg: format!("invalid value: {}", value),
+            })?,
+            None => Default::default(),
+        };
+        let data =
+            HexDecode(transaction.data.as_deref().unwrap_or_default()).map_err(|_| SimulationError::InvalidTransaction { msg: "invalid data".into() })?;
+
+        // gas price is left out so the node does not require the sender to hold funds for gas
+        let call = TransactionObject {
+            from: Some(transaction.from.clone()),
+            to: transaction.to.clone(),
+            gas: transaction.gas.clone(),
+            gas_price: None,
+            value: transaction.value.clone(),
+            data: format!("0x{}", hex::encode(&data)),
+        };
+
+        let (result, security) = futures::join!(self.trace_call(chain, &call), self.scan_security(chain, &transaction.to));
+        let mut result = match result? {
+            Some(frame) => {
+                let (balance_changes, approvals) = decode_trace(&owner, &frame);
+                SimulationResult {
+                    success: frame.error.is_none(),
+                    error: frame.revert_reason.or(frame.error),
+                    gas_used: frame.gas_used,
+                    balance_changes,
+                    approvals,
+                    is_traced: true,
+                    warning: None,
+                    security: None,
+                }
+            }
+            // debug namespace is disabled on most public nodes, eth_call returns no logs so the result is partial
+            None => {
+                let response: JsonRpcResult<String> = jsonrpc_call(&CallRequest { transaction: call }, self.provider.clone(), &chain).await?;
+                let (balance_changes, approvals) = decode_calldata(&owner, &to, value, &data);
+                let error = response.take().err().map(|x| x.message);
+                SimulationResult {
+                    success: error.is_none(),
+                    error,
+                    gas_used: None,
+                    balance_changes,
+                    approvals,
+                    is_traced: false,
+                    warning: Some(UNTRACED_WARNING.into()),
+                    security: None,
+                }
+            }
+        };
+        result.security = security.ok().map(SimulationSecurity::from);
+        self.resolve_operator_approvals(chain, &mut result.approvals).await;
+
+        Ok(result)
+    }
+}
+
+impl TransactionSimulator {
+    // None when the node does not expose debug_traceCall, other errors are returned
+    async fn trace_call(&self, chain: Chain, transaction: &TransactionObject) -> Result<Option<CallFrame>, SimulationError> {
+        let request = TraceCallRequest {
+            transaction: transaction.clone(),
+        };
+        let response: JsonRpcResult<CallFrame> = jsonrpc_call(&request, self.provider.clone(), &chain).await?;
+        match response.take() {
+            Ok(frame) => Ok(Some(frame)),
+            Err(err) if is_method_not_supported(&err) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    // ApprovalForAll is shared by ERC721 and ERC1155, ERC165 tells the collections apart
+    async fn resolve_operator_approvals(&self, chain: Chain, approvals: &mut [SimulationApproval]) {
+        for approval in approvals
+            .iter_mut()
+            .filter(|x| x.asset_type == SimulationAssetType::ERC721 && x.token_id.is_none())
+        {
+            if self.supports_erc1155(chain, &approval.contract).await.unwrap_or_default() {
+                approval.asset_type = SimulationAssetType::ERC1155;
+            }
+        }
+    }
+
+    async fn supports_erc1155(&self, chain: Chain, contract: &str) -> Result<bool, SimulationError> {
+        let data = IERC1155::supportsInterfaceCall {
+            interfaceId: ERC1155_INTERFACE_ID.into(),
+        }
+        .abi_encode();
+        let call = EthereumRpc::Call(TransactionObject::new_call(contract, data), BlockParameter::Latest);
+        let response: JsonRpcResult<String> = jsonrpc_call(&call, self.provider.clone(), &chain).await?;
+        let data = HexDecode(response.take()?).map_err(|err| SimulationError::NetworkError { msg: err.to_string() })?;
+        let result = IERC1155::supportsInterfaceCall::abi_decode_returns(&data, false).map_err(|err| SimulationError::NetworkError { msg: err.to_string() })?;
+        Ok(result._0)
+    }
+
+    async fn scan_security(&self, chain: Chain, address: &str) -> Result<SecurityResponse, SimulationError> {
+        let body = serde_json::json!({
+            "target": { "address": address, "chain": chain.as_ref() },
+            "type": "address",
+        });
+        let target = AlienTarget {
+            url: format!("{}/v1/scan/security", API_URL),
+            method: AlienHttpMethod::Post,
+            headers: Some(HashMap::from([("Content-Type".into(), "application/json".into())])),
+            body: Some(serde_json::to_vec(&body).unwrap()),
+        };
+        let data = self.provider.request(target).await?;
+
+        serde_json::from_slice(&data).map_err(|err| SimulationError::NetworkError { msg: err.to_string() })
+    }
+}
+
+// Method not found, or a node that rejects the debug namespace with a generic error
+fn is_method_not_supported(error: &JsonRpcError) -> bool {
+    let message = error.message.to_lowercase();
+    error.code == METHOD_NOT_FOUND_CODE
+        || [
+            "method not found",
+            "does not exist",
+            "not available",
+            "not supported",
+            "not allowed",
+            "not whitelisted",
+        ]
+        .iter()
+        .any(|x| message.contains(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error(code: i32, message: &str) -> JsonRpcError {
+        JsonRpcError { code, message: message.into() }
+    }
+
+    #[test]
+    fn test_call_request_overrides_sender_balance() {
+        let request = CallRequest {
+            transaction: TransactionObject {
+                from: Some("0x0000000000000000000000000000000000000001".into()),
+                to: "0x0000000000000000000000000000000000000002".into(),
+                gas: None,
+                gas_price: None,
+                value: Some("0x1".into()),
+                data: "0x".into(),
+            },
+        }
+        .to_req(1);
+
+        assert_eq!(request.method, "eth_call");
+        assert_eq!(
+            request.params[2],
+            serde_json::json!({ "0x0000000000000000000000000000000000000001": { "balance": SENDER_BALANCE_OVERRIDE } })
+        );
+    }
+
+    #[test]
+    fn test_is_method_not_supported() {
+        assert!(is_method_not_supported(&error(-32601, "Method not found")));
+        assert!(is_method_not_supported(&error(
+            -32000,
+            "the method debug_traceCall does not exist/is not available"
+        )));
+        assert!(!is_method_not_supported(&error(-32000, "header not found")));
+        assert!(!is_method_not_supported(&error(-32603, "request timed out")));
+    }
+}
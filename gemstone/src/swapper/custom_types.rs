@@ -1,9 +1,10 @@
-use primitives::{AssetId, Chain, ChainType};
+use primitives::{AssetId, Chain, ChainType, FeePriority};
 use std::str::FromStr;
 
 uniffi::custom_type!(Chain, String);
 uniffi::custom_type!(ChainType, String);
 uniffi::custom_type!(AssetId, String);
+uniffi::custom_type!(FeePriority, String);
 
 impl crate::UniffiCustomTypeConverter for Chain {
     type Builtin = String;
@@ -40,3 +41,15 @@ impl crate::UniffiCustomTypeConverter for AssetId {
         obj.to_string()
     }
 }
+
+impl crate::UniffiCustomTypeConverter for FeePriority {
+    type Builtin = String;
+
+    fn into_custom(string: Self::Builtin) -> uniffi::Result<Self> {
+        FeePriority::from_str(&string).map_err(anyhow::Error::msg)
+    }
+
+    fn from_custom(obj: Self) -> Self::Builtin {
+        obj.as_ref().to_string()
+    }
+}
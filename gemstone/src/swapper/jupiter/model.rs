@@ -5,6 +5,7 @@ pub struct QuoteRequest {
     pub input_mint: String,
     pub output_mint: String,
     pub amount: String,
+    pub swap_mode: String,
     pub slippage_bps: u32,
     pub platform_fee_bps: u32,
     pub only_direct_routes: bool,
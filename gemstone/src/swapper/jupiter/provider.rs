@@ -1,7 +1,7 @@
 use super::{client::JupiterClient, model::*, PROGRAM_ADDRESS};
 use crate::{
     network::jsonrpc::{jsonrpc_call_with_cache, JsonRpcResult},
-    swapper::{GemSwapProvider, *},
+    swapper::{transaction_status, GemSwapProvider, *},
 };
 
 use async_trait::async_trait;
@@ -37,6 +37,13 @@ impl Jupiter {
             .ok_or_else(|| SwapperError::InvalidAddress { address: asset_id.to_string() })
     }
 
+    pub fn get_swap_mode(&self, mode: &GemSwapMode) -> String {
+        match mode {
+            GemSwapMode::ExactIn => "ExactIn".into(),
+            GemSwapMode::ExactOut => "ExactOut".into(),
+        }
+    }
+
     pub fn get_fee_mint(&self, mode: &GemSwapMode, input: &str, output: &str) -> String {
         match mode {
             GemSwapMode::ExactIn => {
@@ -117,6 +124,7 @@ impl GemSwapProvider for Jupiter {
             input_mint: input_mint.clone(),
             output_mint: output_mint.clone(),
             amount: request.value.clone(),
+            swap_mode: self.get_swap_mode(&request.mode),
             platform_fee_bps,
             slippage_bps,
             auto_slippage: true,
@@ -127,7 +135,7 @@ impl GemSwapProvider for Jupiter {
         let swap_quote = client.get_swap_quote(quote_request).await?;
 
         let quote = SwapQuote {
-            from_value: request.value.clone(),
+            from_value: swap_quote.in_amount.clone(),
             to_value: swap_quote.out_amount.clone(),
             data: SwapProviderData {
                 provider: self.provider(),
@@ -175,7 +183,7 @@ impl GemSwapProvider for Jupiter {
         };
         Ok(data)
     }
-    async fn get_transaction_status(&self, _chain: Chain, _transaction_hash: &str, _provider: Arc<dyn AlienProvider>) -> Result<bool, SwapperError> {
-        Ok(true)
+    async fn get_transaction_status(&self, _chain: Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<SwapStatus, SwapperError> {
+        transaction_status::fetch_solana_status(transaction_hash, provider).await
     }
 }
@@ -1,11 +1,18 @@
-use crate::network::AlienProvider;
+use crate::network::{
+    jsonrpc::{jsonrpc_call, JsonRpcResult},
+    AlienProvider,
+};
 
+use alloy_core::primitives::U256;
 use async_trait::async_trait;
-use std::{fmt::Debug, sync::Arc};
+use gem_evm::jsonrpc::EthereumRpc;
+use std::{fmt::Debug, str::FromStr, sync::Arc};
 
 mod approval;
 mod custom_types;
 mod permit2_data;
+mod ranking;
+mod transaction_status;
 
 pub mod asset;
 pub mod jupiter;
@@ -17,7 +24,7 @@ pub mod thorchain;
 pub mod universal_router;
 
 pub use models::*;
-use primitives::{AssetId, Chain};
+use primitives::{AssetId, Chain, ChainType};
 use std::collections::HashSet;
 
 #[async_trait]
@@ -26,7 +33,7 @@ pub trait GemSwapProvider: Send + Sync + Debug {
     fn supported_assets(&self) -> Vec<SwapChainAsset>;
     async fn fetch_quote(&self, request: &SwapQuoteRequest, provider: Arc<dyn AlienProvider>) -> Result<SwapQuote, SwapperError>;
     async fn fetch_quote_data(&self, quote: &SwapQuote, provider: Arc<dyn AlienProvider>, data: FetchQuoteData) -> Result<SwapQuoteData, SwapperError>;
-    async fn get_transaction_status(&self, chain: Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<bool, SwapperError>;
+    async fn get_transaction_status(&self, chain: Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<SwapStatus, SwapperError>;
 }
 
 impl dyn GemSwapProvider {
@@ -67,6 +74,14 @@ impl GemSwapper {
             SwapChainAsset::Assets(chain, assets) => chain == asset_id.chain || assets.contains(&asset_id),
         })
     }
+
+    async fn fetch_gas_price(&self, chain: Chain) -> Option<U256> {
+        if chain.chain_type() != ChainType::Ethereum {
+            return None;
+        }
+        let response: JsonRpcResult<String> = jsonrpc_call(&EthereumRpc::GasPrice, self.rpc_provider.clone(), &chain).await.ok()?;
+        U256::from_str(&response.take().ok()?).ok()
+    }
 }
 
 #[uniffi::export]
@@ -80,6 +95,7 @@ impl GemSwapper {
                 Box::new(universal_router::UniswapV3::new_pancakeswap()),
                 Box::new(thorchain::ThorChain::default()),
                 Box::new(jupiter::Jupiter::default()),
+                Box::new(orca::Orca::default()),
                 Box::new(pancakeswap_aptos::PancakeSwapAptos::default()),
             ],
         }
@@ -117,7 +133,7 @@ impl GemSwapper {
         self.swappers.iter().map(|x| x.provider()).collect()
     }
 
-    async fn fetch_quote(&self, request: SwapQuoteRequest) -> Result<Vec<SwapQuote>, SwapperError> {
+    async fn fetch_quote(&self, request: SwapQuoteRequest) -> Result<SwapQuoteResult, SwapperError> {
         if request.from_asset == request.to_asset {
             return Err(SwapperError::NotSupportedPair);
         }
@@ -135,19 +151,32 @@ impl GemSwapper {
             return Err(SwapperError::NotSupportedPair);
         }
 
-        let quotes_futures = providers.into_iter().map(|x| x.fetch_quote(&request, self.rpc_provider.clone()));
-
-        let quotes = futures::future::join_all(quotes_futures.into_iter().map(|fut| async { fut.await.ok() }))
-            .await
+        let quote_request = &request;
+        let quotes_futures = providers
             .into_iter()
-            .flatten()
-            .collect::<Vec<_>>();
+            .map(|x| async move { (x.provider(), x.fetch_quote(quote_request, self.rpc_provider.clone()).await) });
+
+        let mut quotes = vec![];
+        let mut errors = vec![];
+        for (provider, result) in futures::future::join_all(quotes_futures).await {
+            match result {
+                Ok(quote) => quotes.push(quote),
+                Err(err) => errors.push(SwapProviderError {
+                    provider,
+                    message: err.to_string(),
+                }),
+            }
+        }
 
         if quotes.is_empty() {
             return Err(SwapperError::NoQuoteAvailable);
         }
 
-        Ok(quotes)
+        let has_gas_estimate = quotes.iter().flat_map(|x| &x.data.routes).any(|x| x.gas_estimate.is_some());
+        let gas_price = if has_gas_estimate { self.fetch_gas_price(from_chain).await } else { None };
+        let quotes = ranking::rank_quotes(quotes, &request.options.preferred_providers, gas_price);
+
+        Ok(SwapQuoteResult { quotes, errors })
     }
 
     async fn fetch_quote_data(&self, quote: &SwapQuote, data: FetchQuoteData) -> Result<SwapQuoteData, SwapperError> {
@@ -159,7 +188,7 @@ impl GemSwapper {
         swapper.fetch_quote_data(quote, self.rpc_provider.clone(), data).await
     }
 
-    async fn get_transaction_status(&self, chain: Chain, swap_provider: SwapProvider, transaction_hash: &str) -> Result<bool, SwapperError> {
+    async fn get_transaction_status(&self, chain: Chain, swap_provider: SwapProvider, transaction_hash: &str) -> Result<SwapStatus, SwapperError> {
         let swapper = self
             .swappers
             .iter()
@@ -204,6 +233,7 @@ mod tests {
             Box::new(universal_router::UniswapV3::new_pancakeswap()),
             Box::new(thorchain::ThorChain::default()),
             Box::new(jupiter::Jupiter::default()),
+            Box::new(orca::Orca::default()),
         ];
 
         let from_chain = Chain::Ethereum;
@@ -241,8 +271,11 @@ mod tests {
             .filter(|x| GemSwapper::filter_by_supported_chains(x.supported_chains(), &from_chain, &to_chain))
             .collect::<Vec<_>>();
 
-        assert_eq!(filtered.len(), 1);
-        assert_eq!(filtered[0].provider(), SwapProvider::Jupiter);
+        assert_eq!(filtered.len(), 2);
+        assert_eq!(
+            filtered.iter().map(|x| x.provider()).collect::<BTreeSet<_>>(),
+            BTreeSet::from([SwapProvider::Jupiter, SwapProvider::Orca])
+        );
 
         let from_chain = Chain::SmartChain;
         let to_chain = Chain::Bitcoin;
@@ -14,6 +14,8 @@ pub enum SwapperError {
     NotSupportedAsset,
     #[error("Not supported pair")]
     NotSupportedPair,
+    #[error("Not supported swap mode")]
+    NotSupportedMode,
     #[error("Invalid address {address}")]
     InvalidAddress { address: String },
     #[error("Invalid amount")]
@@ -141,6 +143,19 @@ pub enum ApprovalType {
     None,
 }
 
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct SwapProviderError {
+    pub provider: SwapProvider,
+    pub message: String,
+}
+
+// Ranked quotes with the reason each failed provider did not quote
+#[derive(Debug, Clone, uniffi::Record)]
+pub struct SwapQuoteResult {
+    pub quotes: Vec<SwapQuote>,
+    pub errors: Vec<SwapProviderError>,
+}
+
 #[derive(Debug, Clone, PartialEq, uniffi::Record)]
 pub struct ApprovalData {
     pub token: String,
@@ -205,3 +220,36 @@ pub struct SwapAssetList {
     pub chains: Vec<Chain>,
     pub asset_ids: Vec<AssetId>,
 }
+
+#[derive(Debug, Clone, PartialEq, uniffi::Enum)]
+pub enum SwapState {
+    Pending,
+    // cross chain swaps, inbound transaction seen by the provider
+    InboundObserved,
+    // cross chain swaps, swapped and waiting for the outbound transaction
+    OutboundPending,
+    Completed,
+    Refunded,
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct SwapStatus {
+    pub state: SwapState,
+    pub outbound_chain: Option<Chain>,
+    pub outbound_transaction_hash: Option<String>,
+    pub refund_reason: Option<String>,
+    pub received_amount: Option<String>,
+}
+
+impl SwapStatus {
+    pub fn new(state: SwapState) -> Self {
+        Self {
+            state,
+            outbound_chain: None,
+            outbound_transaction_hash: None,
+            refund_reason: None,
+            received_amount: None,
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use super::models::Whirlpool;
+use gem_solana::{
+    pubkey::Pubkey,
+    transaction::{AccountMeta, Instruction},
+    ASSOCIATED_TOKEN_PROGRAM, SYSTEM_PROGRAM, TOKEN_PROGRAM,
+};
+use std::str::FromStr;
+
+const COMPUTE_BUDGET_PROGRAM: &str = "ComputeBudget111111111111111111111111111111";
+
+// sha256("global:<name>")[..8]
+const SWAP_DISCRIMINATOR: [u8; 8] = [248, 198, 158, 145, 225, 117, 135, 200];
+const TWO_HOP_SWAP_DISCRIMINATOR: [u8; 8] = [195, 96, 237, 108, 68, 162, 219, 230];
+
+// Whirlpool sqrt price bounds, used as no limit for the swap direction
+pub const MIN_SQRT_PRICE: u128 = 4295048016;
+pub const MAX_SQRT_PRICE: u128 = 79226673515401279992447579055;
+
+// Accounts of a single whirlpool used in a swap
+#[derive(Debug, Clone)]
+pub struct SwapHop {
+    pub whirlpool: Pubkey,
+    pub pool: Whirlpool,
+    pub a_to_b: bool,
+    pub owner_account_a: Pubkey,
+    pub owner_account_b: Pubkey,
+    pub tick_arrays: [Pubkey; 3],
+    pub oracle: Pubkey,
+}
+
+impl SwapHop {
+    fn sqrt_price_limit(&self) -> u128 {
+        if self.a_to_b {
+            MIN_SQRT_PRICE
+        } else {
+            MAX_SQRT_PRICE
+        }
+    }
+
+    fn pool_accounts(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new(self.owner_account_a.clone(), false),
+            AccountMeta::new(self.pool.token_vault_a.clone(), false),
+            AccountMeta::new(self.owner_account_b.clone(), false),
+            AccountMeta::new(self.pool.token_vault_b.clone(), false),
+        ]
+    }
+
+    fn tick_array_accounts(&self) -> Vec<AccountMeta> {
+        self.tick_arrays.iter().map(|x| AccountMeta::new(x.clone(), false)).collect()
+    }
+}
+
+fn pubkey(address: &str) -> Pubkey {
+    Pubkey::from_str(address).unwrap()
+}
+
+pub fn swap(program: &Pubkey, authority: &Pubkey, hop: &SwapHop, amount: u64, other_amount_threshold: u64) -> Instruction {
+    let mut data = SWAP_DISCRIMINATOR.to_vec();
+    data.extend(amount.to_le_bytes());
+    data.extend(other_amount_threshold.to_le_bytes());
+    data.extend(hop.sqrt_price_limit().to_le_bytes());
+    data.push(true as u8); // amount_specified_is_input
+    data.push(hop.a_to_b as u8);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(pubkey(TOKEN_PROGRAM), false),
+        AccountMeta::new_readonly(authority.clone(), true),
+        AccountMeta::new(hop.whirlpool.clone(), false),
+    ];
+    accounts.extend(hop.pool_accounts());
+    accounts.extend(hop.tick_array_accounts());
+    accounts.push(AccountMeta::new(hop.oracle.clone(), false));
+
+    Instruction::new(program.clone(), accounts, data)
+}
+
+pub fn two_hop_swap(program: &Pubkey, authority: &Pubkey, hop_one: &SwapHop, hop_two: &SwapHop, amount: u64, other_amount_threshold: u64) -> Instruction {
+    let mut data = TWO_HOP_SWAP_DISCRIMINATOR.to_vec();
+    data.extend(amount.to_le_bytes());
+    data.extend(other_amount_threshold.to_le_bytes());
+    data.push(true as u8); // amount_specified_is_input
+    data.push(hop_one.a_to_b as u8);
+    data.push(hop_two.a_to_b as u8);
+    data.extend(hop_one.sqrt_price_limit().to_le_bytes());
+    data.extend(hop_two.sqrt_price_limit().to_le_bytes());
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(pubkey(TOKEN_PROGRAM), false),
+        AccountMeta::new_readonly(authority.clone(), true),
+        AccountMeta::new(hop_one.whirlpool.clone(), false),
+        AccountMeta::new(hop_two.whirlpool.clone(), false),
+    ];
+    accounts.extend(hop_one.pool_accounts());
+    accounts.extend(hop_two.pool_accounts());
+    accounts.extend(hop_one.tick_array_accounts());
+    accounts.extend(hop_two.tick_array_accounts());
+    accounts.push(AccountMeta::new(hop_one.oracle.clone(), false));
+    accounts.push(AccountMeta::new(hop_two.oracle.clone(), false));
+
+    Instruction::new(program.clone(), accounts, data)
+}
+
+pub fn set_compute_unit_limit(units: u32) -> Instruction {
+    let mut data = vec![2];
+    data.extend(units.to_le_bytes());
+    Instruction::new(pubkey(COMPUTE_BUDGET_PROGRAM), vec![], data)
+}
+
+pub fn set_compute_unit_price(micro_lamports: u64) -> Instruction {
+    let mut data = vec![3];
+    data.extend(micro_lamports.to_le_bytes());
+    Instruction::new(pubkey(COMPUTE_BUDGET_PROGRAM), vec![], data)
+}
+
+pub fn create_associated_token_account_idempotent(payer: &Pubkey, account: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(payer.clone(), true),
+        AccountMeta::new(account.clone(), false),
+        AccountMeta::new_readonly(owner.clone(), false),
+        AccountMeta::new_readonly(mint.clone(), false),
+        AccountMeta::new_readonly(pubkey(SYSTEM_PROGRAM), false),
+        AccountMeta::new_readonly(pubkey(TOKEN_PROGRAM), false),
+    ];
+    Instruction::new(pubkey(ASSOCIATED_TOKEN_PROGRAM), accounts, vec![1])
+}
+
+pub fn transfer_lamports(from: &Pubkey, to: &Pubkey, lamports: u64) -> Instruction {
+    let mut data = 2u32.to_le_bytes().to_vec();
+    data.extend(lamports.to_le_bytes());
+    let accounts = vec![AccountMeta::new(from.clone(), true), AccountMeta::new(to.clone(), false)];
+    Instruction::new(pubkey(SYSTEM_PROGRAM), accounts, data)
+}
+
+pub fn sync_native(account: &Pubkey) -> Instruction {
+    Instruction::new(pubkey(TOKEN_PROGRAM), vec![AccountMeta::new(account.clone(), false)], vec![17])
+}
+
+pub fn close_account(account: &Pubkey, destination: &Pubkey, owner: &Pubkey) -> Instruction {
+    let accounts = vec![
+        AccountMeta::new(account.clone(), false),
+        AccountMeta::new(destination.clone(), false),
+        AccountMeta::new_readonly(owner.clone(), true),
+    ];
+    Instruction::new(pubkey(TOKEN_PROGRAM), accounts, vec![9])
+}
@@ -19,6 +19,11 @@ impl JsonRpcRequestConvert for SolanaRpc {
                 serde_json::to_value(default_config).unwrap(),
             ],
             SolanaRpc::GetEpochInfo => todo!(),
+            SolanaRpc::GetLatestBlockhash => vec![serde_json::json!({"commitment": default_config.commitment})],
+            SolanaRpc::GetSignatureStatuses(signatures) => {
+                vec![serde_json::to_value(signatures).unwrap(), serde_json::json!({"searchTransactionHistory": true})]
+            }
+            SolanaRpc::GetRecentPrioritizationFees(accounts) => vec![serde_json::to_value(accounts).unwrap()],
         };
 
         JsonRpcRequest::new(id, &method, params)
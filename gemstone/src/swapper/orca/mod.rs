@@ -1,4 +1,5 @@
 mod fee_tiers;
+mod instruction;
 mod jsonrpc;
 mod models;
 mod provider;
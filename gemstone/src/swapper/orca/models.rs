@@ -120,6 +120,37 @@ pub struct TickArray {
     pub whirlpool: Pubkey,
 }
 
+// Tick array account around the current pool price, missing when not initialized
+#[derive(Debug, Clone)]
+pub struct TickArrayAccount {
+    pub address: Pubkey,
+    pub start_tick_index: i32,
+    pub tick_array: Option<TickArray>,
+}
+
+// Whirlpool used in a route with its swap direction
+#[derive(Debug, Clone)]
+pub struct PoolHop {
+    pub address: Pubkey,
+    pub pool: Whirlpool,
+    pub a_to_b: bool,
+}
+
+impl PoolHop {
+    pub fn new(address: Pubkey, pool: Whirlpool, input_mint: &Pubkey) -> Self {
+        let a_to_b = pool.token_mint_a == *input_mint;
+        Self { address, pool, a_to_b }
+    }
+
+    pub fn output_mint(&self) -> Pubkey {
+        if self.a_to_b {
+            self.pool.token_mint_b.clone()
+        } else {
+            self.pool.token_mint_a.clone()
+        }
+    }
+}
+
 pub fn try_borsh_decode<T: BorshDeserialize>(base64_str: &str) -> Result<T, anyhow::Error> {
     let bytes = STANDARD.decode(base64_str)?;
     T::try_from_slice(&bytes).map_err(|e| anyhow::anyhow!("borsh deserialize error: {:?}", e))
@@ -1,24 +1,35 @@
 use super::fee_tiers::get_splash_pool_fee_tiers;
-use super::whirlpool::{get_tick_array_address, get_whirlpool_address};
+use super::instruction::{self, SwapHop};
+use super::whirlpool::{get_oracle_address, get_tick_array_address, get_whirlpool_address};
 use super::{models::*, FEE_TIER_DISCRIMINATOR, WHIRLPOOL_CONFIG, WHIRLPOOL_PROGRAM};
+use crate::fee::solana::priority_fee;
 use crate::network::JsonRpcResult;
 use crate::{
     network::{jsonrpc::jsonrpc_call, AlienProvider},
-    swapper::{models::*, GemSwapProvider, SwapperError},
+    swapper::{models::*, slippage::apply_slippage_in_bp, transaction_status, GemSwapProvider, SwapperError},
 };
+use alloy_core::primitives::U256;
 use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD, Engine};
 use gem_solana::{
-    get_asset_address,
-    jsonrpc::{AccountData, Filter, Memcmp, SolanaRpc, ValueResult, ENCODING_BASE58},
+    get_asset_address, get_associated_token_address,
+    hash::Hash,
+    jsonrpc::{AccountData, Filter, LatestBlockhash, Memcmp, PrioritizationFee, SolanaRpc, ValueResult, ENCODING_BASE58},
     pubkey::Pubkey,
+    transaction::{Instruction, Transaction},
+    TOKEN_PROGRAM, USDC_TOKEN_MINT, WSOL_TOKEN_ADDRESS,
 };
 use orca_whirlpools_core::{
     get_tick_array_start_tick_index, swap_quote_by_input_token, TickArrayFacade, TickArrays, TickFacade, WhirlpoolFacade, WhirlpoolRewardInfoFacade,
     TICK_ARRAY_SIZE,
 };
-use primitives::Chain;
+use primitives::{AssetId, Chain, FeePriority};
 use std::{cmp::Ordering, iter::zip, str::FromStr, sync::Arc, vec};
 
+// Mints used as intermediate hop when there is no direct pool
+const INTERMEDIATE_MINTS: [&str; 2] = [WSOL_TOKEN_ADDRESS, USDC_TOKEN_MINT];
+const COMPUTE_UNIT_LIMIT: u32 = 400_000;
+
 #[derive(Debug)]
 pub struct Orca {
     pub whirlpool_program: Pubkey,
@@ -50,55 +61,59 @@ impl GemSwapProvider for Orca {
         if request.from_asset.chain != Chain::Solana || request.to_asset.chain != Chain::Solana {
             return Err(SwapperError::NotSupportedChain);
         }
+        if request.mode != GemSwapMode::ExactIn {
+            return Err(SwapperError::NotSupportedMode);
+        }
 
         let amount_in = request.value.parse::<u64>().map_err(|_| SwapperError::InvalidAmount)?;
-        let options = request.options.clone();
-        let slippage_bps = options.slippage_bps as u16;
+        let from_mint = Self::get_mint(&request.from_asset)?;
+        let to_mint = Self::get_mint(&request.to_asset)?;
 
-        let from_asset = get_asset_address(&request.from_asset).ok_or_else(|| SwapperError::InvalidAddress {
-            address: request.from_asset.to_string(),
-        })?;
-        let to_asset = get_asset_address(&request.to_asset).ok_or_else(|| SwapperError::InvalidAddress {
-            address: request.from_asset.to_string(),
-        })?;
-        let fee_tiers = self.fetch_fee_tiers(provider.clone()).await?;
-        let mut pools = self
-            .fetch_whirlpools(&from_asset, &to_asset, fee_tiers, provider.clone(), request.from_asset.chain)
-            .await?;
-
-        if pools.is_empty() {
-            return Err(SwapperError::NoQuoteAvailable);
+        // swap instruction supports legacy token program only
+        let mint_accounts = self.fetch_token_accounts(&from_mint, &to_mint, provider.clone()).await?;
+        if mint_accounts.iter().any(|x| x.owner != TOKEN_PROGRAM) {
+            return Err(SwapperError::NotSupportedAsset);
         }
 
-        // sort by liquidity ↓ and fee_rate ↑
-        pools.sort_by(|(_, a), (_, b)| b.liquidity.cmp(&a.liquidity).then(a.fee_rate.cmp(&b.fee_rate)));
-        let (pool_address, pool) = pools.first().unwrap();
-        let pool_address = Pubkey::from_str(pool_address).unwrap();
-        println!("first_pool: {:?}", pool);
-        println!("pool_address: {:?}", pool_address);
-
-        // let _token_accounts = self.fetch_token_accounts(&pool.token_mint_a, &pool.token_mint_b, provider.clone()).await?;
-        let tick_array = self.fetch_tick_arrays(&pool_address, pool, provider.clone()).await?;
+        let fee_tiers = self.fetch_fee_tiers(provider.clone()).await?;
+        let routes = self.fetch_routes(&from_mint, &to_mint, fee_tiers, provider.clone()).await?;
 
-        let tick_array_facades = tick_array.into_iter().map(|x| TickArrayFacade::from(&x)).collect::<Vec<_>>();
-        let result: [TickArrayFacade; 5] = std::array::from_fn(|i| tick_array_facades[i]);
-        let tick_arrays = TickArrays::from(result);
+        let mut best_route: Option<(u64, Vec<PoolHop>)> = None;
+        for route in routes {
+            let Ok(amount_out) = self.quote_route(&route, amount_in, provider.clone()).await else {
+                continue;
+            };
+            if best_route.as_ref().is_none_or(|(best_amount, _)| amount_out > *best_amount) {
+                best_route = Some((amount_out, route));
+            }
+        }
+        let (amount_out, route) = best_route.ok_or(SwapperError::NoQuoteAvailable)?;
 
-        let quote = swap_quote_by_input_token(amount_in, true, slippage_bps, pool.into(), tick_arrays, None, None).map_err(|c| SwapperError::NetworkError {
-            msg: format!("swap_quote_by_input_token error: {:?}", c),
-        })?;
+        let routes = route
+            .iter()
+            .enumerate()
+            .map(|(index, hop)| SwapRoute {
+                input: if index == 0 {
+                    request.from_asset.clone()
+                } else {
+                    AssetId::from_token(Chain::Solana, &route[index - 1].output_mint().to_string())
+                },
+                output: if index == route.len() - 1 {
+                    request.to_asset.clone()
+                } else {
+                    AssetId::from_token(Chain::Solana, &hop.output_mint().to_string())
+                },
+                route_data: hop.address.to_string(),
+                gas_estimate: None,
+            })
+            .collect();
 
         Ok(SwapQuote {
             from_value: request.value.clone(),
-            to_value: quote.token_est_out.to_string(),
+            to_value: amount_out.to_string(),
             data: SwapProviderData {
                 provider: self.provider(),
-                routes: vec![SwapRoute {
-                    input: request.from_asset.clone(),
-                    output: request.to_asset.clone(),
-                    route_data: pool.fee_rate.to_string(),
-                    gas_estimate: None,
-                }],
+                routes,
                 suggested_slippage_bps: None,
             },
             approval: ApprovalType::None,
@@ -106,17 +121,255 @@ impl GemSwapProvider for Orca {
         })
     }
 
-    async fn fetch_quote_data(&self, _quote: &SwapQuote, _provider: Arc<dyn AlienProvider>, _data: FetchQuoteData) -> Result<SwapQuoteData, SwapperError> {
-        todo!()
+    async fn fetch_quote_data(&self, quote: &SwapQuote, provider: Arc<dyn AlienProvider>, _data: FetchQuoteData) -> Result<SwapQuoteData, SwapperError> {
+        if quote.data.routes.is_empty() || quote.data.routes.len() > 2 {
+            return Err(SwapperError::InvalidRoute);
+        }
+        let wallet = Pubkey::from_str(&quote.request.wallet_address).map_err(|_| SwapperError::InvalidAddress {
+            address: quote.request.wallet_address.clone(),
+        })?;
+        let amount_in = quote.from_value.parse::<u64>().map_err(|_| SwapperError::InvalidAmount)?;
+        // minimum output follows the quote the user accepted
+        let amount_out = quote.to_value.parse::<u64>().map_err(|_| SwapperError::InvalidAmount)?;
+        let min_amount_out = apply_slippage_in_bp(&U256::from(amount_out), quote.request.options.slippage_bps).to::<u64>();
+
+        let route = self.fetch_route(&quote.data.routes, provider.clone()).await?;
+        let tick_arrays = futures::future::try_join_all(route.iter().map(|hop| self.fetch_tick_arrays(&hop.address, &hop.pool, provider.clone()))).await?;
+        let compute_unit_price = self.fetch_compute_unit_price(&route, provider.clone()).await?;
+
+        let hops = zip(route.iter(), tick_arrays.iter())
+            .map(|(hop, tick_arrays)| self.get_swap_hop(&wallet, hop, tick_arrays))
+            .collect::<Result<Vec<_>, _>>()?;
+        let swap_instruction = match hops.as_slice() {
+            [hop] => instruction::swap(&self.whirlpool_program, &wallet, hop, amount_in, min_amount_out),
+            [hop_one, hop_two] => instruction::two_hop_swap(&self.whirlpool_program, &wallet, hop_one, hop_two, amount_in, min_amount_out),
+            _ => return Err(SwapperError::InvalidRoute),
+        };
+        let mints = route.iter().map(|x| x.output_mint()).collect::<Vec<_>>();
+        let instructions = Self::get_swap_instructions(
+            &wallet,
+            &mints,
+            swap_instruction,
+            amount_in,
+            compute_unit_price,
+            quote.request.from_asset.is_native(),
+            quote.request.to_asset.is_native(),
+        )?;
+
+        let blockhash = self.fetch_latest_blockhash(provider).await?;
+        let transaction = Transaction::new(wallet, instructions, blockhash);
+
+        Ok(SwapQuoteData {
+            to: WHIRLPOOL_PROGRAM.to_string(),
+            value: "".to_string(),
+            data: STANDARD.encode(transaction.serialize_unsigned()),
+        })
     }
 
-    async fn get_transaction_status(&self, _chain: Chain, _transaction_hash: &str, _provider: Arc<dyn AlienProvider>) -> Result<bool, SwapperError> {
-        // TODO: the transaction status from the RPC
-        Ok(true)
+    async fn get_transaction_status(&self, _chain: Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<SwapStatus, SwapperError> {
+        transaction_status::fetch_solana_status(transaction_hash, provider).await
     }
 }
 
 impl Orca {
+    fn get_mint(asset_id: &AssetId) -> Result<Pubkey, SwapperError> {
+        get_asset_address(asset_id).ok_or_else(|| SwapperError::InvalidAddress { address: asset_id.to_string() })
+    }
+
+    fn get_token_account(owner: &Pubkey, mint: &Pubkey) -> Result<Pubkey, SwapperError> {
+        let token_program = Pubkey::from_str(TOKEN_PROGRAM).unwrap();
+        get_associated_token_address(owner, mint, &token_program).ok_or_else(|| SwapperError::InvalidAddress { address: mint.to_string() })
+    }
+
+    // Direct pool when available, otherwise two hops through SOL or USDC pools
+    pub async fn fetch_routes(
+        &self,
+        from_mint: &Pubkey,
+        to_mint: &Pubkey,
+        fee_tiers: Vec<FeeTier>,
+        provider: Arc<dyn AlienProvider>,
+    ) -> Result<Vec<Vec<PoolHop>>, SwapperError> {
+        if let Some((address, pool)) = self.fetch_best_pool(from_mint, to_mint, fee_tiers.clone(), provider.clone()).await? {
+            return Ok(vec![vec![PoolHop::new(address, pool, from_mint)]]);
+        }
+
+        let mut routes = vec![];
+        for mint in INTERMEDIATE_MINTS.iter().map(|x| Pubkey::from_str(x).unwrap()) {
+            if mint == *from_mint || mint == *to_mint {
+                continue;
+            }
+            let first = self.fetch_best_pool(from_mint, &mint, fee_tiers.clone(), provider.clone()).await?;
+            let second = self.fetch_best_pool(&mint, to_mint, fee_tiers.clone(), provider.clone()).await?;
+            if let (Some((first_address, first_pool)), Some((second_address, second_pool))) = (first, second) {
+                routes.push(vec![
+                    PoolHop::new(first_address, first_pool, from_mint),
+                    PoolHop::new(second_address, second_pool, &mint),
+                ]);
+            }
+        }
+        if routes.is_empty() {
+            return Err(SwapperError::NoQuoteAvailable);
+        }
+        Ok(routes)
+    }
+
+    pub async fn fetch_best_pool(
+        &self,
+        token_mint_1: &Pubkey,
+        token_mint_2: &Pubkey,
+        fee_tiers: Vec<FeeTier>,
+        provider: Arc<dyn AlienProvider>,
+    ) -> Result<Option<(Pubkey, Whirlpool)>, SwapperError> {
+        let mut pools = self.fetch_whirlpools(token_mint_1, token_mint_2, fee_tiers, provider, self.chain).await?;
+
+        // sort by liquidity ↓ and fee_rate ↑
+        pools.sort_by(|(_, a), (_, b)| b.liquidity.cmp(&a.liquidity).then(a.fee_rate.cmp(&b.fee_rate)));
+        Ok(pools
+            .into_iter()
+            .filter(|(_, pool)| pool.liquidity > 0)
+            .find_map(|(address, pool)| Some((Pubkey::from_str(&address).ok()?, pool))))
+    }
+
+    // Pools of a previously quoted route, directions follow route inputs
+    pub async fn fetch_route(&self, routes: &[SwapRoute], provider: Arc<dyn AlienProvider>) -> Result<Vec<PoolHop>, SwapperError> {
+        let addresses = routes.iter().map(|x| x.route_data.clone()).collect::<Vec<_>>();
+        let call = SolanaRpc::GetMultipleAccounts(addresses.clone());
+        let response: JsonRpcResult<ValueResult<Vec<Option<AccountData>>>> = jsonrpc_call(&call, provider, &self.chain).await?;
+        let accounts = response.take()?.value;
+
+        zip(routes, zip(addresses, accounts))
+            .map(|(route, (address, account))| -> Result<PoolHop, SwapperError> {
+                let account = account.ok_or(SwapperError::InvalidRoute)?;
+                let pool: Whirlpool = try_borsh_decode(account.data[0].as_str()).map_err(|e| SwapperError::ABIError { msg: e.to_string() })?;
+                let address = Pubkey::from_str(&address).map_err(|_| SwapperError::InvalidRoute)?;
+                Ok(PoolHop::new(address, pool, &Self::get_mint(&route.input)?))
+            })
+            .collect()
+    }
+
+    // Chains exact in quotes through route hops, returns estimated output
+    pub async fn quote_route(&self, route: &[PoolHop], amount_in: u64, provider: Arc<dyn AlienProvider>) -> Result<u64, SwapperError> {
+        let mut amount = amount_in;
+        for hop in route {
+            let accounts = self.fetch_tick_arrays(&hop.address, &hop.pool, provider.clone()).await?;
+            let quote = swap_quote_by_input_token(amount, hop.a_to_b, 0, (&hop.pool).into(), Self::get_tick_arrays(&accounts), None, None).map_err(|c| {
+                SwapperError::ComputeQuoteError {
+                    msg: format!("swap_quote_by_input_token error: {:?}", c),
+                }
+            })?;
+            amount = quote.token_est_out;
+        }
+        if amount == 0 {
+            return Err(SwapperError::NoQuoteAvailable);
+        }
+        Ok(amount)
+    }
+
+    // Micro lamports per compute unit recently paid by transactions writing to the route pools
+    pub async fn fetch_compute_unit_price(&self, route: &[PoolHop], provider: Arc<dyn AlienProvider>) -> Result<u64, SwapperError> {
+        let call = SolanaRpc::GetRecentPrioritizationFees(route.iter().map(|x| x.address.to_string()).collect());
+        let response: JsonRpcResult<Vec<PrioritizationFee>> = jsonrpc_call(&call, provider, &self.chain).await?;
+        Ok(priority_fee(&response.take()?, &FeePriority::Normal) as u64)
+    }
+
+    fn get_tick_arrays(accounts: &[TickArrayAccount]) -> TickArrays {
+        let facades = accounts
+            .iter()
+            .map(|x| match &x.tick_array {
+                Some(tick_array) => TickArrayFacade::from(tick_array),
+                None => TickArrayFacade {
+                    start_tick_index: x.start_tick_index,
+                    ticks: std::array::from_fn(|_| TickFacade {
+                        initialized: false,
+                        liquidity_net: 0,
+                        liquidity_gross: 0,
+                        fee_growth_outside_a: 0,
+                        fee_growth_outside_b: 0,
+                        reward_growths_outside: [0; 3],
+                    }),
+                },
+            })
+            .collect::<Vec<_>>();
+        let result: [TickArrayFacade; 5] = std::array::from_fn(|i| facades[i]);
+        TickArrays::from(result)
+    }
+
+    // Three initialized tick arrays in swap direction, repeating the last one when the pool has fewer
+    fn get_swap_tick_arrays(hop: &PoolHop, accounts: &[TickArrayAccount]) -> Option<[Pubkey; 3]> {
+        let pool = &hop.pool;
+        let shift = if hop.a_to_b { 0 } else { pool.tick_spacing as i32 };
+        let start_index = get_tick_array_start_tick_index(pool.tick_current_index + shift, pool.tick_spacing);
+        let offset = (pool.tick_spacing as i32) * (TICK_ARRAY_SIZE as i32) * if hop.a_to_b { -1 } else { 1 };
+
+        let addresses = (0..3)
+            .map(|i| start_index + i * offset)
+            .map_while(|index| {
+                accounts
+                    .iter()
+                    .find(|x| x.start_tick_index == index && x.tick_array.is_some())
+                    .map(|x| x.address.clone())
+            })
+            .collect::<Vec<_>>();
+        let last = addresses.last()?.clone();
+        Some(std::array::from_fn(|i| addresses.get(i).cloned().unwrap_or(last.clone())))
+    }
+
+    fn get_swap_hop(&self, wallet: &Pubkey, hop: &PoolHop, tick_arrays: &[TickArrayAccount]) -> Result<SwapHop, SwapperError> {
+        Ok(SwapHop {
+            whirlpool: hop.address.clone(),
+            pool: hop.pool.clone(),
+            a_to_b: hop.a_to_b,
+            owner_account_a: Self::get_token_account(wallet, &hop.pool.token_mint_a)?,
+            owner_account_b: Self::get_token_account(wallet, &hop.pool.token_mint_b)?,
+            tick_arrays: Self::get_swap_tick_arrays(hop, tick_arrays).ok_or(SwapperError::NoQuoteAvailable)?,
+            oracle: get_oracle_address(&hop.address).ok_or(SwapperError::InvalidRoute)?.0,
+        })
+    }
+
+    // Wraps native SOL input, creates output token accounts and unwraps native SOL output
+    fn get_swap_instructions(
+        wallet: &Pubkey,
+        output_mints: &[Pubkey],
+        swap_instruction: Instruction,
+        amount_in: u64,
+        compute_unit_price: u64,
+        is_native_input: bool,
+        is_native_output: bool,
+    ) -> Result<Vec<Instruction>, SwapperError> {
+        let wsol_mint = Pubkey::from_str(WSOL_TOKEN_ADDRESS).unwrap();
+        let wsol_account = Self::get_token_account(wallet, &wsol_mint)?;
+
+        let mut instructions = vec![
+            instruction::set_compute_unit_limit(COMPUTE_UNIT_LIMIT),
+            instruction::set_compute_unit_price(compute_unit_price),
+        ];
+        if is_native_input {
+            instructions.extend([
+                instruction::create_associated_token_account_idempotent(wallet, &wsol_account, wallet, &wsol_mint),
+                instruction::transfer_lamports(wallet, &wsol_account, amount_in),
+                instruction::sync_native(&wsol_account),
+            ]);
+        }
+        for mint in output_mints {
+            let account = Self::get_token_account(wallet, mint)?;
+            instructions.push(instruction::create_associated_token_account_idempotent(wallet, &account, wallet, mint));
+        }
+        instructions.push(swap_instruction);
+        if is_native_input || is_native_output {
+            instructions.push(instruction::close_account(&wsol_account, wallet, wallet));
+        }
+        Ok(instructions)
+    }
+
+    pub async fn fetch_latest_blockhash(&self, provider: Arc<dyn AlienProvider>) -> Result<Hash, SwapperError> {
+        let call = SolanaRpc::GetLatestBlockhash;
+        let response: JsonRpcResult<ValueResult<LatestBlockhash>> = jsonrpc_call(&call, provider, &self.chain).await?;
+        let blockhash = response.take()?.value.blockhash;
+        Hash::from_str(&blockhash).map_err(|_| SwapperError::NetworkError {
+            msg: format!("invalid blockhash: {}", blockhash),
+        })
+    }
+
     #[allow(unused)]
     pub async fn fetch_fee_tiers(&self, provider: Arc<dyn AlienProvider>) -> Result<Vec<FeeTier>, SwapperError> {
         let call = SolanaRpc::GetProgramAccounts(self.whirlpool_program.to_string(), Self::get_program_filters());
@@ -168,7 +421,12 @@ impl Orca {
         Ok(pools)
     }
 
-    pub async fn fetch_tick_arrays(&self, pool_address: &Pubkey, pool: &Whirlpool, provider: Arc<dyn AlienProvider>) -> Result<Vec<TickArray>, SwapperError> {
+    pub async fn fetch_tick_arrays(
+        &self,
+        pool_address: &Pubkey,
+        pool: &Whirlpool,
+        provider: Arc<dyn AlienProvider>,
+    ) -> Result<Vec<TickArrayAccount>, SwapperError> {
         let start_index = get_tick_array_start_tick_index(pool.tick_current_index, pool.tick_spacing);
         let offset = (pool.tick_spacing as i32) * (TICK_ARRAY_SIZE as i32);
         let tick_arrays = [
@@ -178,28 +436,28 @@ impl Orca {
             start_index + offset,
             start_index + 2 * offset,
         ];
-        println!("tick_arrays: {:?}", tick_arrays);
-        let tick_addresses: Vec<String> = tick_arrays
+        let tick_addresses = tick_arrays
             .iter()
-            .map(|x| get_tick_array_address(pool_address, *x))
-            .filter_map(|x| match x {
-                Some(x) => Some(x.0.to_string()),
-                None => None,
-            })
-            .collect();
-        println!("tick_addresses: {:?}", tick_addresses);
+            .map(|x| get_tick_array_address(pool_address, *x).map(|x| x.0).ok_or(SwapperError::InvalidRoute))
+            .collect::<Result<Vec<_>, _>>()?;
 
-        let call = SolanaRpc::GetMultipleAccounts(tick_addresses);
-        let response: JsonRpcResult<ValueResult<Vec<AccountData>>> = jsonrpc_call(&call, provider, &self.chain).await?;
+        let call = SolanaRpc::GetMultipleAccounts(tick_addresses.iter().map(|x| x.to_string()).collect());
+        let response: JsonRpcResult<ValueResult<Vec<Option<AccountData>>>> = jsonrpc_call(&call, provider, &self.chain).await?;
         let tick_accounts = response.take()?.value;
-        let base64_strs: Vec<String> = tick_accounts.iter().map(|x| x.data[0].clone()).collect();
-        let mut tick_array: Vec<TickArray> = vec![];
-        for base64_str in base64_strs.iter() {
-            let tick = try_borsh_decode::<TickArray>(base64_str).unwrap();
-            tick_array.push(tick);
-        }
 
-        Ok(tick_array)
+        zip(tick_arrays, zip(tick_addresses, tick_accounts))
+            .map(|(start_tick_index, (address, account))| -> Result<TickArrayAccount, SwapperError> {
+                let tick_array = match account {
+                    Some(account) => Some(try_borsh_decode::<TickArray>(&account.data[0]).map_err(|e| SwapperError::ABIError { msg: e.to_string() })?),
+                    None => None,
+                };
+                Ok(TickArrayAccount {
+                    address,
+                    start_tick_index,
+                    tick_array,
+                })
+            })
+            .collect()
     }
 
     pub async fn fetch_token_accounts(
@@ -23,7 +23,6 @@ pub fn get_whirlpool_address(whirlpools_config: &Pubkey, token_mint_a: &Pubkey,
     Pubkey::try_find_program_address(seeds, &whirlpool_program)
 }
 
-#[allow(dead_code)]
 pub fn get_oracle_address(whirlpool: &Pubkey) -> Option<(Pubkey, u8)> {
     let seeds = &[b"oracle", whirlpool.as_ref()];
     let whirlpool_program = Pubkey::from_str(WHIRLPOOL_PROGRAM).ok()?;
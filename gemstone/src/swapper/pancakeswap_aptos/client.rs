@@ -1,12 +1,12 @@
 use crate::{
     network::{AlienHttpMethod, AlienProvider, AlienTarget},
-    swapper::SwapperError,
+    swapper::{GemSwapMode, SwapperError},
 };
 use gem_aptos::model::Resource;
 use num_bigint::BigUint;
 use std::{str::FromStr, sync::Arc};
 
-use super::model::{TokenPairReserve, PANCAKE_SWAP_APTOS_ADDRESS};
+use super::model::{TokenPairReserve, TransactionStatus, PANCAKE_SWAP_APTOS_ADDRESS};
 
 #[derive(Debug)]
 pub struct PancakeSwapAptosClient {
@@ -34,6 +34,20 @@ impl PancakeSwapAptosClient {
         numerator / denominator
     }
 
+    fn calculate_swap_input(reserve_in: BigUint, reserve_out: BigUint, amount_out: BigUint, fee_bps: u32) -> Option<BigUint> {
+        if amount_out >= reserve_out {
+            return None;
+        }
+        let bps_base = BigUint::from(10_000u32);
+        let effective_fee = bps_base.clone() - BigUint::from(fee_bps);
+        let div_ceil = |numerator: BigUint, denominator: BigUint| (numerator + &denominator - BigUint::from(1u32)) / denominator;
+
+        // Inverse of calculate_swap_output, rounded up at each step
+        let effective_amount_in = div_ceil(&reserve_in * &amount_out, &reserve_out - &amount_out);
+
+        Some(div_ceil(effective_amount_in * bps_base, effective_fee))
+    }
+
     fn sort_assets<T: Ord>(&self, asset1: T, asset2: T) -> (T, T) {
         if asset1 <= asset2 {
             (asset1, asset2)
@@ -42,7 +56,16 @@ impl PancakeSwapAptosClient {
         }
     }
 
-    pub async fn get_quote(&self, endpoint: &str, from_asset: &str, to_asset: &str, value: &str, slippage_bps: u32) -> Result<String, SwapperError> {
+    // Returns the output for an exact input or the input for an exact output
+    pub async fn get_quote(
+        &self,
+        endpoint: &str,
+        mode: &GemSwapMode,
+        from_asset: &str,
+        to_asset: &str,
+        value: &str,
+        slippage_bps: u32,
+    ) -> Result<String, SwapperError> {
         let (asset1, asset2) = self.sort_assets(from_asset, to_asset);
         let address = PANCAKE_SWAP_APTOS_ADDRESS;
         let resource = format!("{}::swap::TokenPairReserve<{}, {}>", address, asset1, asset2);
@@ -69,10 +92,46 @@ impl PancakeSwapAptosClient {
 
         let reserve_in = if asset1 == from_asset { reserve_x.clone() } else { reserve_y.clone() };
         let reserve_out = if asset1 == from_asset { reserve_y.clone() } else { reserve_x.clone() };
-        let amount_in = BigUint::from_str(value).unwrap_or_default();
+        let amount = BigUint::from_str(value).unwrap_or_default();
 
-        let value = Self::calculate_swap_output(reserve_in, reserve_out, amount_in, slippage_bps);
+        let value = match mode {
+            GemSwapMode::ExactIn => Self::calculate_swap_output(reserve_in, reserve_out, amount, slippage_bps),
+            GemSwapMode::ExactOut => Self::calculate_swap_input(reserve_in, reserve_out, amount, slippage_bps).ok_or(SwapperError::NoQuoteAvailable)?,
+        };
 
         Ok(value.to_string())
     }
+
+    pub async fn get_transaction_status(&self, endpoint: &str, transaction_hash: &str) -> Result<TransactionStatus, SwapperError> {
+        let target = AlienTarget {
+            url: format!("{}/v1/transactions/by_hash/{}", endpoint, transaction_hash),
+            method: AlienHttpMethod::Get,
+            headers: None,
+            body: None,
+        };
+
+        let data = self
+            .provider
+            .request(target)
+            .await
+            .map_err(|err| SwapperError::NetworkError { msg: err.to_string() })?;
+
+        serde_json::from_slice(&data).map_err(|err| SwapperError::NetworkError { msg: err.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_calculate_swap_input() {
+        let reserve_in = BigUint::from(1_000_000u32);
+        let reserve_out = BigUint::from(2_000_000u32);
+        let amount_in = PancakeSwapAptosClient::calculate_swap_input(reserve_in.clone(), reserve_out.clone(), BigUint::from(10_000u32), 25).unwrap();
+
+        assert_eq!(amount_in, BigUint::from(5039u32));
+        assert!(PancakeSwapAptosClient::calculate_swap_output(reserve_in.clone(), reserve_out.clone(), amount_in, 25) >= BigUint::from(10_000u32));
+        assert!(PancakeSwapAptosClient::calculate_swap_input(reserve_in, reserve_out.clone(), reserve_out, 25).is_none());
+    }
 }
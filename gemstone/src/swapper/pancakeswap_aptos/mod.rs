@@ -3,8 +3,8 @@ use std::sync::Arc;
 mod client;
 mod model;
 use super::{
-    ApprovalType, FetchQuoteData, GemSwapProvider, SwapChainAsset, SwapProvider, SwapProviderData, SwapQuote, SwapQuoteData, SwapQuoteRequest, SwapRoute,
-    SwapperError,
+    ApprovalType, FetchQuoteData, GemSwapMode, GemSwapProvider, SwapChainAsset, SwapProvider, SwapProviderData, SwapQuote, SwapQuoteData, SwapQuoteRequest,
+    SwapRoute, SwapState, SwapStatus, SwapperError,
 };
 
 use crate::network::AlienProvider;
@@ -25,18 +25,23 @@ impl PancakeSwapAptos {
         NATIVE_APTOS_COIN.to_string()
     }
 
-    fn swap_exact_input(&self, assets: Vec<String>, from_value: String, to_value: String) -> TransactionPayload {
+    // Arguments are (amount_in, min_amount_out) for exact input and (amount_out, max_amount_in) for exact output
+    fn swap_payload(&self, mode: &GemSwapMode, assets: Vec<String>, amount: String, threshold: String) -> TransactionPayload {
+        let function = match mode {
+            GemSwapMode::ExactIn => "swap_exact_input",
+            GemSwapMode::ExactOut => "swap_exact_output",
+        };
         let function = match assets.len() {
-            2 => "swap_exact_input",
-            3 => "swap_exact_input_doublehop",
-            4 => "swap_exact_input_triplehop",
+            2 => function.to_string(),
+            3 => format!("{}_doublehop", function),
+            4 => format!("{}_triplehop", function),
             _ => unimplemented!(),
         };
 
         TransactionPayload {
-            function: function.to_string(),
+            function,
             type_arguments: assets,
-            arguments: vec![from_value, to_value],
+            arguments: vec![amount, threshold],
             payload_type: "entry_function_payload".to_string(),
         }
     }
@@ -62,6 +67,7 @@ impl GemSwapProvider for PancakeSwapAptos {
         let quote_value = client
             .get_quote(
                 endpoint.as_str(),
+                &request.mode,
                 from_internal_asset.as_str(),
                 to_internal_asset.as_str(),
                 request.value.to_string().as_str(),
@@ -69,15 +75,21 @@ impl GemSwapProvider for PancakeSwapAptos {
             )
             .await?;
 
+        // quote_value is the output for exact input and the maximum input for exact output
+        let (from_value, to_value) = match request.mode {
+            GemSwapMode::ExactIn => (request.value.clone(), quote_value),
+            GemSwapMode::ExactOut => (quote_value, request.value.clone()),
+        };
+
         let route_data = RouteData {
-            min_value: quote_value.clone(),
+            min_value: to_value.clone(),
             assets: vec![from_internal_asset, to_internal_asset],
         };
         let route_data = serde_json::to_string(&route_data).unwrap();
 
         let quote = SwapQuote {
-            from_value: request.value.clone(),
-            to_value: quote_value.clone(),
+            from_value,
+            to_value,
             data: SwapProviderData {
                 provider: self.provider(),
                 suggested_slippage_bps: None,
@@ -99,11 +111,11 @@ impl GemSwapProvider for PancakeSwapAptos {
         let routes = quote.data.clone().routes;
         let route_data: RouteData = serde_json::from_str(&routes.first().unwrap().route_data).map_err(|_| SwapperError::InvalidRoute)?;
 
-        let payload = self.swap_exact_input(
-            vec![self.to_asset(quote.request.from_asset.clone()), self.to_asset(quote.request.to_asset.clone())],
-            quote.from_value.clone().to_string(),
-            route_data.min_value.clone(),
-        );
+        let assets = vec![self.to_asset(quote.request.from_asset.clone()), self.to_asset(quote.request.to_asset.clone())];
+        let payload = match quote.request.mode {
+            GemSwapMode::ExactIn => self.swap_payload(&quote.request.mode, assets, quote.from_value.clone(), route_data.min_value.clone()),
+            GemSwapMode::ExactOut => self.swap_payload(&quote.request.mode, assets, route_data.min_value.clone(), quote.from_value.clone()),
+        };
 
         let data = SwapQuoteData {
             to: PANCAKE_SWAP_APTOS_ADDRESS.to_string(),
@@ -113,7 +125,16 @@ impl GemSwapProvider for PancakeSwapAptos {
         Ok(data)
     }
 
-    async fn get_transaction_status(&self, _chain: Chain, _transaction_hash: &str, _provider: Arc<dyn AlienProvider>) -> Result<bool, SwapperError> {
-        unimplemented!()
+    async fn get_transaction_status(&self, _chain: Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<SwapStatus, SwapperError> {
+        let endpoint: String = provider.get_endpoint(Chain::Aptos).map_err(SwapperError::from)?;
+        let client = PancakeSwapAptosClient::new(provider);
+
+        let status = client.get_transaction_status(&endpoint, transaction_hash).await?;
+        let state = match status.success {
+            Some(true) => SwapState::Completed,
+            Some(false) => SwapState::Failed,
+            None => SwapState::Pending,
+        };
+        Ok(SwapStatus::new(state))
     }
 }
@@ -8,6 +8,14 @@ pub struct TokenPairReserve {
     pub reserve_y: String,
 }
 
+// success is not set while the transaction is pending
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionStatus {
+    #[serde(rename = "type")]
+    pub transaction_type: String,
+    pub success: Option<bool>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RouteData {
     pub assets: Vec<String>,
@@ -0,0 +1,207 @@
+use super::{GemSwapMode, SwapProvider, SwapQuote};
+use alloy_core::primitives::U256;
+use std::str::FromStr;
+
+fn parse_value(value: &str) -> U256 {
+    U256::from_str(value).unwrap_or_default()
+}
+
+// None when a route has no gas estimate or the quote pays gas on a different chain than its output
+fn gas_cost(quote: &SwapQuote, gas_price: &U256) -> Option<U256> {
+    if quote.request.from_asset.chain != quote.request.to_asset.chain || quote.data.routes.is_empty() {
+        return None;
+    }
+    let gas = quote
+        .data
+        .routes
+        .iter()
+        .map(|x| x.gas_estimate.as_deref().map(parse_value))
+        .try_fold(U256::ZERO, |total, x| x.map(|x| total.saturating_add(x)))?;
+    Some(gas.saturating_mul(*gas_price))
+}
+
+// Gas cost in output asset units. Converted with the quote rate when swapping from the native asset,
+// none when neither side is native as there is no price to convert with.
+pub fn gas_cost_in_output(quote: &SwapQuote, gas_price: &U256) -> Option<U256> {
+    let cost = gas_cost(quote, gas_price)?;
+    if quote.request.to_asset.is_native() {
+        return Some(cost);
+    }
+    let from_value = parse_value(&quote.from_value);
+    if quote.request.from_asset.is_native() && !from_value.is_zero() {
+        return Some(cost.saturating_mul(parse_value(&quote.to_value)) / from_value);
+    }
+    None
+}
+
+// Gas cost in input asset units, the exact output counterpart of gas_cost_in_output
+pub fn gas_cost_in_input(quote: &SwapQuote, gas_price: &U256) -> Option<U256> {
+    let cost = gas_cost(quote, gas_price)?;
+    if quote.request.from_asset.is_native() {
+        return Some(cost);
+    }
+    let to_value = parse_value(&quote.to_value);
+    if quote.request.to_asset.is_native() && !to_value.is_zero() {
+        return Some(cost.saturating_mul(parse_value(&quote.from_value)) / to_value);
+    }
+    None
+}
+
+pub fn net_output(quote: &SwapQuote, gas_price: &U256) -> Option<U256> {
+    Some(parse_value(&quote.to_value).saturating_sub(gas_cost_in_output(quote, gas_price)?))
+}
+
+pub fn net_input(quote: &SwapQuote, gas_price: &U256) -> Option<U256> {
+    Some(parse_value(&quote.from_value).saturating_add(gas_cost_in_input(quote, gas_price)?))
+}
+
+// Preferred providers first in their given order, then by output net of gas for exact input
+// and by input including gas for exact output. Gas is only accounted for when it converts
+// for every quote, otherwise quotes are compared by their raw amounts.
+pub fn rank_quotes(quotes: Vec<SwapQuote>, preferred_providers: &[SwapProvider], gas_price: Option<U256>) -> Vec<SwapQuote> {
+    let preference = |quote: &SwapQuote| preferred_providers.iter().position(|x| *x == quote.data.provider).unwrap_or(usize::MAX);
+    let values = |quote: &SwapQuote| (parse_value(&quote.to_value), parse_value(&quote.from_value));
+
+    let net_values = gas_price.and_then(|gas_price| {
+        quotes
+            .iter()
+            .map(|x| Some((net_output(x, &gas_price)?, net_input(x, &gas_price)?)))
+            .collect::<Option<Vec<_>>>()
+    });
+    let values = net_values.unwrap_or_else(|| quotes.iter().map(values).collect());
+
+    let mut quotes = values.into_iter().zip(quotes).collect::<Vec<_>>();
+    quotes.sort_by(|((a_output, a_input), a), ((b_output, b_input), b)| {
+        let value = match a.request.mode {
+            GemSwapMode::ExactIn => b_output.cmp(a_output),
+            GemSwapMode::ExactOut => a_input.cmp(b_input),
+        };
+        preference(a).cmp(&preference(b)).then(value)
+    });
+    quotes.into_iter().map(|(_, x)| x).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::swapper::{ApprovalType, GemSwapOptions, SwapProviderData, SwapQuoteRequest, SwapRoute};
+    use primitives::{AssetId, Chain};
+
+    fn quote(provider: SwapProvider, to_value: &str, gas_estimate: Option<&str>) -> SwapQuote {
+        let from_asset = AssetId::from_chain(Chain::Ethereum);
+        let to_asset = AssetId::from_token(Chain::Ethereum, "0xdAC17F958D2ee523a2206206994597C13D831ec7");
+        SwapQuote {
+            from_value: "1000000000000000000".into(),
+            to_value: to_value.into(),
+            data: SwapProviderData {
+                provider,
+                suggested_slippage_bps: None,
+                routes: vec![SwapRoute {
+                    input: from_asset.clone(),
+                    output: to_asset.clone(),
+                    route_data: "".into(),
+                    gas_estimate: gas_estimate.map(|x| x.into()),
+                }],
+            },
+            approval: ApprovalType::None,
+            request: SwapQuoteRequest {
+                from_asset,
+                to_asset,
+                wallet_address: "".into(),
+                destination_address: "".into(),
+                value: "1000000000000000000".into(),
+                mode: GemSwapMode::ExactIn,
+                options: GemSwapOptions::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_gas_cost_in_output() {
+        // 1 ETH -> 3000 USDT, 100k gas at 10 gwei = 0.001 ETH = 3 USDT
+        let quote = quote(SwapProvider::UniswapV3, "3000000000", Some("100000"));
+
+        assert_eq!(gas_cost_in_output(&quote, &U256::from(10_000_000_000u64)), Some(U256::from(3_000_000u64)));
+        assert_eq!(net_output(&quote, &U256::from(10_000_000_000u64)), Some(U256::from(2_997_000_000u64)));
+    }
+
+    #[test]
+    fn test_gas_cost_in_output_not_convertible() {
+        let gas_price = U256::from(10_000_000_000u64);
+
+        let mut token_to_token = quote(SwapProvider::UniswapV3, "3000000000", Some("100000"));
+        token_to_token.request.from_asset = AssetId::from_token(Chain::Ethereum, "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+        assert_eq!(gas_cost_in_output(&token_to_token, &gas_price), None);
+
+        let mut cross_chain = quote(SwapProvider::Thorchain, "3000000000", Some("100000"));
+        cross_chain.request.to_asset = AssetId::from_chain(Chain::Bitcoin);
+        assert_eq!(gas_cost_in_output(&cross_chain, &gas_price), None);
+
+        let no_estimate = quote(SwapProvider::Thorchain, "3000000000", None);
+        assert_eq!(gas_cost_in_output(&no_estimate, &gas_price), None);
+    }
+
+    #[test]
+    fn test_rank_quotes() {
+        let quotes = vec![
+            quote(SwapProvider::Thorchain, "2990000000", Some("200000")),
+            quote(SwapProvider::UniswapV3, "3000000000", Some("1000000")),
+            quote(SwapProvider::PancakeSwapV3, "2995000000", Some("100000")),
+        ];
+        let gas_price = Some(U256::from(10_000_000_000u64));
+
+        let ranked = rank_quotes(quotes.clone(), &[], gas_price);
+        assert_eq!(
+            ranked.iter().map(|x| x.data.provider.clone()).collect::<Vec<_>>(),
+            vec![SwapProvider::PancakeSwapV3, SwapProvider::Thorchain, SwapProvider::UniswapV3]
+        );
+
+        let ranked = rank_quotes(quotes, &[SwapProvider::Thorchain], gas_price);
+        assert_eq!(ranked[0].data.provider, SwapProvider::Thorchain);
+        assert_eq!(ranked[1].data.provider, SwapProvider::PancakeSwapV3);
+    }
+
+    #[test]
+    fn test_rank_quotes_without_gas_estimate() {
+        // quote without a gas estimate can't be adjusted, so raw outputs are compared
+        let quotes = vec![
+            quote(SwapProvider::Thorchain, "2990000000", None),
+            quote(SwapProvider::UniswapV3, "3000000000", Some("1000000")),
+            quote(SwapProvider::PancakeSwapV3, "2995000000", Some("100000")),
+        ];
+
+        let ranked = rank_quotes(quotes, &[], Some(U256::from(10_000_000_000u64)));
+        assert_eq!(
+            ranked.iter().map(|x| x.data.provider.clone()).collect::<Vec<_>>(),
+            vec![SwapProvider::UniswapV3, SwapProvider::PancakeSwapV3, SwapProvider::Thorchain]
+        );
+    }
+
+    #[test]
+    fn test_rank_quotes_exact_out() {
+        // ETH -> 3000 USDT, 100k gas at 10 gwei = 0.001 ETH
+        let exact_out = |provider: SwapProvider, from_value: &str, gas_estimate: Option<&str>| {
+            let mut quote = quote(provider, "3000000000", gas_estimate);
+            quote.from_value = from_value.into();
+            quote.request.mode = GemSwapMode::ExactOut;
+            quote
+        };
+        let quotes = vec![
+            exact_out(SwapProvider::Thorchain, "1002000000000000000", Some("100000")),
+            exact_out(SwapProvider::UniswapV3, "1000000000000000000", Some("100000")),
+            exact_out(SwapProvider::PancakeSwapV3, "1000500000000000000", Some("1000000")),
+        ];
+        let gas_price = Some(U256::from(10_000_000_000u64));
+
+        assert_eq!(
+            net_input(&quotes[1], &U256::from(10_000_000_000u64)),
+            Some(U256::from(1_001_000_000_000_000_000u64))
+        );
+
+        let ranked = rank_quotes(quotes, &[], gas_price);
+        assert_eq!(
+            ranked.iter().map(|x| x.data.provider.clone()).collect::<Vec<_>>(),
+            vec![SwapProvider::UniswapV3, SwapProvider::Thorchain, SwapProvider::PancakeSwapV3]
+        );
+    }
+}
@@ -4,6 +4,11 @@ pub fn apply_slippage_in_bp(amount: &U256, bps: u32) -> U256 {
     amount * U256::from(10000 - bps) / U256::from(10000)
 }
 
+// Maximum amount in for exact output swaps
+pub fn apply_slippage_in_bp_max(amount: &U256, bps: u32) -> U256 {
+    amount * U256::from(10000 + bps) / U256::from(10000)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -17,5 +22,6 @@ mod tests {
         let result = apply_slippage_in_bp(&amount, 300);
 
         assert_eq!(result, expected);
+        assert_eq!(apply_slippage_in_bp_max(&amount, 300), U256::from(103));
     }
 }
@@ -1,3 +1,4 @@
+use gem_evm::address::EthereumAddress;
 use primitives::{Asset, AssetId};
 
 use crate::swapper::asset::{
@@ -40,6 +41,19 @@ impl THORChainAsset {
         }
     }
 
+    // THORNode asset notation, e.g. BTC.BTC or ETH.USDT-0XDAC17F958D2EE523A2206206994597C13D831EC7
+    pub fn from_asset_name(name: &str) -> Option<THORChainAsset> {
+        let (chain, symbol) = name.split_once('.')?;
+        let chain = THORChainName::from_long_name(chain)?;
+        match symbol.split_once('-') {
+            Some((_, contract)) => {
+                let token_id = EthereumAddress::parse(&contract.to_lowercase())?.to_checksum();
+                THORChainAsset::from(chain, &token_id)
+            }
+            None => THORChainAsset::from_asset_id(chain.chain().as_asset_id()),
+        }
+    }
+
     pub fn thorchain_asset_token(chain: THORChainName, asset: Asset) -> THORChainAsset {
         THORChainAsset {
             symbol: asset.symbol,
@@ -109,6 +123,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_asset_name() {
+        let asset = THORChainAsset::from_asset_name("ETH.USDT-0XDAC17F958D2EE523A2206206994597C13D831EC7").unwrap();
+        assert_eq!(asset.token_id, Some("0xdAC17F958D2ee523a2206206994597C13D831ec7".to_string()));
+        assert_eq!(asset.decimals, 6);
+
+        let asset = THORChainAsset::from_asset_name("BTC.BTC").unwrap();
+        assert_eq!(asset.chain.chain(), Chain::Bitcoin);
+        assert_eq!(asset.decimals, 8);
+
+        assert!(THORChainAsset::from_asset_name("SOL.SOL").is_none());
+    }
+
     #[test]
     fn test_thorchain_asset_name() {
         let asset_with_token = THORChainAsset {
@@ -53,6 +53,21 @@ impl THORChainName {
         }
     }
 
+    pub fn from_long_name(name: &str) -> Option<THORChainName> {
+        [
+            THORChainName::Doge,
+            THORChainName::Thorchain,
+            THORChainName::Ethereum,
+            THORChainName::Cosmos,
+            THORChainName::Bitcoin,
+            THORChainName::Litecoin,
+            THORChainName::SmartChain,
+            THORChainName::AvalancheC,
+        ]
+        .into_iter()
+        .find(|x| x.long_name() == name)
+    }
+
     pub fn from_chain(chain: &Chain) -> Option<THORChainName> {
         match chain {
             Chain::Thorchain => Some(THORChainName::Thorchain),
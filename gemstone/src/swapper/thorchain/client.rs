@@ -4,7 +4,7 @@ use crate::swapper::thorchain::model::{QuoteSwapRequest, QuoteSwapResponse};
 use std::sync::Arc;
 
 use super::asset::THORChainAsset;
-use super::model::Transaction;
+use super::model::TransactionStatus;
 
 #[derive(Debug)]
 pub struct ThorChainSwapClient {
@@ -57,9 +57,9 @@ impl ThorChainSwapClient {
         Ok(result)
     }
 
-    pub async fn get_transaction_status(&self, endpoint: &str, transaction_hash: &str) -> Result<Transaction, SwapperError> {
+    pub async fn get_transaction_status(&self, endpoint: &str, transaction_hash: &str) -> Result<TransactionStatus, SwapperError> {
         let target = AlienTarget {
-            url: format!("{}/thorchain/tx/{}", endpoint, transaction_hash),
+            url: format!("{}/thorchain/tx/status/{}", endpoint, transaction_hash),
             method: AlienHttpMethod::Get,
             headers: None,
             body: None,
@@ -71,7 +71,7 @@ impl ThorChainSwapClient {
             .await
             .map_err(|err| SwapperError::NetworkError { msg: err.to_string() })?;
 
-        let result: Transaction = serde_json::from_slice(&data).map_err(|err| SwapperError::NetworkError { msg: err.to_string() })?;
+        let result: TransactionStatus = serde_json::from_slice(&data).map_err(|err| SwapperError::NetworkError { msg: err.to_string() })?;
 
         Ok(result)
     }
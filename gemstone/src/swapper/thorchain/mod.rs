@@ -4,6 +4,16 @@ mod client;
 mod model;
 mod provider;
 
+use super::{approval::CheckApprovalType, SwapQuoteRequest, SwapState, SwapStatus, SwapperError};
+use alloy_core::{
+    hex::encode_prefixed as HexEncode,
+    primitives::{Address, U256},
+    sol_types::SolCall,
+};
+use asset::THORChainAsset;
+use chain::THORChainName;
+use gem_evm::thorchain::RouterInterface;
+use model::{TransactionStage, TransactionStatus};
 use num_bigint::BigInt;
 use primitives::Chain;
 use std::str::FromStr;
@@ -14,6 +24,9 @@ pub struct ThorChain {}
 const QUOTE_MINIMUM: i64 = 0;
 const QUOTE_INTERVAL: i64 = 1;
 const QUOTE_QUANTITY: i64 = 0;
+// seconds the router accepts a token deposit after the quote data is built
+const DEPOSIT_EXPIRY: u64 = 15 * 60;
+const REFUND_MEMO_PREFIX: &str = "REFUND";
 
 impl ThorChain {
     fn data(&self, chain: Chain, memo: String) -> String {
@@ -23,6 +36,27 @@ impl ThorChain {
         }
     }
 
+    // tokens are pulled from the wallet by the router on deposit
+    fn approval_check_type(&self, request: &SwapQuoteRequest, router: Option<String>) -> Result<Option<CheckApprovalType>, SwapperError> {
+        let Some(token_id) = &request.from_asset.token_id else {
+            return Ok(None);
+        };
+        let router = router.ok_or(SwapperError::InvalidRoute)?;
+        let amount = U256::from_str(&request.value).map_err(|_| SwapperError::InvalidAmount)?;
+        Ok(Some(CheckApprovalType::ERC20(request.wallet_address.clone(), token_id.clone(), router, amount)))
+    }
+
+    fn router_deposit_data(&self, vault: &str, token_id: &str, amount: &str, memo: String, expiration: u64) -> Result<String, SwapperError> {
+        let call = RouterInterface::depositWithExpiryCall {
+            vault: Address::from_str(vault)?,
+            asset: Address::from_str(token_id)?,
+            amount: U256::from_str(amount).map_err(|_| SwapperError::InvalidAmount)?,
+            memo,
+            expiration: U256::from(expiration),
+        };
+        Ok(HexEncode(call.abi_encode()))
+    }
+
     fn value_from(&self, value: String, decimals: i32) -> BigInt {
         let decimals = decimals - 8;
         if decimals > 0 {
@@ -32,6 +66,53 @@ impl ThorChain {
         }
     }
 
+    fn swap_status(&self, status: &TransactionStatus) -> SwapStatus {
+        let is_completed = |stage: &Option<TransactionStage>| stage.as_ref().is_some_and(|x| x.completed);
+        let outbound = status.out_txs.first();
+        let is_refund = status.planned_out_txs.iter().any(|x| x.refund)
+            || outbound
+                .and_then(|x| x.memo.as_deref())
+                .is_some_and(|x| x.to_uppercase().starts_with(REFUND_MEMO_PREFIX));
+
+        // outbound_signed is not reported for native THORChain outbounds, out_txs is set instead
+        let state = if is_completed(&status.stages.outbound_signed) || outbound.is_some() {
+            if is_refund {
+                SwapState::Refunded
+            } else {
+                SwapState::Completed
+            }
+        } else if is_completed(&status.stages.swap_finalised) {
+            SwapState::OutboundPending
+        } else if status.stages.inbound_observed.completed {
+            SwapState::InboundObserved
+        } else {
+            SwapState::Pending
+        };
+
+        let outbound_chain = outbound
+            .map(|x| x.chain.as_str())
+            .or(status.planned_out_txs.first().map(|x| x.chain.as_str()))
+            .and_then(THORChainName::from_long_name)
+            .map(|x| x.chain());
+        let received_amount = outbound.and_then(|x| x.coins.first()).and_then(|coin| {
+            let asset = THORChainAsset::from_asset_name(&coin.asset)?;
+            Some(self.value_to(coin.amount.clone(), asset.decimals as i32).to_string())
+        });
+
+        SwapStatus {
+            state,
+            outbound_chain,
+            outbound_transaction_hash: outbound.map(|x| x.id.clone()),
+            // the refund outbound memo only references the inbound hash, the reason comes from the reported error
+            refund_reason: if is_refund {
+                status.error.clone().or(status.tx.as_ref().and_then(|x| x.error.clone()))
+            } else {
+                None
+            },
+            received_amount,
+        }
+    }
+
     fn value_to(&self, value: String, decimals: i32) -> BigInt {
         let decimals = decimals - 8;
         if decimals > 0 {
@@ -45,6 +126,8 @@ impl ThorChain {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::swapper::{GemSwapMode, GemSwapOptions};
+    use primitives::AssetId;
 
     #[test]
     fn test_value_from() {
@@ -65,6 +148,128 @@ mod tests {
         assert_eq!(result, BigInt::from(1000000000));
     }
 
+    #[test]
+    fn test_swap_status() {
+        let thorchain = ThorChain::default();
+        let status = |value: serde_json::Value| -> TransactionStatus { serde_json::from_value(value).unwrap() };
+
+        let pending = status(serde_json::json!({
+            "stages": {
+                "inbound_observed": {"completed": true},
+                "swap_finalised": {"completed": false}
+            }
+        }));
+        assert_eq!(thorchain.swap_status(&pending).state, SwapState::InboundObserved);
+
+        let outbound_pending = status(serde_json::json!({
+            "planned_out_txs": [{"chain": "BTC", "to_address": "bc1q", "coin": {"asset": "BTC.BTC", "amount": "100000"}, "refund": false}],
+            "stages": {
+                "inbound_observed": {"completed": true},
+                "swap_finalised": {"completed": true},
+                "outbound_signed": {"completed": false}
+            }
+        }));
+        let result = thorchain.swap_status(&outbound_pending);
+        assert_eq!(result.state, SwapState::OutboundPending);
+        assert_eq!(result.outbound_chain, Some(Chain::Bitcoin));
+
+        let completed = status(serde_json::json!({
+            "out_txs": [{
+                "id": "5F1C8A5FB94FBAF0E74F0CC5B0D6DBE1DCD6FF1A6B8CC6D0DEF9ABE4E30D8D29",
+                "chain": "ETH",
+                "coins": [{"asset": "ETH.USDT-0XDAC17F958D2EE523A2206206994597C13D831EC7", "amount": "1500000000"}],
+                "memo": "OUT:0E1A1F2B"
+            }],
+            "stages": {
+                "inbound_observed": {"completed": true},
+                "swap_finalised": {"completed": true},
+                "outbound_signed": {"completed": true}
+            }
+        }));
+        let result = thorchain.swap_status(&completed);
+        assert_eq!(result.state, SwapState::Completed);
+        assert_eq!(result.outbound_chain, Some(Chain::Ethereum));
+        assert_eq!(result.received_amount, Some("15000000".into()));
+        assert_eq!(result.refund_reason, None);
+
+        let refunded = status(serde_json::json!({
+            "out_txs": [{"id": "AB", "chain": "BTC", "coins": [{"asset": "BTC.BTC", "amount": "99000"}], "memo": "REFUND:0E1A1F2B"}],
+            "stages": {
+                "inbound_observed": {"completed": true},
+                "outbound_signed": {"completed": true}
+            }
+        }));
+        let result = thorchain.swap_status(&refunded);
+        assert_eq!(result.state, SwapState::Refunded);
+        assert_eq!(result.received_amount, Some("99000".into()));
+        assert_eq!(result.refund_reason, None);
+
+        let refunded_with_error = status(serde_json::json!({
+            "tx": {"error": "emit asset 95000 less than price limit 98000"},
+            "out_txs": [{"id": "AB", "chain": "BTC", "coins": [{"asset": "BTC.BTC", "amount": "99000"}], "memo": "REFUND:0E1A1F2B"}],
+            "stages": {
+                "inbound_observed": {"completed": true},
+                "outbound_signed": {"completed": true}
+            }
+        }));
+        let result = thorchain.swap_status(&refunded_with_error);
+        assert_eq!(result.state, SwapState::Refunded);
+        assert_eq!(result.refund_reason, Some("emit asset 95000 less than price limit 98000".into()));
+    }
+
+    #[test]
+    fn test_router_deposit_data() {
+        let thorchain = ThorChain::default();
+        let vault = "0x1d1d2b0b8d5f7c1b7c7d3e6f0a5a5b8e9c0d1e2f";
+        let token_id = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+        let memo = "=:BTC.BTC:bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh:0/1/0".to_string();
+
+        let data = thorchain.router_deposit_data(vault, token_id, "1000000", memo.clone(), 1733000000).unwrap();
+        assert!(data.starts_with("0x"));
+
+        let bytes = hex::decode(data.trim_start_matches("0x")).unwrap();
+        assert_eq!(bytes[..4], RouterInterface::depositWithExpiryCall::SELECTOR);
+
+        let call = RouterInterface::depositWithExpiryCall::abi_decode(&bytes, true).unwrap();
+        assert_eq!(call.vault, Address::from_str(vault).unwrap());
+        assert_eq!(call.asset, Address::from_str(token_id).unwrap());
+        assert_eq!(call.amount, U256::from(1000000));
+        assert_eq!(call.memo, memo);
+        assert_eq!(call.expiration, U256::from(1733000000));
+
+        assert!(thorchain.router_deposit_data(vault, token_id, "abc", memo, 1733000000).is_err());
+    }
+
+    #[test]
+    fn test_approval_check_type() {
+        let thorchain = ThorChain::default();
+        let router = "0xD37BbE5744D730a1d98d8DC97c42F0Ca46aD7146";
+        let token_id = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+        let mut request = SwapQuoteRequest {
+            from_asset: AssetId::from_token(Chain::Ethereum, token_id),
+            to_asset: AssetId::from_chain(Chain::Bitcoin),
+            wallet_address: "0x514BCb1F9AAbb904e6106Bd1052B66d2706dBbb7".into(),
+            destination_address: "bc1qxy2kgdygjrsqtzq2n0yrf2493p83kkfjhx0wlh".into(),
+            value: "1000000".into(),
+            mode: GemSwapMode::ExactIn,
+            options: GemSwapOptions::default(),
+        };
+
+        match thorchain.approval_check_type(&request, Some(router.into())).unwrap() {
+            Some(CheckApprovalType::ERC20(owner, token, spender, amount)) => {
+                assert_eq!(owner, request.wallet_address);
+                assert_eq!(token, token_id);
+                assert_eq!(spender, router);
+                assert_eq!(amount, U256::from(1000000));
+            }
+            other => panic!("unexpected approval check: {:?}", other),
+        }
+        assert!(matches!(thorchain.approval_check_type(&request, None), Err(SwapperError::InvalidRoute)));
+
+        request.from_asset = AssetId::from_chain(Chain::Ethereum);
+        assert!(thorchain.approval_check_type(&request, None).unwrap().is_none());
+    }
+
     #[test]
     fn test_value_to() {
         let thorchain = ThorChain::default();
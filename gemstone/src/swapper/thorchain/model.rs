@@ -15,18 +15,67 @@ pub struct QuoteSwapRequest {
 pub struct QuoteSwapResponse {
     pub expected_amount_out: String,
     pub inbound_address: Option<String>,
+    // set for EVM chains, tokens are deposited through the router
+    pub router: Option<String>,
     pub fees: QuoteFees,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteData {
+    pub inbound_address: String,
+    pub router: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct QuoteFees {}
 
+// https://thornode.ninerealms.com/thorchain/doc, /thorchain/tx/status/{hash}
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Transaction {
-    pub observed_tx: TransactionObserved,
+pub struct TransactionStatus {
+    #[serde(default)]
+    pub out_txs: Vec<TransactionOutbound>,
+    #[serde(default)]
+    pub planned_out_txs: Vec<TransactionPlannedOutbound>,
+    pub stages: TransactionStages,
+    // observed inbound transaction
+    pub tx: Option<TransactionObserved>,
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionObserved {
-    pub status: String, // done
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionOutbound {
+    pub id: String,
+    pub chain: String,
+    pub coins: Vec<Coin>,
+    pub memo: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionPlannedOutbound {
+    pub chain: String,
+    pub coin: Coin,
+    pub refund: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Coin {
+    pub asset: String,
+    pub amount: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionStages {
+    pub inbound_observed: TransactionStage,
+    pub swap_finalised: Option<TransactionStage>,
+    pub outbound_signed: Option<TransactionStage>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionStage {
+    pub completed: bool,
 }
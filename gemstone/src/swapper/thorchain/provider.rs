@@ -1,13 +1,19 @@
 use crate::network::AlienProvider;
+use crate::swapper::approval::check_approval;
 use crate::swapper::asset::{AVALANCHE_USDC, AVALANCHE_USDT, ETHEREUM_DAI, ETHEREUM_USDC, ETHEREUM_USDT, ETHEREUM_WBTC, SMARTCHAIN_USDC, SMARTCHAIN_USDT};
 use crate::swapper::thorchain::client::ThorChainSwapClient;
 use crate::swapper::{GemSwapProvider, SwapChainAsset};
 use async_trait::async_trait;
 use primitives::Chain;
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
 
-use super::{asset::THORChainAsset, chain::THORChainName, ThorChain, QUOTE_INTERVAL, QUOTE_MINIMUM, QUOTE_QUANTITY};
-use crate::swapper::{ApprovalType, FetchQuoteData, SwapProvider, SwapProviderData, SwapQuote, SwapQuoteData, SwapQuoteRequest, SwapRoute, SwapperError};
+use super::{asset::THORChainAsset, chain::THORChainName, model::RouteData, ThorChain, DEPOSIT_EXPIRY, QUOTE_INTERVAL, QUOTE_MINIMUM, QUOTE_QUANTITY};
+use crate::swapper::{
+    ApprovalType, FetchQuoteData, GemSwapMode, SwapProvider, SwapProviderData, SwapQuote, SwapQuoteData, SwapQuoteRequest, SwapRoute, SwapStatus, SwapperError,
+};
 
 #[async_trait]
 impl GemSwapProvider for ThorChain {
@@ -39,15 +45,13 @@ impl GemSwapProvider for ThorChain {
     }
 
     async fn fetch_quote(&self, request: &SwapQuoteRequest, provider: Arc<dyn AlienProvider>) -> Result<SwapQuote, SwapperError> {
+        if request.mode != GemSwapMode::ExactIn {
+            return Err(SwapperError::NotSupportedMode);
+        }
         let endpoint = provider
             .get_endpoint(Chain::Thorchain)
             .map_err(|err| SwapperError::NetworkError { msg: err.to_string() })?;
-        let client = ThorChainSwapClient::new(provider);
-
-        //TODO: currently do not support from_asset_id(). As it requires approval for thorchain router
-        if request.clone().from_asset.token_id.is_some() {
-            return Err(SwapperError::NotSupportedAsset);
-        }
+        let client = ThorChainSwapClient::new(provider.clone());
 
         let from_asset = THORChainAsset::from_asset_id(request.clone().from_asset).ok_or(SwapperError::NotSupportedAsset)?;
         let to_asset = THORChainAsset::from_asset_id(request.clone().to_asset).ok_or(SwapperError::NotSupportedAsset)?;
@@ -69,6 +73,15 @@ impl GemSwapProvider for ThorChain {
             .await?;
 
         let to_value = self.value_to(quote.expected_amount_out, to_asset.decimals as i32);
+        let route_data = RouteData {
+            inbound_address: quote.inbound_address.unwrap_or_default(),
+            router: quote.router,
+        };
+
+        let approval = match self.approval_check_type(request, route_data.router.clone())? {
+            Some(check_type) => check_approval(check_type, provider, &request.from_asset.chain).await?,
+            None => ApprovalType::None,
+        };
 
         let quote = SwapQuote {
             from_value: request.clone().value,
@@ -78,12 +91,12 @@ impl GemSwapProvider for ThorChain {
                 routes: vec![SwapRoute {
                     input: request.from_asset.clone(),
                     output: request.to_asset.clone(),
-                    route_data: quote.inbound_address.unwrap_or_default(),
+                    route_data: serde_json::to_string(&route_data).unwrap_or_default(),
                     gas_estimate: None,
                 }],
                 suggested_slippage_bps: None,
             },
-            approval: ApprovalType::None,
+            approval,
             request: request.clone(),
         };
 
@@ -105,26 +118,38 @@ impl GemSwapProvider for ThorChain {
             )
             .unwrap();
 
-        let to = quote.data.routes.first().unwrap().route_data.clone();
-        let data: String = self.data(quote.request.from_asset.clone().chain, memo);
-
-        let data = SwapQuoteData {
-            to,
-            value: quote.request.value.clone(),
-            data,
+        let route = quote.data.routes.first().ok_or(SwapperError::InvalidRoute)?;
+        let route_data: RouteData = serde_json::from_str(&route.route_data).map_err(|_| SwapperError::InvalidRoute)?;
+
+        let data = match &quote.request.from_asset.token_id {
+            Some(token_id) => {
+                let router = route_data.router.ok_or(SwapperError::InvalidRoute)?;
+                let expiration = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_secs() + DEPOSIT_EXPIRY;
+                SwapQuoteData {
+                    to: router,
+                    value: "0".to_string(),
+                    data: self.router_deposit_data(&route_data.inbound_address, token_id, &quote.request.value, memo, expiration)?,
+                }
+            }
+            None => SwapQuoteData {
+                to: route_data.inbound_address,
+                value: quote.request.value.clone(),
+                data: self.data(quote.request.from_asset.chain, memo),
+            },
         };
 
         Ok(data)
     }
 
-    async fn get_transaction_status(&self, _chain: Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<bool, SwapperError> {
+    async fn get_transaction_status(&self, _chain: Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<SwapStatus, SwapperError> {
         let endpoint = provider
             .get_endpoint(Chain::Thorchain)
             .map_err(|err| SwapperError::NetworkError { msg: err.to_string() })?;
         let client = ThorChainSwapClient::new(provider);
 
-        let status = client.get_transaction_status(&endpoint, transaction_hash).await?;
+        // THORNode expects hashes without the 0x prefix
+        let status = client.get_transaction_status(&endpoint, transaction_hash.trim_start_matches("0x")).await?;
 
-        Ok(status.observed_tx.status == "done")
+        Ok(self.swap_status(&status))
     }
 }
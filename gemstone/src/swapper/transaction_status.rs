@@ -0,0 +1,75 @@
+use super::{SwapState, SwapStatus, SwapperError};
+use crate::network::{jsonrpc::jsonrpc_call, AlienProvider, JsonRpcResult};
+use gem_evm::jsonrpc::{EthereumRpc, TransactionReceipt};
+use gem_solana::jsonrpc::{SignatureStatus, SolanaRpc, ValueResult};
+use primitives::Chain;
+use std::sync::Arc;
+
+const RECEIPT_STATUS_SUCCESS: &str = "0x1";
+
+fn receipt_state(receipt: Option<&TransactionReceipt>) -> SwapState {
+    match receipt {
+        None => SwapState::Pending,
+        Some(receipt) if receipt.status == RECEIPT_STATUS_SUCCESS => SwapState::Completed,
+        Some(_) => SwapState::Failed,
+    }
+}
+
+fn signature_state(status: Option<&SignatureStatus>) -> SwapState {
+    match status {
+        None => SwapState::Pending,
+        Some(status) if status.err.is_some() => SwapState::Failed,
+        Some(status) => match status.confirmation_status.as_deref() {
+            Some("confirmed") | Some("finalized") => SwapState::Completed,
+            _ => SwapState::Pending,
+        },
+    }
+}
+
+// Receipt is null until the transaction is included in a block
+pub async fn fetch_evm_status(chain: &Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<SwapStatus, SwapperError> {
+    let call = EthereumRpc::GetTransactionReceipt(transaction_hash.to_string());
+    // decoded as a value, a missing optional result would also match an error response
+    let response: JsonRpcResult<serde_json::Value> = jsonrpc_call(&call, provider, chain).await?;
+    let receipt: Option<TransactionReceipt> = serde_json::from_value(response.take()?).map_err(|err| SwapperError::NetworkError { msg: err.to_string() })?;
+
+    Ok(SwapStatus::new(receipt_state(receipt.as_ref())))
+}
+
+pub async fn fetch_solana_status(transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<SwapStatus, SwapperError> {
+    let call = SolanaRpc::GetSignatureStatuses(vec![transaction_hash.to_string()]);
+    let response: JsonRpcResult<ValueResult<Vec<Option<SignatureStatus>>>> = jsonrpc_call(&call, provider, &Chain::Solana).await?;
+    let statuses = response.take()?.value;
+
+    Ok(SwapStatus::new(signature_state(statuses.first().and_then(|x| x.as_ref()))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_receipt_state() {
+        let receipt = |status: &str| TransactionReceipt { status: status.into() };
+
+        assert_eq!(receipt_state(None), SwapState::Pending);
+        assert_eq!(receipt_state(Some(&receipt("0x1"))), SwapState::Completed);
+        assert_eq!(receipt_state(Some(&receipt("0x0"))), SwapState::Failed);
+    }
+
+    #[test]
+    fn test_signature_state() {
+        let status: Vec<Option<SignatureStatus>> = serde_json::from_value(serde_json::json!([
+            {"slot": 1, "confirmations": null, "err": null, "confirmationStatus": "finalized"},
+            {"slot": 1, "confirmations": 0, "err": {"InstructionError": [2, {"Custom": 6001}]}, "confirmationStatus": "confirmed"},
+            {"slot": 1, "confirmations": 0, "err": null, "confirmationStatus": "processed"},
+            null
+        ]))
+        .unwrap();
+
+        assert_eq!(signature_state(status[0].as_ref()), SwapState::Completed);
+        assert_eq!(signature_state(status[1].as_ref()), SwapState::Failed);
+        assert_eq!(signature_state(status[2].as_ref()), SwapState::Pending);
+        assert_eq!(signature_state(status[3].as_ref()), SwapState::Pending);
+    }
+}
@@ -6,15 +6,18 @@ use crate::{
     swapper::{
         approval::{check_approval, CheckApprovalType},
         models::*,
-        slippage::apply_slippage_in_bp,
-        GemSwapProvider, SwapperError,
+        slippage::{apply_slippage_in_bp, apply_slippage_in_bp_max},
+        transaction_status, GemSwapProvider, SwapperError,
     },
 };
 use gem_evm::{
     address::EthereumAddress,
     jsonrpc::{BlockParameter, EthereumRpc, TransactionObject},
     uniswap::{
-        command::{encode_commands, PayPortion, Permit2Permit, Sweep, UniversalRouterCommand, UnwrapWeth, V3SwapExactIn, WrapEth, ADDRESS_THIS},
+        command::{
+            encode_commands, PayPortion, Permit2Permit, Sweep, Transfer, UniversalRouterCommand, UnwrapWeth, V3SwapExactIn, V3SwapExactOut, WrapEth,
+            ADDRESS_THIS,
+        },
         contract::IQuoterV2,
         deployment::V3Deployment,
         path::{build_direct_pair, build_pairs, get_base_pair, BasePair, TokenPair},
@@ -53,6 +56,14 @@ impl JsonRpcRequestConvert for EthereumRpc {
                 let value = serde_json::to_value(tx).unwrap();
                 vec![value, block.into()]
             }
+            EthereumRpc::GetTransactionReceipt(hash) => vec![Value::String(hash.to_string())],
+            EthereumRpc::FeeHistory { blocks, reward_percentiles } => {
+                vec![
+                    Value::String(format!("{:#x}", blocks)),
+                    Value::String("latest".into()),
+                    serde_json::json!(reward_percentiles),
+                ]
+            }
         };
 
         JsonRpcRequest::new(id, method, params)
@@ -94,13 +105,30 @@ impl UniswapV3 {
         EthereumAddress::parse(&str).ok_or(SwapperError::InvalidAddress { address: str })
     }
 
+    // Returns (evm_chain, token_in, token_out, amount), amount is the input or the output depending on the swap mode
     fn parse_request(request: &SwapQuoteRequest) -> Result<(EVMChain, EthereumAddress, EthereumAddress, U256), SwapperError> {
         let evm_chain = EVMChain::from_chain(request.from_asset.chain).ok_or(SwapperError::NotSupportedChain)?;
         let token_in = Self::get_asset_address(&request.from_asset, evm_chain)?;
         let token_out = Self::get_asset_address(&request.to_asset, evm_chain)?;
-        let amount_in = U256::from_str(&request.value).map_err(|_| SwapperError::InvalidAmount)?;
+        let amount = U256::from_str(&request.value).map_err(|_| SwapperError::InvalidAmount)?;
+
+        Ok((evm_chain, token_in, token_out, amount))
+    }
+
+    // Fee taken from the output on top of the exact output amount
+    fn exact_out_fee(amount_out: U256, fee_bps: u32) -> U256 {
+        amount_out * U256::from(fee_bps) / U256::from(10000)
+    }
 
-        Ok((evm_chain, token_in, token_out, amount_in))
+    fn is_better_quote(mode: &GemSwapMode, amount: U256, best_amount: Option<U256>) -> bool {
+        if amount.is_zero() {
+            return false;
+        }
+        match (mode, best_amount) {
+            (_, None) => true,
+            (GemSwapMode::ExactIn, Some(best_amount)) => amount > best_amount,
+            (GemSwapMode::ExactOut, Some(best_amount)) => amount < best_amount,
+        }
     }
 
     fn get_intermediaries(token_in: &EthereumAddress, token_out: &EthereumAddress, base_pair: &BasePair) -> Vec<EthereumAddress> {
@@ -141,33 +169,35 @@ impl UniswapV3 {
         paths
     }
 
-    fn build_paths_with_routes(routes: &[SwapRoute]) -> Result<Bytes, SwapperError> {
+    fn build_paths_with_routes(mode: &GemSwapMode, routes: &[SwapRoute]) -> Result<Bytes, SwapperError> {
         if routes.is_empty() {
             return Err(SwapperError::InvalidRoute);
         }
         let fee_tier = FeeTier::try_from(routes[0].route_data.as_str()).map_err(|_| SwapperError::InvalidAmount)?;
-        let token_pairs: Vec<TokenPair> = routes
-            .iter()
-            .map(|route| TokenPair {
-                token_in: route.input.clone().into(),
-                token_out: route.output.clone().into(),
-                fee_tier: fee_tier.clone(),
-            })
-            .collect();
+        let token_pair = |input: &AssetId, output: &AssetId| TokenPair {
+            token_in: input.clone().into(),
+            token_out: output.clone().into(),
+            fee_tier: fee_tier.clone(),
+        };
+        // exact output paths are encoded in reverse order, from token out to token in
+        let token_pairs: Vec<TokenPair> = match mode {
+            GemSwapMode::ExactIn => routes.iter().map(|route| token_pair(&route.input, &route.output)).collect(),
+            GemSwapMode::ExactOut => routes.iter().rev().map(|route| token_pair(&route.output, &route.input)).collect(),
+        };
         let paths = build_pairs(&token_pairs);
         Ok(paths)
     }
 
-    fn build_quoter_request(mode: &GemSwapMode, wallet_address: &str, quoter_v2: &str, amount_in: U256, path: &Bytes) -> EthereumRpc {
+    fn build_quoter_request(mode: &GemSwapMode, wallet_address: &str, quoter_v2: &str, amount: U256, path: &Bytes) -> EthereumRpc {
         let call_data: Vec<u8> = match mode {
             GemSwapMode::ExactIn => IQuoterV2::quoteExactInputCall {
                 path: path.clone(),
-                amountIn: amount_in,
+                amountIn: amount,
             }
             .abi_encode(),
             GemSwapMode::ExactOut => IQuoterV2::quoteExactOutputCall {
                 path: path.clone(),
-                amountOut: amount_in,
+                amountOut: amount,
             }
             .abi_encode(),
         };
@@ -210,15 +240,23 @@ impl UniswapV3 {
         }
     }
 
-    // Returns (amountOut, gasEstimate)
-    fn decode_quoter_response(response: &JsonRpcResponse<String>) -> Result<(U256, U256), SwapperError> {
+    // Returns (amountOut, gasEstimate) for exact input and (amountIn, gasEstimate) for exact output
+    fn decode_quoter_response(mode: &GemSwapMode, response: &JsonRpcResponse<String>) -> Result<(U256, U256), SwapperError> {
         let decoded = HexDecode(&response.result).map_err(|_| SwapperError::NetworkError {
             msg: "Failed to decode hex result".into(),
         })?;
-        let quoter_return =
-            IQuoterV2::quoteExactInputCall::abi_decode_returns(&decoded, true).map_err(|err| SwapperError::ABIError { msg: err.to_string() })?;
-
-        Ok((quoter_return.amountOut, quoter_return.gasEstimate))
+        match mode {
+            GemSwapMode::ExactIn => {
+                let quoter_return =
+                    IQuoterV2::quoteExactInputCall::abi_decode_returns(&decoded, true).map_err(|err| SwapperError::ABIError { msg: err.to_string() })?;
+                Ok((quoter_return.amountOut, quoter_return.gasEstimate))
+            }
+            GemSwapMode::ExactOut => {
+                let quoter_return =
+                    IQuoterV2::quoteExactOutputCall::abi_decode_returns(&decoded, true).map_err(|err| SwapperError::ABIError { msg: err.to_string() })?;
+                Ok((quoter_return.amountIn, quoter_return.gasEstimate))
+            }
+        }
     }
 
     fn build_commands(
@@ -232,7 +270,7 @@ impl UniswapV3 {
     ) -> Result<Vec<UniversalRouterCommand>, SwapperError> {
         let options = request.options.clone();
         let fee_options = options.fee.unwrap_or_default().evm;
-        let recipient = Address::from_str(&request.wallet_address).map_err(|_| SwapperError::InvalidAddress {
+        let payer = Address::from_str(&request.wallet_address).map_err(|_| SwapperError::InvalidAddress {
             address: request.wallet_address.clone(),
         })?;
 
@@ -245,6 +283,7 @@ impl UniswapV3 {
 
         match mode {
             GemSwapMode::ExactIn => {
+                let recipient = payer;
                 let amount_out = apply_slippage_in_bp(&quote_amount, options.slippage_bps + fee_options.bps);
                 if wrap_input_eth {
                     // Wrap ETH, recipient is this_address
@@ -304,7 +343,72 @@ impl UniswapV3 {
                 }
             }
             GemSwapMode::ExactOut => {
-                todo!("swap exact out not implemented");
+                // the exact output can be paid to another address, like a merchant invoice
+                let recipient = if request.destination_address.is_empty() {
+                    payer
+                } else {
+                    Address::from_str(&request.destination_address).map_err(|_| SwapperError::InvalidAddress {
+                        address: request.destination_address.clone(),
+                    })?
+                };
+                // quote_amount is the exact output, fee is swapped on top of it and transferred to fee_address
+                let amount_in_max = apply_slippage_in_bp_max(&amount_in, options.slippage_bps);
+                let fee_amount = Self::exact_out_fee(quote_amount, fee_options.bps);
+                if wrap_input_eth {
+                    // Wrap max ETH in, recipient is this_address
+                    commands.push(UniversalRouterCommand::WRAP_ETH(WrapEth {
+                        recipient: Address::from_str(ADDRESS_THIS).unwrap(),
+                        amount_min: amount_in_max,
+                    }));
+                } else if let Some(permit) = permit {
+                    commands.push(UniversalRouterCommand::PERMIT2_PERMIT(permit));
+                }
+
+                let payer_is_user = !wrap_input_eth;
+                let swap_recipient = if pay_fees || unwrap_output_weth {
+                    Address::from_str(ADDRESS_THIS).unwrap()
+                } else {
+                    recipient
+                };
+                commands.push(UniversalRouterCommand::V3_SWAP_EXACT_OUT(V3SwapExactOut {
+                    recipient: swap_recipient,
+                    amount_out: quote_amount + fee_amount,
+                    amount_in_max,
+                    path: path.clone(),
+                    payer_is_user,
+                }));
+
+                if pay_fees {
+                    // insert TRANSFER of the fee amount to fee_address
+                    commands.push(UniversalRouterCommand::TRANSFER(Transfer {
+                        token: Address::from_slice(&token_out.bytes),
+                        recipient: Address::from_str(fee_options.address.as_str()).unwrap(),
+                        value: fee_amount,
+                    }));
+
+                    if !unwrap_output_weth {
+                        commands.push(UniversalRouterCommand::SWEEP(Sweep {
+                            token: Address::from_slice(&token_out.bytes),
+                            recipient,
+                            amount_min: quote_amount,
+                        }));
+                    }
+                }
+
+                if unwrap_output_weth {
+                    commands.push(UniversalRouterCommand::UNWRAP_WETH(UnwrapWeth {
+                        recipient,
+                        amount_min: quote_amount,
+                    }));
+                }
+
+                if wrap_input_eth {
+                    // refund unspent WETH as ETH to the payer
+                    commands.push(UniversalRouterCommand::UNWRAP_WETH(UnwrapWeth {
+                        recipient: payer,
+                        amount_min: U256::from(0),
+                    }));
+                }
             }
         }
         Ok(commands)
@@ -357,7 +461,7 @@ impl GemSwapProvider for UniswapV3 {
             .provider
             .get_deployment_by_chain(&request.from_asset.chain)
             .ok_or(SwapperError::NotSupportedChain)?;
-        let (evm_chain, token_in, token_out, amount_in) = Self::parse_request(request)?;
+        let (evm_chain, token_in, token_out, amount) = Self::parse_request(request)?;
         _ = evm_chain.weth_contract().ok_or(SwapperError::NotSupportedChain)?;
 
         let fee_tiers = self.provider.get_tiers();
@@ -372,13 +476,21 @@ impl GemSwapProvider for UniswapV3 {
         //     [usdc_hop_fee_tier1, ..., ..., ... ],
         //     [...],
         // ]
-        let paths_array = Self::build_paths(&token_in, &token_out, &fee_tiers, &base_pair);
+        // Exact output paths are reversed and quoted for the output including fee
+        let (paths_array, quote_amount) = match request.mode {
+            GemSwapMode::ExactIn => (Self::build_paths(&token_in, &token_out, &fee_tiers, &base_pair), amount),
+            GemSwapMode::ExactOut => {
+                let fee_bps = request.options.fee.clone().unwrap_or_default().evm.bps;
+                let paths = Self::build_paths(&token_out, &token_in, &fee_tiers, &base_pair);
+                (paths, amount + Self::exact_out_fee(amount, fee_bps))
+            }
+        };
         let requests: Vec<_> = paths_array
             .iter()
             .map(|paths| {
                 let calls: Vec<EthereumRpc> = paths
                     .iter()
-                    .map(|path| Self::build_quoter_request(&request.mode, &request.wallet_address, deployment.quoter_v2, amount_in, &path.1))
+                    .map(|path| Self::build_quoter_request(&request.mode, &request.wallet_address, deployment.quoter_v2, quote_amount, &path.1))
                     .collect();
 
                 // batch fee_tiers.len() requests into one jsonrpc call
@@ -389,7 +501,7 @@ impl GemSwapProvider for UniswapV3 {
         // fire batch requests in parallel
         let batch_results: Vec<_> = futures::future::join_all(requests).await.into_iter().collect();
 
-        let mut max_amount_out: Option<U256> = None;
+        let mut best_amount: Option<U256> = None;
         let mut batch_idx = 0;
         let mut fee_tier_idx = 0;
         let mut gas_estimate: Option<String> = None;
@@ -400,9 +512,9 @@ impl GemSwapProvider for UniswapV3 {
                     for (index, result) in results.iter().enumerate() {
                         match result {
                             JsonRpcResult::Value(value) => {
-                                let quoter_tuple = Self::decode_quoter_response(value)?;
-                                if quoter_tuple.0 > max_amount_out.unwrap_or_default() {
-                                    max_amount_out = Some(quoter_tuple.0);
+                                let quoter_tuple = Self::decode_quoter_response(&request.mode, value)?;
+                                if Self::is_better_quote(&request.mode, quoter_tuple.0, best_amount) {
+                                    best_amount = Some(quoter_tuple.0);
                                     fee_tier_idx = index;
                                     batch_idx = batch;
                                     gas_estimate = Some(quoter_tuple.1.to_string());
@@ -416,15 +528,17 @@ impl GemSwapProvider for UniswapV3 {
             }
         }
 
-        if max_amount_out.is_none() {
-            return Err(SwapperError::NoQuoteAvailable);
-        }
+        let best_amount = best_amount.ok_or(SwapperError::NoQuoteAvailable)?;
+        let (from_value, to_value, approval_amount) = match request.mode {
+            GemSwapMode::ExactIn => (amount, best_amount, amount),
+            GemSwapMode::ExactOut => (best_amount, amount, apply_slippage_in_bp_max(&best_amount, request.options.slippage_bps)),
+        };
 
         let mut approval_type = ApprovalType::None;
         if !request.from_asset.is_native() {
             // Check allowances
             approval_type = self
-                .check_approval(wallet_address, &token_in.to_checksum(), amount_in, &request.from_asset.chain, provider)
+                .check_approval(wallet_address, &token_in.to_checksum(), approval_amount, &request.from_asset.chain, provider)
                 .await?;
         }
 
@@ -444,8 +558,8 @@ impl GemSwapProvider for UniswapV3 {
         let routes = Self::build_swap_route(&asset_id_in, asset_id_intermediary.as_ref(), &asset_id_out, &fee_tier.to_string(), gas_estimate);
 
         Ok(SwapQuote {
-            from_value: request.value.clone(),
-            to_value: max_amount_out.unwrap().to_string(), // safe to unwrap here because we will early return if no quote is available
+            from_value: from_value.to_string(),
+            to_value: to_value.to_string(),
             data: SwapProviderData {
                 provider: self.provider(),
                 routes: routes.clone(),
@@ -458,11 +572,12 @@ impl GemSwapProvider for UniswapV3 {
 
     async fn fetch_quote_data(&self, quote: &SwapQuote, _provider: Arc<dyn AlienProvider>, data: FetchQuoteData) -> Result<SwapQuoteData, SwapperError> {
         let request = &quote.request;
-        let (_, token_in, token_out, amount_in) = Self::parse_request(request)?;
+        let (_, token_in, token_out, _) = Self::parse_request(request)?;
         let deployment = self
             .provider
             .get_deployment_by_chain(&request.from_asset.chain)
             .ok_or(SwapperError::NotSupportedChain)?;
+        let from_amount = U256::from_str(&quote.from_value).map_err(|_| SwapperError::InvalidAmount)?;
         let to_amount = U256::from_str(&quote.to_value).map_err(|_| SwapperError::InvalidAmount)?;
 
         let permit = match data {
@@ -470,13 +585,17 @@ impl GemSwapProvider for UniswapV3 {
             FetchQuoteData::None => None,
         };
 
-        let path: Bytes = Self::build_paths_with_routes(&quote.data.routes)?;
-        let commands = Self::build_commands(request, &token_in, &token_out, amount_in, to_amount, &path, permit)?;
+        let path: Bytes = Self::build_paths_with_routes(&request.mode, &quote.data.routes)?;
+        let commands = Self::build_commands(request, &token_in, &token_out, from_amount, to_amount, &path, permit)?;
         let deadline = SystemTime::now().duration_since(UNIX_EPOCH).expect("Time went backwards").as_secs() + DEFAULT_DEADLINE;
         let encoded = encode_commands(&commands, U256::from(deadline));
 
         let wrap_input_eth = request.from_asset.is_native();
-        let value = if wrap_input_eth { request.value.clone() } else { String::from("0") };
+        let value = match (wrap_input_eth, &request.mode) {
+            (false, _) => String::from("0"),
+            (true, GemSwapMode::ExactIn) => from_amount.to_string(),
+            (true, GemSwapMode::ExactOut) => apply_slippage_in_bp_max(&from_amount, request.options.slippage_bps).to_string(),
+        };
         Ok(SwapQuoteData {
             to: deployment.universal_router.into(),
             value,
@@ -484,9 +603,8 @@ impl GemSwapProvider for UniswapV3 {
         })
     }
 
-    async fn get_transaction_status(&self, _chain: Chain, _transaction_hash: &str, _provider: Arc<dyn AlienProvider>) -> Result<bool, SwapperError> {
-        // TODO: the transaction status from the RPC
-        Ok(true)
+    async fn get_transaction_status(&self, chain: Chain, transaction_hash: &str, provider: Arc<dyn AlienProvider>) -> Result<SwapStatus, SwapperError> {
+        transaction_status::fetch_evm_status(&chain, transaction_hash, provider).await
     }
 }
 
@@ -608,6 +726,32 @@ mod tests {
         assert!(matches!(commands[1], UniversalRouterCommand::V3_SWAP_EXACT_IN(_)));
     }
 
+    #[test]
+    fn test_build_commands_exact_in_ignores_destination() {
+        // exact input swaps keep paying out to the wallet
+        let request = SwapQuoteRequest {
+            from_asset: AssetId::from(Chain::Optimism, Some("0x0b2c639c533813f4aa9d7837caf62653d097ff85".into())),
+            to_asset: AssetId::from(Chain::Optimism, Some("0x94b008aa00579c1307b0ef2c499ad98a8ce58e58".into())),
+            wallet_address: "0x514BCb1F9AAbb904e6106Bd1052B66d2706dBbb7".into(),
+            destination_address: "0x7FFC3DBF3B2b50Ff3A1D5523bc24Bb5043837B14".into(),
+            value: "6500000".into(),
+            mode: GemSwapMode::ExactIn,
+            options: GemSwapOptions::default(),
+        };
+
+        let token_in = EthereumAddress::parse(request.from_asset.token_id.as_ref().unwrap()).unwrap();
+        let token_out = EthereumAddress::parse(request.to_asset.token_id.as_ref().unwrap()).unwrap();
+        let amount_in = U256::from_str(&request.value).unwrap();
+        let path = build_direct_pair(&token_in, &token_out, FeeTier::FiveHundred as u32);
+        let commands = UniswapV3::build_commands(&request, &token_in, &token_out, amount_in, U256::from(6507936), &path, None).unwrap();
+
+        assert_eq!(commands.len(), 1);
+        let UniversalRouterCommand::V3_SWAP_EXACT_IN(swap) = &commands[0] else {
+            panic!("expected V3_SWAP_EXACT_IN");
+        };
+        assert_eq!(swap.recipient, Address::from_str(&request.wallet_address).unwrap());
+    }
+
     #[test]
     fn test_build_commands_usdc_to_aave() {
         let request = SwapQuoteRequest {
@@ -702,4 +846,105 @@ mod tests {
         assert!(matches!(commands[2], UniversalRouterCommand::PAY_PORTION(_)));
         assert!(matches!(commands[3], UniversalRouterCommand::UNWRAP_WETH(_)));
     }
+
+    #[test]
+    fn test_build_commands_exact_out_eth_to_usdc() {
+        let request = SwapQuoteRequest {
+            // ETH -> 5 USDC
+            from_asset: AssetId::from(Chain::Optimism, None),
+            to_asset: AssetId::from(Chain::Optimism, Some("0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85".into())),
+            wallet_address: "0x514BCb1F9AAbb904e6106Bd1052B66d2706dBbb7".into(),
+            destination_address: "0x514BCb1F9AAbb904e6106Bd1052B66d2706dBbb7".into(),
+            value: "5000000".into(),
+            mode: GemSwapMode::ExactOut,
+            options: GemSwapOptions {
+                slippage_bps: 100,
+                fee: Some(SwapReferralFees::evm(SwapReferralFee {
+                    bps: 25,
+                    address: "0x7FFC3DBF3B2b50Ff3A1D5523bc24Bb5043837B14".into(),
+                })),
+                preferred_providers: vec![],
+            },
+        };
+
+        let token_in = EthereumAddress::parse("0x4200000000000000000000000000000000000006").unwrap();
+        let token_out = EthereumAddress::parse(request.to_asset.token_id.as_ref().unwrap()).unwrap();
+        let routes = UniswapV3::build_swap_route(
+            &AssetId::from(Chain::Optimism, Some(token_in.to_checksum())),
+            None,
+            &AssetId::from(Chain::Optimism, Some(token_out.to_checksum())),
+            "500",
+            None,
+        );
+        let path = UniswapV3::build_paths_with_routes(&request.mode, &routes).unwrap();
+
+        assert_eq!(
+            HexEncode(&path),
+            "0x0b2c639c533813f4aa9d7837caf62653d097ff850001f44200000000000000000000000000000000000006"
+        );
+
+        let amount_in = U256::from(2004000000000000u64);
+        let commands = UniswapV3::build_commands(&request, &token_in, &token_out, amount_in, U256::from(5000000), &path, None).unwrap();
+
+        assert_eq!(commands.len(), 5);
+        assert!(matches!(commands[0], UniversalRouterCommand::WRAP_ETH(_)));
+        assert!(matches!(commands[2], UniversalRouterCommand::TRANSFER(_)));
+        assert!(matches!(commands[3], UniversalRouterCommand::SWEEP(_)));
+        assert!(matches!(commands[4], UniversalRouterCommand::UNWRAP_WETH(_)));
+        let UniversalRouterCommand::V3_SWAP_EXACT_OUT(swap) = &commands[1] else {
+            panic!("expected V3_SWAP_EXACT_OUT");
+        };
+        assert_eq!(
+            *swap,
+            V3SwapExactOut {
+                recipient: Address::from_str(ADDRESS_THIS).unwrap(),
+                amount_out: U256::from(5012500),
+                amount_in_max: U256::from(2024040000000000u64),
+                path,
+                payer_is_user: false,
+            }
+        );
+    }
+
+    #[test]
+    fn test_build_commands_exact_out_refund_recipient() {
+        // ETH -> 5 USDC sent to another address, unspent ETH is refunded to the payer
+        let request = SwapQuoteRequest {
+            from_asset: AssetId::from(Chain::Optimism, None),
+            to_asset: AssetId::from(Chain::Optimism, Some("0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85".into())),
+            wallet_address: "0x514BCb1F9AAbb904e6106Bd1052B66d2706dBbb7".into(),
+            destination_address: "0x7FFC3DBF3B2b50Ff3A1D5523bc24Bb5043837B14".into(),
+            value: "5000000".into(),
+            mode: GemSwapMode::ExactOut,
+            options: GemSwapOptions {
+                slippage_bps: 100,
+                fee: None,
+                preferred_providers: vec![],
+            },
+        };
+
+        let token_in = EthereumAddress::parse("0x4200000000000000000000000000000000000006").unwrap();
+        let token_out = EthereumAddress::parse(request.to_asset.token_id.as_ref().unwrap()).unwrap();
+        let routes = UniswapV3::build_swap_route(
+            &AssetId::from(Chain::Optimism, Some(token_in.to_checksum())),
+            None,
+            &AssetId::from(Chain::Optimism, Some(token_out.to_checksum())),
+            "500",
+            None,
+        );
+        let path = UniswapV3::build_paths_with_routes(&request.mode, &routes).unwrap();
+        let amount_in = U256::from(2004000000000000u64);
+        let commands = UniswapV3::build_commands(&request, &token_in, &token_out, amount_in, U256::from(5000000), &path, None).unwrap();
+
+        assert_eq!(commands.len(), 3);
+        let UniversalRouterCommand::V3_SWAP_EXACT_OUT(swap) = &commands[1] else {
+            panic!("expected V3_SWAP_EXACT_OUT");
+        };
+        assert_eq!(swap.recipient, Address::from_str(&request.destination_address).unwrap());
+        let UniversalRouterCommand::UNWRAP_WETH(refund) = &commands[2] else {
+            panic!("expected UNWRAP_WETH");
+        };
+        assert_eq!(refund.recipient, Address::from_str(&request.wallet_address).unwrap());
+        assert_eq!(refund.amount_min, U256::from(0));
+    }
 }
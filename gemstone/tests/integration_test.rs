@@ -112,6 +112,10 @@ mod tests {
         assert_eq!(quote.from_value, "1000000");
         assert!(quote.to_value.parse::<u64>().unwrap() > 0);
 
+        let quote_data = swap_provider.fetch_quote_data(&quote, network_provider.clone(), FetchQuoteData::None).await?;
+
+        assert!(!quote_data.data.is_empty());
+
         Ok(())
     }
 }